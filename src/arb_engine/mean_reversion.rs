@@ -0,0 +1,95 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Confidence of a signal whose sum is infinitely far past the band. A certain
+/// arb is 1; a bet on reversion never gets close.
+const MAX_MEAN_REVERSION_CONFIDENCE: Decimal = dec!(0.5);
+
+/// Which way the sum is expected to move back toward its mean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReversionDirection {
+    /// Sum spiked above its mean and should fall
+    Down,
+    /// Sum dipped below its mean and should rise
+    Up,
+}
+
+/// A binary market whose YES+NO ask sum has strayed past `mean_reversion_band`
+/// from its rolling mean. A directional bet that the sum reverts, not a
+/// risk-free arb, so it's only surfaced for a strategy that takes such bets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeanReversionSignal {
+    pub market_id: String,
+    pub sum: Decimal,
+    /// Mean of the sums before this one
+    pub rolling_mean: Decimal,
+    /// `sum - rolling_mean`
+    pub deviation: Decimal,
+    pub direction: ReversionDirection,
+    /// Grows with how far past the band the sum is, up to 0.5
+    pub confidence: Decimal,
+    /// Detection timestamp (ms)
+    pub timestamp: i64,
+}
+
+impl MeanReversionSignal {
+    /// Signal for `sum` against the `rolling_mean` of the sums before it, if it
+    /// strays further than `band`
+    pub(super) fn check(
+        market_id: &str,
+        sum: Decimal,
+        rolling_mean: Decimal,
+        band: Decimal,
+        timestamp: i64,
+    ) -> Option<Self> {
+        let deviation = sum - rolling_mean;
+        if deviation.abs() <= band {
+            return None;
+        }
+
+        Some(Self {
+            market_id: market_id.to_string(),
+            sum,
+            rolling_mean,
+            deviation,
+            direction: if deviation > Decimal::ZERO {
+                ReversionDirection::Down
+            } else {
+                ReversionDirection::Up
+            },
+            confidence: MAX_MEAN_REVERSION_CONFIDENCE * (Decimal::ONE - band / deviation.abs()),
+            timestamp,
+        })
+    }
+}
+
+/// A market's most recent sums, oldest first
+#[derive(Debug, Clone, Default)]
+pub(super) struct SumHistory {
+    sums: VecDeque<Decimal>,
+    /// When the last sum was pushed
+    pub(super) last_sampled: Option<Instant>,
+    /// Signal raised by the last sum, if it strayed past the band
+    pub(super) signal: Option<MeanReversionSignal>,
+}
+
+impl SumHistory {
+    /// Mean of the last `window` sums, once that many have been seen
+    pub(super) fn mean(&self, window: usize) -> Option<Decimal> {
+        if window == 0 || self.sums.len() < window {
+            return None;
+        }
+        Some(self.sums.iter().sum::<Decimal>() / Decimal::from(window))
+    }
+
+    pub(super) fn push(&mut self, sum: Decimal, window: usize) {
+        self.sums.push_back(sum);
+        self.last_sampled = Some(Instant::now());
+        while self.sums.len() > window {
+            self.sums.pop_front();
+        }
+    }
+}
@@ -1,13 +1,40 @@
+use crate::error::HftpmError;
 use crate::gamma_api::Market;
-use crate::orderbook::OrderBookManager;
+use crate::monitoring::{
+    CapitalUse, NearMissKind, NearMissRecorder, RejectReason, RejectionCounts, RejectionStats,
+};
+use crate::orderbook::{AskLadder, MarketBooks, OrderBookManager};
 use crate::risk::RiskManager;
-use crate::utils::Config;
+use crate::utils::sim::{SimClock, SimEnv};
+use crate::utils::{saturating_i64, Config, QualityScoringConfig};
 use anyhow::{Context, Result};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+mod mean_reversion;
+mod opportunity;
+
+pub use mean_reversion::{MeanReversionSignal, ReversionDirection};
+pub use opportunity::{ArbSide, Opportunity, OpportunityLeg};
+
+use mean_reversion::SumHistory;
+
+/// How often a market still waiting on books is logged
+const WARMUP_LOG_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a market can go unsampled before its per-market state is dropped
+pub const IDLE_MARKET_STATE_TTL: Duration = Duration::from_secs(3600);
+
+/// Ask levels per leg kept for short-window depth-weighted sizing
+pub const SHORT_WINDOW_DEPTH_LEVELS: usize = 5;
+
+const MINUTES_PER_YEAR: i64 = 365 * 24 * 60;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArbitrageOpportunity {
     pub market_id: String,
@@ -27,6 +54,22 @@ pub struct ArbitrageOpportunity {
 pub enum ArbType {
     Binary,
     MultiOutcome,
+    /// Sell a held complete set into bids summing above $1
+    SellCompleteSet,
+    /// Buy both sides of a 15-minute crypto market (see `detect_short_window_arbitrage`)
+    ShortWindow,
+    /// Lock in mispricing between two correlated markets
+    CrossMarket,
+}
+
+impl ArbType {
+    #[inline]
+    pub fn side(&self) -> ArbSide {
+        match self {
+            ArbType::SellCompleteSet => ArbSide::Sell,
+            _ => ArbSide::Buy,
+        }
+    }
 }
 
 impl std::fmt::Display for ArbType {
@@ -34,6 +77,9 @@ impl std::fmt::Display for ArbType {
         match self {
             ArbType::Binary => write!(f, "Binary"),
             ArbType::MultiOutcome => write!(f, "MultiOutcome"),
+            ArbType::SellCompleteSet => write!(f, "SellCompleteSet"),
+            ArbType::ShortWindow => write!(f, "ShortWindow"),
+            ArbType::CrossMarket => write!(f, "CrossMarket"),
         }
     }
 }
@@ -86,42 +132,200 @@ pub struct ShortWindowArbOpportunity {
     pub market_question: String,
     /// Minutes until market resolution
     pub minutes_to_expiry: i64,
-    /// YES price (ask)
-    pub yes_price: Decimal,
-    /// NO price (ask)
-    pub no_price: Decimal,
-    /// Sum of YES + NO (should be < 1.0)
+    /// One leg per outcome (YES/NO for binary, every bucket for n-outcome markets)
+    pub legs: Vec<ShortWindowLeg>,
+    /// Sum of all leg best asks (should be < 1.0)
     pub sum_prices: Decimal,
+    /// Depth-weighted cost of one set when buying `position_size` through the book
+    pub vwap_sum: Decimal,
     /// Raw edge before fees (1.0 - sum_prices)
     pub raw_edge: Decimal,
-    /// Net edge after 2% fees
+    /// Net edge after 2% fees, at the top of book
     pub net_edge: Decimal,
-    /// Position size for each side (buy equal amounts)
+    /// Net edge after fees at `vwap_sum`; what `position_size` actually earns
+    pub vwap_net_edge: Decimal,
+    /// Position size for each leg (buy equal amounts), sized so the VWAP edge
+    /// still clears the short-window min edge for `minutes_to_expiry`
     pub position_size: Decimal,
-    /// Expected profit after fees
+    /// Expected profit after fees, at `vwap_sum`
     pub expected_profit: Decimal,
-    /// YES asset ID for execution
-    pub yes_asset_id: String,
-    /// NO asset ID for execution
-    pub no_asset_id: String,
-    /// Minimum liquidity available
+    /// Thinnest leg's size across its retained ask levels
     pub min_liquidity: Decimal,
     /// Detection timestamp
     pub detected_at: i64,
-    /// Annualized return (for comparison)
+    /// Annualized return as a fraction, after fill/utilization haircuts and capped
     pub annualized_return: Decimal,
+    /// Fill probability assumed when computing `annualized_return`
+    pub assumed_fill_probability: Decimal,
+    /// Capital utilization assumed when computing `annualized_return`
+    pub assumed_capital_utilization: Decimal,
+}
+
+/// One outcome leg of a short-window arb
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortWindowLeg {
+    /// Asset ID for execution
+    pub asset_id: String,
+    /// Outcome name ("Up", "YES", a bucket label) the asset pays out on
+    pub outcome: String,
+    /// Best ask price
+    pub price: Decimal,
+    /// Size available at the best ask
+    pub size: Decimal,
+    /// Top ask levels `(price, size)`, best first; orders are priced at the
+    /// level `position_size` reaches (see `ask_limit_for_size`)
+    pub depth: AskLadder,
+}
+
+/// Largest complete-set size, up to `max_size`, whose depth-weighted cost still
+/// clears `min_edge` after `fee_rate`, with the VWAP sum at that size.
+///
+/// `ladders` holds each leg's asks, best first. Every set takes one share from
+/// each leg, so the marginal set costs the sum of the legs' current levels;
+/// sizing stops where the running average would push the edge below `min_edge`.
+/// `None` when even the top of book doesn't clear it.
+pub fn size_to_vwap_edge(
+    ladders: &[AskLadder],
+    max_size: Decimal,
+    fee_rate: Decimal,
+    min_edge: Decimal,
+) -> Option<(Decimal, Decimal)> {
+    // Highest average set cost that still earns `min_edge`
+    let max_avg_cost = Decimal::ONE - fee_rate - min_edge;
+    let mut levels = vec![0usize; ladders.len()];
+    let mut taken = vec![Decimal::ZERO; ladders.len()];
+    let mut filled = Decimal::ZERO;
+    let mut cost = Decimal::ZERO;
+
+    while !ladders.is_empty() && filled < max_size {
+        let mut marginal = Decimal::ZERO;
+        let mut step = max_size - filled;
+        for ((ladder, &level), taken) in ladders.iter().zip(&levels).zip(&taken) {
+            let Some(&(price, size)) = ladder.get(level) else {
+                step = Decimal::ZERO;
+                break;
+            };
+            marginal += price;
+            step = step.min(size - taken);
+        }
+
+        if step <= Decimal::ZERO {
+            break;
+        }
+
+        // Dearer than the cap: take only what keeps the average at or under it
+        if marginal > max_avg_cost {
+            let room = (max_avg_cost * filled - cost) / (marginal - max_avg_cost);
+            step = step
+                .min(room)
+                .round_dp_with_strategy(2, rust_decimal::RoundingStrategy::ToZero);
+            if step > Decimal::ZERO {
+                filled += step;
+                cost += marginal * step;
+            }
+            break;
+        }
+
+        filled += step;
+        cost += marginal * step;
+        for ((ladder, level), taken) in ladders.iter().zip(&mut levels).zip(&mut taken) {
+            *taken += step;
+            if *taken >= ladder[*level].1 {
+                *level += 1;
+                *taken = Decimal::ZERO;
+            }
+        }
+    }
+
+    if filled <= Decimal::ZERO {
+        return None;
+    }
+
+    Some((filled, cost / filled))
+}
+
+/// Price of the deepest ask level buying `size` through `ladder` reaches: the
+/// limit an order for `size` needs to fill. The last level if the ladder runs
+/// out first; `None` if it is empty.
+pub fn ask_limit_for_size(ladder: &AskLadder, size: Decimal) -> Option<Decimal> {
+    let mut filled = Decimal::ZERO;
+    for &(price, level_size) in ladder {
+        filled += level_size;
+        if filled >= size {
+            return Some(price);
+        }
+    }
+    ladder.last().map(|&(price, _)| price)
+}
+
+/// Estimate an annualized return for a short-window arb that doesn't assume
+/// every detection fills and capital is redeployed instantly every cycle.
+///
+/// `net_edge * cycles_per_year * fill_probability * capital_utilization`,
+/// capped at `max_annualized_return`. Returned as a fraction (1.0 = 100%); zero
+/// once the window has expired.
+pub fn estimate_annualized_return(
+    net_edge: Decimal,
+    minutes_to_expiry: i64,
+    fill_probability: Decimal,
+    capital_utilization: Decimal,
+    max_annualized_return: Decimal,
+) -> Decimal {
+    if minutes_to_expiry <= 0 {
+        return Decimal::ZERO;
+    }
+    let cycles_per_year = Decimal::from(MINUTES_PER_YEAR) / Decimal::from(minutes_to_expiry);
+    let realistic = net_edge * cycles_per_year * fill_probability * capital_utilization;
+
+    realistic.min(max_annualized_return)
+}
+
+/// Whether the current books hold every leg's size at or better than its
+/// quoted price. Legs are sent as limits at that price, so depth past it
+/// can't fill them.
+pub fn legs_fill_at_limit(arb_op: &dyn Opportunity, orderbook_manager: &OrderBookManager) -> bool {
+    arb_op.legs().iter().all(|leg| {
+        let Some(book) = orderbook_manager.get_book(&leg.market_id, &leg.asset_id) else {
+            return false;
+        };
+        let depth = match leg.side {
+            ArbSide::Buy => book.ask_depth_at(leg.price),
+            ArbSide::Sell => book.bid_depth_at(leg.price),
+        };
+        depth >= leg.size
+    })
+}
+
+/// Name of the outcome traded under `asset_id`, or `fallback` when the market
+/// is unknown or doesn't list the token
+fn outcome_name(
+    market: Option<&Market>,
+    asset_id: &str,
+    fallback: impl FnOnce() -> String,
+) -> String {
+    market
+        .and_then(|m| m.outcome_for_token(asset_id))
+        .map(|o| o.name.clone())
+        .unwrap_or_else(fallback)
 }
 
 impl std::fmt::Display for ShortWindowArbOpportunity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let legs = self
+            .legs
+            .iter()
+            .map(|leg| format!("{:.2}¢", leg.price * Decimal::ONE_HUNDRED))
+            .collect::<Vec<_>>()
+            .join(" + ");
+
         write!(
             f,
-            "⚡ 15m ARB: {} | YES={:.2}¢ + NO={:.2}¢ = {:.2}¢ | Edge: {:.2}% | Profit: ${:.2} | Expires: {}min",
+            "⚡ 15m ARB: {} | {} = {:.2}¢ | Edge: {:.2}% ({:.2}% VWAP) | Profit: ${:.2} | Expires: {}min",
             self.market_question.chars().take(30).collect::<String>(),
-            self.yes_price * Decimal::ONE_HUNDRED,
-            self.no_price * Decimal::ONE_HUNDRED,
+            legs,
             self.sum_prices * Decimal::ONE_HUNDRED,
             self.net_edge * Decimal::ONE_HUNDRED,
+            self.vwap_net_edge * Decimal::ONE_HUNDRED,
             self.expected_profit,
             self.minutes_to_expiry
         )
@@ -131,27 +335,38 @@ impl std::fmt::Display for ShortWindowArbOpportunity {
 impl ArbitrageOpportunity {
     /// Calculate quality score based on RN1 strategy metrics
     /// Returns score 0-10 (higher = better opportunity)
-    pub fn calculate_quality_score(&self) -> Decimal {
-        use rust_decimal_macros::dec;
-
-        // 1. Edge quality (weight: 40%)
-        let edge_score = (self.total_edge * dec!(100)).min(dec!(10));
-
-        // 2. Liquidity depth (weight: 30%)
-        let liquidity_score = (self.min_liquidity / dec!(1000)).min(dec!(10));
-
-        // 3. Position size (weight: 20%)
-        let size_score = (self.position_size / dec!(500)).min(dec!(10));
+    pub fn calculate_quality_score(&self, scoring: &QualityScoringConfig) -> Decimal {
+        self.quality_score(scoring)
+    }
+}
 
-        // 4. Expected profit (weight: 10%)
-        let profit_score = (self.net_profit / dec!(50)).min(dec!(10));
+/// Cap that limits a position's size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizingCap {
+    /// `max_arb_size` scaled by edge over `min_edge` (at most 2x)
+    Edge,
+    /// What the bankroll buys at the set's price
+    Bankroll,
+    /// The thinnest leg's depth
+    Liquidity,
+    /// `max_arb_size`
+    Limit,
+}
 
-        // Weighted score
-        (edge_score * dec!(0.4))
-            + (liquidity_score * dec!(0.3))
-            + (size_score * dec!(0.2))
-            + (profit_score * dec!(0.1))
-    }
+/// Every cap on a binary arb's size and which one set it, from `explain_sizing`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SizingExplanation {
+    pub raw_edge: Decimal,
+    pub by_edge: Decimal,
+    /// `None` when the set is free (`raw_edge >= 1`) and the bankroll can't bind
+    pub by_bankroll: Option<Decimal>,
+    pub by_liquidity: Decimal,
+    pub by_limit: Decimal,
+    /// The smallest cap
+    pub size: Decimal,
+    /// Cap that set `size`; ties go to the first of edge, bankroll, liquidity, limit
+    pub binding: SizingCap,
 }
 
 pub struct ArbEngine {
@@ -159,6 +374,18 @@ pub struct ArbEngine {
     detections: u64,
     executions: u64,
     latency_tracker: crate::utils::LatencyTracker,
+    /// Last known wallet (or simulated) balance; `None` until first reported
+    available_balance: Option<Decimal>,
+    /// Market -> when we last logged it waiting on missing books
+    warmup_logged: HashMap<String, Instant>,
+    /// Where edge and liquidity rejections that nearly passed are recorded, if anywhere
+    near_misses: Option<Arc<NearMissRecorder>>,
+    /// Why detection turned gaps away, per market; private unless shared via `with_rejections`
+    rejections: Arc<RejectionStats>,
+    /// Market -> its recent binary ask sums, for mean-reversion signals
+    sum_histories: HashMap<String, SumHistory>,
+    /// Whether rejections are counted; off while `evaluate` runs
+    recording: bool,
 }
 
 impl ArbEngine {
@@ -168,6 +395,89 @@ impl ArbEngine {
             detections: 0,
             executions: 0,
             latency_tracker: crate::utils::LatencyTracker::new(),
+            available_balance: None,
+            warmup_logged: HashMap::new(),
+            near_misses: None,
+            rejections: Arc::new(RejectionStats::new()),
+            sum_histories: HashMap::new(),
+            recording: true,
+        }
+    }
+
+    /// Record edge and liquidity rejections that nearly passed in `near_misses`
+    pub fn with_near_misses(mut self, near_misses: Arc<NearMissRecorder>) -> Self {
+        self.near_misses = Some(near_misses);
+        self
+    }
+
+    /// Count rejections in `rejections` (the monitor's, for the dashboard)
+    pub fn with_rejections(mut self, rejections: Arc<RejectionStats>) -> Self {
+        self.rejections = rejections;
+        self
+    }
+
+    #[inline]
+    fn reject(&self, market_id: &str, reason: RejectReason) {
+        if self.recording {
+            self.rejections.record(market_id, reason);
+        }
+    }
+
+    /// `value` fell short of `threshold`; recorded if close enough to count
+    #[inline]
+    fn near_miss(&self, market_id: &str, kind: NearMissKind, value: Decimal, threshold: Decimal) {
+        if let Some(near_misses) = &self.near_misses {
+            near_misses.record_below(market_id, kind, value, threshold);
+        }
+    }
+
+    /// Whether a book in this market is older than `stale_book_max_age_ms`. The
+    /// sweeper evicts such markets within seconds; detection skips them meanwhile.
+    fn has_stale_book(&self, orderbook_manager: &OrderBookManager, market_id: &str) -> bool {
+        let max_age_ms = saturating_i64(self.config.execution.stale_book_max_age_ms);
+        let stale = orderbook_manager
+            .market_age_ms(market_id)
+            .is_some_and(|age_ms| age_ms > max_age_ms);
+        if stale {
+            debug!(
+                "⏭️  Skipping {}: a book is older than {}ms",
+                market_id, max_age_ms
+            );
+        }
+        stale
+    }
+
+    fn log_missing_legs(&mut self, market_id: &str, missing: &[String]) {
+        let now = Instant::now();
+        let due = self
+            .warmup_logged
+            .get(market_id)
+            .is_none_or(|last| now.duration_since(*last) >= WARMUP_LOG_INTERVAL);
+
+        if due {
+            debug!(
+                "⏳ {} waiting on {} missing book(s): {:?}",
+                market_id,
+                missing.len(),
+                missing
+            );
+            self.warmup_logged.insert(market_id.to_string(), now);
+        }
+    }
+
+    /// Record the balance trades are paid from (`get_balance` live, the simulated
+    /// balance otherwise); detection never sizes a trade above it
+    pub fn set_available_balance(&mut self, balance: Decimal) {
+        self.available_balance = Some(balance);
+    }
+
+    /// Capital a new trade may use: undeployed bankroll, capped by the wallet balance
+    #[inline]
+    pub fn available_capital(&self, risk_manager: &RiskManager) -> Decimal {
+        let undeployed = risk_manager.available_bankroll();
+        match self.available_balance {
+            Some(balance) => undeployed.min(balance.max(Decimal::ZERO)),
+            None => undeployed,
         }
     }
 
@@ -176,27 +486,27 @@ impl ArbEngine {
         &mut self,
         orderbook_manager: &OrderBookManager,
         market_id: &str,
+        market: Option<&Market>,
         risk_manager: &RiskManager,
     ) -> Result<Option<ArbitrageOpportunity>> {
         let start = std::time::Instant::now();
 
-        let market_books = orderbook_manager
-            .get_market_books(market_id)
-            .context("Market not found")?;
-
-        let best_asks = orderbook_manager
-            .get_best_asks_for_market(market_id)
-            .context("Failed to get best asks")?;
-
-        if best_asks.is_empty() {
+        // Until every registered leg has a book, any sum is a partial one
+        let missing = orderbook_manager.missing_assets(market_id);
+        if !missing.is_empty() {
+            self.log_missing_legs(market_id, &missing);
+            return Ok(None);
+        }
+        if self.has_stale_book(orderbook_manager, market_id) {
             return Ok(None);
         }
 
-        let arb_op = if market_books.is_binary() {
-            self.detect_binary_arbitrage(market_id, &market_books, &best_asks, risk_manager)?
-        } else {
-            self.detect_multi_outcome_arbitrage(market_id, &market_books, &best_asks, risk_manager)?
-        };
+        // Borrow the books in place; cloning deep books dominated detection latency
+        let arb_op = orderbook_manager
+            .with_market_books(market_id, |market_books| {
+                self.evaluate_books(market_id, market, market_books, risk_manager)
+            })
+            .context("Market not found")?;
 
         // Record latency after detection is done
         let elapsed = start.elapsed().as_nanos() as u64;
@@ -205,6 +515,12 @@ impl ArbEngine {
         if let Some(ref op) = arb_op {
             self.detections += 1;
             info!(
+                event = "arb_detected",
+                market_id = %op.market_id,
+                arb_type = %op.arb_type,
+                edge = %op.total_edge,
+                profit = %op.net_profit,
+                latency_ms = self.latency_tracker.avg_latency_ms(),
                 "🎯 Arbitrage detected #{}: {} (latency: {:.2}ms)",
                 self.detections,
                 op,
@@ -215,24 +531,295 @@ impl ArbEngine {
         Ok(arb_op)
     }
 
-    /// RN1 strategy: Only execute high-quality opportunities
-    pub fn should_execute_opportunity(&self, arb_op: &ArbitrageOpportunity) -> bool {
-        use rust_decimal_macros::dec;
+    /// Flag a binary market whose YES+NO ask sum strays more than
+    /// `mean_reversion_band` from the mean of its last `mean_reversion_window`
+    /// sums. Each call samples the sum, so call it once per book change. Does
+    /// nothing unless `enable_mean_reversion_signals` is set.
+    pub fn detect_mean_reversion(
+        &mut self,
+        orderbook_manager: &OrderBookManager,
+        market_id: &str,
+    ) -> Option<MeanReversionSignal> {
+        let trading = &self.config.trading;
+        if !trading.enable_mean_reversion_signals
+            || self.has_stale_book(orderbook_manager, market_id)
+        {
+            return None;
+        }
+
+        let sum =
+            orderbook_manager.with_market_books(market_id, MarketBooks::get_binary_book_sum)??;
+        let window = trading.mean_reversion_window;
+        let history = self.sum_histories.entry(market_id.to_string()).or_default();
+        // Against the sums before this one, so a spike doesn't drag the mean toward itself
+        let signal = history.mean(window).and_then(|rolling_mean| {
+            MeanReversionSignal::check(
+                market_id,
+                sum,
+                rolling_mean,
+                trading.mean_reversion_band,
+                chrono::Utc::now().timestamp_millis(),
+            )
+        });
+        history.push(sum, window);
+        history.signal = signal.clone();
+
+        if let Some(signal) = &signal {
+            info!(
+                event = "mean_reversion_signal",
+                market_id = %market_id,
+                sum = %sum,
+                mean = %signal.rolling_mean,
+                confidence = %signal.confidence,
+                "📈 Mean-reversion signal on {}: sum {} vs mean {:.4} ({:?}, confidence {:.2})",
+                market_id,
+                sum,
+                signal.rolling_mean,
+                signal.direction,
+                signal.confidence
+            );
+        }
 
-        let quality_score = arb_op.calculate_quality_score();
-        let min_quality = dec!(5.0); // Top 50% threshold
+        signal
+    }
+
+    /// Markets whose latest sampled sum is still past the mean-reversion band,
+    /// newest first
+    pub fn mean_reversion_signals(&self) -> Vec<MeanReversionSignal> {
+        let mut signals: Vec<_> = self
+            .sum_histories
+            .values()
+            .filter_map(|history| history.signal.clone())
+            .collect();
+        signals.sort_by_key(|signal| std::cmp::Reverse(signal.timestamp));
+        signals
+    }
+
+    /// Drop per-market state for markets not sampled within
+    /// `IDLE_MARKET_STATE_TTL` of `now`, and warmup log marks too old to
+    /// suppress anything, so markets that stop trading don't pile up
+    pub fn prune_idle_markets(&mut self, now: Instant) {
+        self.sum_histories.retain(|_, history| {
+            history.last_sampled.is_some_and(|sampled| {
+                now.saturating_duration_since(sampled) < IDLE_MARKET_STATE_TTL
+            })
+        });
+        self.warmup_logged
+            .retain(|_, logged| now.saturating_duration_since(*logged) < WARMUP_LOG_INTERVAL);
+    }
+
+    /// Arbitrage in `market_books` as they stand (buying a set under $1, or
+    /// selling a held set over it), for callers that hold their own books and
+    /// drive detection themselves.
+    ///
+    /// Unlike `detect_arbitrage` it has no side effects: no detection count,
+    /// latency sample, rejection or near miss is recorded, and nothing is
+    /// logged above debug. Every book the market needs must already be in
+    /// `market_books`; without market metadata, legs are named YES/NO or
+    /// `Outcome_<i>`.
+    pub fn evaluate(
+        &self,
+        market_books: &MarketBooks,
+        risk_manager: &RiskManager,
+    ) -> Option<ArbitrageOpportunity> {
+        self.unrecorded()
+            .evaluate_books(&market_books.market_id, None, market_books, risk_manager)
+    }
+
+    /// This engine's settings and balance with recording off, for `evaluate`
+    fn unrecorded(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            detections: 0,
+            executions: 0,
+            latency_tracker: crate::utils::LatencyTracker::new(),
+            available_balance: self.available_balance,
+            warmup_logged: HashMap::new(),
+            near_misses: None,
+            rejections: self.rejections.clone(),
+            sum_histories: HashMap::new(),
+            recording: false,
+        }
+    }
+
+    fn evaluate_books(
+        &self,
+        market_id: &str,
+        market: Option<&Market>,
+        market_books: &MarketBooks,
+        risk_manager: &RiskManager,
+    ) -> Option<ArbitrageOpportunity> {
+        if let Some(asset_id) = market_books.crossed_asset_id() {
+            debug!(
+                "⏭️  Skipping {}: book for {} is crossed/locked",
+                market_id, asset_id
+            );
+            return None;
+        }
+
+        let best_asks = market_books.best_asks();
+
+        let buy_side = if best_asks.is_empty() {
+            None
+        } else if market_books.is_binary() {
+            self.detect_binary_arbitrage(market_id, market, market_books, &best_asks, risk_manager)
+        } else {
+            self.detect_multi_outcome_arbitrage(
+                market_id,
+                market,
+                market_books,
+                &best_asks,
+                risk_manager,
+            )
+        };
+
+        // Asks summing under $1 and bids over it can't both hold in uncrossed books
+        buy_side.or_else(|| {
+            self.detect_sell_side_arbitrage(market_id, market, market_books, risk_manager)
+        })
+    }
+
+    /// Bid-side arbitrage: the outcome bids sum above $1 plus fees, so selling a
+    /// complete set beats holding it to its $1 redemption value.
+    ///
+    /// For `size` sets: proceeds = size * sum(bids), redemption value = size * $1,
+    /// fee = size * 2%, net = proceeds - redemption value - fee. Only sets we already
+    /// hold (every leg in `RiskManager` positions) are sold; minting is not supported.
+    /// Edge `price`/`expected_cost` are the bid and the leg's sale proceeds.
+    fn detect_sell_side_arbitrage(
+        &self,
+        market_id: &str,
+        market: Option<&Market>,
+        market_books: &MarketBooks,
+        risk_manager: &RiskManager,
+    ) -> Option<ArbitrageOpportunity> {
+        let expected_legs = market.map_or(market_books.books.len(), |m| m.assets_ids.len());
+        let best_bids = market_books.best_bids();
+        if best_bids.len() < 2 || best_bids.len() != expected_legs {
+            return None;
+        }
+
+        if self.has_unexecutable_leg(&best_bids) {
+            return None;
+        }
+
+        let sum_bids: Decimal = best_bids.iter().map(|(_, price, _)| *price).sum();
+        let fee_rate = Decimal::from(2) / Decimal::ONE_HUNDRED;
+
+        if sum_bids <= Decimal::ONE + fee_rate {
+            return None;
+        }
+
+        // Sets we hold: the smallest position across every leg
+        let held_sets = best_bids
+            .iter()
+            .map(|(asset_id, _, _)| {
+                risk_manager
+                    .get_position(asset_id)
+                    .map_or(Decimal::ZERO, |p| p.size)
+            })
+            .min()
+            .unwrap_or(Decimal::ZERO);
+
+        if held_sets.is_zero() {
+            debug!("Sell-side arb on {} but we don't hold the set", market_id);
+            return None;
+        }
+
+        let min_liquidity = best_bids
+            .iter()
+            .map(|(_, _, size)| *size)
+            .min()
+            .unwrap_or(Decimal::ZERO);
+
+        if min_liquidity < self.config.trading.min_liquidity.into() {
+            self.reject(market_id, RejectReason::Liquidity);
+            return None;
+        }
+
+        let position_size = held_sets
+            .min(min_liquidity)
+            .min(self.config.trading.max_arb_size.into());
+
+        let proceeds = position_size * sum_bids;
+        let redemption_value = position_size * Decimal::ONE;
+        let fee_cost = position_size * fee_rate;
+        let net_profit = proceeds - redemption_value - fee_cost;
+        let total_edge = net_profit / position_size;
+
+        if total_edge < self.config.trading.min_edge {
+            self.reject(market_id, RejectReason::Edge);
+            return None;
+        }
+
+        if !self.meets_profit_floor(market_id, net_profit) {
+            return None;
+        }
+
+        if risk_manager.is_market_blacklisted(market_id)
+            || risk_manager.is_market_paused(market_id)
+            || risk_manager.is_market_in_cooldown(market_id)
+        {
+            self.reject(market_id, RejectReason::Blacklist);
+            return None;
+        }
+
+        let edges = best_bids
+            .iter()
+            .enumerate()
+            .map(|(i, (asset_id, price, _))| ArbEdge {
+                asset_id: asset_id.clone(),
+                outcome: outcome_name(market, asset_id, || format!("Outcome_{}", i)),
+                price: *price,
+                size: position_size,
+                expected_cost: position_size * *price,
+            })
+            .collect();
+
+        let arb_op = ArbitrageOpportunity {
+            market_id: market_id.to_string(),
+            arb_type: ArbType::SellCompleteSet,
+            edges,
+            total_edge,
+            min_liquidity,
+            position_size,
+            expected_profit_usd: net_profit + fee_cost,
+            fee_cost,
+            net_profit,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            detection_latency_ms: None,
+        };
+
+        debug!(
+            "Sell-side arbitrage on {}: bids sum {:.4}",
+            market_id, sum_bids
+        );
+
+        Some(arb_op)
+    }
+
+    /// RN1 strategy: Only execute high-quality opportunities
+    pub fn should_execute_opportunity(&self, arb_op: &dyn Opportunity) -> bool {
+        let scoring = &self.config.quality_scoring;
+        let quality_score = arb_op.quality_score(scoring);
+        let min_quality = scoring.min_quality_score;
 
         if quality_score < min_quality {
             debug!(
                 "⏭️  Skipping low-quality opportunity: {} (score: {:.2}/10, min: {:.2})",
-                arb_op.market_id, quality_score, min_quality
+                arb_op.market_id(),
+                quality_score,
+                min_quality
             );
             return false;
         }
 
         info!(
+            event = "arb_quality_pass",
+            market_id = %arb_op.market_id(),
+            quality_score = %quality_score,
             "✅ High-quality opportunity: {} (score: {:.2}/10)",
-            arb_op.market_id, quality_score
+            arb_op.market_id(), quality_score
         );
         true
     }
@@ -241,12 +828,13 @@ impl ArbEngine {
     fn detect_binary_arbitrage(
         &self,
         market_id: &str,
-        _market_books: &crate::orderbook::MarketBooks,
+        market: Option<&Market>,
+        _market_books: &MarketBooks,
         best_asks: &[(String, Decimal, Decimal)],
         risk_manager: &RiskManager,
-    ) -> Result<Option<ArbitrageOpportunity>> {
+    ) -> Option<ArbitrageOpportunity> {
         if best_asks.len() != 2 {
-            return Ok(None);
+            return None;
         }
 
         let (asset_yes, price_yes, size_yes) = &best_asks[0];
@@ -259,7 +847,22 @@ impl ArbEngine {
                 "Insufficient liquidity for {}: ${} < ${}",
                 market_id, min_liquidity, self.config.trading.min_liquidity
             );
-            return Ok(None);
+            self.reject(market_id, RejectReason::Liquidity);
+            self.near_miss(
+                market_id,
+                NearMissKind::Liquidity,
+                min_liquidity,
+                self.config.trading.min_liquidity.into(),
+            );
+            return None;
+        }
+
+        if self.has_unexecutable_leg(best_asks) {
+            debug!(
+                "Skipping {}: leg priced outside executable range (resolved?)",
+                market_id
+            );
+            return None;
         }
 
         let sum_prices = *price_yes + *price_no;
@@ -269,28 +872,25 @@ impl ArbEngine {
                 "No arbitrage for {}: sum = {:.4} >= 1.0",
                 market_id, sum_prices
             );
-            return Ok(None);
+            return None;
         }
 
         let raw_edge = Decimal::ONE - sum_prices;
         let fee_rate = Decimal::from(2) / Decimal::ONE_HUNDRED;
 
-        let max_position_by_edge = self.calculate_max_position(
+        let sizing = self.explain_sizing(
             raw_edge,
-            self.config.trading.min_edge,
-            self.config.trading.bankroll,
+            min_liquidity,
+            self.available_capital(risk_manager),
         );
-
-        let max_position_by_liquidity = min_liquidity;
-        let max_position_by_limit = self.config.trading.max_arb_size.into();
-
-        let position_size = max_position_by_edge
-            .min(max_position_by_liquidity)
-            .min(max_position_by_limit);
+        let position_size = sizing.size;
 
         if position_size < self.config.trading.min_liquidity.into() {
-            debug!("Position too small for {}: ${}", market_id, position_size);
-            return Ok(None);
+            debug!(
+                "Position too small for {}: ${} (bound by {:?})",
+                market_id, position_size, sizing.binding
+            );
+            return None;
         }
 
         let expected_cost = position_size * sum_prices;
@@ -300,7 +900,8 @@ impl ArbEngine {
 
         if net_profit <= Decimal::ZERO {
             debug!("No profit after fees for {}: ${}", market_id, net_profit);
-            return Ok(None);
+            self.reject(market_id, RejectReason::Edge);
+            return None;
         }
 
         let total_edge = net_profit / position_size;
@@ -312,12 +913,30 @@ impl ArbEngine {
                 total_edge * Decimal::ONE_HUNDRED,
                 self.config.trading.min_edge * Decimal::ONE_HUNDRED
             );
-            return Ok(None);
+            self.reject(market_id, RejectReason::Edge);
+            self.near_miss(
+                market_id,
+                NearMissKind::Edge,
+                total_edge,
+                self.config.trading.min_edge,
+            );
+            return None;
         }
 
-        if risk_manager.is_market_blacklisted(market_id) {
-            debug!("Market {} is blacklisted", market_id);
-            return Ok(None);
+        if !self.meets_profit_floor(market_id, net_profit) {
+            return None;
+        }
+
+        if risk_manager.is_market_blacklisted(market_id)
+            || risk_manager.is_market_paused(market_id)
+            || risk_manager.is_market_in_cooldown(market_id)
+        {
+            debug!(
+                "Market {} is blacklisted, paused or cooling down",
+                market_id
+            );
+            self.reject(market_id, RejectReason::Blacklist);
+            return None;
         }
 
         let arb_op = ArbitrageOpportunity {
@@ -326,14 +945,14 @@ impl ArbEngine {
             edges: vec![
                 ArbEdge {
                     asset_id: asset_yes.clone(),
-                    outcome: "YES".to_string(),
+                    outcome: outcome_name(market, asset_yes, || "YES".to_string()),
                     price: *price_yes,
                     size: position_size,
                     expected_cost: position_size * *price_yes,
                 },
                 ArbEdge {
                     asset_id: asset_no.clone(),
-                    outcome: "NO".to_string(),
+                    outcome: outcome_name(market, asset_no, || "NO".to_string()),
                     price: *price_no,
                     size: position_size,
                     expected_cost: position_size * *price_no,
@@ -345,32 +964,44 @@ impl ArbEngine {
             expected_profit_usd: net_profit + fee_cost,
             fee_cost,
             net_profit,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as i64,
+            timestamp: chrono::Utc::now().timestamp_millis(),
             detection_latency_ms: None,
         };
 
-        Ok(Some(arb_op))
+        Some(arb_op)
     }
 
     #[inline]
     fn detect_multi_outcome_arbitrage(
         &self,
         market_id: &str,
-        _market_books: &crate::orderbook::MarketBooks,
+        market: Option<&Market>,
+        _market_books: &MarketBooks,
         best_asks: &[(String, Decimal, Decimal)],
         risk_manager: &RiskManager,
-    ) -> Result<Option<ArbitrageOpportunity>> {
+    ) -> Option<ArbitrageOpportunity> {
         if best_asks.len() < 2 {
-            return Ok(None);
+            return None;
+        }
+
+        if !self.config.trading.within_outcome_limit(best_asks.len()) {
+            debug!(
+                "⏭️  Skipping {}: {} outcomes > max {}",
+                market_id,
+                best_asks.len(),
+                self.config.trading.max_outcomes_for_arb
+            );
+            return None;
+        }
+
+        if self.has_unexecutable_leg(best_asks) {
+            return None;
         }
 
         let sum_prices: Decimal = best_asks.iter().map(|(_, price, _)| *price).sum();
 
         if sum_prices >= Decimal::ONE {
-            return Ok(None);
+            return None;
         }
 
         let min_liquidity: Decimal = best_asks
@@ -380,7 +1011,14 @@ impl ArbEngine {
             .unwrap_or(Decimal::ZERO);
 
         if min_liquidity < self.config.trading.min_liquidity.into() {
-            return Ok(None);
+            self.reject(market_id, RejectReason::Liquidity);
+            self.near_miss(
+                market_id,
+                NearMissKind::Liquidity,
+                min_liquidity,
+                self.config.trading.min_liquidity.into(),
+            );
+            return None;
         }
 
         let raw_edge = Decimal::ONE - sum_prices;
@@ -389,7 +1027,8 @@ impl ArbEngine {
         let max_position_by_edge = self.calculate_max_position(
             raw_edge,
             self.config.trading.min_edge,
-            self.config.trading.bankroll,
+            self.available_capital(risk_manager),
+            sum_prices,
         );
 
         let max_position_by_liquidity = min_liquidity * Decimal::from(best_asks.len() as i64);
@@ -400,7 +1039,7 @@ impl ArbEngine {
             .min(max_position_by_limit);
 
         if position_size < self.config.trading.min_liquidity.into() {
-            return Ok(None);
+            return None;
         }
 
         let per_outcome_position = position_size / Decimal::from(best_asks.len() as i64);
@@ -411,17 +1050,33 @@ impl ArbEngine {
         let net_profit = expected_payout - expected_cost - fee_cost;
 
         if net_profit <= Decimal::ZERO {
-            return Ok(None);
+            self.reject(market_id, RejectReason::Edge);
+            return None;
         }
 
         let total_edge = net_profit / position_size;
 
         if total_edge < self.config.trading.min_edge {
-            return Ok(None);
+            self.reject(market_id, RejectReason::Edge);
+            self.near_miss(
+                market_id,
+                NearMissKind::Edge,
+                total_edge,
+                self.config.trading.min_edge,
+            );
+            return None;
         }
 
-        if risk_manager.is_market_blacklisted(market_id) {
-            return Ok(None);
+        if !self.meets_profit_floor(market_id, net_profit) {
+            return None;
+        }
+
+        if risk_manager.is_market_blacklisted(market_id)
+            || risk_manager.is_market_paused(market_id)
+            || risk_manager.is_market_in_cooldown(market_id)
+        {
+            self.reject(market_id, RejectReason::Blacklist);
+            return None;
         }
 
         let edges: Vec<ArbEdge> = best_asks
@@ -429,7 +1084,7 @@ impl ArbEngine {
             .enumerate()
             .map(|(i, (asset_id, price, _size))| ArbEdge {
                 asset_id: asset_id.clone(),
-                outcome: format!("Outcome_{}", i),
+                outcome: outcome_name(market, asset_id, || format!("Outcome_{}", i)),
                 price: *price,
                 size: per_outcome_position,
                 expected_cost: per_outcome_position * *price,
@@ -446,23 +1101,90 @@ impl ArbEngine {
             expected_profit_usd: net_profit + fee_cost,
             fee_cost,
             net_profit,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as i64,
+            timestamp: chrono::Utc::now().timestamp_millis(),
             detection_latency_ms: None,
         };
 
-        Ok(Some(arb_op))
+        Some(arb_op)
     }
 
+    /// Whether `net_profit` clears `min_net_profit_usd`; a high edge on a few
+    /// dollars of depth can still net less than gas and fees eat
     #[inline]
-    fn calculate_max_position(
+    fn meets_profit_floor(&self, market_id: &str, net_profit: Decimal) -> bool {
+        let min_profit = self.config.trading.min_net_profit_usd;
+        if net_profit < min_profit {
+            debug!(
+                "Profit too small for {}: ${:.2} < ${:.2}",
+                market_id, net_profit, min_profit
+            );
+            self.reject(market_id, RejectReason::Profit);
+            return false;
+        }
+        true
+    }
+
+    /// Whether any leg's ask is below `min_executable_price` or above its complement,
+    /// i.e. the book has snapped to a resolved outcome
+    #[inline]
+    fn has_unexecutable_leg(&self, best_asks: &[(String, Decimal, Decimal)]) -> bool {
+        let min_price = self.config.trading.min_executable_price;
+        let max_price = Decimal::ONE - min_price;
+
+        best_asks
+            .iter()
+            .any(|(_, price, _)| *price < min_price || *price > max_price)
+    }
+
+    /// How a binary arb with `raw_edge` (1 - sum of asks) and `min_liquidity` on
+    /// its thinnest leg would be sized against `bankroll` under the current
+    /// config: each cap detection applies, the size it picks, and which cap bound.
+    ///
+    /// Pure: nothing is recorded, so hypothetical opportunities can be reviewed
+    /// before trading live. Pass `available_capital` for the bankroll detection
+    /// would see right now.
+    pub fn explain_sizing(
         &self,
         raw_edge: Decimal,
-        min_edge: Decimal,
-        _bankroll: u64,
-    ) -> Decimal {
+        min_liquidity: Decimal,
+        bankroll: Decimal,
+    ) -> SizingExplanation {
+        let sum_prices = Decimal::ONE - raw_edge;
+        let by_edge = self.max_position_by_edge(raw_edge, self.config.trading.min_edge);
+        let by_bankroll = (sum_prices > Decimal::ZERO).then(|| bankroll / sum_prices);
+        let by_limit = Decimal::from(self.config.trading.max_arb_size);
+
+        let caps = [
+            (SizingCap::Edge, Some(by_edge)),
+            (SizingCap::Bankroll, by_bankroll),
+            (SizingCap::Liquidity, Some(min_liquidity)),
+            (SizingCap::Limit, Some(by_limit)),
+        ];
+        let (binding, size) = caps
+            .into_iter()
+            .filter_map(|(cap, value)| value.map(|value| (cap, value)))
+            .fold((SizingCap::Edge, by_edge), |best, (cap, value)| {
+                if value < best.1 {
+                    (cap, value)
+                } else {
+                    best
+                }
+            });
+
+        SizingExplanation {
+            raw_edge,
+            by_edge,
+            by_bankroll,
+            by_liquidity: min_liquidity,
+            by_limit,
+            size,
+            binding,
+        }
+    }
+
+    /// `max_arb_size` scaled by how far `raw_edge` clears `min_edge`, at most 2x
+    #[inline]
+    fn max_position_by_edge(&self, raw_edge: Decimal, min_edge: Decimal) -> Decimal {
         let edge_ratio = raw_edge / min_edge;
         let base_max = Decimal::from(self.config.trading.max_arb_size);
 
@@ -473,6 +1195,24 @@ impl ArbEngine {
         }
     }
 
+    /// Edge-scaled `max_arb_size`, capped so that buying the set at `sum_prices`
+    /// never costs more than `available_capital`
+    #[inline]
+    fn calculate_max_position(
+        &self,
+        raw_edge: Decimal,
+        min_edge: Decimal,
+        available_capital: Decimal,
+        sum_prices: Decimal,
+    ) -> Decimal {
+        let max_by_edge = self.max_position_by_edge(raw_edge, min_edge);
+
+        if sum_prices <= Decimal::ZERO {
+            return max_by_edge;
+        }
+        max_by_edge.min(available_capital / sum_prices)
+    }
+
     // =========================================================================
     // Short-Window Arbitrage Detection (gabagool-style)
     // =========================================================================
@@ -490,14 +1230,16 @@ impl ArbEngine {
 
         // Check if this is a short-window market
         let short_window_info = market.analyze_short_window(markets_config);
-        if !short_window_info.is_short_window {
+        if !short_window_info.is_short_window
+            || self.has_stale_book(orderbook_manager, &market.market)
+        {
             return Ok(None);
         }
 
         let minutes_to_expiry = short_window_info.minutes_to_expiry.unwrap_or(0);
 
         // Skip if too close to expiry (settlement risk)
-        if minutes_to_expiry < markets_config.min_minutes_to_expiry as i64 {
+        if minutes_to_expiry < saturating_i64(markets_config.min_minutes_to_expiry) {
             debug!(
                 "Short-window market too close to expiry: {} ({}min < {}min)",
                 market.question, minutes_to_expiry, markets_config.min_minutes_to_expiry
@@ -505,22 +1247,42 @@ impl ArbEngine {
             return Ok(None);
         }
 
-        // Get best asks for YES and NO
-        let best_asks = orderbook_manager
-            .get_best_asks_for_market(&market.market)
-            .context("Failed to get best asks for short-window market")?;
+        if let Some(asset_id) = orderbook_manager.crossed_asset_id(&market.market) {
+            debug!(
+                "⏭️  Skipping short-window {}: book for {} is crossed/locked",
+                market.question, asset_id
+            );
+            return Ok(None);
+        }
 
-        if best_asks.len() != 2 {
-            return Ok(None); // Must be binary market
+        // Top few ask levels for every outcome; the best of each prices the edge
+        let ladders = orderbook_manager
+            .get_ask_ladders_for_market(&market.market, SHORT_WINDOW_DEPTH_LEVELS)
+            .context("Failed to get asks for short-window market")?;
+        let best_asks: Vec<(String, Decimal, Decimal)> = ladders
+            .iter()
+            .map(|(asset_id, levels)| (asset_id.clone(), levels[0].0, levels[0].1))
+            .collect();
+
+        // Need an ask on every outcome, otherwise the set doesn't guarantee a payout
+        let expected_legs = market.assets_ids.len().max(2);
+        if best_asks.len() != expected_legs {
+            return Ok(None);
         }
 
-        let (yes_asset_id, yes_price, yes_size) = &best_asks[0];
-        let (no_asset_id, no_price, no_size) = &best_asks[1];
+        // A leg snapped to ~0/~1 means the market resolved; the "edge" can't be filled
+        if self.has_unexecutable_leg(&best_asks) {
+            debug!(
+                "Short-window market looks resolved: {} (leg outside executable range)",
+                market.question
+            );
+            return Ok(None);
+        }
 
         // Calculate sum and edge
-        let sum_prices = *yes_price + *no_price;
+        let sum_prices = best_asks.iter().map(|(_, price, _)| *price).sum::<Decimal>();
 
-        // The magic: if YES + NO < $1, we have guaranteed profit
+        // The magic: if the outcome asks sum < $1, we have guaranteed profit
         if sum_prices >= Decimal::ONE {
             debug!(
                 "No short-window arb: {} sum = {:.4} >= 1.0",
@@ -535,8 +1297,12 @@ impl ArbEngine {
         // Net edge after fees
         let net_edge = raw_edge - fee_rate;
 
-        // Use lower threshold for short-window markets
-        let min_edge = self.config.trading.short_window_min_edge;
+        // Lower threshold for short-window markets, rising with the time left for one
+        // side to run away before resolution
+        let min_edge = self
+            .config
+            .trading
+            .short_window_min_edge_for(minutes_to_expiry);
         if net_edge < min_edge {
             debug!(
                 "Short-window edge too small: {} {:.2}% < {:.2}%",
@@ -544,70 +1310,133 @@ impl ArbEngine {
                 net_edge * Decimal::ONE_HUNDRED,
                 min_edge * Decimal::ONE_HUNDRED
             );
+            self.reject(&market.market, RejectReason::Edge);
+            self.near_miss(&market.market, NearMissKind::Edge, net_edge, min_edge);
             return Ok(None);
         }
 
-        // Check liquidity
-        let min_liquidity = (*yes_size).min(*no_size);
+        // Check liquidity across the retained levels, not just the top
+        let min_liquidity = ladders
+            .iter()
+            .map(|(_, levels)| levels.iter().map(|(_, size)| *size).sum::<Decimal>())
+            .min()
+            .unwrap_or(Decimal::ZERO);
         if min_liquidity < Decimal::from(self.config.trading.min_liquidity) {
             debug!(
                 "Short-window liquidity too low: {} ${} < ${}",
                 market.question, min_liquidity, self.config.trading.min_liquidity
             );
+            self.reject(&market.market, RejectReason::Liquidity);
+            self.near_miss(
+                &market.market,
+                NearMissKind::Liquidity,
+                min_liquidity,
+                self.config.trading.min_liquidity.into(),
+            );
             return Ok(None);
         }
 
-        // Check blacklist
-        if risk_manager.is_market_blacklisted(&market.market) {
+        // Check blacklist, pause and cooldown
+        if risk_manager.is_market_blacklisted(&market.market)
+            || risk_manager.is_market_paused(&market.market)
+            || risk_manager.is_market_in_cooldown(&market.market)
+        {
+            self.reject(&market.market, RejectReason::Blacklist);
             return Ok(None);
         }
 
-        // Calculate position size (conservative for short-window)
+        // Size into the book only as far as the depth-weighted edge holds up
         let max_size = Decimal::from(self.config.trading.short_window_max_size);
-        let position_size = min_liquidity.min(max_size);
+        let depth: Vec<AskLadder> = ladders.iter().map(|(_, levels)| levels.clone()).collect();
+        let Some((mut position_size, mut vwap_sum)) =
+            size_to_vwap_edge(&depth, max_size, fee_rate, min_edge)
+        else {
+            return Ok(None);
+        };
 
-        // Expected profit = position * net_edge
-        let expected_profit = position_size * net_edge;
+        // Capital buys fewer sets at the VWAP than at the top of book
+        let capital = self.available_capital(risk_manager);
+        if position_size * vwap_sum > capital {
+            let affordable = (capital / vwap_sum)
+                .round_dp_with_strategy(2, rust_decimal::RoundingStrategy::ToZero);
+            match size_to_vwap_edge(&depth, affordable, fee_rate, min_edge) {
+                Some(sized) => (position_size, vwap_sum) = sized,
+                None => {
+                    debug!("No capital left for short-window {}", market.question);
+                    return Ok(None);
+                }
+            }
+        }
 
-        // Calculate annualized return for comparison
-        // If 15min resolution with 2% edge = (2% * 4 * 24 * 365) = 70,080% annualized!
-        let cycles_per_year =
-            Decimal::from(365 * 24 * 60) / Decimal::from(minutes_to_expiry.max(1));
-        let annualized_return = net_edge * cycles_per_year;
+        let vwap_net_edge = Decimal::ONE - vwap_sum - fee_rate;
+        let expected_profit = position_size * vwap_net_edge;
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as i64;
+        if !self.meets_profit_floor(&market.market, expected_profit) {
+            return Ok(None);
+        }
+
+        // Annualized return for comparison, haircut by fill probability and capital
+        // utilization (naive 2% edge * every 15min cycle would claim 70,080%)
+        let fill_probability = self.config.trading.short_window_fill_probability;
+        let capital_utilization = self.config.trading.short_window_capital_utilization;
+        let max_annualized_return = self.config.trading.short_window_max_annualized_return;
+        let annualized_return = estimate_annualized_return(
+            net_edge,
+            minutes_to_expiry,
+            fill_probability,
+            capital_utilization,
+            max_annualized_return,
+        );
+
+        let now = chrono::Utc::now().timestamp_millis();
 
         let opportunity = ShortWindowArbOpportunity {
             market_id: market.market.clone(),
             market_question: market.question.clone(),
             minutes_to_expiry,
-            yes_price: *yes_price,
-            no_price: *no_price,
+            legs: ladders
+                .into_iter()
+                .enumerate()
+                .map(|(i, (asset_id, depth))| ShortWindowLeg {
+                    outcome: outcome_name(Some(market), &asset_id, || format!("Outcome_{}", i)),
+                    asset_id,
+                    price: depth[0].0,
+                    size: depth[0].1,
+                    depth,
+                })
+                .collect(),
             sum_prices,
+            vwap_sum,
             raw_edge,
             net_edge,
+            vwap_net_edge,
             position_size,
             expected_profit,
-            yes_asset_id: yes_asset_id.clone(),
-            no_asset_id: no_asset_id.clone(),
             min_liquidity,
             detected_at: now,
             annualized_return,
+            assumed_fill_probability: fill_probability,
+            assumed_capital_utilization: capital_utilization,
         };
 
         self.detections += 1;
-        info!("🎯 SHORT-WINDOW ARB #{}: {}", self.detections, opportunity);
+        info!(
+            event = "short_window_arb_detected",
+            market_id = %opportunity.market_id,
+            edge = %net_edge,
+            profit = %opportunity.expected_profit,
+            minutes_to_expiry = minutes_to_expiry,
+            "🎯 SHORT-WINDOW ARB #{}: {}",
+            self.detections,
+            opportunity
+        );
 
-        // Log the annualized return for perspective
-        if annualized_return > dec!(1000) {
-            warn!(
-                "🚀 MASSIVE annualized return: {:.0}% ({:.2}% edge × {} cycles/year)",
-                annualized_return,
-                net_edge * Decimal::ONE_HUNDRED,
-                cycles_per_year
+        if annualized_return >= max_annualized_return {
+            debug!(
+                "Annualized return capped at {:.0}% ({:.0}% fill, {:.0}% utilization)",
+                annualized_return * Decimal::ONE_HUNDRED,
+                fill_probability * Decimal::ONE_HUNDRED,
+                capital_utilization * Decimal::ONE_HUNDRED
             );
         }
 
@@ -636,7 +1465,7 @@ impl ArbEngine {
         }
 
         // Sort by expected profit (highest first)
-        opportunities.sort_by(|a, b| b.expected_profit.cmp(&a.expected_profit));
+        opportunities.sort_by_key(|o| std::cmp::Reverse(o.expected_profit));
 
         if !opportunities.is_empty() {
             info!(
@@ -652,7 +1481,8 @@ impl ArbEngine {
         opportunities
     }
 
-    pub fn get_statistics(&self) -> (u64, u64, f64) {
+    /// Detections, executions, execution rate, and rejections summed over markets
+    pub fn get_statistics(&self) -> (u64, u64, f64, RejectionCounts) {
         (
             self.detections,
             self.executions,
@@ -661,9 +1491,16 @@ impl ArbEngine {
             } else {
                 0.0
             },
+            self.rejections.totals(),
         )
     }
 
+    /// Rejections for `market_id` by reason
+    #[inline]
+    pub fn rejection_counts(&self, market_id: &str) -> RejectionCounts {
+        self.rejections.for_market(market_id)
+    }
+
     pub fn get_latency_stats(&self) -> (f64, u64, u64) {
         (
             self.latency_tracker.avg_latency_ms(),
@@ -693,17 +1530,15 @@ pub struct SimulatedShortWindowTrade {
     pub expected_resolution_time: i64,
     /// Minutes until resolution at entry
     pub minutes_to_expiry: i64,
-    /// YES price at entry
-    pub yes_price: Decimal,
-    /// NO price at entry
-    pub no_price: Decimal,
-    /// Sum of YES + NO at entry
+    /// Ask price of each leg at entry
+    pub leg_prices: Vec<Decimal>,
+    /// Sum of all leg prices at entry
     pub sum_prices: Decimal,
-    /// Position size (same for YES and NO)
+    /// Position size (same for every leg)
     pub position_size: Decimal,
-    /// Total cost to enter (position * sum_prices)
+    /// Total cost to enter (position * VWAP sum)
     pub entry_cost: Decimal,
-    /// Expected profit if one side resolves to $1
+    /// Expected profit once one leg resolves to $1
     pub expected_profit: Decimal,
     /// Trade status
     pub status: SimulatedTradeStatus,
@@ -725,6 +1560,22 @@ pub enum SimulatedTradeStatus {
     Expired,
 }
 
+/// What `ShortWindowArbTracker::save_state` writes; the derived stats are
+/// recomputed on load
+#[derive(Debug, Serialize, Deserialize)]
+struct TrackerState {
+    /// Unix seconds when the state was saved
+    saved_at: i64,
+    trades: Vec<SimulatedShortWindowTrade>,
+    total_pnl: Decimal,
+    trades_entered: u64,
+    trades_won: u64,
+    trades_lost: u64,
+    total_capital_deployed: Decimal,
+    simulated_balance: Decimal,
+    initial_balance: Decimal,
+}
+
 /// Tracker for simulated short-window arb trades
 #[derive(Debug, Clone)]
 pub struct ShortWindowArbTracker {
@@ -748,10 +1599,19 @@ pub struct ShortWindowArbTracker {
     simulated_balance: Decimal,
     /// Initial balance
     initial_balance: Decimal,
+    /// Clock and trade ID source
+    sim_env: SimEnv,
+    /// Chance an expired trade resolves as picked off rather than won
+    adversarial_fill_probability: f64,
 }
 
 impl ShortWindowArbTracker {
     pub fn new(initial_balance: Decimal) -> Self {
+        Self::with_sim_env(initial_balance, SimEnv::real())
+    }
+
+    /// Tracker whose trade IDs and entry/resolution times come from `sim_env`
+    pub fn with_sim_env(initial_balance: Decimal, sim_env: SimEnv) -> Self {
         info!(
             "📊 Short-Window Arb Tracker initialized with ${} balance",
             initial_balance
@@ -767,18 +1627,49 @@ impl ShortWindowArbTracker {
             avg_profit: Decimal::ZERO,
             simulated_balance: initial_balance,
             initial_balance,
+            sim_env,
+            adversarial_fill_probability: 0.0,
         }
     }
 
+    /// Resolve each expired trade as picked off with `probability` instead of
+    /// assuming it won, for a conservative P&L
+    pub fn with_adversarial_fills(mut self, probability: f64) -> Self {
+        self.adversarial_fill_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Clock trades are entered and resolved on
+    pub fn clock(&self) -> &SimClock {
+        &self.sim_env.clock
+    }
+
+    /// Whether an open trade already exists for this market
+    pub fn has_open_trade(&self, market_id: &str) -> bool {
+        self.trades
+            .iter()
+            .any(|t| t.market_id == market_id && t.status == SimulatedTradeStatus::Open)
+    }
+
     /// Simulate entering a short-window arb trade
-    pub fn simulate_entry(&mut self, opp: &ShortWindowArbOpportunity) -> SimulatedShortWindowTrade {
-        let trade_id = format!("SIM_SW_{}", uuid::Uuid::new_v4());
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+    /// Returns None if the market already has an open trade, so a mispricing that
+    /// persists across scan ticks is only entered once
+    pub fn simulate_entry(
+        &mut self,
+        opp: &ShortWindowArbOpportunity,
+    ) -> Option<SimulatedShortWindowTrade> {
+        if self.has_open_trade(&opp.market_id) {
+            debug!(
+                "⏭️ [SIM] Skipping {}: trade already open",
+                opp.market_question.chars().take(30).collect::<String>()
+            );
+            return None;
+        }
 
-        let entry_cost = opp.position_size * opp.sum_prices;
+        let trade_id = self.sim_env.ids.next_id("SIM_SW");
+        let now = self.sim_env.clock.now_secs();
+
+        let entry_cost = opp.position_size * opp.vwap_sum;
 
         // Deduct from simulated balance
         self.simulated_balance -= entry_cost;
@@ -790,10 +1681,10 @@ impl ShortWindowArbTracker {
             market_id: opp.market_id.clone(),
             market_question: opp.market_question.clone(),
             entry_time: now,
-            expected_resolution_time: now + (opp.minutes_to_expiry * 60),
+            expected_resolution_time: now
+                .saturating_add(opp.minutes_to_expiry.max(0).saturating_mul(60)),
             minutes_to_expiry: opp.minutes_to_expiry,
-            yes_price: opp.yes_price,
-            no_price: opp.no_price,
+            leg_prices: opp.legs.iter().map(|leg| leg.price).collect(),
             sum_prices: opp.sum_prices,
             position_size: opp.position_size,
             entry_cost,
@@ -804,6 +1695,10 @@ impl ShortWindowArbTracker {
         };
 
         info!(
+            event = "short_window_sim_entry",
+            market_id = %opp.market_id,
+            cost = %entry_cost,
+            profit = %opp.expected_profit,
             "🎮 [SIM] Entered short-window arb: {} | Cost: ${:.2} | Expected: ${:.2} profit | Balance: ${:.2}",
             opp.market_question.chars().take(30).collect::<String>(),
             entry_cost,
@@ -812,17 +1707,14 @@ impl ShortWindowArbTracker {
         );
 
         self.trades.push(trade.clone());
-        trade
+        Some(trade)
     }
 
     /// Simulate resolution of a trade (called when market resolves)
     /// In theory, one side always pays $1, so we always profit if sum < $1
     pub fn simulate_resolution(&mut self, trade_id: &str, won: bool) {
         if let Some(trade) = self.trades.iter_mut().find(|t| t.id == trade_id) {
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as i64;
+            let now = self.sim_env.clock.now_secs();
 
             trade.resolution_time = Some(now);
 
@@ -839,6 +1731,10 @@ impl ShortWindowArbTracker {
                 self.trades_won += 1;
 
                 info!(
+                    event = "short_window_sim_resolved",
+                    market_id = %trade.market_id,
+                    won = true,
+                    profit = %profit,
                     "✅ [SIM] Trade WON: {} | Profit: ${:.2} | Total P&L: ${:.2}",
                     trade.market_question.chars().take(30).collect::<String>(),
                     profit,
@@ -853,6 +1749,10 @@ impl ShortWindowArbTracker {
                 self.trades_lost += 1;
 
                 warn!(
+                    event = "short_window_sim_resolved",
+                    market_id = %trade.market_id,
+                    won = false,
+                    profit = %(-trade.entry_cost),
                     "❌ [SIM] Trade LOST: {} | Loss: ${:.2} | Total P&L: ${:.2}",
                     trade.market_question.chars().take(30).collect::<String>(),
                     trade.entry_cost,
@@ -864,13 +1764,55 @@ impl ShortWindowArbTracker {
         }
     }
 
+    /// Resolve a trade that was picked off near expiry: only its priciest leg
+    /// filled and that side lost, so the leg's cost is gone and the unfilled
+    /// legs' share of the entry cost comes back
+    fn simulate_pickoff(&mut self, trade_id: &str) {
+        if let Some(trade) = self.trades.iter_mut().find(|t| t.id == trade_id) {
+            let leg_price = trade
+                .leg_prices
+                .iter()
+                .copied()
+                .max()
+                .unwrap_or(Decimal::ZERO);
+            let loss = (leg_price * trade.position_size).min(trade.entry_cost);
+
+            trade.resolution_time = Some(self.sim_env.clock.now_secs());
+            trade.status = SimulatedTradeStatus::ResolvedLoss;
+            trade.actual_profit = Some(-loss);
+
+            self.simulated_balance += trade.entry_cost - loss;
+            self.total_pnl -= loss;
+            self.trades_lost += 1;
+
+            warn!(
+                event = "short_window_sim_resolved",
+                market_id = %trade.market_id,
+                won = false,
+                picked_off = true,
+                profit = %(-loss),
+                "🦈 [SIM] Trade PICKED OFF: {} | Loss: ${:.2} | Total P&L: ${:.2}",
+                trade.market_question.chars().take(30).collect::<String>(),
+                loss,
+                self.total_pnl
+            );
+
+            self.update_stats();
+        }
+    }
+
+    /// Whether the next expired trade resolves as picked off
+    fn picked_off(&self) -> bool {
+        self.adversarial_fill_probability > 0.0
+            && self.sim_env.rng.next_f64() < self.adversarial_fill_probability
+    }
+
     /// Auto-resolve open trades that have passed their expected resolution time
-    /// Assumes they won (since Sum-<$1 arb should always win)
-    pub fn auto_resolve_expired(&mut self) {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+    /// Assumes they won (since Sum-<$1 arb should always win), unless adversarial
+    /// fills pick some off
+    /// Returns the trades resolved by this call
+    pub fn auto_resolve_expired(&mut self) -> Vec<SimulatedShortWindowTrade> {
+        let now = self.sim_env.clock.now_secs();
 
         let expired_ids: Vec<String> = self
             .trades
@@ -879,10 +1821,20 @@ impl ShortWindowArbTracker {
             .map(|t| t.id.clone())
             .collect();
 
-        for trade_id in expired_ids {
-            // Assume win for Sum-<$1 arb (one side always pays $1)
-            self.simulate_resolution(&trade_id, true);
+        for trade_id in &expired_ids {
+            if self.picked_off() {
+                self.simulate_pickoff(trade_id);
+            } else {
+                // Assume win for Sum-<$1 arb (one side always pays $1)
+                self.simulate_resolution(trade_id, true);
+            }
         }
+
+        self.trades
+            .iter()
+            .filter(|t| expired_ids.contains(&t.id))
+            .cloned()
+            .collect()
     }
 
     fn update_stats(&mut self) {
@@ -923,6 +1875,19 @@ impl ShortWindowArbTracker {
         &self.trades
     }
 
+    /// Capital each trade committed and when it came back, for measuring turnover
+    pub fn capital_uses(&self) -> Vec<CapitalUse> {
+        self.trades
+            .iter()
+            .map(|t| CapitalUse {
+                amount: t.entry_cost,
+                entered_at: t.entry_time,
+                exited_at: t.resolution_time,
+                profit: t.actual_profit.unwrap_or(Decimal::ZERO),
+            })
+            .collect()
+    }
+
     /// Get open trades only
     pub fn get_open_trades(&self) -> Vec<&SimulatedShortWindowTrade> {
         self.trades
@@ -930,6 +1895,86 @@ impl ShortWindowArbTracker {
             .filter(|t| t.status == SimulatedTradeStatus::Open)
             .collect()
     }
+
+    /// Write trades and running totals to `path` as JSON, for `load_state` to
+    /// pick up after a restart
+    pub fn save_state(&self, path: &Path) -> Result<()> {
+        let state = TrackerState {
+            saved_at: self.sim_env.clock.now_secs(),
+            trades: self.trades.clone(),
+            total_pnl: self.total_pnl,
+            trades_entered: self.trades_entered,
+            trades_won: self.trades_won,
+            trades_lost: self.trades_lost,
+            total_capital_deployed: self.total_capital_deployed,
+            simulated_balance: self.simulated_balance,
+            initial_balance: self.initial_balance,
+        };
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        // Write then rename so a crash mid-write never leaves a truncated state file
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(&state)?)
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        info!(
+            "💾 Saved {} short-window trades ({} open) to {}",
+            self.trades.len(),
+            self.get_open_trades().len(),
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// Replace this tracker's trades and totals with those saved at `path`, so
+    /// open trades keep heading for their resolution time. State saved more than
+    /// `max_age_secs` ago belongs to an earlier session and is refused.
+    pub fn load_state(&mut self, path: &Path, max_age_secs: u64) -> Result<()> {
+        let json = std::fs::read(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                HftpmError::NotFound(format!("Short-window state {}", path.display()))
+            }
+            _ => HftpmError::Io(format!("Failed to read {}: {}", path.display(), e)),
+        })?;
+        let state: TrackerState = serde_json::from_slice(&json)
+            .map_err(|e| HftpmError::Parse(format!("Failed to parse {}: {}", path.display(), e)))?;
+
+        let age_secs = self.sim_env.clock.now_secs().saturating_sub(state.saved_at);
+        if age_secs > saturating_i64(max_age_secs) {
+            return Err(HftpmError::Stale(format!(
+                "Short-window state {} is {}s old (max {}s)",
+                path.display(),
+                age_secs,
+                max_age_secs
+            ))
+            .into());
+        }
+
+        self.trades = state.trades;
+        self.total_pnl = state.total_pnl;
+        self.trades_entered = state.trades_entered;
+        self.trades_won = state.trades_won;
+        self.trades_lost = state.trades_lost;
+        self.total_capital_deployed = state.total_capital_deployed;
+        self.simulated_balance = state.simulated_balance;
+        self.initial_balance = state.initial_balance;
+        self.update_stats();
+
+        info!(
+            "♻️  Restored {} short-window trades ({} open) from {} ({}s old)",
+            self.trades.len(),
+            self.get_open_trades().len(),
+            path.display(),
+            age_secs
+        );
+        Ok(())
+    }
 }
 
 /// Statistics for short-window arb tracking
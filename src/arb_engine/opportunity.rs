@@ -0,0 +1,305 @@
+use super::{ask_limit_for_size, ArbType, ArbitrageOpportunity, ShortWindowArbOpportunity};
+use crate::parallel_scanner::{CrossMarketOpportunity, MultiOutcomeOpportunity};
+use crate::utils::QualityScoringConfig;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Which way an opportunity's legs trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArbSide {
+    /// Buy a complete set below $1
+    Buy,
+    /// Sell a held complete set above $1
+    Sell,
+}
+
+/// One order an opportunity needs filled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpportunityLeg {
+    pub market_id: String,
+    pub asset_id: String,
+    pub outcome: String,
+    pub side: ArbSide,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+impl OpportunityLeg {
+    /// Cash the leg moves: paid for buys, negative (received) for sells
+    #[inline]
+    pub fn signed_cost(&self) -> Decimal {
+        match self.side {
+            ArbSide::Buy => self.price * self.size,
+            ArbSide::Sell => -(self.price * self.size),
+        }
+    }
+}
+
+/// What the execution, risk and monitoring layers need from any kind of
+/// opportunity, so each new strategy plugs into one pipeline instead of its own
+/// execute/record plumbing.
+pub trait Opportunity: std::fmt::Debug + std::fmt::Display + Send + Sync {
+    fn arb_type(&self) -> ArbType;
+
+    /// Primary market, the one cooldowns, exposure and logs are keyed by
+    fn market_id(&self) -> &str;
+
+    /// Every market the legs trade in
+    fn market_ids(&self) -> Vec<&str> {
+        vec![self.market_id()]
+    }
+
+    /// Orders to place; empty when the opportunity can't be executed as-is
+    fn legs(&self) -> Vec<OpportunityLeg>;
+
+    /// Complete sets traded
+    fn position_size(&self) -> Decimal;
+
+    /// Profit net of fees if every leg fills
+    fn expected_profit(&self) -> Decimal;
+
+    fn fee_cost(&self) -> Decimal;
+
+    /// Net edge per set
+    fn edge(&self) -> Decimal;
+
+    /// Thinnest leg's available size
+    fn min_liquidity(&self) -> Decimal;
+
+    /// Unix ms when the opportunity was detected, if the detector stamps it
+    fn detected_at_ms(&self) -> Option<i64> {
+        None
+    }
+
+    #[inline]
+    fn side(&self) -> ArbSide {
+        self.arb_type().side()
+    }
+
+    /// Score 0-10 (higher = better) from edge, liquidity, size and profit
+    fn quality_score(&self, scoring: &QualityScoringConfig) -> Decimal {
+        let max_component = Decimal::TEN;
+
+        // 1. Edge quality (default weight: 40%)
+        let edge_score = (self.edge() * scoring.edge_multiplier).min(max_component);
+
+        // 2. Liquidity depth (default weight: 30%)
+        let liquidity_score = (self.min_liquidity() / scoring.liquidity_divisor).min(max_component);
+
+        // 3. Position size (default weight: 20%)
+        let size_score = (self.position_size() / scoring.size_divisor).min(max_component);
+
+        // 4. Expected profit (default weight: 10%)
+        let profit_score = (self.expected_profit() / scoring.profit_divisor).min(max_component);
+
+        // Weighted score
+        (edge_score * scoring.edge_weight)
+            + (liquidity_score * scoring.liquidity_weight)
+            + (size_score * scoring.size_weight)
+            + (profit_score * scoring.profit_weight)
+    }
+
+    /// P&L of an execution whose legs moved `cost` in cash (see
+    /// `OpportunityLeg::signed_cost`). With every leg filled, `position_size`
+    /// complete sets were bought (paying out $1 each) or sold (giving up $1
+    /// each), less fees; legs traded without their complement count at cost only.
+    fn realized_pnl(&self, cost: Decimal, all_legs_filled: bool) -> Decimal {
+        if !all_legs_filled {
+            return -cost;
+        }
+
+        match self.side() {
+            ArbSide::Buy => self.position_size() - cost - self.fee_cost(),
+            ArbSide::Sell => -cost - self.position_size() - self.fee_cost(),
+        }
+    }
+}
+
+impl Opportunity for ArbitrageOpportunity {
+    fn arb_type(&self) -> ArbType {
+        self.arb_type.clone()
+    }
+
+    fn market_id(&self) -> &str {
+        &self.market_id
+    }
+
+    fn legs(&self) -> Vec<OpportunityLeg> {
+        let side = self.arb_type.side();
+        self.edges
+            .iter()
+            .map(|edge| OpportunityLeg {
+                market_id: self.market_id.clone(),
+                asset_id: edge.asset_id.clone(),
+                outcome: edge.outcome.clone(),
+                side,
+                price: edge.price,
+                size: edge.size,
+            })
+            .collect()
+    }
+
+    fn position_size(&self) -> Decimal {
+        self.position_size
+    }
+
+    fn expected_profit(&self) -> Decimal {
+        self.net_profit
+    }
+
+    fn fee_cost(&self) -> Decimal {
+        self.fee_cost
+    }
+
+    fn edge(&self) -> Decimal {
+        self.total_edge
+    }
+
+    fn min_liquidity(&self) -> Decimal {
+        self.min_liquidity
+    }
+
+    fn detected_at_ms(&self) -> Option<i64> {
+        Some(self.timestamp)
+    }
+}
+
+impl Opportunity for ShortWindowArbOpportunity {
+    fn arb_type(&self) -> ArbType {
+        ArbType::ShortWindow
+    }
+
+    fn market_id(&self) -> &str {
+        &self.market_id
+    }
+
+    /// Sized past the top of book, so each leg's limit is the deepest ask
+    /// level its size reaches rather than the best ask
+    fn legs(&self) -> Vec<OpportunityLeg> {
+        self.legs
+            .iter()
+            .map(|leg| OpportunityLeg {
+                market_id: self.market_id.clone(),
+                asset_id: leg.asset_id.clone(),
+                outcome: leg.outcome.clone(),
+                side: ArbSide::Buy,
+                price: ask_limit_for_size(&leg.depth, self.position_size).unwrap_or(leg.price),
+                size: self.position_size,
+            })
+            .collect()
+    }
+
+    fn position_size(&self) -> Decimal {
+        self.position_size
+    }
+
+    fn expected_profit(&self) -> Decimal {
+        self.expected_profit
+    }
+
+    /// Fees are whatever separates the raw VWAP edge from the net one
+    fn fee_cost(&self) -> Decimal {
+        self.position_size * (Decimal::ONE - self.vwap_sum - self.vwap_net_edge)
+    }
+
+    fn edge(&self) -> Decimal {
+        self.vwap_net_edge
+    }
+
+    fn min_liquidity(&self) -> Decimal {
+        self.min_liquidity
+    }
+
+    fn detected_at_ms(&self) -> Option<i64> {
+        Some(self.detected_at)
+    }
+}
+
+impl Opportunity for MultiOutcomeOpportunity {
+    fn arb_type(&self) -> ArbType {
+        ArbType::MultiOutcome
+    }
+
+    fn market_id(&self) -> &str {
+        &self.market_id
+    }
+
+    fn legs(&self) -> Vec<OpportunityLeg> {
+        self.outcomes
+            .iter()
+            .map(|outcome| OpportunityLeg {
+                market_id: self.market_id.clone(),
+                asset_id: outcome.asset_id.clone(),
+                outcome: outcome.name.clone(),
+                side: ArbSide::Buy,
+                price: outcome.ask_price,
+                size: self.position_size,
+            })
+            .collect()
+    }
+
+    fn position_size(&self) -> Decimal {
+        self.position_size
+    }
+
+    fn expected_profit(&self) -> Decimal {
+        self.expected_profit
+    }
+
+    /// The scanner prices fees into `edge` (sum against $0.98)
+    fn fee_cost(&self) -> Decimal {
+        self.position_size - self.position_size * self.total_price - self.expected_profit
+    }
+
+    fn edge(&self) -> Decimal {
+        self.edge
+    }
+
+    fn min_liquidity(&self) -> Decimal {
+        self.min_liquidity
+    }
+}
+
+impl Opportunity for CrossMarketOpportunity {
+    fn arb_type(&self) -> ArbType {
+        ArbType::CrossMarket
+    }
+
+    fn market_id(&self) -> &str {
+        &self.market_a_id
+    }
+
+    fn market_ids(&self) -> Vec<&str> {
+        vec![&self.market_a_id, &self.market_b_id]
+    }
+
+    fn legs(&self) -> Vec<OpportunityLeg> {
+        self.legs.clone()
+    }
+
+    fn position_size(&self) -> Decimal {
+        self.position_size
+    }
+
+    fn expected_profit(&self) -> Decimal {
+        self.expected_profit
+    }
+
+    fn fee_cost(&self) -> Decimal {
+        self.position_size * self.edge - self.expected_profit
+    }
+
+    fn edge(&self) -> Decimal {
+        self.expected_profit
+            .checked_div(self.position_size)
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    fn min_liquidity(&self) -> Decimal {
+        self.min_liquidity
+    }
+
+    fn detected_at_ms(&self) -> Option<i64> {
+        Some(self.detected_at)
+    }
+}
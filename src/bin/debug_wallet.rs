@@ -7,7 +7,6 @@ use hfptm::utils::Config;
 use polymarket_client_sdk::auth::{state::Authenticated, Normal};
 use polymarket_client_sdk::clob::{Client, Config as ClobConfig};
 use alloy::signers::{local::PrivateKeySigner, Signer};
-use tracing::info;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -25,6 +25,7 @@ use tracing::{info, warn, error};
 
 /// Latency measurement results
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
 struct LatencyMeasurement {
     test_name: String,
     order_type: String,
@@ -130,7 +131,7 @@ async fn main() -> Result<()> {
 
     // Fetch a short-window market to test on
     info!("🔍 Finding a short-window market for testing...");
-    let gamma_client = GammaClient::new(&config.server.gamma_url);
+    let gamma_client = GammaClient::new(&config.server.gamma_url, &config.execution)?;
     let short_window_markets = gamma_client.fetch_short_window_markets(&config.markets).await?;
     
     if short_window_markets.is_empty() {
@@ -345,7 +346,7 @@ async fn test_order_latency(
         .size(size)
         .price(price)
         .side(Side::Buy)
-        .order_type(order_type.clone())
+        .order_type(order_type)
         .build()
         .await
     {
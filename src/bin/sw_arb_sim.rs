@@ -7,52 +7,33 @@
 //!   cargo run --release --bin sw_arb_sim
 //!   # Or after building:
 //!   ./target/release/sw_arb_sim
+//!   ./target/release/sw_arb_sim --export-csv logs/sw_trades.csv
 //!
 //! Output:
 //!   - Real-time opportunity detection
 //!   - Simulated trade execution
 //!   - P&L tracking with periodic stats
-//!   - JSON export of all trades on exit
+//!   - JSON export of all trades on exit (plus CSV with --export-csv)
+//!   - Tracker state saved on exit and restored on the next start
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::{DateTime, Utc};
 use hfptm::{
-    arb_engine::ShortWindowArbTracker,
-    gamma_api::{GammaClient, Market},
-    utils::Config,
+    arb_engine::{
+        size_to_vwap_edge, ShortWindowArbTracker, ShortWindowLeg, SHORT_WINDOW_DEPTH_LEVELS,
+    },
+    gamma_api::GammaClient,
+    orderbook::OrderBookManager,
+    utils::{build_http_client, Config},
+    HftpmError,
 };
-use reqwest::Client;
 use rust_decimal::Decimal;
-use serde::Deserialize;
-use std::collections::HashMap;
 use std::fs;
-use std::str::FromStr;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
-/// Order book response from CLOB API
-#[derive(Debug, Deserialize)]
-struct BookResponse {
-    market: Option<String>,
-    asset_id: Option<String>,
-    bids: Option<Vec<PriceLevel>>,
-    asks: Option<Vec<PriceLevel>>,
-}
-
-#[derive(Debug, Deserialize)]
-struct PriceLevel {
-    price: String,
-    size: String,
-}
-
-/// Simplified order book data
-#[derive(Debug, Clone)]
-struct SimpleOrderBook {
-    best_ask_price: Decimal,
-    best_ask_size: Decimal,
-}
-
 /// Short-window arb opportunity (simplified)
 #[derive(Debug, Clone)]
 struct SwArbOpportunity {
@@ -62,7 +43,9 @@ struct SwArbOpportunity {
     yes_price: Decimal,
     no_price: Decimal,
     sum_prices: Decimal,
+    vwap_sum: Decimal,
     net_edge: Decimal,
+    vwap_net_edge: Decimal,
     position_size: Decimal,
     expected_profit: Decimal,
     yes_asset_id: String,
@@ -78,6 +61,8 @@ async fn main() -> Result<()> {
     println!("╚═══════════════════════════════════════════════════════════════╝");
     println!();
 
+    let export_csv_path = hfptm::utils::export::export_csv_arg(std::env::args().skip(1));
+
     // Load config
     let config = Config::load()?;
 
@@ -103,15 +88,27 @@ async fn main() -> Result<()> {
     println!();
 
     // HTTP client for CLOB API
-    let http_client = Arc::new(
-        Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()?,
-    );
+    let http_client = Arc::new(build_http_client(&config.execution)?);
 
     // Initialize components  
-    let gamma_client = GammaClient::new(&config.server.gamma_url);
-    let mut tracker = ShortWindowArbTracker::new(Decimal::from(config.trading.bankroll));
+    let gamma_client = GammaClient::new(&config.server.gamma_url, &config.execution)?;
+    let orderbook_manager = OrderBookManager::new(&config)?;
+    let mut tracker = ShortWindowArbTracker::new(Decimal::from(config.trading.bankroll))
+        .with_adversarial_fills(config.simulation.adversarial_fill_probability);
+
+    // Pick up the previous run's trades so open ones still resolve
+    let state_path = Path::new(&config.simulation.short_window_state_path);
+    let persist_state = !config.simulation.short_window_state_path.is_empty();
+    if persist_state {
+        if let Err(e) =
+            tracker.load_state(state_path, config.simulation.short_window_state_max_age_secs)
+        {
+            match e.downcast_ref::<HftpmError>() {
+                Some(HftpmError::NotFound(_)) => info!("🆕 No saved state, starting a fresh session"),
+                _ => warn!("⚠️  Not restoring saved state, starting fresh: {}", e),
+            }
+        }
+    }
 
     // Session tracking
     let session_start = Utc::now();
@@ -121,10 +118,9 @@ async fn main() -> Result<()> {
     let mut edge_count: u64 = 0;
     
     // Thresholds from config
-    let min_edge = config.trading.short_window_min_edge;
     let max_size = Decimal::from(config.trading.short_window_max_size);
     let min_liquidity = Decimal::from(config.trading.min_liquidity);
-    let fee_rate = Decimal::from_str("0.02")?; // 2% Polymarket fee
+    let fee_rate = Decimal::new(2, 2); // 2% Polymarket fee
 
     info!("🚀 Starting simulation loop (Ctrl+C to stop)...");
     println!();
@@ -190,8 +186,8 @@ async fn main() -> Result<()> {
                     let no_asset_id = &market.assets_ids[1];
                     
                     let (yes_book, no_book) = match tokio::try_join!(
-                        fetch_order_book(&http_client, &config.server.rest_url, yes_asset_id),
-                        fetch_order_book(&http_client, &config.server.rest_url, no_asset_id)
+                        orderbook_manager.fetch_book_rest(&http_client, &config.server.rest_url, yes_asset_id),
+                        orderbook_manager.fetch_book_rest(&http_client, &config.server.rest_url, no_asset_id)
                     ) {
                         Ok((Some(y), Some(n))) => (y, n),
                         Ok(_) => {
@@ -203,9 +199,18 @@ async fn main() -> Result<()> {
                             continue;
                         }
                     };
+
+                    let yes_depth = yes_book.top_asks(SHORT_WINDOW_DEPTH_LEVELS);
+                    let no_depth = no_book.top_asks(SHORT_WINDOW_DEPTH_LEVELS);
+                    let (Some(&(yes_ask_price, yes_ask_size)), Some(&(no_ask_price, no_ask_size))) =
+                        (yes_depth.first(), no_depth.first())
+                    else {
+                        debug!("Empty ask side for {}", market.question);
+                        continue;
+                    };
                     
                     // Check for Sum-<$1 arb
-                    let sum_prices = yes_book.best_ask_price + no_book.best_ask_price;
+                    let sum_prices = yes_ask_price + no_ask_price;
                     
                     if sum_prices >= Decimal::ONE {
                         debug!("No arb: {} sum={:.4}", market.question, sum_prices);
@@ -214,6 +219,7 @@ async fn main() -> Result<()> {
                     
                     let raw_edge = Decimal::ONE - sum_prices;
                     let net_edge = raw_edge - fee_rate;
+                    let min_edge = config.trading.short_window_min_edge_for(minutes_to_expiry);
                     
                     if net_edge < min_edge {
                         debug!("Edge too small: {} {:.2}% < {:.2}%", 
@@ -223,31 +229,49 @@ async fn main() -> Result<()> {
                         continue;
                     }
                     
-                    // Check liquidity
-                    let liquidity = yes_book.best_ask_size.min(no_book.best_ask_size);
+                    // Check liquidity across the retained levels
+                    let depth_size = |depth: &[(Decimal, Decimal)]| {
+                        depth.iter().map(|(_, size)| *size).sum::<Decimal>()
+                    };
+                    let liquidity = depth_size(&yes_depth).min(depth_size(&no_depth));
                     if liquidity < min_liquidity {
                         debug!("Liquidity too low: {} ${}", market.question, liquidity);
                         continue;
                     }
                     
-                    // Calculate position size
-                    let position_size = liquidity.min(max_size);
-                    let expected_profit = position_size * net_edge;
+                    // Size into the book only as far as the VWAP edge clears min_edge
+                    let Some((position_size, vwap_sum)) = size_to_vwap_edge(
+                        &[yes_depth.clone(), no_depth.clone()],
+                        max_size,
+                        fee_rate,
+                        min_edge,
+                    ) else {
+                        continue;
+                    };
+                    let vwap_net_edge = Decimal::ONE - vwap_sum - fee_rate;
+                    let expected_profit = position_size * vwap_net_edge;
                     
                     let opp = SwArbOpportunity {
                         market_id: market.market.clone(),
                         market_question: market.question.clone(),
                         minutes_to_expiry,
-                        yes_price: yes_book.best_ask_price,
-                        no_price: no_book.best_ask_price,
+                        yes_price: yes_ask_price,
+                        no_price: no_ask_price,
                         sum_prices,
+                        vwap_sum,
                         net_edge,
+                        vwap_net_edge,
                         position_size,
                         expected_profit,
                         yes_asset_id: yes_asset_id.clone(),
                         no_asset_id: no_asset_id.clone(),
                     };
                     
+                    // Mispricing persists across ticks; don't re-enter a market we hold
+                    if tracker.has_open_trade(&opp.market_id) {
+                        continue;
+                    }
+
                     opportunities_detected += 1;
                     total_edge_sum += net_edge;
                     edge_count += 1;
@@ -257,18 +281,38 @@ async fn main() -> Result<()> {
                         market_id: opp.market_id.clone(),
                         market_question: opp.market_question.clone(),
                         minutes_to_expiry: opp.minutes_to_expiry,
-                        yes_price: opp.yes_price,
-                        no_price: opp.no_price,
+                        legs: vec![
+                            ShortWindowLeg {
+                                asset_id: opp.yes_asset_id.clone(),
+                                outcome: market
+                                    .outcome_for_token(&opp.yes_asset_id)
+                                    .map_or_else(|| "YES".to_string(), |o| o.name.clone()),
+                                price: opp.yes_price,
+                                size: yes_ask_size,
+                                depth: yes_depth,
+                            },
+                            ShortWindowLeg {
+                                asset_id: opp.no_asset_id.clone(),
+                                outcome: market
+                                    .outcome_for_token(&opp.no_asset_id)
+                                    .map_or_else(|| "NO".to_string(), |o| o.name.clone()),
+                                price: opp.no_price,
+                                size: no_ask_size,
+                                depth: no_depth,
+                            },
+                        ],
                         sum_prices: opp.sum_prices,
-                        raw_edge: raw_edge,
+                        vwap_sum: opp.vwap_sum,
+                        raw_edge,
                         net_edge: opp.net_edge,
+                        vwap_net_edge: opp.vwap_net_edge,
                         position_size: opp.position_size,
                         expected_profit: opp.expected_profit,
-                        yes_asset_id: opp.yes_asset_id.clone(),
-                        no_asset_id: opp.no_asset_id.clone(),
                         min_liquidity: liquidity,
                         detected_at: chrono::Utc::now().timestamp_millis(),
                         annualized_return: Decimal::ZERO, // Not needed for sim
+                        assumed_fill_probability: config.trading.short_window_fill_probability,
+                        assumed_capital_utilization: config.trading.short_window_capital_utilization,
                     };
                     
                     // Simulate entry
@@ -281,8 +325,10 @@ async fn main() -> Result<()> {
                     println!("│ Market: {:<54} │", truncate_str(&opp.market_question, 54));
                     println!("│ YES: ${:<6.4}  NO: ${:<6.4}  SUM: ${:<6.4}                     │",
                         opp.yes_price, opp.no_price, opp.sum_prices);
+                    println!("│ VWAP SUM: ${:<6.4}  (edge {:<5.2}% at top of book)                │",
+                        opp.vwap_sum, opp.net_edge * Decimal::from(100));
                     println!("│ Edge: {:<5.2}%  Position: ${:<6.2}  Profit: ${:<6.2}               │",
-                        opp.net_edge * Decimal::from(100),
+                        opp.vwap_net_edge * Decimal::from(100),
                         opp.position_size,
                         opp.expected_profit);
                     println!("│ Expires in: {} minutes                                          │", opp.minutes_to_expiry);
@@ -337,6 +383,12 @@ async fn main() -> Result<()> {
     println!("║  Win Rate: {:.1}%                                             ", stats.win_rate * Decimal::from(100));
     println!("╚═══════════════════════════════════════════════════════════════╝");
 
+    if persist_state {
+        if let Err(e) = tracker.save_state(state_path) {
+            warn!("Failed to save tracker state: {}", e);
+        }
+    }
+
     // Export trades to JSON
     let export_path = format!("logs/sw_arb_sim_{}.json", session_start.format("%Y%m%d_%H%M%S"));
     if let Err(e) = export_trades(&tracker, &export_path, &session_start, &session_end, &stats) {
@@ -345,42 +397,14 @@ async fn main() -> Result<()> {
         info!("📁 Trades exported to: {}", export_path);
     }
 
-    Ok(())
-}
-
-/// Fetch order book from CLOB REST API
-async fn fetch_order_book(
-    client: &Client,
-    base_url: &str,
-    token_id: &str,
-) -> Result<Option<SimpleOrderBook>> {
-    let url = format!("{}/book?token_id={}", base_url, token_id);
-    
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .context("Failed to fetch order book")?;
-    
-    if !response.status().is_success() {
-        return Ok(None);
+    if let Some(csv_path) = export_csv_path {
+        match hfptm::utils::export::export_csv(&csv_path, tracker.get_trades()) {
+            Ok(()) => info!("📁 Trades exported to: {}", csv_path.display()),
+            Err(e) => warn!("Failed to export CSV: {}", e),
+        }
     }
-    
-    let book: BookResponse = response.json().await.context("Failed to parse order book")?;
-    
-    // Get best ask (lowest ask price)
-    let best_ask = book.asks
-        .and_then(|asks| asks.into_iter().next())
-        .and_then(|level| {
-            let price = Decimal::from_str(&level.price).ok()?;
-            let size = Decimal::from_str(&level.size).ok()?;
-            Some(SimpleOrderBook {
-                best_ask_price: price,
-                best_ask_size: size,
-            })
-        });
-    
-    Ok(best_ask)
+
+    Ok(())
 }
 
 /// Truncate string for display
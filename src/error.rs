@@ -0,0 +1,112 @@
+use crate::risk::RiskRejectReason;
+use polymarket_client_sdk::error::{Kind, Status};
+use reqwest::StatusCode;
+use thiserror::Error;
+
+pub type Result<T, E = HftpmError> = std::result::Result<T, E>;
+
+/// What went wrong, so callers can tell a retryable hiccup from a fatal setup
+/// problem without matching on strings. Only the top-level `run()` flattens
+/// these into `anyhow`.
+#[derive(Debug, Error)]
+pub enum HftpmError {
+    /// A market, book or cached resource we looked up doesn't exist (yet)
+    #[error("{0} not found")]
+    NotFound(String),
+
+    /// Credentials missing, malformed or rejected by the exchange
+    #[error("authentication failed: {0}")]
+    Auth(String),
+
+    /// Transport failure or non-success HTTP status; worth retrying
+    #[error("network error: {0}")]
+    Network(String),
+
+    /// The exchange asked us to slow down (HTTP 429)
+    #[error("rate limited: {0}")]
+    RateLimited(String),
+
+    /// Data too old to act on
+    #[error("stale data: {0}")]
+    Stale(String),
+
+    /// A response, cache file or value that didn't parse
+    #[error("parse error: {0}")]
+    Parse(String),
+
+    #[error("risk check rejected trade: {0}")]
+    RiskRejected(RiskRejectReason),
+
+    /// Invalid configuration; fixing it needs a restart
+    #[error("invalid config: {0}")]
+    Config(String),
+
+    /// The exchange refused a request for a reason other than the above
+    #[error("exchange error: {0}")]
+    Exchange(String),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+}
+
+impl HftpmError {
+    /// Worth trying again after a pause
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Network(_) | Self::RateLimited(_))
+    }
+
+    /// Retrying can't help: stop instead of looping on it
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::Auth(_) | Self::Config(_))
+    }
+
+    /// Classify an HTTP status from `context`
+    pub fn from_status(status: StatusCode, context: impl std::fmt::Display) -> Self {
+        let message = format!("{}: HTTP {}", context, status);
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Auth(message),
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited(message),
+            StatusCode::NOT_FOUND => Self::NotFound(message),
+            _ => Self::Network(message),
+        }
+    }
+
+    /// Classify a CLOB SDK error raised while doing `context`
+    pub fn from_clob(err: polymarket_client_sdk::error::Error, context: &str) -> Self {
+        if let Some(status) = err.downcast_ref::<Status>() {
+            return match status.status_code {
+                StatusCode::BAD_REQUEST => Self::Exchange(format!("{}: {}", context, err)),
+                code => Self::from_status(code, format!("{}: {}", context, status.message)),
+            };
+        }
+
+        let message = format!("{}: {}", context, err);
+        match err.kind() {
+            Kind::Validation => Self::Exchange(message),
+            Kind::Synchronization => Self::Auth(message),
+            _ => Self::Network(message),
+        }
+    }
+}
+
+impl From<reqwest::Error> for HftpmError {
+    fn from(err: reqwest::Error) -> Self {
+        match err.status() {
+            Some(status) => Self::from_status(status, err),
+            None if err.is_decode() => Self::Parse(err.to_string()),
+            None => Self::Network(err.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for HftpmError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Parse(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for HftpmError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err.to_string())
+    }
+}
@@ -0,0 +1,230 @@
+use super::{OrderRateLimiter, OrderResult, SignedOrder, RATE_LIMIT_BACKOFF};
+use crate::error::{HftpmError, Result};
+use crate::utils::ExecutionConfig;
+use async_trait::async_trait;
+use polymarket_client_sdk::clob::types::{PostOrderResponse, Side};
+use rust_decimal::Decimal;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// Most orders the CLOB accepts in one `POST /orders`
+pub const MAX_BATCH_ORDERS: usize = 15;
+
+/// Posts a batch of signed orders to the exchange in a single request
+#[async_trait]
+pub trait OrderSubmitter: Send + Sync {
+    /// One response per order, in the order given
+    async fn post_orders(&self, orders: &[SignedOrder]) -> Result<Vec<PostOrderResponse>>;
+}
+
+/// How legs whose submission failed on a transient error are sent again
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Resubmissions after the first attempt
+    pub max_retries: usize,
+    /// Wait before the first retry, doubling for each one after
+    pub backoff: Duration,
+    /// No retry is sent that would go out after this (the opportunity's age limit)
+    pub deadline: Option<Instant>,
+}
+
+impl RetryPolicy {
+    /// Submit once and keep whatever comes back
+    pub const NONE: Self = Self {
+        max_retries: 0,
+        backoff: Duration::ZERO,
+        deadline: None,
+    };
+
+    /// `max_retries` and `retry_backoff_ms` from `config`, stopping at `deadline`
+    pub fn from_config(config: &ExecutionConfig, deadline: Option<Instant>) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            backoff: Duration::from_millis(config.retry_backoff_ms),
+            deadline,
+        }
+    }
+
+    /// Wait before retry number `retry` (from 1)
+    #[inline]
+    fn backoff_for(&self, retry: usize) -> Duration {
+        self.backoff.saturating_mul(1 << (retry - 1).min(16))
+    }
+}
+
+/// Submit every leg of an opportunity in as few requests as the CLOB allows.
+///
+/// The legs reach the matching engine together instead of racing as separate
+/// requests, which narrows the window where one leg is live without the other.
+/// The batch endpoint is not atomic: each order is still matched on its own, so
+/// one leg can fill while another is rejected, and opportunities with more than
+/// `MAX_BATCH_ORDERS` legs go out as back-to-back batches.
+///
+/// Per `retry`, a request that fails on a retryable error (timeout, 5xx, rate
+/// limit) is sent again, and so are legs the exchange rejected while accepting
+/// the rest, so a transient hiccup doesn't leave the arb one-sided. Retries
+/// resend the same signed orders: a leg that was accepted before its response
+/// got lost is recognized rather than placed twice.
+pub async fn submit_orders_batch(
+    submitter: &dyn OrderSubmitter,
+    rate_limiter: &OrderRateLimiter,
+    signed_orders: &[SignedOrder],
+    retry: RetryPolicy,
+) -> Vec<OrderResult> {
+    let mut results = Vec::with_capacity(signed_orders.len());
+
+    for batch in signed_orders.chunks(MAX_BATCH_ORDERS) {
+        let mut batch_results: Vec<Option<OrderResult>> = vec![None; batch.len()];
+        // Indexes into `batch` of the legs not yet accepted
+        let mut pending: Vec<usize> = (0..batch.len()).collect();
+        let mut attempt = 0;
+
+        loop {
+            let orders: Vec<SignedOrder> = pending.iter().map(|&i| batch[i].clone()).collect();
+            let (retryable, reason) = match post_batch(submitter, rate_limiter, &orders).await {
+                Ok(posted) => {
+                    for (&i, result) in pending.iter().zip(posted) {
+                        batch_results[i] = Some(result);
+                    }
+                    pending.retain(|&i| batch_results[i].as_ref().is_some_and(|r| !r.success));
+                    (true, "leg rejected".to_string())
+                }
+                Err(e) => {
+                    for &i in &pending {
+                        batch_results[i] = Some(OrderResult {
+                            asset_id: batch[i].asset_id.clone(),
+                            success: false,
+                            order_id: None,
+                            error: Some(e.to_string()),
+                            matched_price: None,
+                        });
+                    }
+                    (e.is_retryable(), e.to_string())
+                }
+            };
+
+            if pending.is_empty() {
+                break;
+            }
+
+            attempt += 1;
+            let backoff = retry.backoff_for(attempt);
+            let in_time = retry
+                .deadline
+                .is_none_or(|deadline| Instant::now() + backoff < deadline);
+
+            if retryable && attempt <= retry.max_retries && in_time {
+                warn!(
+                    "🔁 {} of {} orders failed ({}), retry {}/{} in {:?}",
+                    pending.len(),
+                    batch.len(),
+                    reason,
+                    attempt,
+                    retry.max_retries,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                continue;
+            }
+
+            error!(
+                "❌ {} of {} orders failed: {}",
+                pending.len(),
+                batch.len(),
+                reason
+            );
+            break;
+        }
+
+        results.extend(batch_results.into_iter().flatten());
+    }
+
+    results
+}
+
+/// One request for `batch`, backing off the rate limiter if it's throttled
+async fn post_batch(
+    submitter: &dyn OrderSubmitter,
+    rate_limiter: &OrderRateLimiter,
+    batch: &[SignedOrder],
+) -> Result<Vec<OrderResult>> {
+    for _ in batch {
+        rate_limiter.acquire().await;
+    }
+
+    match submitter.post_orders(batch).await {
+        Ok(responses) => Ok(batch
+            .iter()
+            .enumerate()
+            .map(|(i, signed_order)| order_result(signed_order, responses.get(i)))
+            .collect()),
+        Err(e) => {
+            if matches!(e, HftpmError::RateLimited(_)) {
+                rate_limiter.back_off(RATE_LIMIT_BACKOFF).await;
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Outcome of one order in a batch from its entry in the response
+fn order_result(signed_order: &SignedOrder, response: Option<&PostOrderResponse>) -> OrderResult {
+    let asset_id = signed_order.asset_id.clone();
+
+    match response {
+        Some(response) if response.success => {
+            info!(
+                event = "order_submitted",
+                asset_id = %signed_order.asset_id,
+                "✅ Order submitted: {} - {:?}",
+                signed_order.asset_id, response
+            );
+            let order_id = if response.order_id.is_empty() {
+                signed_order.order_hash.clone()
+            } else {
+                response.order_id.clone()
+            };
+            OrderResult {
+                asset_id,
+                success: true,
+                order_id: Some(order_id),
+                error: None,
+                matched_price: matched_price(signed_order, response),
+            }
+        }
+        Some(response) => {
+            let reason = response
+                .error_msg
+                .clone()
+                .unwrap_or_else(|| format!("{:?}", response.status));
+            error!("❌ Order failed: {} - {}", signed_order.asset_id, reason);
+            OrderResult {
+                asset_id,
+                success: false,
+                order_id: None,
+                error: Some(reason),
+                matched_price: None,
+            }
+        }
+        None => {
+            error!("❌ No response for order: {}", signed_order.asset_id);
+            OrderResult {
+                asset_id,
+                success: false,
+                order_id: None,
+                error: Some("Missing from batch response".to_string()),
+                matched_price: None,
+            }
+        }
+    }
+}
+
+/// Average price of what matched on placement. A buy makes USDC and takes
+/// shares, a sell the other way round; nothing matched means no price.
+fn matched_price(signed_order: &SignedOrder, response: &PostOrderResponse) -> Option<Decimal> {
+    let (usdc, shares) = match signed_order.side {
+        Side::Buy => (response.making_amount, response.taking_amount),
+        _ => (response.taking_amount, response.making_amount),
+    };
+    (shares > Decimal::ZERO).then(|| usdc / shares)
+}
@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Where the breaker stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Executions go through; failures are being counted
+    Closed,
+    /// Tripped: nothing is executed until `until`
+    Open { until: Instant },
+    /// Cooldown over: one probe execution is out to test recovery
+    HalfOpen { probe_started: Instant },
+}
+
+/// Whether an execution may go ahead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerPermit {
+    Allowed,
+    /// The single execution let through to test recovery; its outcome closes
+    /// or re-trips the breaker
+    Probe,
+    Rejected,
+}
+
+/// Stops executing once the exchange keeps failing every order (expired auth,
+/// halted market, no funds) instead of hammering it with doomed submissions.
+///
+/// `max_failures` consecutive failures within `window` trip it open for
+/// `cooldown`. After that a single probe is let through: success closes it,
+/// failure opens it for another cooldown. A probe that never reports back is
+/// replaced after a cooldown. `max_failures` of 0 disables it.
+pub struct CircuitBreaker {
+    max_failures: usize,
+    window: Duration,
+    cooldown: Duration,
+    /// Consecutive failures still inside `window`, oldest first
+    failures: VecDeque<Instant>,
+    state: BreakerState,
+}
+
+impl CircuitBreaker {
+    pub fn new(max_failures: u32, window: Duration, cooldown: Duration) -> Self {
+        Self {
+            max_failures: max_failures as usize,
+            window,
+            cooldown,
+            failures: VecDeque::new(),
+            state: BreakerState::Closed,
+        }
+    }
+
+    #[inline]
+    pub fn state(&self) -> BreakerState {
+        self.state
+    }
+
+    #[inline]
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Ask to execute at `now`; a `Probe` must be followed by a recorded outcome
+    pub fn allow(&mut self, now: Instant) -> BreakerPermit {
+        match self.state {
+            BreakerState::Closed => BreakerPermit::Allowed,
+            BreakerState::Open { until } if now < until => BreakerPermit::Rejected,
+            BreakerState::HalfOpen { probe_started }
+                if now.duration_since(probe_started) < self.cooldown =>
+            {
+                BreakerPermit::Rejected
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => {
+                self.state = BreakerState::HalfOpen { probe_started: now };
+                BreakerPermit::Probe
+            }
+        }
+    }
+
+    /// Record a successful execution, returning true if it closed the breaker
+    pub fn record_success(&mut self) -> bool {
+        self.failures.clear();
+        let recovered = matches!(self.state, BreakerState::HalfOpen { .. });
+        self.state = BreakerState::Closed;
+        recovered
+    }
+
+    /// Record a failed execution at `now`, returning true if it tripped the breaker
+    pub fn record_failure(&mut self, now: Instant) -> bool {
+        if self.max_failures == 0 {
+            return false;
+        }
+
+        match self.state {
+            BreakerState::HalfOpen { .. } => {
+                self.trip(now);
+                true
+            }
+            BreakerState::Open { .. } => false,
+            BreakerState::Closed => {
+                while self
+                    .failures
+                    .front()
+                    .is_some_and(|first| now.duration_since(*first) > self.window)
+                {
+                    self.failures.pop_front();
+                }
+                self.failures.push_back(now);
+
+                if self.failures.len() >= self.max_failures {
+                    self.trip(now);
+                    return true;
+                }
+                false
+            }
+        }
+    }
+
+    /// Failures counted toward tripping
+    #[inline]
+    pub fn recent_failures(&self) -> usize {
+        self.failures.len()
+    }
+
+    fn trip(&mut self, now: Instant) {
+        self.failures.clear();
+        self.state = BreakerState::Open {
+            until: now + self.cooldown,
+        };
+    }
+}
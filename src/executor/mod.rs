@@ -1,30 +1,54 @@
-use crate::arb_engine::ArbitrageOpportunity;
-use crate::utils::ScopedTimer;
+use crate::arb_engine::{ArbSide, ArbType, Opportunity, OpportunityLeg};
+use crate::error::{HftpmError, Result};
+use crate::gamma_api::{parse_end_date, Market};
+use crate::orderbook::OrderBookManager;
+use crate::utils::sim::SimEnv;
+use crate::utils::{ScopedTimer, TradingMode};
+use alloy::primitives::Address;
 use alloy::signers::{local::PrivateKeySigner, Signer};
-use anyhow::{Context, Result};
-use futures::future::join_all;
+use async_trait::async_trait;
+use futures::TryStreamExt;
 use polymarket_client_sdk::auth::{state::Authenticated, Normal};
 use polymarket_client_sdk::clob::{
     types::{
-        BalanceAllowanceResponse, CancelOrdersResponse, OrderType, PostOrderResponse, Side,
-        SignedOrder as SdkSignedOrder,
+        BalanceAllowanceResponse, CancelOrdersResponse, OpenOrderResponse, OrderType,
+        OrdersRequest, PostOrderResponse, Side, SignatureType, SignedOrder as SdkSignedOrder,
+        SignedOrderBuilder as SdkSignedOrderBuilder, TradeResponse, TraderSide, TradesRequest,
     },
     Client, Config as ClobConfig,
 };
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use std::time::Instant;
-use tracing::{error, info, warn};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+mod batch;
+mod circuit_breaker;
+mod rate_limit;
+
+pub use batch::{submit_orders_batch, OrderSubmitter, RetryPolicy, MAX_BATCH_ORDERS};
+pub use circuit_breaker::{BreakerPermit, BreakerState, CircuitBreaker};
+pub use rate_limit::OrderRateLimiter;
+
+/// How long all submissions pause after the CLOB answers 429
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Paper-live orders kept for inspection; the oldest are dropped first
+pub const MAX_PAPER_ORDERS: usize = 1000;
 
 #[derive(Debug, Clone)]
 pub struct SignedOrder {
     pub asset_id: String,
+    pub side: Side,
     pub price: Decimal,
     pub size: Decimal,
     pub order_hash: String,
     pub created_at: Instant,
+    /// The signed order sent to the exchange. Retries resend this exact order,
+    /// so a leg the exchange already accepted can't be placed twice.
+    pub signed: Option<Arc<SdkSignedOrder>>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,17 +58,141 @@ pub struct ExecutionResult {
     pub partial_fill: bool,
     pub filled_amount: Decimal,
     pub total_cost: Decimal,
+    /// Cost of the filled quantity at the quoted leg prices
+    pub expected_cost: Decimal,
+    /// Cost actually paid for the filled quantity
+    pub realized_cost: Decimal,
+    /// Complete-set payout minus everything spent minus fees. Legs bought
+    /// without their complement count as cost with no payout.
+    pub realized_pnl: Decimal,
+    /// Fees charged on the sets traded, already taken out of `realized_pnl`
+    pub fees_paid: Decimal,
     pub orders: Vec<OrderResult>,
     pub execution_time_ms: u64,
     pub error_message: Option<String>,
 }
 
+impl ExecutionResult {
+    /// Orders were placed but none has filled yet; the fills come later
+    #[inline]
+    pub fn is_resting(&self) -> bool {
+        self.success && !self.filled && !self.partial_fill
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OrderResult {
     pub asset_id: String,
     pub success: bool,
     pub order_id: Option<String>,
     pub error: Option<String>,
+    /// Average price of the size matched when the order was placed, if any
+    pub matched_price: Option<Decimal>,
+}
+
+/// Net holding of one outcome token on the exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangePosition {
+    pub market_id: String,
+    pub asset_id: String,
+    pub outcome: String,
+    pub size: Decimal,
+    pub avg_price: Decimal,
+}
+
+/// Order resting on the exchange, with its unfilled remainder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeOpenOrder {
+    pub order_id: String,
+    pub market_id: String,
+    pub asset_id: String,
+    pub outcome: String,
+    pub is_buy: bool,
+    pub price: Decimal,
+    pub remaining_size: Decimal,
+}
+
+/// How the CLOB client signs orders: the wallet type and, for proxy/Safe
+/// wallets, the funder address that holds the funds and makes the orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClobSigningConfig {
+    pub signature_type: SignatureType,
+    pub funder: Option<Address>,
+}
+
+impl ClobSigningConfig {
+    /// Map `signature_type` (0=EOA, 1=Proxy, 2=Safe) and `funder_address` from config
+    pub fn from_credentials(credentials: &crate::utils::CredentialsConfig) -> Result<Self> {
+        let signature_type = match credentials.signature_type {
+            0 => SignatureType::Eoa,
+            1 => SignatureType::Proxy,
+            2 => SignatureType::GnosisSafe,
+            other => {
+                return Err(HftpmError::Config(format!(
+                    "Invalid signature_type {} (expected 0=EOA, 1=Proxy, 2=Gnosis Safe)",
+                    other
+                )))
+            }
+        };
+
+        // An EOA signs and funds its own orders; a funder is only meaningful for proxy/Safe
+        if signature_type == SignatureType::Eoa {
+            return Ok(Self {
+                signature_type,
+                funder: None,
+            });
+        }
+
+        let funder: Address = credentials.funder_address.trim().parse().map_err(|_| {
+            HftpmError::Config(format!(
+                "signature_type {} ({}) needs funder_address set to the proxy/Safe wallet, got {:?}",
+                credentials.signature_type, signature_type, credentials.funder_address
+            ))
+        })?;
+
+        if funder.is_zero() {
+            return Err(HftpmError::Config(format!(
+                "signature_type {} ({}) requires a non-zero funder_address",
+                credentials.signature_type, signature_type
+            )));
+        }
+
+        Ok(Self {
+            signature_type,
+            funder: Some(funder),
+        })
+    }
+}
+
+/// Where the detection loop sends arbs: the CLOB (`OrderExecutor`), the
+/// simulator (`SimulationExecutor`), or a stand-in in tests
+#[async_trait]
+pub trait Executor: Send + Sync {
+    async fn execute_arbitrage(
+        &self,
+        arb_op: &dyn Opportunity,
+        orderbook_manager: &OrderBookManager,
+    ) -> Result<ExecutionResult>;
+
+    /// Balance trades are paid from, for sizing
+    async fn available_balance(&self) -> Result<Decimal>;
+
+    /// Simulated balance and P&L since start, `None` unless this is a simulator
+    async fn simulated_account(&self) -> Option<(Decimal, Decimal)> {
+        None
+    }
+
+    /// Fill simulated arbs whose fill latency has passed and return them;
+    /// nothing to do unless this is a simulator
+    async fn settle_fills(&self, _orderbook_manager: &OrderBookManager) -> Vec<SettledFill> {
+        Vec::new()
+    }
+
+    /// Whether the exchange behind this executor is reachable; always true
+    /// unless it talks to one
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,8 +203,11 @@ pub struct SimulatedTrade {
     pub edges: Vec<SimulatedEdge>,
     pub total_cost: Decimal,
     pub expected_payout: Decimal,
+    /// Zero until a pending redemption is credited
     pub net_profit: Decimal,
     pub execution_time_ms: u64,
+    /// Bought sets still waiting on resolution (`redeem_at_resolution`)
+    pub pending_redemption: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,16 +218,149 @@ pub struct SimulatedEdge {
     pub cost: Decimal,
 }
 
+/// Complete sets bought in simulation whose $1 redemption waits on resolution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRedemption {
+    pub market_id: String,
+    pub sets: Decimal,
+    /// Credited at resolution: $1 per set less fees
+    pub net_payout: Decimal,
+    pub resolves_at_ms: i64,
+}
+
+/// An arb handed to the simulator, as much of it as filling and recording the
+/// fill needs
+#[derive(Debug, Clone)]
+pub struct SimulatedOrder {
+    market_id: String,
+    market_ids: Vec<String>,
+    arb_type: ArbType,
+    position_size: Decimal,
+    expected_profit: Decimal,
+    fee_cost: Decimal,
+    edge: Decimal,
+    min_liquidity: Decimal,
+    detected_at_ms: Option<i64>,
+    legs: Vec<OpportunityLeg>,
+    /// Ids the leg orders were placed under, in leg order; empty when the
+    /// arb fills straight away
+    order_ids: Vec<String>,
+    start_ms: i64,
+}
+
+impl SimulatedOrder {
+    fn new(arb_op: &dyn Opportunity, legs: Vec<OpportunityLeg>, start_ms: i64) -> Self {
+        Self {
+            market_id: arb_op.market_id().to_string(),
+            market_ids: arb_op.market_ids().into_iter().map(String::from).collect(),
+            arb_type: arb_op.arb_type(),
+            position_size: arb_op.position_size(),
+            expected_profit: arb_op.expected_profit(),
+            fee_cost: arb_op.fee_cost(),
+            edge: arb_op.edge(),
+            min_liquidity: arb_op.min_liquidity(),
+            detected_at_ms: arb_op.detected_at_ms(),
+            legs,
+            order_ids: Vec::new(),
+            start_ms,
+        }
+    }
+
+    /// Ids the leg orders were placed under, in leg order
+    pub fn order_ids(&self) -> &[String] {
+        &self.order_ids
+    }
+}
+
+impl Opportunity for SimulatedOrder {
+    fn arb_type(&self) -> ArbType {
+        self.arb_type.clone()
+    }
+
+    fn market_id(&self) -> &str {
+        &self.market_id
+    }
+
+    fn market_ids(&self) -> Vec<&str> {
+        self.market_ids.iter().map(String::as_str).collect()
+    }
+
+    fn legs(&self) -> Vec<OpportunityLeg> {
+        self.legs.clone()
+    }
+
+    fn position_size(&self) -> Decimal {
+        self.position_size
+    }
+
+    fn expected_profit(&self) -> Decimal {
+        self.expected_profit
+    }
+
+    fn fee_cost(&self) -> Decimal {
+        self.fee_cost
+    }
+
+    fn edge(&self) -> Decimal {
+        self.edge
+    }
+
+    fn min_liquidity(&self) -> Decimal {
+        self.min_liquidity
+    }
+
+    fn detected_at_ms(&self) -> Option<i64> {
+        self.detected_at_ms
+    }
+}
+
+impl std::fmt::Display for SimulatedOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} {} | {} sets | ${:.2} expected",
+            self.arb_type, self.market_id, self.position_size, self.expected_profit
+        )
+    }
+}
+
+/// A simulated arb waiting out its fill latency before it reads the books
+struct PendingFill {
+    order: SimulatedOrder,
+    due_ms: i64,
+}
+
+/// A simulated arb whose fill latency has passed, filled against the books
+#[derive(Debug, Clone)]
+pub struct SettledFill {
+    pub order: SimulatedOrder,
+    /// Size filled on each of the order's legs, in leg order
+    pub filled_sizes: Vec<Decimal>,
+    pub result: ExecutionResult,
+}
+
+/// Book depth taken by fills earlier in the same batch, per asset and side
+type TakenDepth = HashMap<(String, ArbSide), Decimal>;
+
 pub struct SimulationExecutor {
-    #[allow(dead_code)]
     config: Arc<crate::utils::Config>,
     trades: Arc<tokio::sync::RwLock<VecDeque<SimulatedTrade>>>,
     simulated_balance: Arc<tokio::sync::RwLock<Decimal>>,
     initial_balance: Decimal,
+    sim_env: SimEnv,
+    /// Market -> resolution time (ms), from `register_market`
+    resolution_times: parking_lot::RwLock<HashMap<String, i64>>,
+    pending_redemptions: tokio::sync::Mutex<Vec<PendingRedemption>>,
+    pending_fills: tokio::sync::Mutex<Vec<PendingFill>>,
 }
 
 impl SimulationExecutor {
     pub fn new(config: &crate::utils::Config) -> Self {
+        Self::with_sim_env(config, SimEnv::real())
+    }
+
+    /// Simulator driven by the given clock and ID source (seeded for replays)
+    pub fn with_sim_env(config: &crate::utils::Config, sim_env: SimEnv) -> Self {
         let initial_balance = Decimal::from(config.trading.bankroll);
 
         info!("🎮 Simulation mode enabled - NO REAL TRADES");
@@ -87,28 +371,258 @@ impl SimulationExecutor {
             trades: Arc::new(tokio::sync::RwLock::new(VecDeque::with_capacity(1000))),
             simulated_balance: Arc::new(tokio::sync::RwLock::new(initial_balance)),
             initial_balance,
+            sim_env,
+            resolution_times: parking_lot::RwLock::new(HashMap::new()),
+            pending_redemptions: tokio::sync::Mutex::new(Vec::new()),
+            pending_fills: tokio::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Remember when a market resolves, for `redeem_at_resolution`
+    pub fn register_market(&self, market: &Market) {
+        if let Some(end) = market.end_date.as_deref().and_then(parse_end_date) {
+            self.resolution_times
+                .write()
+                .insert(market.market.clone(), end.timestamp_millis());
+        }
+    }
+
+    /// Credit every pending redemption whose market has resolved and realize
+    /// the profit of its trades; returns the amount credited
+    pub async fn settle_redemptions(&self) -> Decimal {
+        let now_ms = self.sim_env.clock.now_ms();
+        let mut pending = self.pending_redemptions.lock().await;
+
+        let mut credited = Decimal::ZERO;
+        let mut resolved_markets = HashSet::new();
+        pending.retain(|redemption| {
+            let resolved = redemption.resolves_at_ms <= now_ms;
+            if resolved {
+                credited += redemption.net_payout;
+                resolved_markets.insert(redemption.market_id.clone());
+            }
+            !resolved
+        });
+        drop(pending);
+
+        if !resolved_markets.is_empty() {
+            let fee_rate = self.config.simulation.fee_rate;
+            let mut trades = self.trades.write().await;
+            for trade in trades.iter_mut().filter(|trade| {
+                trade.pending_redemption && resolved_markets.contains(&trade.market_id)
+            }) {
+                trade.pending_redemption = false;
+                trade.net_profit =
+                    trade.expected_payout * (Decimal::ONE - fee_rate) - trade.total_cost;
+            }
+        }
+
+        if !credited.is_zero() {
+            let mut balance = self.simulated_balance.write().await;
+            *balance += credited;
+            info!(
+                "🎮 SIMULATED: Redeemed ${:.2} of resolved sets | Balance: ${:.2}",
+                credited, *balance
+            );
         }
+
+        credited
+    }
+
+    /// Sets bought but not yet redeemed
+    pub async fn pending_redemptions(&self) -> Vec<PendingRedemption> {
+        self.pending_redemptions.lock().await.clone()
     }
 
+    #[inline]
+    fn resolution_time_ms(&self, market_id: &str) -> i64 {
+        self.resolution_times
+            .read()
+            .get(market_id)
+            .copied()
+            .unwrap_or_else(|| {
+                let delay_secs = self.config.simulation.default_resolution_delay_secs;
+                self.sim_env.clock.now_ms() + delay_secs as i64 * 1000
+            })
+    }
+
+    /// Simulate an arb per `[simulation]`. The fill latency isn't slept through:
+    /// the arb is held until it has passed and then filled against the books as
+    /// they are by then (see `settle_fills`), so the detection loop keeps
+    /// running meanwhile and the result only says the orders were placed. With
+    /// no latency configured the arb fills straight away.
+    ///
+    /// Fills skip what `fill_probability` says doesn't happen. Buy legs only
+    /// fill up to the ask depth at or below their price, sell legs up to the
+    /// bid depth at or above it; payout and fees scale with the number of
+    /// complete sets actually traded. With `redeem_at_resolution` the payout of
+    /// bought sets is held until the market resolves (see `settle_redemptions`).
     pub async fn simulate_arbitrage(
         &self,
-        arb_op: &ArbitrageOpportunity,
+        arb_op: &dyn Opportunity,
+        orderbook_manager: &OrderBookManager,
     ) -> Result<ExecutionResult> {
-        let start_time = Instant::now();
+        let start_ms = self.sim_env.clock.now_ms();
+        let simulation = &self.config.simulation;
+
+        self.settle_redemptions().await;
 
         info!(
             "🎮 SIMULATED: Executing arbitrage for market {}",
-            arb_op.market_id
+            arb_op.market_id()
         );
 
-        let total_cost = arb_op
-            .edges
+        let legs = arb_op.legs();
+        if legs.is_empty() {
+            return Ok(self.unfilled_result(start_ms, "No executable legs"));
+        }
+
+        let mut order = SimulatedOrder::new(arb_op, legs, start_ms);
+
+        let latency_ms = simulation.fill_latency_ms
+            + self
+                .sim_env
+                .rng
+                .next_u64_upto(simulation.fill_latency_jitter_ms);
+        if latency_ms == 0 {
+            let (result, _) = self
+                .fill_against_book(&order, orderbook_manager, &mut TakenDepth::new())
+                .await;
+            return Ok(result);
+        }
+
+        order.order_ids = order
+            .legs
             .iter()
-            .map(|e| e.expected_cost)
-            .sum::<Decimal>();
-        let expected_payout = arb_op.position_size;
-        let fee_cost = arb_op.fee_cost;
-        let net_profit = arb_op.net_profit;
+            .map(|_| self.sim_env.ids.next_id("SIM"))
+            .collect();
+        let placed = self.placed_result(&order);
+        self.pending_fills.lock().await.push(PendingFill {
+            order,
+            due_ms: start_ms + latency_ms as i64,
+        });
+        Ok(placed)
+    }
+
+    /// Fill every arb whose latency has passed against the books as they are
+    /// now, oldest first, and return them whether they filled or not. Depth one
+    /// of them takes isn't there for the next.
+    pub async fn settle_fills(&self, orderbook_manager: &OrderBookManager) -> Vec<SettledFill> {
+        let now_ms = self.sim_env.clock.now_ms();
+        let due: Vec<PendingFill> = {
+            let mut pending = self.pending_fills.lock().await;
+            if pending.is_empty() {
+                return Vec::new();
+            }
+            let (due, waiting) = std::mem::take(&mut *pending)
+                .into_iter()
+                .partition(|fill| fill.due_ms <= now_ms);
+            *pending = waiting;
+            due
+        };
+
+        let mut taken = TakenDepth::new();
+        let mut settled = Vec::with_capacity(due.len());
+        for fill in due {
+            let (result, filled_sizes) = self
+                .fill_against_book(&fill.order, orderbook_manager, &mut taken)
+                .await;
+            settled.push(SettledFill {
+                order: fill.order,
+                filled_sizes,
+                result,
+            });
+        }
+        settled
+    }
+
+    /// Fill `order` against the books as they stand, less the depth in `taken`,
+    /// and add what it fills to `taken`. Returns the result and the size filled
+    /// on each leg.
+    async fn fill_against_book(
+        &self,
+        order: &SimulatedOrder,
+        orderbook_manager: &OrderBookManager,
+        taken: &mut TakenDepth,
+    ) -> (ExecutionResult, Vec<Decimal>) {
+        let simulation = &self.config.simulation;
+        let start_ms = order.start_ms;
+        let legs = &order.legs;
+        let unfilled = |reason: &str| {
+            (
+                self.unfilled_result(start_ms, reason),
+                vec![Decimal::ZERO; legs.len()],
+            )
+        };
+
+        if self.sim_env.rng.next_f64() >= simulation.fill_probability {
+            info!(
+                "🎮 SIMULATED: Arbitrage for {} did not fill",
+                order.market_id
+            );
+            return unfilled("Simulated order did not fill");
+        }
+
+        let filled_sizes: Vec<Decimal> = legs
+            .iter()
+            .map(|leg| {
+                let already_taken = taken
+                    .get(&(leg.asset_id.clone(), leg.side))
+                    .copied()
+                    .unwrap_or(Decimal::ZERO);
+                orderbook_manager
+                    .get_book(&leg.market_id, &leg.asset_id)
+                    .map(|book| match leg.side {
+                        ArbSide::Buy => book.ask_depth_at(leg.price),
+                        ArbSide::Sell => book.bid_depth_at(leg.price),
+                    })
+                    .map(|depth| (depth - already_taken).max(Decimal::ZERO).min(leg.size))
+                    .unwrap_or(Decimal::ZERO)
+            })
+            .collect();
+
+        // Share of the intended position covered on every leg
+        let fill_ratio = legs
+            .iter()
+            .zip(&filled_sizes)
+            .map(|(leg, filled)| {
+                if leg.size.is_zero() {
+                    Decimal::ZERO
+                } else {
+                    *filled / leg.size
+                }
+            })
+            .min()
+            .unwrap_or(Decimal::ZERO);
+
+        // Sells bring cash in, so they count as negative cost
+        let leg_costs: Vec<Decimal> = legs
+            .iter()
+            .zip(&filled_sizes)
+            .map(|(leg, filled)| match leg.side {
+                ArbSide::Buy => leg.price * *filled,
+                ArbSide::Sell => -(leg.price * *filled),
+            })
+            .collect();
+        let total_cost = leg_costs.iter().sum::<Decimal>();
+        let sets = order.position_size * fill_ratio;
+        let fee_cost = sets * simulation.fee_rate;
+        let is_sell = order.arb_type.side() == ArbSide::Sell;
+        // Only bought sets pay out; sold ones are given up
+        let expected_payout = if is_sell { Decimal::ZERO } else { sets };
+        let net_profit = if is_sell {
+            -total_cost - sets - fee_cost
+        } else {
+            sets - total_cost - fee_cost
+        };
+
+        if filled_sizes.iter().all(|filled| filled.is_zero()) {
+            warn!(
+                "🎮 SIMULATED: No depth at quoted prices for {}",
+                order.market_id
+            );
+            return unfilled("No simulated depth at quoted prices");
+        }
 
         let mut balance = self.simulated_balance.write().await;
 
@@ -117,47 +631,67 @@ impl SimulationExecutor {
                 "🎮 SIMULATED: Insufficient balance: ${:.2} < ${:.2}",
                 *balance, total_cost
             );
-            return Ok(ExecutionResult {
-                success: false,
-                filled: false,
-                partial_fill: false,
-                filled_amount: Decimal::ZERO,
-                total_cost: Decimal::ZERO,
-                orders: vec![],
-                execution_time_ms: start_time.elapsed().as_millis() as u64,
-                error_message: Some("Insufficient simulated balance".to_string()),
-            });
+            return unfilled("Insufficient simulated balance");
         }
 
         *balance -= total_cost;
-        *balance += expected_payout;
-        *balance -= fee_cost;
+        if is_sell {
+            *balance -= fee_cost;
+        } else if !simulation.redeem_at_resolution {
+            *balance += expected_payout - fee_cost;
+        }
 
         let current_balance = *balance;
         let total_pnl = current_balance - self.initial_balance;
         drop(balance);
 
+        for (leg, filled) in legs.iter().zip(&filled_sizes) {
+            *taken
+                .entry((leg.asset_id.clone(), leg.side))
+                .or_insert(Decimal::ZERO) += *filled;
+        }
+
+        if simulation.redeem_at_resolution && !expected_payout.is_zero() {
+            self.pending_redemptions
+                .lock()
+                .await
+                .push(PendingRedemption {
+                    market_id: order.market_id.clone(),
+                    sets: expected_payout,
+                    net_payout: expected_payout - fee_cost,
+                    resolves_at_ms: self.resolution_time_ms(&order.market_id),
+                });
+        }
+
+        let fully_filled = fill_ratio == Decimal::ONE;
+        // Bought sets held for redemption realize nothing until they resolve
+        let pending_redemption = simulation.redeem_at_resolution && !is_sell;
+        let realized_pnl = if pending_redemption {
+            Decimal::ZERO
+        } else {
+            net_profit
+        };
+
         let simulated_trade = SimulatedTrade {
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as i64,
-            market_id: arb_op.market_id.clone(),
-            arb_type: format!("{:?}", arb_op.arb_type),
-            edges: arb_op
-                .edges
+            timestamp: self.sim_env.clock.now_secs(),
+            market_id: order.market_id.clone(),
+            arb_type: format!("{:?}", order.arb_type),
+            edges: legs
                 .iter()
-                .map(|e| SimulatedEdge {
-                    asset_id: e.asset_id.clone(),
-                    price: e.price,
-                    size: e.size,
-                    cost: e.expected_cost,
+                .zip(&filled_sizes)
+                .zip(&leg_costs)
+                .map(|((leg, filled), cost)| SimulatedEdge {
+                    asset_id: leg.asset_id.clone(),
+                    price: leg.price,
+                    size: *filled,
+                    cost: *cost,
                 })
                 .collect(),
             total_cost,
             expected_payout,
-            net_profit,
-            execution_time_ms: start_time.elapsed().as_millis() as u64,
+            net_profit: realized_pnl,
+            execution_time_ms: self.elapsed_ms(start_ms),
+            pending_redemption,
         };
 
         let mut trades = self.trades.write().await;
@@ -168,49 +702,159 @@ impl SimulationExecutor {
         drop(trades);
 
         info!(
-            "🎮 SIMULATED FILL: ${:.2} profit | Balance: ${:.2} (P&L: ${:.2})",
-            net_profit, current_balance, total_pnl
+            event = "arb_sim_fill",
+            market_id = %order.market_id,
+            cost = %total_cost,
+            profit = %net_profit,
+            fill_ratio = %fill_ratio,
+            balance = %current_balance,
+            "🎮 SIMULATED {}: ${:.2} profit | Balance: ${:.2} (P&L: ${:.2})",
+            if fully_filled { "FILL" } else { "PARTIAL FILL" },
+            net_profit,
+            current_balance,
+            total_pnl
         );
 
-        let order_results: Vec<OrderResult> = arb_op
-            .edges
+        let order_results: Vec<OrderResult> = legs
             .iter()
-            .map(|edge| OrderResult {
-                asset_id: edge.asset_id.clone(),
-                success: true,
-                order_id: Some(format!("SIM_{}", uuid::Uuid::new_v4())),
-                error: None,
+            .zip(&filled_sizes)
+            .enumerate()
+            .map(|(i, (leg, filled))| OrderResult {
+                asset_id: leg.asset_id.clone(),
+                success: !filled.is_zero(),
+                order_id: Some(
+                    order
+                        .order_ids
+                        .get(i)
+                        .cloned()
+                        .unwrap_or_else(|| self.sim_env.ids.next_id("SIM")),
+                ),
+                error: filled.is_zero().then(|| "No depth at price".to_string()),
+                matched_price: (!filled.is_zero()).then_some(leg.price),
             })
             .collect();
 
-        Ok(ExecutionResult {
+        let result = ExecutionResult {
+            success: fully_filled,
+            filled: fully_filled,
+            partial_fill: !fully_filled,
+            filled_amount: expected_payout,
+            total_cost,
+            // Simulated fills take the quoted price
+            expected_cost: total_cost,
+            realized_cost: total_cost,
+            realized_pnl,
+            fees_paid: fee_cost,
+            orders: order_results,
+            execution_time_ms: self.elapsed_ms(start_ms),
+            error_message: None,
+        };
+        (result, filled_sizes)
+    }
+
+    /// Orders for `order` placed in the simulator, not filled yet
+    fn placed_result(&self, order: &SimulatedOrder) -> ExecutionResult {
+        ExecutionResult {
             success: true,
-            filled: true,
+            filled: false,
             partial_fill: false,
-            filled_amount: arb_op.position_size,
-            total_cost: net_profit,
-            orders: order_results,
-            execution_time_ms: start_time.elapsed().as_millis() as u64,
+            filled_amount: Decimal::ZERO,
+            total_cost: Decimal::ZERO,
+            expected_cost: Decimal::ZERO,
+            realized_cost: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            orders: order
+                .legs
+                .iter()
+                .zip(&order.order_ids)
+                .map(|(leg, order_id)| OrderResult {
+                    asset_id: leg.asset_id.clone(),
+                    success: true,
+                    order_id: Some(order_id.clone()),
+                    error: None,
+                    matched_price: None,
+                })
+                .collect(),
+            execution_time_ms: 0,
             error_message: None,
-        })
+        }
+    }
+
+    /// Nothing bought, with `reason` as the error
+    fn unfilled_result(&self, start_ms: i64, reason: &str) -> ExecutionResult {
+        ExecutionResult {
+            success: false,
+            filled: false,
+            partial_fill: false,
+            filled_amount: Decimal::ZERO,
+            total_cost: Decimal::ZERO,
+            expected_cost: Decimal::ZERO,
+            realized_cost: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: self.elapsed_ms(start_ms),
+            error_message: Some(reason.to_string()),
+        }
+    }
+
+    #[inline]
+    fn elapsed_ms(&self, start_ms: i64) -> u64 {
+        (self.sim_env.clock.now_ms() - start_ms).max(0) as u64
+    }
+
+    /// Simulated fills so far (last 1000)
+    pub async fn get_trades(&self) -> Vec<SimulatedTrade> {
+        self.trades.read().await.iter().cloned().collect()
     }
 
-    #[allow(dead_code)]
     pub async fn get_simulated_balance(&self) -> Decimal {
         *self.simulated_balance.read().await
     }
 
-    #[allow(dead_code)]
     pub async fn get_simulated_pnl(&self) -> Decimal {
         *self.simulated_balance.read().await - self.initial_balance
     }
 }
 
+#[async_trait]
+impl Executor for SimulationExecutor {
+    async fn execute_arbitrage(
+        &self,
+        arb_op: &dyn Opportunity,
+        orderbook_manager: &OrderBookManager,
+    ) -> Result<ExecutionResult> {
+        self.simulate_arbitrage(arb_op, orderbook_manager).await
+    }
+
+    async fn available_balance(&self) -> Result<Decimal> {
+        self.settle_redemptions().await;
+        Ok(self.get_simulated_balance().await)
+    }
+
+    async fn settle_fills(&self, orderbook_manager: &OrderBookManager) -> Vec<SettledFill> {
+        SimulationExecutor::settle_fills(self, orderbook_manager).await
+    }
+
+    async fn simulated_account(&self) -> Option<(Decimal, Decimal)> {
+        self.settle_redemptions().await;
+        Some((
+            self.get_simulated_balance().await,
+            self.get_simulated_pnl().await,
+        ))
+    }
+}
+
 pub struct OrderExecutor {
     #[allow(dead_code)]
     config: Arc<crate::utils::Config>,
     clob_client: Client<Authenticated<Normal>>,
     signer: PrivateKeySigner,
+    /// Every order submission waits here first
+    rate_limiter: OrderRateLimiter,
+    /// Orders signed but held back in paper-live mode, the newest `MAX_PAPER_ORDERS`
+    paper_orders: parking_lot::Mutex<VecDeque<SignedOrder>>,
 }
 
 impl OrderExecutor {
@@ -220,44 +864,70 @@ impl OrderExecutor {
         let private_key = &config.credentials.private_key;
 
         // Parse the private key for signing with Polygon chain ID (137)
-        let mut signer: PrivateKeySigner =
-            private_key.parse().context("Failed to parse private key")?;
+        let mut signer: PrivateKeySigner = private_key
+            .parse()
+            .map_err(|_| HftpmError::Auth("Failed to parse private key".to_string()))?;
 
         // Set chain ID for Polygon
         signer.set_chain_id(Some(137));
 
+        let signing = ClobSigningConfig::from_credentials(&config.credentials)?;
+
         let clob_config = ClobConfig::default();
 
         // Create unauthenticated client first
-        let unauth_client = Client::new(&config.server.rest_url, clob_config)?;
-
-        // Authenticate the client
-        let clob_client: Client<Authenticated<Normal>> = unauth_client
+        let unauth_client = Client::new(&config.server.rest_url, clob_config).map_err(|e| {
+            HftpmError::Config(format!(
+                "Invalid CLOB url {}: {}",
+                config.server.rest_url, e
+            ))
+        })?;
+
+        // Authenticate the client, signing for the configured wallet type
+        let mut auth_builder = unauth_client
             .authentication_builder(&signer)
-            .authenticate()
-            .await
-            .context("Failed to authenticate CLOB client")?;
+            .signature_type(signing.signature_type);
+        if let Some(funder) = signing.funder {
+            auth_builder = auth_builder.funder(funder);
+        }
+
+        let clob_client: Client<Authenticated<Normal>> =
+            auth_builder.authenticate().await.map_err(|e| {
+                // Anything but a transport failure means the credentials were refused
+                match HftpmError::from_clob(e, "Failed to authenticate CLOB client") {
+                    HftpmError::Exchange(message) => HftpmError::Auth(message),
+                    other => other,
+                }
+            })?;
 
         info!("✅ Order executor initialized and authenticated");
-        info!("📝 Signature type: {}", config.credentials.signature_type);
+        match signing.funder {
+            Some(funder) => info!(
+                "📝 Signature type: {} | Funder: {}",
+                signing.signature_type, funder
+            ),
+            None => info!("📝 Signature type: {}", signing.signature_type),
+        }
 
         Ok(Self {
             config: Arc::new(config.clone()),
             clob_client,
             signer,
+            rate_limiter: OrderRateLimiter::new(config.execution.max_orders_per_sec),
+            paper_orders: parking_lot::Mutex::new(VecDeque::with_capacity(MAX_PAPER_ORDERS)),
         })
     }
 
-    async fn validate_prices(&self, arb_op: &ArbitrageOpportunity) -> Result<bool> {
+    async fn validate_prices(&self, arb_op: &dyn Opportunity) -> Result<bool> {
         let slippage_tolerance = self.config.trading.slippage_tolerance;
 
         // In production, re-fetch current orderbook prices here
         // For now, log the validation check
-        for edge in &arb_op.edges {
+        for leg in arb_op.legs() {
             info!(
                 "🔍 Validating price for {}: {:.4} (tolerance: {:.2}%)",
-                edge.asset_id,
-                edge.price,
+                leg.asset_id,
+                leg.price,
                 slippage_tolerance * Decimal::ONE_HUNDRED
             );
         }
@@ -267,17 +937,43 @@ impl OrderExecutor {
 
     pub async fn execute_arbitrage(
         &self,
-        arb_op: &ArbitrageOpportunity,
+        arb_op: &dyn Opportunity,
+        orderbook_manager: &OrderBookManager,
     ) -> Result<ExecutionResult> {
         let _timer = ScopedTimer::new("execute_arbitrage", None);
 
-        info!("🎯 Executing GTC arbitrage for market {}", arb_op.market_id);
+        info!(
+            event = "arb_execute_start",
+            market_id = %arb_op.market_id(),
+            edge = %arb_op.edge(),
+            "🎯 Executing GTC arbitrage for market {}",
+            arb_op.market_id()
+        );
+
+        let legs = arb_op.legs();
+        if legs.is_empty() {
+            warn!("⚠️ {} has no executable legs, skipping", arb_op.market_id());
+            return Ok(ExecutionResult {
+                success: false,
+                filled: false,
+                partial_fill: false,
+                filled_amount: Decimal::ZERO,
+                total_cost: Decimal::ZERO,
+                expected_cost: Decimal::ZERO,
+                realized_cost: Decimal::ZERO,
+                realized_pnl: Decimal::ZERO,
+                fees_paid: Decimal::ZERO,
+                orders: vec![],
+                execution_time_ms: 0,
+                error_message: Some("No executable legs".to_string()),
+            });
+        }
 
         // Validate prices haven't moved beyond slippage tolerance
         if !self.validate_prices(arb_op).await? {
             warn!(
                 "⚠️ Price slippage detected for {}, aborting execution",
-                arb_op.market_id
+                arb_op.market_id()
             );
             return Ok(ExecutionResult {
                 success: false,
@@ -285,6 +981,10 @@ impl OrderExecutor {
                 partial_fill: false,
                 filled_amount: Decimal::ZERO,
                 total_cost: Decimal::ZERO,
+                expected_cost: Decimal::ZERO,
+                realized_cost: Decimal::ZERO,
+                realized_pnl: Decimal::ZERO,
+                fees_paid: Decimal::ZERO,
                 orders: vec![],
                 execution_time_ms: 0,
                 error_message: Some("Price slippage exceeded tolerance".to_string()),
@@ -294,15 +994,30 @@ impl OrderExecutor {
         let start_time = Instant::now();
 
         // Create and submit GTC orders (fast ~50ms per order)
-        let signed_orders = self.create_signed_orders(arb_op).await?;
+        let signed_orders = self.create_signed_orders(&legs, orderbook_manager).await?;
 
         info!(
             "📦 Created {} GTC orders for {} (avoiding 500ms taker delay)",
             signed_orders.len(),
-            arb_op.market_id
+            arb_op.market_id()
         );
 
-        let submission_results = self.submit_orders_parallel(&signed_orders).await?;
+        if self.config.trading.trading_mode == TradingMode::PaperLive {
+            return Ok(self.hold_paper_orders(signed_orders, start_time));
+        }
+
+        // Retries must land while the detected prices are still trusted
+        let max_age_ms = self.config.execution.max_opportunity_age_ms;
+        let deadline = arb_op
+            .detected_at_ms()
+            .filter(|_| max_age_ms > 0)
+            .map(|detected_at| {
+                let age_ms = chrono::Utc::now().timestamp_millis() - detected_at;
+                let remaining_ms = (max_age_ms as i64 - age_ms).max(0) as u64;
+                Instant::now() + Duration::from_millis(remaining_ms)
+            });
+        let retry = RetryPolicy::from_config(&self.config.execution, deadline);
+        let submission_results = self.submit_orders_batch(&signed_orders, retry).await;
 
         let success_count = submission_results.iter().filter(|r| r.success).count();
         let filled_count = submission_results
@@ -310,14 +1025,18 @@ impl OrderExecutor {
             .filter(|r| r.success && r.order_id.is_some())
             .count();
 
-        // For GTC orders, we need to wait briefly for fills
+        // With the user channel on, fills and cancels arrive asynchronously and
+        // feed positions from there; orders are left resting
+        let awaiting_fills = self.config.user_channel_enabled();
+
+        // Otherwise wait briefly for GTC fills, then cancel whatever is left
         // Short-window arb opportunities typically have immediate liquidity
-        if success_count > 0 {
+        if success_count > 0 && !awaiting_fills {
             info!("⏳ Waiting 200ms for GTC orders to fill...");
             tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-            
+
             // Cancel any unfilled orders to avoid stale positions
-            match self.cancel_open_orders(&arb_op.market_id).await {
+            match self.cancel_open_orders(arb_op.market_id()).await {
                 Ok(cancelled) => {
                     if cancelled > 0 {
                         info!("🗑️  Cancelled {} unfilled GTC orders", cancelled);
@@ -333,35 +1052,56 @@ impl OrderExecutor {
             .iter()
             .filter(|r| r.success)
             .map(|r| {
-                arb_op
-                    .edges
-                    .iter()
-                    .find(|e| e.asset_id == r.asset_id)
-                    .map(|e| e.expected_cost)
+                legs.iter()
+                    .find(|leg| leg.asset_id == r.asset_id)
+                    .map(|leg| leg.signed_cost())
                     .unwrap_or(Decimal::ZERO)
             })
             .sum::<Decimal>();
 
-        let filled_amount = arb_op
-            .edges
-            .iter()
-            .filter_map(|edge| {
-                submission_results
-                    .iter()
-                    .find(|r| r.asset_id == edge.asset_id && r.success)
-                    .and_then(|r| r.order_id.as_ref())
-                    .map(|_| edge.size)
-            })
-            .sum::<Decimal>();
+        let filled_amount = if awaiting_fills {
+            Decimal::ZERO
+        } else {
+            legs.iter()
+                .filter_map(|leg| {
+                    submission_results
+                        .iter()
+                        .find(|r| r.asset_id == leg.asset_id && r.success)
+                        .and_then(|r| r.order_id.as_ref())
+                        .map(|_| leg.size)
+                })
+                .sum::<Decimal>()
+        };
 
         let execution_time = start_time.elapsed();
         let execution_time_ms = execution_time.as_millis() as u64;
 
+        // Resting orders haven't filled yet; the user channel reports their cost
+        let (expected_cost, filled_cost) = if awaiting_fills {
+            (Decimal::ZERO, Decimal::ZERO)
+        } else {
+            accepted_leg_costs(&legs, &signed_orders, &submission_results)
+        };
+
         let all_success = success_count == signed_orders.len();
-        let all_filled = filled_count == signed_orders.len();
-        let partial_fill = success_count > 0 && !all_filled;
+        // Resting orders haven't filled yet; the user channel will report it
+        let all_filled = !awaiting_fills && filled_count == signed_orders.len();
+        let partial_fill = !awaiting_fills && success_count > 0 && !all_filled;
+        let realized_pnl = arb_op.realized_pnl(filled_cost, all_filled);
+        // Fees are only charged on complete sets (see `realized_pnl`)
+        let fees_paid = if all_filled {
+            arb_op.fee_cost()
+        } else {
+            Decimal::ZERO
+        };
 
         info!(
+            event = "arb_executed",
+            market_id = %arb_op.market_id(),
+            orders_submitted = success_count,
+            orders_total = signed_orders.len(),
+            cost = %total_cost,
+            latency_ms = execution_time_ms,
             "✅ GTC Execution: {}/{} orders submitted, {:.2}ms total",
             success_count,
             signed_orders.len(),
@@ -374,6 +1114,10 @@ impl OrderExecutor {
             partial_fill,
             filled_amount,
             total_cost,
+            expected_cost,
+            realized_cost: filled_cost,
+            realized_pnl,
+            fees_paid,
             orders: submission_results,
             execution_time_ms,
             error_message: if !all_success {
@@ -388,149 +1132,279 @@ impl OrderExecutor {
         })
     }
 
+    /// Log the orders paper-live mode would have submitted and keep them for
+    /// inspection. Nothing was submitted, so nothing filled.
+    fn hold_paper_orders(
+        &self,
+        signed_orders: Vec<SignedOrder>,
+        start_time: Instant,
+    ) -> ExecutionResult {
+        for order in &signed_orders {
+            info!(
+                event = "paper_order",
+                asset_id = %order.asset_id,
+                "📝 [PAPER] Would submit {:?} {} @ {} for {} ({})",
+                order.side,
+                order.size,
+                order.price,
+                order.asset_id,
+                order.order_hash
+            );
+        }
+
+        let count = signed_orders.len();
+        let mut paper_orders = self.paper_orders.lock();
+        paper_orders.extend(signed_orders);
+        while paper_orders.len() > MAX_PAPER_ORDERS {
+            paper_orders.pop_front();
+        }
+        drop(paper_orders);
+
+        ExecutionResult {
+            success: false,
+            filled: false,
+            partial_fill: false,
+            filled_amount: Decimal::ZERO,
+            total_cost: Decimal::ZERO,
+            expected_cost: Decimal::ZERO,
+            realized_cost: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: start_time.elapsed().as_millis() as u64,
+            error_message: Some(format!(
+                "Paper-live: {} orders signed, not submitted",
+                count
+            )),
+        }
+    }
+
+    /// The last `limit` orders signed in paper-live mode, oldest first
+    pub fn paper_orders(&self, limit: usize) -> Vec<SignedOrder> {
+        let paper_orders = self.paper_orders.lock();
+        let skip = paper_orders.len().saturating_sub(limit);
+        paper_orders.iter().skip(skip).cloned().collect()
+    }
+
     #[inline]
     async fn create_signed_orders(
         &self,
-        arb_op: &ArbitrageOpportunity,
+        legs: &[OpportunityLeg],
+        orderbook_manager: &OrderBookManager,
     ) -> Result<Vec<SignedOrder>> {
-        let mut signed_orders = Vec::with_capacity(arb_op.edges.len());
-
-        for edge in &arb_op.edges {
-            let price = edge.price;
-            let size = edge.size;
+        let mut signed_orders = Vec::with_capacity(legs.len());
+
+        for leg in legs {
+            // Off-tick prices are rejected by the exchange
+            let price = orderbook_manager.round_to_tick(&leg.market_id, leg.price, leg.side);
+            let size = leg.size;
+            let side = match leg.side {
+                ArbSide::Buy => Side::Buy,
+                ArbSide::Sell => Side::Sell,
+            };
 
             // Use GTC (Good Till Cancelled) instead of FOK to avoid 500ms taker delay
             // By posting at the current ask price, we act as an aggressive maker
             let signable_order = self
                 .clob_client
                 .limit_order()
-                .token_id(&edge.asset_id)
+                .token_id(&leg.asset_id)
                 .size(size)
                 .price(price)
-                .side(Side::Buy)
+                .side(side)
                 .order_type(OrderType::GTC) // GTC = 50ms vs FOK = 500ms
                 .build()
                 .await
-                .context("Failed to build order")?;
+                .map_err(|e| HftpmError::from_clob(e, "Failed to build order"))?;
 
             let sdk_signed_order: SdkSignedOrder = self
                 .clob_client
                 .sign(&self.signer, signable_order)
                 .await
-                .context("Failed to sign order")?;
+                .map_err(|e| HftpmError::from_clob(e, "Failed to sign order"))?;
 
             let order_hash = self.calculate_order_hash(&sdk_signed_order);
 
             signed_orders.push(SignedOrder {
-                asset_id: edge.asset_id.clone(),
+                asset_id: leg.asset_id.clone(),
+                side,
                 price,
                 size,
                 order_hash,
                 created_at: Instant::now(),
+                signed: Some(Arc::new(sdk_signed_order)),
             });
         }
 
         Ok(signed_orders)
     }
 
-    async fn submit_orders_parallel(
+    /// Submit all of an opportunity's orders as one batch; see `batch::submit_orders_batch`
+    pub async fn submit_orders_batch(
         &self,
         signed_orders: &[SignedOrder],
-    ) -> Result<Vec<OrderResult>> {
-        let futures: Vec<_> = signed_orders
-            .iter()
-            .map(|signed_order| self.submit_single_order(signed_order))
-            .collect();
+        retry: RetryPolicy,
+    ) -> Vec<OrderResult> {
+        batch::submit_orders_batch(self, &self.rate_limiter, signed_orders, retry).await
+    }
+
+    pub async fn cancel_open_orders(&self, _market_id: &str) -> Result<usize> {
+        info!("🗑️  Cancelling orders");
 
-        let results = join_all(futures).await;
+        let response: CancelOrdersResponse = self
+            .clob_client
+            .cancel_all_orders()
+            .await
+            .map_err(|e| HftpmError::from_clob(e, "Failed to cancel orders"))?;
 
-        Ok(results)
+        let cancel_count = response.canceled.len();
+        info!("✅ Cancelled {} orders", cancel_count);
+
+        Ok(cancel_count)
     }
 
-    async fn submit_single_order(&self, signed_order: &SignedOrder) -> OrderResult {
-        // Re-create and sign the order for submission
-        // Use GTC to avoid 500ms taker delay
-        let signable_order = match self
+    /// Net positions held by this account in unresolved markets, rebuilt
+    /// from its fill history
+    pub async fn fetch_open_positions(&self) -> Result<Vec<ExchangePosition>> {
+        let request = TradesRequest::default();
+        let trades: Vec<TradeResponse> = self
             .clob_client
-            .limit_order()
-            .token_id(&signed_order.asset_id)
-            .size(signed_order.size)
-            .price(signed_order.price)
-            .side(Side::Buy)
-            .order_type(OrderType::GTC) // GTC = 50ms vs FOK = 500ms
-            .build()
+            .stream_data(|client, cursor| client.trades(&request, cursor))
+            .try_collect()
             .await
-        {
-            Ok(order) => order,
-            Err(e) => {
-                error!(
-                    "❌ Failed to build order: {} - {}",
-                    signed_order.asset_id, e
-                );
-                return OrderResult {
-                    asset_id: signed_order.asset_id.clone(),
-                    success: false,
-                    order_id: None,
-                    error: Some(e.to_string()),
-                };
-            }
-        };
+            .map_err(|e| HftpmError::from_clob(e, "Failed to fetch trades"))?;
+
+        #[derive(Default)]
+        struct Holding {
+            market_id: String,
+            outcome: String,
+            net_size: Decimal,
+            bought_cost: Decimal,
+            bought_size: Decimal,
+        }
 
-        let sdk_signed: SdkSignedOrder =
-            match self.clob_client.sign(&self.signer, signable_order).await {
-                Ok(signed) => signed,
-                Err(e) => {
-                    error!("❌ Failed to sign order: {} - {}", signed_order.asset_id, e);
-                    return OrderResult {
-                        asset_id: signed_order.asset_id.clone(),
-                        success: false,
-                        order_id: None,
-                        error: Some(e.to_string()),
-                    };
-                }
+        let address = self.clob_client.address();
+        let mut holdings: HashMap<String, Holding> = HashMap::new();
+
+        for trade in &trades {
+            let fills: Vec<(&str, &str, Side, Decimal, Decimal)> = match trade.trader_side {
+                TraderSide::Taker => vec![(
+                    trade.asset_id.as_str(),
+                    trade.outcome.as_str(),
+                    trade.side,
+                    trade.size,
+                    trade.price,
+                )],
+                _ => trade
+                    .maker_orders
+                    .iter()
+                    .filter(|maker| maker.maker_address == address)
+                    .map(|maker| {
+                        (
+                            maker.asset_id.as_str(),
+                            maker.outcome.as_str(),
+                            maker.side,
+                            maker.matched_amount,
+                            maker.price,
+                        )
+                    })
+                    .collect(),
             };
 
-        let response: Result<Vec<PostOrderResponse>, _> =
-            self.clob_client.post_order(sdk_signed).await;
-
-        match response {
-            Ok(responses) => {
-                info!(
-                    "✅ Order submitted: {} - {:?}",
-                    signed_order.asset_id, responses
-                );
-                OrderResult {
-                    asset_id: signed_order.asset_id.clone(),
-                    success: true,
-                    order_id: Some(signed_order.order_hash.clone()),
-                    error: None,
+            for (asset_id, outcome, side, size, price) in fills {
+                let holding = holdings
+                    .entry(asset_id.to_string())
+                    .or_insert_with(|| Holding {
+                        market_id: trade.market.clone(),
+                        outcome: outcome.to_string(),
+                        ..Default::default()
+                    });
+
+                match side {
+                    Side::Buy => {
+                        holding.net_size += size;
+                        holding.bought_cost += size * price;
+                        holding.bought_size += size;
+                    }
+                    Side::Sell => holding.net_size -= size,
+                    _ => {}
                 }
             }
-            Err(e) => {
-                error!("❌ Order failed: {} - {}", signed_order.asset_id, e);
-                OrderResult {
-                    asset_id: signed_order.asset_id.clone(),
-                    success: false,
-                    order_id: None,
-                    error: Some(e.to_string()),
+        }
+
+        holdings.retain(|_, holding| holding.net_size > Decimal::ZERO);
+
+        // Fill history includes markets that have since resolved; their tokens
+        // are redeemed or worthless and no longer count toward exposure
+        let market_ids: HashSet<String> = holdings
+            .values()
+            .map(|holding| holding.market_id.clone())
+            .collect();
+        let mut resolved = HashSet::new();
+        for market_id in market_ids {
+            match self.clob_client.market(&market_id).await {
+                Ok(market) if market.closed => {
+                    resolved.insert(market_id);
                 }
+                Ok(_) => {}
+                Err(e) => warn!(
+                    "Failed to check whether market {} has resolved, keeping its position: {}",
+                    market_id, e
+                ),
             }
         }
-    }
 
-    pub async fn cancel_open_orders(&self, _market_id: &str) -> Result<usize> {
-        info!("🗑️  Cancelling orders");
+        let positions: Vec<ExchangePosition> = holdings
+            .into_iter()
+            .filter(|(_, holding)| !resolved.contains(&holding.market_id))
+            .map(|(asset_id, holding)| ExchangePosition {
+                market_id: holding.market_id,
+                asset_id,
+                outcome: holding.outcome,
+                size: holding.net_size,
+                avg_price: if holding.bought_size > Decimal::ZERO {
+                    holding.bought_cost / holding.bought_size
+                } else {
+                    Decimal::ZERO
+                },
+            })
+            .collect();
 
-        let response: CancelOrdersResponse = self
+        info!(
+            "📦 Found {} open positions across {} fills",
+            positions.len(),
+            trades.len()
+        );
+
+        Ok(positions)
+    }
+
+    /// Orders still resting on the book for this account
+    pub async fn fetch_open_orders(&self) -> Result<Vec<ExchangeOpenOrder>> {
+        let request = OrdersRequest::default();
+        let orders: Vec<OpenOrderResponse> = self
             .clob_client
-            .cancel_all_orders()
+            .stream_data(|client, cursor| client.orders(&request, cursor))
+            .try_collect()
             .await
-            .context("Failed to cancel orders")?;
+            .map_err(|e| HftpmError::from_clob(e, "Failed to fetch open orders"))?;
+
+        let open_orders: Vec<ExchangeOpenOrder> = orders
+            .into_iter()
+            .map(|order| ExchangeOpenOrder {
+                order_id: order.id.to_string(),
+                market_id: order.market,
+                asset_id: order.asset_id,
+                outcome: order.outcome,
+                is_buy: order.side == Side::Buy,
+                price: order.price,
+                remaining_size: (order.original_size - order.size_matched).max(Decimal::ZERO),
+            })
+            .collect();
 
-        let cancel_count = response.canceled.len();
-        info!("✅ Cancelled {} orders", cancel_count);
+        info!("📋 Found {} open orders", open_orders.len());
 
-        Ok(cancel_count)
+        Ok(open_orders)
     }
 
     pub async fn get_balance(&self) -> Result<Decimal> {
@@ -541,7 +1415,7 @@ impl OrderExecutor {
             .clob_client
             .balance_allowance(&request)
             .await
-            .context("Failed to get balance")?;
+            .map_err(|e| HftpmError::from_clob(e, "Failed to get balance"))?;
 
         Ok(response.balance)
     }
@@ -571,3 +1445,89 @@ impl OrderExecutor {
         hex::encode(result)
     }
 }
+
+/// Cost of the legs the exchange accepted: at the prices seen when the
+/// opportunity was detected, and at the prices actually paid. A leg matched on
+/// placement pays its matched price; one left resting fills at the limit it was
+/// sent with, which can differ from the detected price once rounded to the tick.
+pub fn accepted_leg_costs(
+    legs: &[OpportunityLeg],
+    signed_orders: &[SignedOrder],
+    results: &[OrderResult],
+) -> (Decimal, Decimal) {
+    let mut expected = Decimal::ZERO;
+    let mut realized = Decimal::ZERO;
+
+    for result in results.iter().filter(|r| r.success) {
+        let Some(leg) = legs.iter().find(|leg| leg.asset_id == result.asset_id) else {
+            continue;
+        };
+        let sent_price = signed_orders
+            .iter()
+            .find(|order| order.asset_id == result.asset_id)
+            .map_or(leg.price, |order| order.price);
+        let paid = result.matched_price.unwrap_or(sent_price) * leg.size;
+
+        expected += leg.signed_cost();
+        realized += match leg.side {
+            ArbSide::Buy => paid,
+            ArbSide::Sell => -paid,
+        };
+    }
+
+    (expected, realized)
+}
+
+/// A copy of `signed` to post again: same salt and signature, so the exchange
+/// sees the same order
+fn resend(signed: &SdkSignedOrder) -> Result<SdkSignedOrder> {
+    SdkSignedOrderBuilder::default()
+        .order(signed.order.clone())
+        .signature(signed.signature)
+        .order_type(signed.order_type)
+        .owner(signed.owner)
+        .build()
+        .map_err(|e| HftpmError::from_clob(e, "Failed to copy signed order"))
+}
+
+#[async_trait]
+impl OrderSubmitter for OrderExecutor {
+    /// Sends the orders as signed in `create_signed_orders`; an unsigned one
+    /// sends none of them rather than a lone leg
+    async fn post_orders(&self, orders: &[SignedOrder]) -> Result<Vec<PostOrderResponse>> {
+        let sdk_orders = orders
+            .iter()
+            .map(|signed_order| match &signed_order.signed {
+                Some(sdk_order) => resend(sdk_order),
+                None => Err(HftpmError::Exchange(format!(
+                    "Order for {} was never signed",
+                    signed_order.asset_id
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.clob_client
+            .post_orders(sdk_orders)
+            .await
+            .map_err(|e| HftpmError::from_clob(e, "Failed to post orders"))
+    }
+}
+
+#[async_trait]
+impl Executor for OrderExecutor {
+    async fn execute_arbitrage(
+        &self,
+        arb_op: &dyn Opportunity,
+        orderbook_manager: &OrderBookManager,
+    ) -> Result<ExecutionResult> {
+        OrderExecutor::execute_arbitrage(self, arb_op, orderbook_manager).await
+    }
+
+    async fn available_balance(&self) -> Result<Decimal> {
+        self.get_balance().await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        OrderExecutor::health_check(self).await
+    }
+}
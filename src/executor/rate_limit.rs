@@ -0,0 +1,79 @@
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+/// Token bucket shared by every order submission.
+///
+/// Legs of one arb and back-to-back arbs all draw from the same bucket, so a burst
+/// of detections can't push the API key over the CLOB's order-rate limit. When the
+/// bucket is empty callers queue until a token refills rather than being rejected.
+pub struct OrderRateLimiter {
+    refill_per_sec: f64,
+    capacity: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+    /// Set after a 429; no token is handed out before it
+    paused_until: Option<Instant>,
+}
+
+impl OrderRateLimiter {
+    pub fn new(max_orders_per_sec: u32) -> Self {
+        let capacity = max_orders_per_sec.max(1) as f64;
+
+        Self {
+            refill_per_sec: capacity,
+            capacity,
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Wait for a submission slot. Waiters are served in arrival order because
+    /// the bucket lock is held while sleeping.
+    pub async fn acquire(&self) {
+        let mut state = self.state.lock().await;
+
+        if let Some(until) = state.paused_until.take() {
+            tokio::time::sleep_until(until).await;
+        }
+
+        loop {
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+            state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+            state.last_refill = now;
+
+            if state.tokens >= 1.0 {
+                state.tokens -= 1.0;
+                return;
+            }
+
+            let wait = Duration::from_secs_f64((1.0 - state.tokens) / self.refill_per_sec);
+            debug!("🚦 Order rate limit: queueing submission for {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// The exchange rejected a submission with 429: drain the bucket and hold
+    /// every submission for `backoff`
+    pub async fn back_off(&self, backoff: Duration) {
+        let mut state = self.state.lock().await;
+        let until = Instant::now() + backoff;
+
+        warn!(
+            "🚦 CLOB rate limited us (429), pausing submissions for {:?}",
+            backoff
+        );
+        state.tokens = 0.0;
+        state.last_refill = until;
+        state.paused_until = Some(state.paused_until.map_or(until, |p| p.max(until)));
+    }
+}
@@ -1,12 +1,16 @@
-use anyhow::{Context, Result};
-use chrono::{DateTime, Timelike, Utc};
+use crate::error::{HftpmError, Result};
+use crate::utils::saturating_i64;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use futures::future::join_all;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventInfo {
@@ -38,10 +42,15 @@ pub struct EventMarket {
     pub condition_id: String,
     #[serde(default)]
     pub description: Option<String>,
-    #[serde(default)]
-    pub outcomes: Option<String>, // JSON string: "[\"Up\", \"Down\"]"
-    #[serde(rename = "clobTokenIds", default)]
-    pub clob_token_ids: Option<String>, // JSON string with token IDs
+    /// Outcome names, e.g. `["Up", "Down"]`; empty if missing or unparseable
+    #[serde(default, deserialize_with = "deserialize_lenient_list")]
+    pub outcomes: Vec<String>,
+    #[serde(
+        rename = "clobTokenIds",
+        default,
+        deserialize_with = "deserialize_lenient_list"
+    )]
+    pub clob_token_ids: Vec<String>,
     #[serde(rename = "endDate")]
     pub end_date: Option<String>,
     #[serde(rename = "volume24hr", default)]
@@ -59,24 +68,22 @@ pub struct EventMarket {
 impl EventMarket {
     /// Convert EventMarket to the standard Market struct
     pub fn to_market(&self, event_id: &str, category: Option<String>) -> Option<Market> {
-        // Parse outcomes from JSON string
-        let outcomes = self.outcomes.as_ref().and_then(|s| {
-            serde_json::from_str::<Vec<String>>(s).ok()
-        }).unwrap_or_default();
-        
+        let outcomes = self.outcomes.clone();
+        let token_ids = self.clob_token_ids.clone();
+
         if outcomes.is_empty() {
             return None;
         }
-        
-        // Parse token IDs from JSON string
-        let token_ids = self.clob_token_ids.as_ref().and_then(|s| {
-            serde_json::from_str::<Vec<String>>(s).ok()
-        }).unwrap_or_default();
-        
+
         if token_ids.len() != outcomes.len() {
             return None;
         }
-        
+
+        if let Some(problem) = token_id_problem(&token_ids) {
+            debug!("Skipping event market with {}: {}", problem, self.question);
+            return None;
+        }
+
         let outcome_structs: Vec<Outcome> = outcomes
             .into_iter()
             .enumerate()
@@ -86,7 +93,7 @@ impl EventMarket {
                 token_id: token_ids.get(i).cloned().unwrap_or_default(),
             })
             .collect();
-        
+
         Some(Market {
             id: self.id.clone(),
             question: self.question.clone(),
@@ -163,6 +170,36 @@ impl Market {
     pub fn event_id(&self) -> Option<&str> {
         self.events.first().map(|e| e.id.as_str())
     }
+
+    /// Find the outcome (e.g. "Yes", "Up") traded under a CLOB token id.
+    /// Falls back to the token's position in `assets_ids` when the outcome
+    /// entries were parsed without their token ids (/markets endpoint).
+    pub fn outcome_for_token(&self, token_id: &str) -> Option<&Outcome> {
+        self.outcomes
+            .iter()
+            .find(|o| !o.token_id.is_empty() && o.token_id == token_id)
+            .or_else(|| {
+                self.assets_ids
+                    .iter()
+                    .position(|id| id == token_id)
+                    .and_then(|i| self.outcomes.get(i))
+            })
+    }
+}
+
+/// Why `token_ids` can't key order books, if they can't: an empty id, or one
+/// id shared by two outcomes
+fn token_id_problem(token_ids: &[String]) -> Option<&'static str> {
+    if token_ids.iter().any(|id| id.trim().is_empty()) {
+        return Some("an empty token id");
+    }
+
+    let mut seen = HashSet::new();
+    if !token_ids.iter().all(|id| seen.insert(id)) {
+        return Some("duplicate token ids");
+    }
+
+    None
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -172,48 +209,88 @@ pub struct Outcome {
     pub token_id: String,
 }
 
-// Deserialize outcomes from JSON string like "[\"Yes\", \"No\"]"
+/// Gamma string-encodes list fields on some endpoints (`"[\"Yes\", \"No\"]"`)
+/// and sends plain arrays on others; the on-disk market cache stores plain
+/// arrays. A plain array is tried first.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EncodedList<T> {
+    Plain(Vec<T>),
+    Json(String),
+}
+
+impl<T: serde::de::DeserializeOwned> EncodedList<T> {
+    fn decode(self) -> serde_json::Result<Vec<T>> {
+        match self {
+            EncodedList::Plain(items) => Ok(items),
+            EncodedList::Json(s) => serde_json::from_str(&s),
+        }
+    }
+}
+
+/// Gamma lists outcome names; the market cache stores full `Outcome`s
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OutcomeEntry {
+    Full(Outcome),
+    Name(String),
+}
+
+// Deserialize outcomes from a list of names, plain or string-encoded
 fn deserialize_outcomes<'de, D>(deserializer: D) -> Result<Vec<Outcome>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     use serde::de::Error;
 
-    let s: Option<String> = Option::deserialize(deserializer)?;
-    if let Some(s) = s {
-        let outcome_names: Vec<String> = serde_json::from_str(&s)
-            .map_err(|e| Error::custom(format!("Failed to parse outcomes: {}", e)))?;
-
-        Ok(outcome_names
-            .into_iter()
-            .enumerate()
-            .map(|(i, name)| Outcome {
+    let Some(encoded) = Option::<EncodedList<OutcomeEntry>>::deserialize(deserializer)? else {
+        return Ok(Vec::new());
+    };
+    let entries = encoded
+        .decode()
+        .map_err(|e| Error::custom(format!("Failed to parse outcomes: {}", e)))?;
+
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, entry)| match entry {
+            OutcomeEntry::Full(outcome) => outcome,
+            OutcomeEntry::Name(name) => Outcome {
                 id: i.to_string(),
                 name,
                 token_id: String::new(),
-            })
-            .collect())
-    } else {
-        Ok(Vec::new())
-    }
+            },
+        })
+        .collect())
 }
 
-// Deserialize token IDs from JSON string like "[\"123...\", \"456...\"]"
+// Deserialize token IDs from a list, plain or string-encoded
 fn deserialize_token_ids<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     use serde::de::Error;
 
-    let s: Option<String> = Option::deserialize(deserializer)?;
-    if let Some(s) = s {
-        serde_json::from_str(&s)
-            .map_err(|e| Error::custom(format!("Failed to parse token IDs: {}", e)))
-    } else {
-        Ok(Vec::new())
+    match Option::<EncodedList<String>>::deserialize(deserializer)? {
+        Some(encoded) => encoded
+            .decode()
+            .map_err(|e| Error::custom(format!("Failed to parse token IDs: {}", e))),
+        None => Ok(Vec::new()),
     }
 }
 
+// Like `deserialize_token_ids`, but an unparseable list comes out empty instead
+// of failing the whole event, leaving `EventMarket::to_market` to skip the market
+fn deserialize_lenient_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let encoded = Option::<EncodedList<String>>::deserialize(deserializer).unwrap_or(None);
+    Ok(encoded
+        .and_then(|encoded| encoded.decode().ok())
+        .unwrap_or_default())
+}
+
 // ============================================================================
 // Short-Window (15m up/down) Market Detection
 // ============================================================================
@@ -235,12 +312,121 @@ static UP_DOWN_PATTERNS: Lazy<Regex> = Lazy::new(|| {
     .expect("Invalid regex pattern")
 });
 
+/// End dates further out than this are treated as bogus rather than as a market
+/// resolving in centuries
+const MAX_MINUTES_TO_EXPIRY: i64 = 10 * 365 * 24 * 60;
+
+/// Farthest ahead `short_window_event_slugs` looks, however large the horizon asked for
+const MAX_SLUG_HORIZON_MINUTES: u64 = 24 * 60;
+
+/// Offset-aware end_date formats, tried in order after RFC 3339
+const OFFSET_DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f%#z",
+    "%Y-%m-%d %H:%M:%S%.f%#z",
+    "%Y-%m-%dT%H:%M%#z",
+];
+
+/// Offset-less end_date formats, interpreted as UTC
+const NAIVE_DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.fZ",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%MZ",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// Parse a Gamma end_date (ISO 8601 with or without offset, bare date, or Unix
+/// seconds/milliseconds). A miss hides the market from short-window detection,
+/// so failures are logged.
+pub fn parse_end_date(end_date: &str) -> Option<DateTime<Utc>> {
+    let s = end_date.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    for format in OFFSET_DATE_FORMATS {
+        if let Ok(dt) = DateTime::parse_from_str(s, format) {
+            return Some(dt.with_timezone(&Utc));
+        }
+    }
+
+    for format in NAIVE_DATE_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+            return Some(dt.and_utc());
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+    }
+
+    // Unix timestamp: 13+ digits are milliseconds, otherwise seconds
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        let ts: i64 = s.parse().ok()?;
+        return if s.len() >= 13 {
+            DateTime::from_timestamp_millis(ts)
+        } else {
+            DateTime::from_timestamp(ts, 0)
+        };
+    }
+
+    debug!("Unparseable end_date {:?}, no known format matched", end_date);
+    None
+}
+
+/// Whole minutes from `now` until `expiry`, or None if it has already passed or
+/// is beyond `MAX_MINUTES_TO_EXPIRY`
+fn minutes_until(expiry: DateTime<Utc>, now: DateTime<Utc>) -> Option<i64> {
+    let minutes = expiry.signed_duration_since(now).num_minutes();
+    if !(0..=MAX_MINUTES_TO_EXPIRY).contains(&minutes) {
+        debug!("Ignoring end_date {} ({} min from now)", expiry, minutes);
+        return None;
+    }
+    Some(minutes)
+}
+
+/// Crypto tickers that have 15m up/down markets
+const TICKERS_15M: &[&str] = &[
+    "btc", "eth", "sol", "link", "doge", "xrp", "sui", "pepe", "avax", "ada", "bnb", "pol",
+    "near", "apt", "hype",
+];
+
+/// Crypto tickers that have 1h up/down markets
+const TICKERS_1H: &[&str] = &["btc", "eth", "sol", "link", "doge", "xrp", "sui", "pepe"];
+
+/// Event slugs for every 15m and 1h up/down window live now or starting within
+/// `horizon_minutes` (at most a day). Slugs are keyed by the window's start
+/// timestamp, so we walk each boundary from the current window up to `now + horizon`.
+pub fn short_window_event_slugs(now: DateTime<Utc>, horizon_minutes: u64) -> Vec<String> {
+    let horizon_minutes = horizon_minutes.min(MAX_SLUG_HORIZON_MINUTES) as i64;
+    let horizon_end = now.timestamp() + horizon_minutes * 60;
+    let mut slugs = Vec::new();
+
+    for (interval_secs, label, tickers) in [(15 * 60, "15m", TICKERS_15M), (3600, "1h", TICKERS_1H)]
+    {
+        let mut window_start = now.timestamp() - now.timestamp().rem_euclid(interval_secs);
+        loop {
+            for ticker in tickers {
+                slugs.push(format!("{}-updown-{}-{}", ticker, label, window_start));
+            }
+            window_start += interval_secs;
+            if window_start >= horizon_end {
+                break;
+            }
+        }
+    }
+
+    slugs
+}
+
 /// Result of short-window market analysis
 #[derive(Debug, Clone)]
 pub struct ShortWindowInfo {
     /// Whether this qualifies as a short-window market
     pub is_short_window: bool,
-    /// Minutes until market resolution (if end_date is set)
+    /// Minutes until market resolution; None if end_date is unset, unparseable,
+    /// already past, or implausibly far out
     pub minutes_to_expiry: Option<i64>,
     /// Whether the question/slug matches up/down patterns
     pub matches_pattern: bool,
@@ -253,25 +439,16 @@ impl Market {
         let now = Utc::now();
 
         // Check end_date proximity
-        let minutes_to_expiry = self.end_date.as_ref().and_then(|end_date_str| {
-            // Try parsing ISO 8601 format
-            DateTime::parse_from_rfc3339(end_date_str)
-                .ok()
-                .or_else(|| {
-                    // Try other common formats
-                    DateTime::parse_from_str(end_date_str, "%Y-%m-%dT%H:%M:%S%.fZ").ok()
-                })
-                .or_else(|| DateTime::parse_from_str(end_date_str, "%Y-%m-%d %H:%M:%S").ok())
-                .map(|dt| {
-                    let expiry = dt.with_timezone(&Utc);
-                    let duration = expiry.signed_duration_since(now);
-                    duration.num_minutes()
-                })
-        });
+        let minutes_to_expiry = self
+            .end_date
+            .as_deref()
+            .and_then(parse_end_date)
+            .and_then(|expiry| minutes_until(expiry, now));
 
         // Check if within short window and above minimum buffer
         let in_short_window = minutes_to_expiry.is_some_and(|mins| {
-            mins > config.min_minutes_to_expiry as i64 && mins <= config.short_window_minutes as i64
+            mins > saturating_i64(config.min_minutes_to_expiry)
+                && mins <= saturating_i64(config.short_window_minutes)
         });
 
         // Check question/slug for up/down patterns
@@ -292,24 +469,34 @@ impl Market {
     }
 }
 
+/// The last successful `/markets` fetch, as persisted by `GammaClient::save_cache`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketCacheFile {
+    /// Unix seconds when the markets were fetched
+    pub fetched_at: i64,
+    pub markets: Vec<Market>,
+}
+
 pub struct GammaClient {
     client: Arc<Client>,
     base_url: String,
     markets_cache: Arc<tokio::sync::RwLock<HashMap<String, Market>>>,
+    /// Markets from the last successful fetch, in API order, for the on-disk cache
+    last_fetch: Arc<tokio::sync::RwLock<Option<MarketCacheFile>>>,
 }
 
 impl GammaClient {
-    pub fn new(base_url: &str) -> Self {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
+    /// Fails with `Config` if the HTTP client can't be built from `execution`
+    pub fn new(base_url: &str, execution: &crate::utils::ExecutionConfig) -> Result<Self> {
+        let client = crate::utils::build_http_client(execution)
+            .map_err(|e| HftpmError::Config(format!("{:#}", e)))?;
 
-        Self {
+        Ok(Self {
             client: Arc::new(client),
             base_url: base_url.to_string(),
             markets_cache: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
-        }
+            last_fetch: Arc::new(tokio::sync::RwLock::new(None)),
+        })
     }
 
     pub async fn fetch_markets(
@@ -326,21 +513,21 @@ impl GammaClient {
 
         debug!("Fetching markets from {}", url);
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to fetch markets from Gamma API")?;
+        let response = self.client.get(&url).send().await.map_err(|e| {
+            HftpmError::Network(format!("Failed to fetch markets from Gamma API: {}", e))
+        })?;
 
         if !response.status().is_success() {
-            anyhow::bail!("Gamma API returned status: {}", response.status());
+            return Err(HftpmError::from_status(
+                response.status(),
+                "Gamma API /markets",
+            ));
         }
 
         let markets: Vec<Market> = response
             .json()
             .await
-            .context("Failed to parse Gamma API response")?;
+            .map_err(|e| HftpmError::Parse(format!("Failed to parse Gamma API response: {}", e)))?;
 
         // Note: We don't limit here - let the caller decide how many to use
         // The config has max_order_books in trading section for that
@@ -358,9 +545,137 @@ impl GammaClient {
         }
         drop(cache);
 
+        *self.last_fetch.write().await = Some(MarketCacheFile {
+            fetched_at: Utc::now().timestamp(),
+            markets: filtered_markets.clone(),
+        });
+
         Ok(filtered_markets)
     }
 
+    /// `fetch_markets`, persisting the result to `market_cache_path`. If Gamma is
+    /// unreachable, fall back to that cache as long as it is no older than
+    /// `market_cache_max_age_secs`. An empty path disables the cache.
+    pub async fn fetch_markets_or_cached(
+        &self,
+        markets_config: &crate::utils::MarketsConfig,
+    ) -> Result<Vec<Market>> {
+        let cache_path = Path::new(&markets_config.market_cache_path);
+
+        match self.fetch_markets(markets_config).await {
+            Ok(markets) => {
+                if !markets_config.market_cache_path.is_empty() {
+                    if let Err(e) = self.save_cache(cache_path).await {
+                        warn!("Failed to save market cache: {:#}", e);
+                    }
+                }
+                Ok(markets)
+            }
+            Err(e) if !markets_config.market_cache_path.is_empty() => {
+                warn!(
+                    "⚠️  GAMMA API UNAVAILABLE ({:#}) - falling back to cached markets in {}",
+                    e,
+                    cache_path.display()
+                );
+                // No usable cache: the fetch failure is the one worth reporting
+                let markets = match self
+                    .load_cached_markets(cache_path, markets_config.market_cache_max_age_secs)
+                    .await
+                {
+                    Ok(markets) => markets,
+                    Err(cache_err) => {
+                        warn!("No usable market cache: {}", cache_err);
+                        return Err(e);
+                    }
+                };
+                warn!(
+                    "⚠️  Running on {} cached markets; new listings won't be picked up until Gamma recovers",
+                    markets.len()
+                );
+                Ok(markets)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write the last successful `fetch_markets` result to `path` as JSON
+    pub async fn save_cache(&self, path: &Path) -> Result<()> {
+        let (json, count) = {
+            let last_fetch = self.last_fetch.read().await;
+            let snapshot = last_fetch.as_ref().ok_or_else(|| {
+                HftpmError::NotFound("Successful market fetch to cache".to_string())
+            })?;
+            (serde_json::to_vec(snapshot)?, snapshot.markets.len())
+        };
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                HftpmError::Io(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+
+        // Write then rename so a crash mid-write never leaves a truncated cache
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, json).await.map_err(|e| {
+            HftpmError::Io(format!("Failed to write {}: {}", tmp_path.display(), e))
+        })?;
+        tokio::fs::rename(&tmp_path, path)
+            .await
+            .map_err(|e| HftpmError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+
+        debug!("💾 Cached {} markets to {}", count, path.display());
+        Ok(())
+    }
+
+    /// Load markets saved by `save_cache`, refusing a cache older than `max_age_secs`
+    pub async fn load_cached_markets(&self, path: &Path, max_age_secs: u64) -> Result<Vec<Market>> {
+        let json = tokio::fs::read(path).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                HftpmError::NotFound(format!("Market cache {}", path.display()))
+            }
+            _ => HftpmError::Io(format!(
+                "Failed to read market cache {}: {}",
+                path.display(),
+                e
+            )),
+        })?;
+        let snapshot: MarketCacheFile = serde_json::from_slice(&json).map_err(|e| {
+            HftpmError::Parse(format!(
+                "Failed to parse market cache {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let age_secs = Utc::now().timestamp().saturating_sub(snapshot.fetched_at);
+        if age_secs > saturating_i64(max_age_secs) {
+            return Err(HftpmError::Stale(format!(
+                "Market cache {} is {}s old (max {}s)",
+                path.display(),
+                age_secs,
+                max_age_secs
+            )));
+        }
+
+        info!(
+            "📂 Loaded {} cached markets from {} ({}s old)",
+            snapshot.markets.len(),
+            path.display(),
+            age_secs
+        );
+
+        let mut cache = self.markets_cache.write().await;
+        for market in &snapshot.markets {
+            cache.insert(market.market.clone(), market.clone());
+        }
+        drop(cache);
+
+        let markets = snapshot.markets.clone();
+        *self.last_fetch.write().await = Some(snapshot);
+
+        Ok(markets)
+    }
+
     #[inline]
     fn should_include_market(&self, market: &Market, config: &crate::utils::MarketsConfig) -> bool {
         // =====================================================================
@@ -418,6 +733,11 @@ impl GammaClient {
             return false;
         }
 
+        if let Some(problem) = token_id_problem(&market.assets_ids) {
+            debug!("Skipping market with {}: {}", problem, market.question);
+            return false;
+        }
+
         // =====================================================================
         // SHORT-WINDOW MARKET CHECK (dynamic 15m up/down discovery)
         // These markets bypass category filters but have their own volume threshold
@@ -560,54 +880,29 @@ impl GammaClient {
         }
 
         info!("🔍 Fetching short-window markets from Events API...");
-        
-        let mut short_window_markets = Vec::new();
-        let now = chrono::Utc::now();
-        
-        // Generate timestamps for the next few 15-minute intervals
-        // Round down to nearest 15 minutes, then get current + next few windows
-        let current_minute = now.minute();
-        let interval_start = (current_minute / 15) * 15;
-        let base_time = now
-            .with_minute(interval_start).unwrap_or(now)
-            .with_second(0).unwrap_or(now)
-            .with_nanosecond(0).unwrap_or(now);
-        
-        // Crypto tickers that have 15m markets
-        let tickers_15m = ["btc", "eth", "sol", "link", "doge", "xrp", "sui", "pepe", "avax", "ada", "bnb", "pol", "near", "apt", "hype"];
-        let tickers_1h = ["btc", "eth", "sol", "link", "doge", "xrp", "sui", "pepe"];
-        
-        // Fetch 15m events (current and next 3 intervals)
-        for offset_mins in [0i64, 15, 30, 45] {
-            let event_time = base_time + chrono::Duration::minutes(offset_mins);
-            let timestamp = event_time.timestamp();
-            
-            for ticker in &tickers_15m {
-                let event_slug = format!("{}-updown-15m-{}", ticker, timestamp);
-                if let Some(market) = self.fetch_event_by_slug(&event_slug, markets_config).await {
-                    short_window_markets.push(market);
-                }
-            }
-        }
-        
-        // Fetch 1h events (current and next hour)
-        let hour_start = now
-            .with_minute(0).unwrap_or(now)
-            .with_second(0).unwrap_or(now)
-            .with_nanosecond(0).unwrap_or(now);
-        
-        for offset_hours in [0i64, 1] {
-            let event_time = hour_start + chrono::Duration::hours(offset_hours);
-            let timestamp = event_time.timestamp();
-            
-            for ticker in &tickers_1h {
-                let event_slug = format!("{}-updown-1h-{}", ticker, timestamp);
-                if let Some(market) = self.fetch_event_by_slug(&event_slug, markets_config).await {
-                    short_window_markets.push(market);
-                }
+
+        let slugs = short_window_event_slugs(chrono::Utc::now(), markets_config.short_window_minutes);
+        let permits = Arc::new(Semaphore::new(markets_config.max_concurrent_event_fetches.max(1)));
+
+        // Probe every slug concurrently, bounded so we don't hammer the Gamma API
+        let probes = slugs.iter().map(|event_slug| {
+            let permits = permits.clone();
+            async move {
+                let _permit = permits.acquire().await.ok()?;
+                self.fetch_event_by_slug(event_slug, markets_config).await
             }
-        }
+        });
+        let results = join_all(probes).await;
+
+        // The same market can surface under more than one slug
+        let mut seen = HashSet::new();
+        let short_window_markets: Vec<Market> = results
+            .into_iter()
+            .flatten()
+            .filter(|market| seen.insert(market.market.clone()))
+            .collect();
 
+        debug!("Probed {} short-window event slugs", slugs.len());
         info!("✅ Found {} short-window markets from Events API", short_window_markets.len());
 
         // Add to cache
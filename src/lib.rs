@@ -1,4 +1,5 @@
 pub mod arb_engine;
+pub mod error;
 pub mod executor;
 pub mod gamma_api;
 pub mod market_maker;
@@ -11,13 +12,16 @@ pub mod volume_farmer;
 pub mod websocket;
 
 pub use arb_engine::{ArbEngine, ArbitrageOpportunity, ShortWindowArbTracker};
-pub use executor::{OrderExecutor, SignedOrder};
+pub use error::HftpmError;
+pub use executor::{Executor, OrderExecutor, SignedOrder, SimulationExecutor};
 pub use gamma_api::GammaClient;
 pub use market_maker::MarketMaker;
-pub use monitoring::{Metrics, Monitor};
+pub use monitoring::{DailyReporter, DailySummary, Metrics, Monitor};
 pub use orderbook::{OrderBook, OrderBookManager};
 pub use parallel_scanner::ParallelScanner;
-pub use risk::{Inventory, Position, RiskManager};
+pub use risk::{
+    CapitalAllocator, Inventory, Position, RiskDecision, RiskManager, RiskRejectReason,
+};
 pub use utils::{setup_tracing, Config, LatencyTracker, Strategy};
 pub use volume_farmer::VolumeFarmer;
 pub use websocket::WebSocketClient;
@@ -36,8 +40,10 @@ static GLOBAL: Jemalloc = Jemalloc;
 #[global_allocator]
 static GLOBAL: std::alloc::System = std::alloc::System;
 
-pub async fn run() -> Result<()> {
-    let config = Config::load()?;
+/// Run the bot. If `export_csv` is set, trades are written to CSV on graceful shutdown.
+/// `profile` overlays `config/<profile>` on the base config (`HFTPM_PROFILE` if `None`).
+pub async fn run(export_csv: Option<std::path::PathBuf>, profile: Option<String>) -> Result<()> {
+    let config = Config::load_profile(profile.as_deref())?;
 
     utils::setup_tracing(&config.monitoring.log_level, &config.monitoring.log_file);
 
@@ -51,6 +57,9 @@ pub async fn run() -> Result<()> {
         utils::TradingMode::Simulation => {
             info!("🎮 SIMULATION MODE - No real trades will be executed");
         }
+        utils::TradingMode::PaperLive => {
+            info!("📝 PAPER-LIVE MODE - Orders are signed against the exchange but never submitted");
+        }
     }
 
     // Log strategy
@@ -83,22 +92,59 @@ pub async fn run() -> Result<()> {
     let orderbook_manager = std::sync::Arc::new(OrderBookManager::new(&config)?);
     let orderbook_manager_scanner = orderbook_manager.clone();
 
-    let mut arb_engine = ArbEngine::new(&config);
-    let mut risk_manager = RiskManager::new(&config);
-    let executor = OrderExecutor::new(&config).await?;
+    // Evict books that stopped updating so detection never acts on dead prices.
+    // Positions are left alone: a stale feed doesn't change what we actually hold.
+    tokio::spawn(run_stale_book_sweeper(
+        orderbook_manager.clone(),
+        config.execution.stale_book_sweep_interval_secs,
+        config.execution.stale_book_max_age_ms,
+    ));
+
     let mut monitor = Monitor::new(&config).await?;
-    let gamma_client = GammaClient::new(&config.server.gamma_url);
+    monitor.start_dashboard().await;
+    let mut arb_engine = ArbEngine::new(&config)
+        .with_near_misses(monitor.near_misses())
+        .with_rejections(monitor.rejections());
+    // One risk view shared by the WebSocket loop and the periodic strategy loop
+    let risk_manager = std::sync::Arc::new(
+        RiskManager::new(&config)
+            .with_near_misses(monitor.near_misses())
+            .with_paused_markets(monitor.paused_markets()),
+    );
+    let executor = std::sync::Arc::new(OrderExecutor::new(&config).await?);
+
+    // Exposure limits must start from what the account already holds
+    if config.trading.trading_mode == utils::TradingMode::Live {
+        // A failed fetch shouldn't keep the bot down; start from what was fetched
+        let positions = executor.fetch_open_positions().await.unwrap_or_else(|e| {
+            tracing::error!(
+                "Failed to fetch open positions, starting without them: {}",
+                e
+            );
+            Vec::new()
+        });
+        let open_orders = executor.fetch_open_orders().await.unwrap_or_else(|e| {
+            tracing::error!("Failed to fetch open orders, starting without them: {}", e);
+            Vec::new()
+        });
+        risk_manager.seed_positions(&positions, &open_orders)?;
+    }
+
+    let gamma_client = GammaClient::new(&config.server.gamma_url, &config.execution)?;
 
     // Initialize RN1-style components
-    let mut market_maker = MarketMaker::new(&config);
-    let mut volume_farmer = VolumeFarmer::new(&config);
+    // Both draw on the risk manager's allocator so Hybrid mode shares one capital cap
+    let mut market_maker = MarketMaker::new(&config).with_capital(risk_manager.capital().clone());
+    let mut volume_farmer = VolumeFarmer::new(&config).with_capital(risk_manager.capital().clone());
 
     // Initialize short-window arb tracker (gabagool-style Sum-<$1 arb)
     let mut sw_arb_tracker =
-        ShortWindowArbTracker::new(rust_decimal::Decimal::from(config.trading.bankroll));
+        ShortWindowArbTracker::new(rust_decimal::Decimal::from(config.trading.bankroll))
+            .with_adversarial_fills(config.simulation.adversarial_fill_probability);
     // Separate arb engine for short-window scanning (avoids borrow conflicts with WS loop)
-    let mut sw_arb_engine = ArbEngine::new(&config);
-    let sw_risk_manager = RiskManager::new(&config);
+    let mut sw_arb_engine = ArbEngine::new(&config)
+        .with_near_misses(monitor.near_misses())
+        .with_rejections(monitor.rejections());
     info!(
         "⚡ Short-window arb enabled: {}% min edge, ${} max size",
         config.trading.short_window_min_edge * rust_decimal::Decimal::from(100),
@@ -106,7 +152,7 @@ pub async fn run() -> Result<()> {
     );
 
     // Fetch regular markets from /markets endpoint
-    let mut markets = gamma_client.fetch_markets(&config.markets).await?;
+    let mut markets = gamma_client.fetch_markets_or_cached(&config.markets).await?;
     info!("📈 Loaded {} markets from Gamma API /markets endpoint", markets.len());
 
     // Fetch short-window markets from /events endpoint (15m Up/Down markets)
@@ -118,6 +164,28 @@ pub async fn run() -> Result<()> {
     }
     info!("📈 Total markets loaded: {}", markets.len());
 
+    // Chosen once: everything downstream of detection goes through `Executor`
+    let simulation_executor = (config.trading.trading_mode == utils::TradingMode::Simulation)
+        .then(|| std::sync::Arc::new(SimulationExecutor::new(&config)));
+
+    // YES/NO come from each market's outcome order, never from book shape.
+    // Markets of one event share its exposure cap. Refreshed markets go through here too.
+    let register_market = {
+        let orderbook_manager = orderbook_manager.clone();
+        let risk_manager = risk_manager.clone();
+        let known_markets = monitor.known_markets();
+        let simulation_executor = simulation_executor.clone();
+        move |market: &gamma_api::Market| {
+            orderbook_manager.register_market(market);
+            risk_manager.register_market(market);
+            known_markets.insert(market.market.clone());
+            if let Some(simulation_executor) = &simulation_executor {
+                simulation_executor.register_market(market);
+            }
+        }
+    };
+    markets.iter().for_each(&register_market);
+
     // Initialize parallel scanner for 16-core optimization
     let parallel_scanner = std::sync::Arc::new(ParallelScanner::new(&config, markets.clone()));
     let parallel_scanner_loop = parallel_scanner.clone();
@@ -131,7 +199,21 @@ pub async fn run() -> Result<()> {
     );
 
     info!("🔌 Creating WebSocket client...");
-    let mut ws_client = WebSocketClient::new(&config, &markets).await?;
+    let trade_executor: std::sync::Arc<dyn Executor> = match &simulation_executor {
+        Some(simulation_executor) => simulation_executor.clone(),
+        None => executor.clone(),
+    };
+    // The scanner runs in the strategy loop; its cross-market finds are executed
+    // by the WebSocket loop, through the same risk checks as its own
+    let (cross_market_tx, cross_market_rx) =
+        tokio::sync::mpsc::channel(websocket::client::CROSS_MARKET_QUEUE_CAPACITY);
+    // Refreshed markets go to the WebSocket loop too, which resubscribes its connections
+    let (market_updates_tx, market_updates_rx) =
+        tokio::sync::mpsc::channel(websocket::client::MARKET_UPDATE_QUEUE_CAPACITY);
+    let mut ws_client = WebSocketClient::new(&config, &markets, trade_executor)
+        .await?
+        .with_cross_market_feed(cross_market_rx)
+        .with_market_updates(market_updates_rx);
     info!("📡 Subscribing to {} markets...", markets.len());
     ws_client.subscribe_all_markets().await?;
     info!("✅ Subscribed to all markets, starting main loop...");
@@ -155,13 +237,20 @@ pub async fn run() -> Result<()> {
         }
     }
 
+    // Shares the monitor's execution history, which the WebSocket loop borrows
+    let daily_reporter = monitor.daily_reporter();
+    let capital_efficiency = monitor.capital_efficiency_feed();
+
+    // `run` is the runtime's `block_on` future, so it (and the detection loop it
+    // drives below) stays on this thread; spawned tasks run on the workers
+    utils::affinity::pin_current_thread(&config.latency);
+
     tokio::select! {
         // Main WebSocket loop (for orderbook updates + arbitrage detection)
         result = ws_client.run(
             &orderbook_manager,
             &mut arb_engine,
-            &mut risk_manager,
-            &executor,
+            &risk_manager,
             &mut monitor,
         ) => {
             info!("🛑 WebSocket loop ended: {:?}", result);
@@ -174,10 +263,17 @@ pub async fn run() -> Result<()> {
             &parallel_scanner_loop,
             &orderbook_manager_scanner,
             &mut sw_arb_engine,
-            &sw_risk_manager,
-            &markets,
+            &risk_manager,
+            &cross_market_tx,
+            &mut markets,
+            &gamma_client,
+            &register_market,
+            &market_updates_tx,
             &config.markets,
             &mut sw_arb_tracker,
+            &daily_reporter,
+            &capital_efficiency,
+            &config.schedule,
         ) => {
             info!("🛑 Strategy loop ended");
         }
@@ -195,12 +291,77 @@ pub async fn run() -> Result<()> {
                 info!("⚡ Final Short-Window Arb Stats: {}", sw_arb_tracker.get_stats());
             }
             info!("🔬 Final Scanner Stats: {}", parallel_scanner.get_stats().await);
+
+            if let Some(path) = export_csv {
+                export_session_csv(
+                    &path,
+                    &monitor,
+                    simulation_executor.as_deref(),
+                    &sw_arb_tracker,
+                )
+                .await;
+            }
         }
     }
 
     Ok(())
 }
 
+/// Write each trade type to its own CSV next to `path` (e.g. `trades_arb.csv`)
+async fn export_session_csv(
+    path: &std::path::Path,
+    monitor: &Monitor,
+    simulation_executor: Option<&SimulationExecutor>,
+    sw_arb_tracker: &ShortWindowArbTracker,
+) {
+    use utils::export::{export_csv, suffixed_path};
+
+    let sim_trades = match simulation_executor {
+        Some(sim) => sim.get_trades().await,
+        None => Vec::new(),
+    };
+
+    let arb_path = suffixed_path(path, "arb");
+    let sw_path = suffixed_path(path, "short_window");
+    let sim_path = suffixed_path(path, "sim");
+
+    let results = [
+        (&arb_path, export_csv(&arb_path, &monitor.get_recent_trades().await)),
+        (&sw_path, export_csv(&sw_path, sw_arb_tracker.get_trades())),
+        (&sim_path, export_csv(&sim_path, &sim_trades)),
+    ];
+
+    for (path, result) in results {
+        match result {
+            Ok(()) => info!("📁 Trades exported to: {}", path.display()),
+            Err(e) => tracing::warn!("Failed to export {}: {:?}", path.display(), e),
+        }
+    }
+}
+
+/// Periodically evict stale order books from the shared manager
+async fn run_stale_book_sweeper(
+    orderbook_manager: std::sync::Arc<OrderBookManager>,
+    interval_secs: u64,
+    max_age_ms: u64,
+) {
+    let mut sweep_interval =
+        tokio::time::interval(std::time::Duration::from_secs(interval_secs.max(1)));
+
+    loop {
+        sweep_interval.tick().await;
+
+        let swept = orderbook_manager.cleanup_stale_books(max_age_ms);
+        if !swept.is_empty() {
+            info!(
+                "🧹 Swept {} stale order books (older than {}ms)",
+                swept.len(),
+                max_age_ms
+            );
+        }
+    }
+}
+
 /// Run periodic strategy execution + parallel market scanning
 /// Integrates with the 16-core parallel scanner for cross-market and multi-outcome detection
 #[allow(clippy::too_many_arguments)]
@@ -212,10 +373,19 @@ async fn run_periodic_strategies(
     orderbook_manager: &std::sync::Arc<OrderBookManager>,
     arb_engine: &mut ArbEngine,
     risk_manager: &RiskManager,
-    markets: &[gamma_api::Market],
+    cross_market_tx: &tokio::sync::mpsc::Sender<parallel_scanner::CrossMarketOpportunity>,
+    markets: &mut Vec<gamma_api::Market>,
+    gamma_client: &GammaClient,
+    register_market: &dyn Fn(&gamma_api::Market),
+    market_updates_tx: &tokio::sync::mpsc::Sender<Vec<gamma_api::Market>>,
     markets_config: &utils::MarketsConfig,
     sw_arb_tracker: &mut ShortWindowArbTracker,
+    daily_reporter: &DailyReporter,
+    capital_efficiency: &monitoring::CapitalEfficiencyFeed,
+    schedule: &utils::ScheduleConfig,
 ) -> Result<()> {
+    use arb_engine::SimulatedTradeStatus;
+    use risk::{Candidate, ScoreEv};
     use std::time::Duration;
 
     // Stats logging every 60 seconds
@@ -224,13 +394,23 @@ async fn run_periodic_strategies(
     let mut scan_interval = tokio::time::interval(Duration::from_secs(5));
     // Short-window arb scanning every 2 seconds (faster for 15m markets)
     let mut sw_arb_interval = tokio::time::interval(Duration::from_secs(2));
+    // Market refetch; markets were just loaded, so the first one waits a full period
+    let refresh_enabled = markets_config.market_refresh_interval_secs > 0;
+    let refresh_period = Duration::from_secs(markets_config.market_refresh_interval_secs.max(1));
+    let mut refresh_interval =
+        tokio::time::interval_at(tokio::time::Instant::now() + refresh_period, refresh_period);
+    // Strategy totals already covered by a daily summary
+    let mut reported_volume = rust_decimal::Decimal::ZERO;
+    let mut reported_rewards = rust_decimal::Decimal::ZERO;
 
     loop {
         tokio::select! {
             _ = stats_interval.tick() => {
-                // Log periodic stats based on strategy
+                // Log periodic stats based on strategy, for those in their window
                 match strategy {
-                    Strategy::MarketMaking | Strategy::Hybrid => {
+                    Strategy::MarketMaking | Strategy::Hybrid
+                        if schedule.is_active_now(&Strategy::MarketMaking) =>
+                    {
                         info!("📊 {}", market_maker.get_stats());
                     }
                     _ => {}
@@ -242,19 +422,41 @@ async fn run_periodic_strategies(
                         if volume_farmer.should_reset_budget() {
                             volume_farmer.reset_daily_budget();
                         }
-                        info!("🗑️  {}", volume_farmer.get_stats());
+                        if schedule.is_active_now(&Strategy::VolumeFarming) {
+                            info!("🗑️  {}", volume_farmer.get_stats());
+                        }
                     }
                     _ => {}
                 }
 
+                // UTC day rolled over: publish the day that closed
+                if let Some(closed) = risk_manager.roll_daily_pnl() {
+                    let volume = volume_farmer.get_stats().total_notional_volume;
+                    let rewards = market_maker.get_stats().estimated_rewards;
+                    let summary = daily_reporter.summarize(
+                        &closed,
+                        volume - reported_volume,
+                        rewards - reported_rewards,
+                    );
+                    reported_volume = volume;
+                    reported_rewards = rewards;
+                    daily_reporter.publish(&summary).await;
+                }
+
                 // Log scanner stats
                 let loaded_markets = orderbook_manager.get_all_market_ids().len();
-                info!("🔬 {} | 📚 {} books loaded", parallel_scanner.get_stats().await, loaded_markets);
+                let (ready, registered) = orderbook_manager.warmup_progress();
+                info!(
+                    "🔬 {} | 📚 {} books loaded ({}/{} markets ready)",
+                    parallel_scanner.get_stats().await, loaded_markets, ready, registered
+                );
             }
             _ = scan_interval.tick() => {
                 // Run parallel scans for arbitrage opportunities
                 match strategy {
-                    Strategy::Arbitrage | Strategy::Hybrid => {
+                    Strategy::Arbitrage | Strategy::Hybrid
+                        if schedule.is_active_now(&Strategy::Arbitrage) =>
+                    {
                         // Scan for multi-outcome arbitrage (sum of all outcomes < $1)
                         let multi_opps = parallel_scanner.scan_multi_outcome_parallel(orderbook_manager).await;
                         if !multi_opps.is_empty() {
@@ -284,48 +486,138 @@ async fn run_periodic_strategies(
                                 );
                             }
 
-                            // TODO: Execute cross-market trades
-                            // For now, just log that we found opportunities
-                            info!("📊 Found {} cross-market opportunities (execution not yet implemented)", cross_opps.len());
+                            info!("📊 Found {} cross-market opportunities", cross_opps.len());
+                            // A full queue means execution is behind; the next scan finds them again
+                            for opp in cross_opps {
+                                if cross_market_tx.try_send(opp).is_err() {
+                                    tracing::debug!("Cross-market queue full, dropping the rest of this scan");
+                                    break;
+                                }
+                            }
                         }
                     }
                     _ => {}
                 }
             }
-            // Short-window arb scanning (gabagool-style Sum-<$1 on 15m markets)
+            _ = refresh_interval.tick(), if refresh_enabled => {
+                // A failed fetch keeps the current set rather than falling back to the disk cache
+                let fetched = match gamma_client.fetch_markets(markets_config).await {
+                    Ok(mut fresh) => gamma_client
+                        .fetch_short_window_markets(markets_config)
+                        .await
+                        .map(|short_window| {
+                            fresh.extend(short_window);
+                            fresh
+                        }),
+                    Err(e) => Err(e),
+                };
+                match fetched {
+                    Ok(fresh) => {
+                        fresh.iter().for_each(register_market);
+                        parallel_scanner.update_markets(fresh.clone()).await;
+                        if market_updates_tx.send(fresh.clone()).await.is_err() {
+                            tracing::debug!("WebSocket loop gone, refreshed markets not subscribed");
+                        }
+                        info!(
+                            "🔄 Refreshed markets: {} -> {} ({} correlations)",
+                            markets.len(),
+                            fresh.len(),
+                            parallel_scanner.num_correlations().await
+                        );
+                        *markets = fresh;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Market refresh failed, keeping {} markets: {:#}",
+                            markets.len(),
+                            e
+                        );
+                    }
+                }
+            }
+            // Short-window arb scanning (gabagool-style Sum-<$1 on 15m markets), then
+            // every active strategy's opportunities compete for the shared capital
             _ = sw_arb_interval.tick() => {
-                match strategy {
-                    Strategy::Arbitrage | Strategy::Hybrid => {
-                        // Auto-resolve expired trades (assume win for Sum-<$1 arb)
-                        sw_arb_tracker.auto_resolve_expired();
+                let arb_active = matches!(strategy, Strategy::Arbitrage | Strategy::Hybrid);
+                let mut candidates = Vec::new();
+
+                if arb_active {
+                    // Auto-resolve expired trades (assume win for Sum-<$1 arb)
+                    // Losing markets go into cooldown so we don't re-enter a broken book
+                    for trade in sw_arb_tracker.auto_resolve_expired() {
+                        risk_manager.capital().release(&Strategy::Arbitrage, trade.entry_cost);
+                        if trade.status == SimulatedTradeStatus::ResolvedLoss {
+                            risk_manager.record_market_failure(&trade.market_id);
+                        }
+                    }
 
-                        // Scan for short-window arb opportunities
+                    // Open trades still resolve outside the window; nothing new is entered
+                    if schedule.is_active_now(&Strategy::Arbitrage) {
                         let sw_opps = arb_engine.scan_short_window_markets(
                             orderbook_manager,
                             markets,
                             markets_config,
                             risk_manager,
                         );
+                        candidates.extend(sw_opps.into_iter().map(Candidate::ShortWindowArb));
+                    }
+                }
+
+                if matches!(strategy, Strategy::MarketMaking | Strategy::Hybrid) {
+                    // Last round's quotes fill against the current books, new
+                    // inventory is hedged, then the rest are cancelled so this
+                    // round's funding replaces them
+                    market_maker.simulate_fills(orderbook_manager).await;
+                    market_maker.hedge_inventory(orderbook_manager).await;
+                    market_maker.refresh_orders().await;
+
+                    if schedule.is_active_now(&Strategy::MarketMaking) {
+                        let mm_opps = market_maker.find_opportunities(orderbook_manager, markets);
+                        candidates.extend(mm_opps.into_iter().map(Candidate::MarketMaking));
+                    }
+                }
+
+                if matches!(strategy, Strategy::VolumeFarming | Strategy::Hybrid) {
+                    // Positions in ended markets no longer tie up capital
+                    volume_farmer.release_resolved(markets);
+
+                    if schedule.is_active_now(&Strategy::VolumeFarming) {
+                        let vf_opps = volume_farmer.find_opportunities(orderbook_manager, markets);
+                        candidates.extend(vf_opps.into_iter().map(Candidate::VolumeFarming));
+                    }
+                }
+
+                // Best EV per dollar-second first, so the cap goes to what earns most
+                let clock = sw_arb_tracker.clock().clone();
+                let funded = risk::ev::fund_by_ev(
+                    candidates,
+                    risk_manager.capital(),
+                    &clock,
+                    sw_arb_tracker,
+                    market_maker,
+                    volume_farmer,
+                )
+                .await;
+                for candidate in &funded {
+                    if let Candidate::ShortWindowArb(opp) = candidate {
+                        info!(
+                            "⚡ [SIM] {} | Entry: ${:.2} | Expected: ${:.2} | Resolves: {}min",
+                            opp.market_question.chars().take(35).collect::<String>(),
+                            opp.capital_required(),
+                            opp.expected_profit,
+                            opp.minutes_to_expiry
+                        );
+                    }
+                }
 
-                        // Simulate entry for each opportunity found
-                        for opp in sw_opps {
-                            let trade = sw_arb_tracker.simulate_entry(&opp);
-                            info!(
-                                "⚡ [SIM] {} | Entry: ${:.2} | Expected: ${:.2} | Resolves: {}min",
-                                trade.market_question.chars().take(35).collect::<String>(),
-                                trade.entry_cost,
-                                trade.expected_profit,
-                                trade.minutes_to_expiry
-                            );
-                        }
+                if arb_active {
+                    capital_efficiency.record(&sw_arb_tracker.capital_uses()).await;
 
-                        // Log stats periodically (every ~30 seconds based on 2s interval)
-                        let stats = sw_arb_tracker.get_stats();
-                        if stats.trades_entered > 0 && stats.trades_entered % 15 == 0 {
-                            info!("⚡ {}", stats);
-                        }
+                    // Log stats periodically (every ~30 seconds based on 2s interval)
+                    let stats = sw_arb_tracker.get_stats();
+                    if stats.trades_entered > 0 && stats.trades_entered.is_multiple_of(15) {
+                        info!("⚡ {}", stats);
                     }
-                    _ => {}
                 }
             }
         }
@@ -9,7 +9,11 @@ async fn main() -> Result<()> {
     println!("🔗 GitHub: https://github.com/your-repo/HFTPM");
     println!();
 
-    if let Err(e) = hfptm::run().await {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let export_csv = hfptm::utils::export::export_csv_arg(args.clone());
+    let profile = hfptm::utils::profile_arg(args);
+
+    if let Err(e) = hfptm::run(export_csv, profile).await {
         error!("💥 Fatal error: {:?}", e);
         std::process::exit(1);
     }
@@ -1,22 +1,30 @@
 //! Market Making Module - RN1-style limit order placement for spread + rewards
 //!
 //! This module implements the core market-making strategy:
-//! 1. Place limit orders at midpoint +/- spread to earn the spread
-//! 2. Qualify for Polymarket's liquidity rewards (orders near midpoint)
+//! 1. Place a ladder of limit orders at midpoint +/- spread (and further out) to earn the spread
+//! 2. Qualify for Polymarket's liquidity rewards (orders near midpoint, scored per their formula)
 //! 3. Track open orders and manage inventory
-//! 4. Use synthetic hedging instead of selling (avoid taker fees)
+//! 4. Hedge filled inventory by bidding for the complementary token instead of selling
+//!    (avoid taker fees)
 
+use crate::arb_engine::ArbSide;
 use crate::gamma_api::Market;
-use crate::orderbook::OrderBookManager;
-use crate::utils::Config;
+use crate::orderbook::{OrderBook, OrderBookManager};
+use crate::risk::{CapitalAllocator, RiskDecision};
+use crate::utils::sim::SimEnv;
+use crate::utils::{Config, MidpointMode, Strategy};
 use anyhow::Result;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// Polymarket's `c`: inside the midpoint band, one-sided liquidity scores
+/// its side's score divided by this
+const SINGLE_SIDED_SCORE_DIVISOR: Decimal = dec!(3);
 
 /// Represents an open limit order
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,11 +35,13 @@ pub struct OpenOrder {
     pub side: OrderSide,
     pub price: Decimal,
     pub size: Decimal,
+    /// Size still waiting to be filled (equals `size` until the first fill)
+    pub remaining_size: Decimal,
     pub created_at: i64,
     pub status: OrderStatus,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OrderSide {
     Bid, // Buy order
     Ask, // Sell order
@@ -45,17 +55,55 @@ pub enum OrderStatus {
     Cancelled,
 }
 
+/// Shares of one asset bought by filled bids, and how much of it is hedged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryPosition {
+    pub market_id: String,
+    pub asset_id: String,
+    pub size: Decimal,
+    pub avg_price: Decimal,
+    /// Complementary shares bought against this position; each pairs with one
+    /// of ours into a set that redeems for $1
+    pub hedged_size: Decimal,
+    /// Latest hedge bid on the complementary token
+    pub hedge_order_id: Option<String>,
+}
+
+/// One bid/ask pair of the quote ladder; level 0 is the innermost
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderRung {
+    pub level: usize,
+    pub bid_price: Decimal,
+    pub ask_price: Decimal,
+    pub size: Decimal,
+    pub estimated_reward: Decimal,
+}
+
 /// Market making opportunity
 #[derive(Debug, Clone)]
 pub struct MMOpportunity {
     pub market_id: String,
     pub asset_id: String,
     pub midpoint: Decimal,
+    /// Innermost rung's quotes and size
     pub bid_price: Decimal,
     pub ask_price: Decimal,
     pub size: Decimal,
     pub spread_bps: Decimal,
+    /// Sum over every rung
     pub estimated_reward: Decimal,
+    /// Every rung to place, innermost first
+    pub ladder: Vec<LadderRung>,
+}
+
+/// What quoting one asset needs from its book
+struct QuotePrices {
+    best_bid: Decimal,
+    best_ask: Decimal,
+    /// Reference price to quote around
+    midpoint: Decimal,
+    /// `competing_score` of the resting liquidity
+    competing_score: Decimal,
 }
 
 /// Statistics for a market we're making
@@ -63,6 +111,7 @@ pub struct MMOpportunity {
 pub struct MarketStats {
     pub orders_placed: u64,
     pub orders_filled: u64,
+    pub volume_filled: Decimal,
     pub volume_provided: Decimal,
     pub spread_earned: Decimal,
     pub estimated_rewards: Decimal,
@@ -72,17 +121,31 @@ pub struct MarketStats {
 /// The Market Maker engine
 pub struct MarketMaker {
     config: Arc<Config>,
-    open_orders: HashMap<String, OpenOrder>, // order_id -> order
+    open_orders: BTreeMap<String, OpenOrder>, // order_id -> order (ordered so fills replay identically)
     market_stats: HashMap<String, MarketStats>,
     total_volume: Decimal,
     total_rewards_estimate: Decimal,
     last_refresh: Instant,
     simulated_balance: Decimal,
+    /// Cash locked by resting bids, as an exchange would hold it for open orders
+    reserved_balance: Decimal,
     initial_balance: Decimal,
+    sim_env: SimEnv,
+    /// Bids reserve their notional here too, so other strategies see it committed
+    capital: Arc<CapitalAllocator>,
+    /// Asset id -> position built up by bid fills (ordered so hedges replay identically)
+    inventory: BTreeMap<String, InventoryPosition>,
+    /// Hedge order id -> asset id of the position it hedges
+    hedge_orders: HashMap<String, String>,
 }
 
 impl MarketMaker {
     pub fn new(config: &Config) -> Self {
+        Self::with_sim_env(config, SimEnv::real())
+    }
+
+    /// Market maker whose simulated orders take IDs and timestamps from `sim_env`
+    pub fn with_sim_env(config: &Config, sim_env: SimEnv) -> Self {
         let initial_balance = Decimal::from(config.trading.bankroll);
 
         info!("📊 Market Maker initialized");
@@ -92,19 +155,49 @@ impl MarketMaker {
             "   Max orders/market: {}",
             config.trading.mm_max_orders_per_market
         );
+        info!(
+            "   Ladder: {} levels, {} bps step, {}x size decay",
+            config.trading.mm_ladder_levels,
+            config.trading.mm_ladder_step_bps,
+            config.trading.mm_ladder_size_decay
+        );
 
         Self {
             config: Arc::new(config.clone()),
-            open_orders: HashMap::new(),
+            open_orders: BTreeMap::new(),
             market_stats: HashMap::new(),
             total_volume: Decimal::ZERO,
             total_rewards_estimate: Decimal::ZERO,
             last_refresh: Instant::now(),
             simulated_balance: initial_balance,
+            reserved_balance: Decimal::ZERO,
             initial_balance,
+            sim_env,
+            capital: Arc::new(CapitalAllocator::new(config)),
+            inventory: BTreeMap::new(),
+            hedge_orders: HashMap::new(),
         }
     }
 
+    /// Draw on `capital`, shared with the other strategies, instead of a
+    /// private allocator
+    pub fn with_capital(mut self, capital: Arc<CapitalAllocator>) -> Self {
+        self.capital = capital;
+        self
+    }
+
+    /// Simulated balance not locked by open orders
+    #[inline]
+    pub fn available_balance(&self) -> Decimal {
+        self.simulated_balance - self.reserved_balance
+    }
+
+    /// Notional of the unfilled remainder of open bids
+    #[inline]
+    pub fn reserved_balance(&self) -> Decimal {
+        self.reserved_balance
+    }
+
     /// Find market making opportunities from current orderbook state
     pub fn find_opportunities(
         &self,
@@ -112,82 +205,250 @@ impl MarketMaker {
         markets: &[Market],
     ) -> Vec<MMOpportunity> {
         let mut opportunities = Vec::new();
-        let spread_decimal = Decimal::from(self.config.trading.mm_spread_bps) / dec!(10000);
-        let order_size = Decimal::from(self.config.trading.mm_order_size);
 
         for market in markets {
             // Get best bid and ask for each asset in the market
             for asset_id in &market.assets_ids {
-                if let Some((best_bid, best_ask)) =
-                    self.get_best_prices(orderbook_manager, &market.market, asset_id)
+                if let Some(QuotePrices {
+                    best_bid,
+                    best_ask,
+                    midpoint,
+                    competing_score,
+                }) = self.get_quote_prices(orderbook_manager, &market.market, asset_id)
                 {
-                    // Calculate midpoint
-                    let midpoint = (best_bid + best_ask) / dec!(2);
-
-                    // Our bid and ask prices (inside the current spread if possible)
-                    let half_spread = midpoint * spread_decimal / dec!(2);
-                    let our_bid = midpoint - half_spread;
-                    let our_ask = midpoint + half_spread;
-
-                    // Only make markets where we can place competitive orders
+                    // Only make markets where we can place competitive orders. Counted
+                    // in the market's own ticks, so a 0.001-tick market near the
+                    // extremes isn't judged by a 0.01-tick market's spread
                     let current_spread = best_ask - best_bid;
-                    let current_spread_bps = (current_spread / midpoint) * dec!(10000);
+                    let tick = orderbook_manager.tick_size(&market.market);
+                    let min_spread = tick * Decimal::from(self.config.trading.mm_min_spread_ticks);
 
-                    // Skip if spread is too tight (we can't compete)
-                    if current_spread_bps < Decimal::from(self.config.trading.mm_spread_bps / 2) {
+                    // Skip if spread is too tight (no price inside to improve on)
+                    if current_spread < min_spread {
                         debug!(
-                            "Spread too tight for {}: {} bps",
-                            asset_id, current_spread_bps
+                            "Spread too tight for {}: {} < {} ticks of {}",
+                            asset_id, current_spread, self.config.trading.mm_min_spread_ticks, tick
                         );
                         continue;
                     }
 
-                    // Estimate daily reward (rough approximation)
-                    // Polymarket rewards ~$1 per $846 liquidity provided
-                    let estimated_daily_reward = order_size * dec!(2) / dec!(846);
+                    let mut ladder = self.build_ladder(midpoint);
+                    for rung in &mut ladder {
+                        rung.bid_price = orderbook_manager.round_to_tick(
+                            &market.market,
+                            rung.bid_price,
+                            ArbSide::Buy,
+                        );
+                        rung.ask_price = orderbook_manager.round_to_tick(
+                            &market.market,
+                            rung.ask_price,
+                            ArbSide::Sell,
+                        );
+                    }
+                    self.improve_prices(&mut ladder, best_bid, best_ask, tick, min_spread);
+                    self.estimate_rewards(competing_score, midpoint, &mut ladder);
+                    let Some(inner) = ladder.first() else {
+                        continue;
+                    };
 
                     opportunities.push(MMOpportunity {
                         market_id: market.market.clone(),
                         asset_id: asset_id.clone(),
                         midpoint,
-                        bid_price: our_bid,
-                        ask_price: our_ask,
-                        size: order_size,
+                        bid_price: inner.bid_price,
+                        ask_price: inner.ask_price,
+                        size: inner.size,
                         spread_bps: Decimal::from(self.config.trading.mm_spread_bps),
-                        estimated_reward: estimated_daily_reward,
+                        estimated_reward: ladder.iter().map(|r| r.estimated_reward).sum(),
+                        ladder,
                     });
                 }
             }
         }
 
         // Sort by estimated reward (highest first)
-        opportunities.sort_by(|a, b| b.estimated_reward.cmp(&a.estimated_reward));
+        opportunities.sort_by_key(|o| std::cmp::Reverse(o.estimated_reward));
 
         opportunities
     }
 
-    /// Get best bid and ask prices for an asset
-    fn get_best_prices(
+    /// Step the inner rung ahead of the resting competition for fills and reward
+    /// score: the bid rises to `mm_price_improve_ticks` above the best bid and
+    /// the ask falls to that far below the best ask, where that is tighter than
+    /// the spread-based price. Quotes are never widened, and the outer rungs move
+    /// with the inner one. Left at the spread-based prices if our own quotes
+    /// would then cross or sit less than `min_spread` apart.
+    fn improve_prices(
         &self,
-        orderbook_manager: &OrderBookManager,
-        market_id: &str,
-        asset_id: &str,
-    ) -> Option<(Decimal, Decimal)> {
-        let book = orderbook_manager.get_book(market_id, asset_id)?;
+        ladder: &mut Vec<LadderRung>,
+        best_bid: Decimal,
+        best_ask: Decimal,
+        tick: Decimal,
+        min_spread: Decimal,
+    ) {
+        let ticks = self.config.trading.mm_price_improve_ticks;
+        if ticks == 0 {
+            return;
+        }
+        let Some(inner) = ladder.first() else {
+            return;
+        };
 
-        let best_bid = book
-            .bids
+        let improvement = tick * Decimal::from(ticks);
+        let bid = inner.bid_price.max(best_bid + improvement);
+        let ask = inner.ask_price.min(best_ask - improvement);
+        if bid >= ask || ask - bid < min_spread {
+            debug!(
+                "Not improving on {} / {}: {} / {} is under the {} minimum spread",
+                best_bid, best_ask, bid, ask, min_spread
+            );
+            return;
+        }
+
+        let bid_shift = bid - inner.bid_price;
+        let ask_shift = ask - inner.ask_price;
+        for rung in ladder.iter_mut() {
+            rung.bid_price += bid_shift;
+            rung.ask_price += ask_shift;
+        }
+        ladder.retain(|rung| rung.bid_price > Decimal::ZERO && rung.ask_price < Decimal::ONE);
+    }
+
+    /// Quote ladder around `midpoint`. Rung `n` sits `mm_ladder_step_bps * n` (of the
+    /// midpoint) outside the base half-spread and is `mm_ladder_size_decay^n` times
+    /// `mm_order_size`. Capped at `mm_max_orders_per_market / 2` rungs; stops early
+    /// once a rung would leave the (0, 1) price range. Rewards are filled in by
+    /// `estimate_rewards`.
+    pub fn build_ladder(&self, midpoint: Decimal) -> Vec<LadderRung> {
+        let trading = &self.config.trading;
+        let half_spread = midpoint * Decimal::from(trading.mm_spread_bps) / dec!(20000);
+        let step = midpoint * Decimal::from(trading.mm_ladder_step_bps) / dec!(10000);
+        let levels = trading
+            .mm_ladder_levels
+            .min(trading.mm_max_orders_per_market / 2)
+            .max(1);
+
+        let mut ladder = Vec::with_capacity(levels);
+        let mut size = Decimal::from(trading.mm_order_size);
+
+        for level in 0..levels {
+            let offset = half_spread + step * Decimal::from(level);
+            let bid_price = midpoint - offset;
+            let ask_price = midpoint + offset;
+            if bid_price <= Decimal::ZERO || ask_price >= Decimal::ONE {
+                break;
+            }
+
+            ladder.push(LadderRung {
+                level,
+                bid_price,
+                ask_price,
+                size,
+                estimated_reward: Decimal::ZERO,
+            });
+
+            size *= trading.mm_ladder_size_decay;
+        }
+
+        ladder
+    }
+
+    /// Daily liquidity reward for each rung, following Polymarket's scoring:
+    ///
+    /// - An order `s` away from the midpoint scores `((v - s) / v)^2 * size`, where
+    ///   `v` is `mm_reward_max_spread`; orders at or beyond `v` score nothing.
+    /// - A side's score is the sum over its orders. Between 0.10 and 0.90 the market
+    ///   score is `max(min(bid, ask), max(bid, ask) / 3)`, so single-sided liquidity
+    ///   counts at a third; outside that band it's `min(bid, ask)` (two-sided
+    ///   quoting required). See `two_sided_score`.
+    /// - The pool (`mm_reward_pool_usd`) is split pro rata between our score and
+    ///   `competing`, the score of the liquidity already resting in the book
+    ///   (`competing_score`).
+    pub fn estimate_rewards(
+        &self,
+        competing: Decimal,
+        midpoint: Decimal,
+        ladder: &mut [LadderRung],
+    ) {
+        let rung_scores: Vec<Decimal> = ladder
             .iter()
-            .max_by(|a, b| a.0.cmp(b.0))
-            .map(|(price, _)| *price)?;
+            .map(|rung| {
+                two_sided_score(
+                    midpoint,
+                    self.reward_score(midpoint, rung.bid_price, rung.size),
+                    self.reward_score(midpoint, rung.ask_price, rung.size),
+                )
+            })
+            .collect();
+
+        let total = competing + rung_scores.iter().copied().sum::<Decimal>();
+        let pool = self.config.trading.mm_reward_pool_usd;
 
-        let best_ask = book
+        for (rung, score) in ladder.iter_mut().zip(rung_scores) {
+            rung.estimated_reward = if total.is_zero() {
+                Decimal::ZERO
+            } else {
+                pool * score / total
+            };
+        }
+    }
+
+    /// Two-sided reward score of the liquidity resting in `book`
+    pub fn competing_score(&self, book: &OrderBook, midpoint: Decimal) -> Decimal {
+        let book_bids: Decimal = book
+            .bids
+            .iter()
+            .map(|(price, size)| self.reward_score(midpoint, *price, *size))
+            .sum();
+        let book_asks: Decimal = book
             .asks
             .iter()
-            .min_by(|a, b| a.0.cmp(b.0))
-            .map(|(price, _)| *price)?;
+            .map(|(price, size)| self.reward_score(midpoint, *price, *size))
+            .sum();
+        two_sided_score(midpoint, book_bids, book_asks)
+    }
+
+    /// Polymarket's per-order score `((v - s) / v)^2 * size`
+    fn reward_score(&self, midpoint: Decimal, price: Decimal, size: Decimal) -> Decimal {
+        let max_spread = self.config.trading.mm_reward_max_spread;
+        let distance = (price - midpoint).abs();
+        if max_spread <= Decimal::ZERO || distance >= max_spread {
+            return Decimal::ZERO;
+        }
+
+        let proximity = (max_spread - distance) / max_spread;
+        proximity * proximity * size
+    }
+
+    /// Top of book and the reference price to quote around (per `mm_midpoint_mode`),
+    /// read in place rather than from a cloned book
+    fn get_quote_prices(
+        &self,
+        orderbook_manager: &OrderBookManager,
+        market_id: &str,
+        asset_id: &str,
+    ) -> Option<QuotePrices> {
+        orderbook_manager.with_book(market_id, asset_id, |book| {
+            if book.is_crossed() {
+                debug!("⏭️  Not quoting {}: book is crossed/locked", asset_id);
+                return None;
+            }
+
+            let (best_bid, _) = book.best_bid()?;
+            let (best_ask, _) = book.best_ask()?;
+
+            let midpoint = match self.config.trading.mm_midpoint_mode {
+                MidpointMode::Arithmetic => book.midpoint()?,
+                MidpointMode::Microprice => book.microprice()?,
+            };
 
-        Some((best_bid, best_ask))
+            Some(QuotePrices {
+                best_bid,
+                best_ask,
+                midpoint,
+                competing_score: self.competing_score(book, midpoint),
+            })
+        })?
     }
 
     /// Simulate placing market making orders (simulation mode)
@@ -199,65 +460,86 @@ impl MarketMaker {
         let max_markets = self.config.trading.max_order_books.min(opportunities.len());
 
         for opp in opportunities.iter().take(max_markets) {
-            // Check if we already have orders in this market
+            // Check if we already have orders resting in this market
             let existing_orders = self
                 .open_orders
                 .values()
-                .filter(|o| o.asset_id == opp.asset_id)
+                .filter(|o| {
+                    o.asset_id == opp.asset_id
+                        && matches!(o.status, OrderStatus::Open | OrderStatus::PartialFill)
+                })
                 .count();
 
-            if existing_orders >= self.config.trading.mm_max_orders_per_market {
-                continue;
-            }
-
-            // Simulate placing bid order
-            let bid_order = self
-                .simulate_order(
-                    &opp.market_id,
-                    &opp.asset_id,
-                    OrderSide::Bid,
-                    opp.bid_price,
-                    opp.size,
-                )
-                .await?;
-
-            // Simulate placing ask order (we're selling to close, so this is like a synthetic hedge)
-            let ask_order = self
-                .simulate_order(
-                    &opp.market_id,
-                    &opp.asset_id,
-                    OrderSide::Ask,
-                    opp.ask_price,
-                    opp.size,
-                )
-                .await?;
-
-            results.push(SimulatedMMResult {
-                market_id: opp.market_id.clone(),
-                asset_id: opp.asset_id.clone(),
-                bid_order_id: bid_order.order_id.clone(),
-                ask_order_id: ask_order.order_id.clone(),
-                bid_price: opp.bid_price,
-                ask_price: opp.ask_price,
-                size: opp.size,
-                estimated_reward: opp.estimated_reward,
-            });
+            // Each rung is a bid + ask; place inner rungs first while there's room
+            let rungs_left = self
+                .config
+                .trading
+                .mm_max_orders_per_market
+                .saturating_sub(existing_orders)
+                / 2;
+
+            for rung in opp.ladder.iter().take(rungs_left) {
+                // Simulate placing bid order; rejected if its notional can't be reserved
+                let bid_order = match self
+                    .simulate_order(
+                        &opp.market_id,
+                        &opp.asset_id,
+                        OrderSide::Bid,
+                        rung.bid_price,
+                        rung.size,
+                    )
+                    .await
+                {
+                    Ok(order) => order,
+                    Err(e) => {
+                        warn!(
+                            "⏭️  Skipping MM rung {} on {}: {}",
+                            rung.level, opp.asset_id, e
+                        );
+                        continue;
+                    }
+                };
 
-            // Update stats
-            let stats = self.market_stats.entry(opp.market_id.clone()).or_default();
-            stats.orders_placed += 2;
-            stats.volume_provided += opp.size * dec!(2);
-            stats.estimated_rewards += opp.estimated_reward;
-            stats.last_update = Some(Instant::now());
+                // Simulate placing ask order (we're selling to close, so this is like a synthetic hedge)
+                let ask_order = self
+                    .simulate_order(
+                        &opp.market_id,
+                        &opp.asset_id,
+                        OrderSide::Ask,
+                        rung.ask_price,
+                        rung.size,
+                    )
+                    .await?;
+
+                results.push(SimulatedMMResult {
+                    market_id: opp.market_id.clone(),
+                    asset_id: opp.asset_id.clone(),
+                    level: rung.level,
+                    bid_order_id: bid_order.order_id.clone(),
+                    ask_order_id: ask_order.order_id.clone(),
+                    bid_price: rung.bid_price,
+                    ask_price: rung.ask_price,
+                    size: rung.size,
+                    estimated_reward: rung.estimated_reward,
+                });
 
-            self.total_volume += opp.size * dec!(2);
-            self.total_rewards_estimate += opp.estimated_reward;
+                // Update stats
+                let stats = self.market_stats.entry(opp.market_id.clone()).or_default();
+                stats.orders_placed += 2;
+                stats.volume_provided += rung.size * dec!(2);
+                stats.estimated_rewards += rung.estimated_reward;
+                stats.last_update = Some(Instant::now());
+
+                self.total_volume += rung.size * dec!(2);
+                self.total_rewards_estimate += rung.estimated_reward;
+            }
         }
 
         Ok(results)
     }
 
-    /// Simulate a single order placement
+    /// Simulate a single order placement. Bids reserve their notional from the
+    /// available balance; asks sell the outcome and lock no cash.
     async fn simulate_order(
         &mut self,
         market_id: &str,
@@ -266,11 +548,25 @@ impl MarketMaker {
         price: Decimal,
         size: Decimal,
     ) -> Result<OpenOrder> {
-        let order_id = format!("SIM_MM_{}", uuid::Uuid::new_v4());
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+        if side == OrderSide::Bid {
+            let notional = price * size;
+            if notional > self.available_balance() {
+                anyhow::bail!(
+                    "Insufficient balance: ${:.2} available < ${:.2}",
+                    self.available_balance(),
+                    notional
+                );
+            }
+            if let RiskDecision::Reject(reason) =
+                self.capital.try_reserve(&Strategy::MarketMaking, notional)
+            {
+                anyhow::bail!("Capital unavailable: {}", reason);
+            }
+            self.reserved_balance += notional;
+        }
+
+        let order_id = self.sim_env.ids.next_id("SIM_MM");
+        let timestamp = self.sim_env.clock.now_secs();
 
         let order = OpenOrder {
             order_id: order_id.clone(),
@@ -279,6 +575,7 @@ impl MarketMaker {
             side,
             price,
             size,
+            remaining_size: size,
             created_at: timestamp,
             status: OrderStatus::Open,
         };
@@ -293,7 +590,11 @@ impl MarketMaker {
         Ok(order)
     }
 
-    /// Simulate order fills based on price movement
+    /// Simulate order fills against the current book. An order only fills up to
+    /// the size resting at levels that cross it, so fills build up over ticks
+    /// as more liquidity arrives. Orders fill best price first and each book
+    /// level is only filled once per pass, so quotes on the same asset share
+    /// its depth rather than each filling against all of it.
     pub async fn simulate_fills(
         &mut self,
         orderbook_manager: &OrderBookManager,
@@ -301,38 +602,86 @@ impl MarketMaker {
         let mut fills = Vec::new();
         let mut orders_to_fill = Vec::new();
 
-        for (order_id, order) in &self.open_orders {
-            if order.status != OrderStatus::Open {
+        let mut resting: Vec<&OpenOrder> = self
+            .open_orders
+            .values()
+            .filter(|order| matches!(order.status, OrderStatus::Open | OrderStatus::PartialFill))
+            .collect();
+        // Highest bids and lowest asks reach the book first, then oldest first
+        resting.sort_by(|a, b| {
+            let is_bid = |order: &OpenOrder| order.side == OrderSide::Bid;
+            let by_price = match a.side {
+                OrderSide::Bid => b.price.cmp(&a.price),
+                OrderSide::Ask => a.price.cmp(&b.price),
+            };
+            a.asset_id
+                .cmp(&b.asset_id)
+                .then(is_bid(a).cmp(&is_bid(b)))
+                .then(by_price)
+                .then(a.created_at.cmp(&b.created_at))
+                .then(a.order_id.cmp(&b.order_id))
+        });
+
+        // (asset, side, level price) -> size already filled at that level
+        let mut consumed: HashMap<(&str, OrderSide, Decimal), Decimal> = HashMap::new();
+
+        for order in resting {
+            let crossing = orderbook_manager.with_book(&order.market_id, &order.asset_id, |book| {
+                match order.side {
+                    // Bid fills against asks at or below our bid, cheapest first
+                    OrderSide::Bid => book
+                        .asks
+                        .range(..=order.price)
+                        .map(|(price, size)| (*price, *size))
+                        .collect::<Vec<_>>(),
+                    // Ask fills against bids at or above our ask, richest first
+                    OrderSide::Ask => book
+                        .bids
+                        .range(order.price..)
+                        .rev()
+                        .map(|(price, size)| (*price, *size))
+                        .collect(),
+                }
+            });
+            let Some(crossing) = crossing else {
                 continue;
-            }
-
-            // Get current best prices
-            if let Some((best_bid, best_ask)) =
-                self.get_best_prices(orderbook_manager, &order.market_id, &order.asset_id)
-            {
-                let should_fill = match order.side {
-                    // Bid fills when market ask drops to our bid
-                    OrderSide::Bid => best_ask <= order.price,
-                    // Ask fills when market bid rises to our ask
-                    OrderSide::Ask => best_bid >= order.price,
-                };
+            };
 
-                if should_fill {
-                    orders_to_fill.push(order_id.clone());
+            let mut fill_size = Decimal::ZERO;
+            for (level, size) in crossing {
+                let remaining = order.remaining_size - fill_size;
+                if remaining <= Decimal::ZERO {
+                    break;
                 }
+                let used = consumed
+                    .entry((order.asset_id.as_str(), order.side, level))
+                    .or_insert(Decimal::ZERO);
+                let take = (size - *used).max(Decimal::ZERO).min(remaining);
+                *used += take;
+                fill_size += take;
+            }
+
+            if fill_size > Decimal::ZERO {
+                orders_to_fill.push((order.order_id.clone(), fill_size));
             }
         }
 
-        for order_id in orders_to_fill {
+        for (order_id, fill_size) in orders_to_fill {
             if let Some(order) = self.open_orders.get_mut(&order_id) {
-                order.status = OrderStatus::Filled;
+                order.remaining_size -= fill_size;
+                order.status = if order.remaining_size.is_zero() {
+                    OrderStatus::Filled
+                } else {
+                    OrderStatus::PartialFill
+                };
 
-                let cost = order.price * order.size;
+                let cost = order.price * fill_size;
 
                 // Update simulated balance
                 match order.side {
                     OrderSide::Bid => {
-                        // We bought - deduct cost
+                        // We bought - the reservation becomes a realized debit
+                        self.reserved_balance -= cost;
                         self.simulated_balance -= cost;
                     }
                     OrderSide::Ask => {
@@ -341,9 +690,49 @@ impl MarketMaker {
                     }
                 }
 
+                // Hedge fills complete sets; anything else moves our inventory
+                if let Some(hedged_asset) = self.hedge_orders.get(&order_id) {
+                    if let Some(position) = self.inventory.get_mut(hedged_asset) {
+                        position.hedged_size += fill_size;
+                    }
+                } else {
+                    match order.side {
+                        OrderSide::Bid => {
+                            let position = self
+                                .inventory
+                                .entry(order.asset_id.clone())
+                                .or_insert_with(|| InventoryPosition {
+                                    market_id: order.market_id.clone(),
+                                    asset_id: order.asset_id.clone(),
+                                    size: Decimal::ZERO,
+                                    avg_price: Decimal::ZERO,
+                                    hedged_size: Decimal::ZERO,
+                                    hedge_order_id: None,
+                                });
+                            let size = position.size + fill_size;
+                            position.avg_price = (position.avg_price * position.size + cost) / size;
+                            position.size = size;
+                        }
+                        OrderSide::Ask => {
+                            if let Some(position) = self.inventory.get_mut(&order.asset_id) {
+                                // Free what the bids that bought these shares reserved
+                                let sold = fill_size.min(position.size);
+                                self.capital
+                                    .release(&Strategy::MarketMaking, position.avg_price * sold);
+                                position.size -= sold;
+                                // Unhedged shares go first; a hedge can't cover more than is held
+                                position.hedged_size = position.hedged_size.min(position.size);
+                            }
+                        }
+                    }
+                }
+
                 // Update stats
                 if let Some(stats) = self.market_stats.get_mut(&order.market_id) {
-                    stats.orders_filled += 1;
+                    stats.volume_filled += fill_size;
+                    if order.status == OrderStatus::Filled {
+                        stats.orders_filled += 1;
+                    }
                 }
 
                 fills.push(SimulatedFill {
@@ -352,12 +741,26 @@ impl MarketMaker {
                     asset_id: order.asset_id.clone(),
                     side: order.side,
                     price: order.price,
-                    size: order.size,
+                    size: fill_size,
+                    remaining_size: order.remaining_size,
                 });
 
                 info!(
-                    "🎮 [SIM] FILLED {:?}: {} @ ${:.4} | Balance: ${:.2}",
-                    order.side, order.asset_id, order.price, self.simulated_balance
+                    event = "mm_fill",
+                    market_id = %order.market_id,
+                    asset_id = %order.asset_id,
+                    side = ?order.side,
+                    price = %order.price,
+                    size = %fill_size,
+                    remaining = %order.remaining_size,
+                    "🎮 [SIM] {:?} {:?}: {} @ ${:.4} x {} ({} left) | Balance: ${:.2}",
+                    order.status,
+                    order.side,
+                    order.asset_id,
+                    order.price,
+                    fill_size,
+                    order.remaining_size,
+                    self.simulated_balance
                 );
             }
         }
@@ -365,35 +768,153 @@ impl MarketMaker {
         fills
     }
 
+    /// Hedge unhedged inventory synthetically: rather than selling a filled
+    /// YES position (paying the taker fee), bid for the same size of the
+    /// complementary NO token so each pair redeems for $1 whichever way the
+    /// market resolves.
+    ///
+    /// The bid is priced off the complement's book: a tick under its best ask so
+    /// it rests as a maker order, or at its best bid when nothing is offered. A
+    /// position gets one hedge bid at a time; whatever it leaves unhedged is
+    /// picked up on the next call once that bid is done. Positions outside
+    /// binary markets are left alone.
+    pub async fn hedge_inventory(
+        &mut self,
+        orderbook_manager: &OrderBookManager,
+    ) -> Vec<OpenOrder> {
+        // Hedge bids that filled or were cancelled have nothing left to track
+        let open_orders = &self.open_orders;
+        self.hedge_orders.retain(|order_id, _| {
+            open_orders.get(order_id).is_some_and(|order| {
+                matches!(order.status, OrderStatus::Open | OrderStatus::PartialFill)
+            })
+        });
+
+        let unhedged: Vec<(String, String, Decimal)> = self
+            .inventory
+            .values()
+            .filter(|position| !self.hedge_pending(position))
+            .filter_map(|position| {
+                let size = position.size - position.hedged_size;
+                (size > Decimal::ZERO)
+                    .then(|| (position.market_id.clone(), position.asset_id.clone(), size))
+            })
+            .collect();
+
+        let mut hedges = Vec::new();
+        for (market_id, asset_id, size) in unhedged {
+            let Some((complement, complement_quote)) = orderbook_manager
+                .with_market_books(&market_id, |books| {
+                    let complement = books.complement_of(&asset_id)?;
+                    let book = books.books.iter().find(|book| book.asset_id == complement);
+                    let quote = book.map(|book| {
+                        (
+                            book.best_bid().map(|(price, _)| price),
+                            book.best_ask().map(|(price, _)| price),
+                        )
+                    });
+                    Some((complement.to_string(), quote))
+                })
+                .flatten()
+            else {
+                debug!("⏭️  No complementary token to hedge {} with", asset_id);
+                continue;
+            };
+
+            let tick = orderbook_manager.tick_size(&market_id);
+            let price = match complement_quote {
+                Some((_, Some(best_ask))) => best_ask - tick,
+                Some((Some(best_bid), None)) => best_bid,
+                _ => {
+                    debug!(
+                        "⏭️  No {} book to price the hedge for {} off",
+                        complement, asset_id
+                    );
+                    continue;
+                }
+            };
+            let price = ((price / tick).floor() * tick).normalize();
+            if price <= Decimal::ZERO {
+                debug!("⏭️  No maker price left to hedge {} at", asset_id);
+                continue;
+            }
+
+            match self
+                .simulate_order(&market_id, &complement, OrderSide::Bid, price, size)
+                .await
+            {
+                Ok(order) => {
+                    info!(
+                        "🛡️  [SIM] Hedging {} x {} with a bid for {} @ ${:.4}",
+                        asset_id, size, complement, price
+                    );
+                    self.hedge_orders
+                        .insert(order.order_id.clone(), asset_id.clone());
+                    if let Some(position) = self.inventory.get_mut(&asset_id) {
+                        position.hedge_order_id = Some(order.order_id.clone());
+                    }
+                    hedges.push(order);
+                }
+                Err(e) => warn!("⏭️  Skipping hedge for {}: {}", asset_id, e),
+            }
+        }
+
+        hedges
+    }
+
+    /// Whether `position`'s latest hedge bid is still resting
+    fn hedge_pending(&self, position: &InventoryPosition) -> bool {
+        position
+            .hedge_order_id
+            .as_ref()
+            .and_then(|order_id| self.open_orders.get(order_id))
+            .is_some_and(|order| {
+                matches!(order.status, OrderStatus::Open | OrderStatus::PartialFill)
+            })
+    }
+
+    /// Position held in `asset_id`, if any bid on it has filled
+    pub fn inventory(&self, asset_id: &str) -> Option<&InventoryPosition> {
+        self.inventory.get(asset_id)
+    }
+
+    /// Look up a simulated order by id
+    pub fn get_order(&self, order_id: &str) -> Option<&OpenOrder> {
+        self.open_orders.get(order_id)
+    }
+
     /// Check if orders need refreshing
     pub fn needs_refresh(&self) -> bool {
         self.last_refresh.elapsed() > Duration::from_secs(self.config.trading.mm_order_refresh_secs)
     }
 
-    /// Cancel and refresh stale orders
-    pub async fn refresh_orders(&mut self) {
-        let stale_threshold = Duration::from_secs(self.config.trading.mm_order_refresh_secs);
-        let now = Instant::now();
-
-        // Cancel stale open orders
-        let stale_orders: Vec<_> = self
+    /// Cancel every resting quote so the next round can place fresh ones,
+    /// releasing what their bids reserved. Hedge bids keep resting, and
+    /// cancelled orders are dropped. Returns how many were cancelled.
+    pub async fn refresh_orders(&mut self) -> usize {
+        let stale: Vec<String> = self
             .open_orders
             .iter()
-            .filter(|(_, order)| {
-                order.status == OrderStatus::Open
-                    && now.duration_since(Instant::now()) > stale_threshold
+            .filter(|(order_id, order)| {
+                matches!(order.status, OrderStatus::Open | OrderStatus::PartialFill)
+                    && !self.hedge_orders.contains_key(*order_id)
             })
-            .map(|(id, _)| id.clone())
+            .map(|(order_id, _)| order_id.clone())
             .collect();
 
-        for order_id in stale_orders {
-            if let Some(order) = self.open_orders.get_mut(&order_id) {
-                order.status = OrderStatus::Cancelled;
+        for order_id in &stale {
+            if let Some(order) = self.open_orders.remove(order_id) {
+                if order.side == OrderSide::Bid {
+                    let unfilled = order.price * order.remaining_size;
+                    self.reserved_balance -= unfilled;
+                    self.capital.release(&Strategy::MarketMaking, unfilled);
+                }
                 debug!("🎮 [SIM] Cancelled stale order: {}", order_id);
             }
         }
 
         self.last_refresh = Instant::now();
+        stale.len()
     }
 
     /// Get current statistics
@@ -401,7 +922,7 @@ impl MarketMaker {
         let pnl = self.simulated_balance - self.initial_balance;
 
         MMStats {
-            total_orders_placed: self.open_orders.len() as u64,
+            total_orders_placed: self.market_stats.values().map(|s| s.orders_placed).sum(),
             open_orders: self
                 .open_orders
                 .values()
@@ -412,19 +933,40 @@ impl MarketMaker {
                 .values()
                 .filter(|o| o.status == OrderStatus::Filled)
                 .count() as u64,
+            partially_filled_orders: self
+                .open_orders
+                .values()
+                .filter(|o| o.status == OrderStatus::PartialFill)
+                .count() as u64,
             total_volume: self.total_volume,
             estimated_rewards: self.total_rewards_estimate,
             simulated_balance: self.simulated_balance,
+            reserved_balance: self.reserved_balance,
             pnl,
             markets_active: self.market_stats.len() as u64,
         }
     }
 }
 
+/// Polymarket's `Q_min` from the two side scores. Between 0.10 and 0.90
+/// one-sided liquidity still scores, at a third:
+/// `max(min(bid, ask), max(bid / 3, ask / 3))`. Outside that band only
+/// two-sided quoting counts: `min(bid, ask)`.
+pub fn two_sided_score(midpoint: Decimal, bid_score: Decimal, ask_score: Decimal) -> Decimal {
+    let two_sided = bid_score.min(ask_score);
+    if (dec!(0.10)..=dec!(0.90)).contains(&midpoint) {
+        two_sided.max(bid_score.max(ask_score) / SINGLE_SIDED_SCORE_DIVISOR)
+    } else {
+        two_sided
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SimulatedMMResult {
     pub market_id: String,
     pub asset_id: String,
+    /// Ladder rung these orders belong to
+    pub level: usize,
     pub bid_order_id: String,
     pub ask_order_id: String,
     pub bid_price: Decimal,
@@ -440,7 +982,10 @@ pub struct SimulatedFill {
     pub asset_id: String,
     pub side: OrderSide,
     pub price: Decimal,
+    /// Size filled on this tick
     pub size: Decimal,
+    /// Size still open on the order after this fill
+    pub remaining_size: Decimal,
 }
 
 #[derive(Debug, Clone)]
@@ -448,9 +993,11 @@ pub struct MMStats {
     pub total_orders_placed: u64,
     pub open_orders: u64,
     pub filled_orders: u64,
+    pub partially_filled_orders: u64,
     pub total_volume: Decimal,
     pub estimated_rewards: Decimal,
     pub simulated_balance: Decimal,
+    pub reserved_balance: Decimal,
     pub pnl: Decimal,
     pub markets_active: u64,
 }
@@ -459,13 +1006,15 @@ impl std::fmt::Display for MMStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "MM Stats: {} orders ({} open, {} filled) | Vol: ${:.0} | Est. Rewards: ${:.2} | Balance: ${:.2} (P&L: ${:.2})",
+            "MM Stats: {} orders ({} open, {} partial, {} filled) | Vol: ${:.0} | Est. Rewards: ${:.2} | Balance: ${:.2} (${:.2} reserved, P&L: ${:.2})",
             self.total_orders_placed,
             self.open_orders,
+            self.partially_filled_orders,
             self.filled_orders,
             self.total_volume,
             self.estimated_rewards,
             self.simulated_balance,
+            self.reserved_balance,
             self.pnl
         )
     }
@@ -0,0 +1,109 @@
+use super::Metrics;
+use chrono::Utc;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Capital committed to one trade: `amount` at risk from `entered_at` until
+/// `exited_at` (unix secs), when it came back with `profit`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapitalUse {
+    pub amount: Decimal,
+    pub entered_at: i64,
+    /// None while the capital is still at risk
+    pub exited_at: Option<i64>,
+    /// Realized profit; ignored until `exited_at` is set
+    pub profit: Decimal,
+}
+
+/// How hard capital worked over a session, for comparing strategies on
+/// something other than raw P&L
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CapitalEfficiency {
+    /// Capital committed by entries within the session, counting each recycle
+    pub capital_deployed: Decimal,
+    /// Capital at risk averaged over the session's length
+    pub avg_capital_at_risk: Decimal,
+    /// `capital_deployed / avg_capital_at_risk`: how many times capital recycled
+    pub turnover: f64,
+    /// Realized profit per dollar of average capital at risk, per day
+    pub profit_per_dollar_day: f64,
+}
+
+impl CapitalEfficiency {
+    /// Measure `uses` over the session from `session_start` to `now`. Capital
+    /// entered before the session only counts for the time it was at risk within it.
+    pub fn measure(uses: &[CapitalUse], session_start: i64, now: i64) -> Self {
+        let session_secs = now - session_start;
+        if session_secs <= 0 {
+            return Self::default();
+        }
+
+        let mut capital_deployed = Decimal::ZERO;
+        let mut capital_secs = Decimal::ZERO;
+        let mut profit = Decimal::ZERO;
+        let in_session = |t: i64| (session_start..=now).contains(&t);
+        for usage in uses {
+            if in_session(usage.entered_at) {
+                capital_deployed += usage.amount;
+            }
+            if usage.exited_at.is_some_and(in_session) {
+                profit += usage.profit;
+            }
+            let from = usage.entered_at.max(session_start);
+            let to = usage.exited_at.unwrap_or(now).min(now);
+            if to > from {
+                capital_secs += usage.amount * Decimal::from(to - from);
+            }
+        }
+
+        let avg_capital_at_risk = capital_secs / Decimal::from(session_secs);
+        if avg_capital_at_risk <= Decimal::ZERO {
+            return Self {
+                capital_deployed,
+                ..Self::default()
+            };
+        }
+
+        let days = Decimal::from(session_secs) / Decimal::from(SECS_PER_DAY);
+        Self {
+            capital_deployed,
+            avg_capital_at_risk,
+            turnover: (capital_deployed / avg_capital_at_risk)
+                .to_f64()
+                .unwrap_or(0.0),
+            profit_per_dollar_day: (profit / avg_capital_at_risk / days)
+                .to_f64()
+                .unwrap_or(0.0),
+        }
+    }
+}
+
+/// Publishes capital efficiency into the monitor's metrics. Shares them with
+/// `Monitor`, so it can run on the strategy loop while `Monitor` is borrowed by
+/// the WebSocket loop.
+pub struct CapitalEfficiencyFeed {
+    metrics: Arc<tokio::sync::RwLock<Metrics>>,
+    /// Unix secs the monitor started; the session efficiency is measured over
+    session_start: i64,
+}
+
+impl CapitalEfficiencyFeed {
+    pub(super) fn new(metrics: Arc<tokio::sync::RwLock<Metrics>>, session_start: i64) -> Self {
+        Self {
+            metrics,
+            session_start,
+        }
+    }
+
+    /// Measure `uses` over the session so far and publish the result
+    pub async fn record(&self, uses: &[CapitalUse]) -> CapitalEfficiency {
+        let efficiency =
+            CapitalEfficiency::measure(uses, self.session_start, Utc::now().timestamp());
+        self.metrics.write().await.capital_efficiency = efficiency;
+        efficiency
+    }
+}
@@ -0,0 +1,129 @@
+use super::Metrics;
+use crate::utils::Config;
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// One subsystem's part of `/health`
+#[derive(Debug, Clone, Serialize)]
+pub struct SubsystemHealth {
+    pub name: &'static str,
+    pub healthy: bool,
+    pub detail: String,
+}
+
+/// What `/health` serves: healthy only if every subsystem is
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub status: &'static str,
+    pub timestamp: String,
+    pub subsystems: Vec<SubsystemHealth>,
+}
+
+impl HealthReport {
+    #[inline]
+    pub fn is_healthy(&self) -> bool {
+        self.subsystems.iter().all(|subsystem| subsystem.healthy)
+    }
+}
+
+/// Subsystem state `/health` needs beyond `Metrics`: when detection last ran
+/// and whether the executor's last health check passed.
+///
+/// The WebSocket loop feeds it; the dashboard turns it into a `HealthReport`,
+/// answering 503 when anything is degraded so orchestrators can probe it.
+pub struct HealthState {
+    /// Feed silence tolerated while connected (`ws_stall_threshold_ms`)
+    max_message_age_ms: u64,
+    /// Detection idle time tolerated; 0 disables the check
+    max_detection_idle_ms: i64,
+    /// Unix ms of the last detection pass, or of startup before the first
+    last_detection_ms: AtomicI64,
+    /// `None` until the first check
+    executor_healthy: Mutex<Option<bool>>,
+}
+
+impl HealthState {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            max_message_age_ms: config.alerts.ws_stall_threshold_ms,
+            max_detection_idle_ms: config.monitoring.health_max_detection_idle_secs as i64 * 1000,
+            last_detection_ms: AtomicI64::new(Utc::now().timestamp_millis()),
+            executor_healthy: Mutex::new(None),
+        }
+    }
+
+    #[inline]
+    pub fn record_detection_pass(&self) {
+        self.last_detection_ms
+            .store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn record_executor_health(&self, healthy: bool) {
+        *self.executor_healthy.lock() = Some(healthy);
+    }
+
+    /// Health judged from `metrics` and what has been recorded here
+    pub fn report(&self, metrics: &Metrics) -> HealthReport {
+        let websocket = SubsystemHealth {
+            name: "websocket",
+            healthy: metrics.websocket_connected,
+            detail: if metrics.websocket_connected {
+                "connected".to_string()
+            } else {
+                "disconnected".to_string()
+            },
+        };
+
+        let feed = SubsystemHealth {
+            name: "market_feed",
+            healthy: metrics.ws_last_message_age_ms <= self.max_message_age_ms,
+            detail: format!(
+                "last message {}ms ago (limit {}ms)",
+                metrics.ws_last_message_age_ms, self.max_message_age_ms
+            ),
+        };
+
+        let (healthy, detail) = match *self.executor_healthy.lock() {
+            Some(true) => (true, "health check passing"),
+            Some(false) => (false, "health check failing"),
+            None => (true, "not checked yet"),
+        };
+        let executor = SubsystemHealth {
+            name: "executor",
+            healthy,
+            detail: detail.to_string(),
+        };
+
+        let idle_ms =
+            Utc::now().timestamp_millis() - self.last_detection_ms.load(Ordering::Relaxed);
+        let detection = if self.max_detection_idle_ms == 0 {
+            SubsystemHealth {
+                name: "detection",
+                healthy: true,
+                detail: format!("last pass {}ms ago (not checked)", idle_ms),
+            }
+        } else {
+            SubsystemHealth {
+                name: "detection",
+                healthy: idle_ms <= self.max_detection_idle_ms,
+                detail: format!(
+                    "last pass {}ms ago (limit {}ms)",
+                    idle_ms, self.max_detection_idle_ms
+                ),
+            }
+        };
+
+        let mut report = HealthReport {
+            status: "healthy",
+            timestamp: Utc::now().to_rfc3339(),
+            subsystems: vec![websocket, feed, executor, detection],
+        };
+        if !report.is_healthy() {
+            report.status = "degraded";
+        }
+        report
+    }
+}
@@ -1,45 +1,139 @@
-use crate::arb_engine::ArbitrageOpportunity;
-use crate::executor::ExecutionResult;
-use crate::risk::RiskManager;
-use crate::utils::{Config, LatencyTracker};
+pub mod efficiency;
+pub mod health;
+pub mod near_miss;
+pub mod rejections;
+pub mod report;
+pub mod sinks;
+
+pub use efficiency::{CapitalEfficiency, CapitalEfficiencyFeed, CapitalUse};
+pub use health::{HealthReport, HealthState, SubsystemHealth};
+pub use near_miss::{NearMiss, NearMissKind, NearMissRecorder};
+pub use rejections::{RejectReason, RejectionCounts, RejectionReport, RejectionStats};
+pub use report::{DailyReporter, DailySummary, MarketPnl};
+pub use sinks::{AlertSink, DiscordSink, TelegramSink, WebhookSink};
+
+use crate::arb_engine::Opportunity;
+use crate::executor::{ExecutionResult, Executor};
+use crate::gamma_api::Market;
+use crate::risk::{RiskManager, RiskRejectReason};
+use crate::utils::{Config, LatencyTracker, MonitoringConfig, TradingMode};
+use crate::websocket::{FeedHealth, UserFill};
 use anyhow::Result;
 use axum::{
-    extract::{Query, State},
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::Json,
-    routing::get,
+    routing::{get, post},
     Router,
 };
 use chrono::Utc;
+use dashmap::{DashMap, DashSet};
+use futures::future::join_all;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::net::SocketAddr;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
 
 const MAX_RECENT_TRADES: usize = 100;
-#[allow(dead_code)]
+const MAX_ALERTS: usize = 500;
+/// Retention window when `metrics_retention_hours` is 0
 const MAX_METRICS_RETENTION_HOURS: u64 = 24;
 
+type DashboardState = (
+    Arc<tokio::sync::RwLock<Metrics>>,
+    Arc<tokio::sync::RwLock<VecDeque<TradeRecord>>>,
+    Arc<tokio::sync::RwLock<VecDeque<Alert>>>,
+    MonitoringConfig,
+    // Effective config with secrets redacted; the config never changes after startup
+    Arc<serde_json::Value>,
+    Arc<NearMissRecorder>,
+    Arc<HealthState>,
+    Arc<RejectionStats>,
+    Arc<DashSet<String>>,
+    Arc<parking_lot::Mutex<Vec<StaleMarket>>>,
+    // Markets that can be paused, from `register_market`
+    Arc<DashSet<String>>,
+);
+
+/// A market whose oldest book hasn't changed in `age_ms`, as `/stale_books` lists it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StaleMarket {
+    pub market_id: String,
+    pub age_ms: i64,
+}
+
+/// Entries a dashboard list returns when the query names no `limit`
+const DEFAULT_QUERY_LIMIT: usize = 20;
+
 #[derive(Debug, Deserialize)]
 struct LimitQuery {
     limit: Option<usize>,
 }
 
+impl LimitQuery {
+    /// The requested limit, or the default, never more than `max`
+    #[inline]
+    fn capped(&self, max: usize) -> usize {
+        self.limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(max)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Metrics {
     pub uptime_seconds: u64,
     pub arb_detections: u64,
     pub arb_executions: u64,
     pub arb_missed: u64,
+    /// Detections dropped for being older than `max_opportunity_age_ms` at execution
+    pub arb_expired: u64,
     pub total_pnl: rust_decimal::Decimal,
+    /// Fees paid on executions and user-channel fills since startup
+    pub total_fees_paid: Decimal,
     pub avg_latency_ms: f64,
     pub p50_latency_ns: u64,
     pub p99_latency_ns: u64,
     pub websocket_connected: bool,
     pub active_positions: usize,
     pub active_arbs: usize,
+    /// Book updates coalesced away by the WebSocket rate limiter
+    pub dropped_updates: u64,
+    /// Executions where every leg filled
+    pub fills: u64,
+    /// Executions where only some legs (or part of a leg) filled
+    pub partial_fills: u64,
+    /// Share of execution attempts that filled completely
+    pub fill_rate: f64,
+    /// Realized vs quoted cost of filled quantity, cost-weighted (positive = paid more)
+    pub avg_slippage_bps: f64,
+    /// Fills of our orders reported by the exchange's user channel
+    pub exchange_fills: u64,
+    /// Notional (price * size) of those fills
+    pub exchange_fill_volume: Decimal,
+    /// Market-channel reconnects since startup
+    pub ws_reconnects: u64,
+    /// Time since the market channel last delivered data
+    pub ws_last_message_age_ms: u64,
+    pub ws_messages_per_sec: f64,
+    /// Price changes whose reported top of book disagreed with ours (dropped updates)
+    pub ws_sequence_gaps: u64,
+    /// Last balance read from the exchange (live mode only)
+    pub exchange_balance: Option<Decimal>,
+    /// Simulator balance (simulation mode only)
+    pub sim_balance: Option<Decimal>,
+    /// Simulator P&L since startup (simulation mode only)
+    pub sim_pnl: Option<Decimal>,
+    /// Capital turnover and profit per dollar-day of the short-window trades
+    pub capital_efficiency: CapitalEfficiency,
+    #[serde(skip)]
+    filled_expected_cost: Decimal,
+    #[serde(skip)]
+    filled_realized_cost: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -50,6 +144,8 @@ pub struct TradeRecord {
     pub position_size: rust_decimal::Decimal,
     pub expected_profit: rust_decimal::Decimal,
     pub actual_profit: rust_decimal::Decimal,
+    /// Fees the execution paid (`ExecutionResult::fees_paid`)
+    pub fees: rust_decimal::Decimal,
     pub execution_time_ms: u64,
     pub success: bool,
 }
@@ -62,7 +158,7 @@ pub struct Alert {
     pub severity: AlertSeverity,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum AlertType {
     TradeExecuted,
     ArbitrageDetected,
@@ -70,9 +166,13 @@ pub enum AlertType {
     Error,
     PnlDrawdown,
     RiskLimitBreached,
+    FeedStalled,
+    BalanceDeviation,
+    DailySummary,
+    CircuitBreakerTripped,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum AlertSeverity {
     Info,
     Warning,
@@ -80,28 +180,164 @@ pub enum AlertSeverity {
     Critical,
 }
 
+/// An entry of a history that ages out
+pub trait Timestamped {
+    /// Unix seconds
+    fn timestamp(&self) -> i64;
+}
+
+impl Timestamped for Alert {
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+impl Timestamped for TradeRecord {
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+/// Drop entries older than `cutoff` (unix seconds), then the oldest beyond
+/// `max_len`. Entries are appended as they happen, so both come off the front.
+pub fn evict_history<T: Timestamped>(history: &mut VecDeque<T>, max_len: usize, cutoff: i64) {
+    while history
+        .front()
+        .is_some_and(|entry| entry.timestamp() < cutoff)
+    {
+        history.pop_front();
+    }
+
+    while history.len() > max_len {
+        history.pop_front();
+    }
+}
+
+/// Up to `limit` entries no older than `cutoff`, newest first. Readers skip
+/// what `evict_history` hasn't dropped yet rather than taking a write lock.
+pub fn newest_since<T: Timestamped + Clone>(
+    history: &VecDeque<T>,
+    cutoff: i64,
+    limit: usize,
+) -> Vec<T> {
+    history
+        .iter()
+        .rev()
+        .take_while(|entry| entry.timestamp() >= cutoff)
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
+/// Oldest timestamp the `metrics_retention_hours` window keeps
+fn retention_cutoff(monitoring: &MonitoringConfig) -> i64 {
+    let hours = match monitoring.metrics_retention_hours {
+        0 => MAX_METRICS_RETENTION_HOURS,
+        hours => hours,
+    };
+    Utc::now().timestamp() - (hours * 3600) as i64
+}
+
+/// Whether a dashboard request may change state: it must carry
+/// `Authorization: Bearer <dashboard_token>`, or without a token configured,
+/// come from this machine
+fn dashboard_write_allowed(
+    monitoring: &MonitoringConfig,
+    headers: &HeaderMap,
+    peer: SocketAddr,
+) -> bool {
+    if monitoring.dashboard_token.is_empty() {
+        return peer.ip().is_loopback();
+    }
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token == monitoring.dashboard_token)
+}
+
+/// Paused markets in a stable order for the dashboard
+fn sorted_markets(markets: &DashSet<String>) -> Vec<String> {
+    let mut markets: Vec<String> = markets.iter().map(|id| id.clone()).collect();
+    markets.sort();
+    markets
+}
+
+/// Append `alert` to `alerts`, evicting what has aged out or is over the cap
+pub(crate) fn push_alert(
+    alerts: &mut VecDeque<Alert>,
+    alert: Alert,
+    monitoring: &MonitoringConfig,
+) {
+    alerts.push_back(alert);
+    evict_history(alerts, MAX_ALERTS, retention_cutoff(monitoring));
+}
+
 pub struct Monitor {
     config: Arc<Config>,
     metrics: Arc<tokio::sync::RwLock<Metrics>>,
     recent_trades: Arc<tokio::sync::RwLock<VecDeque<TradeRecord>>>,
+    /// Executions not yet covered by a daily summary (unbounded, unlike `recent_trades`)
+    day_trades: Arc<parking_lot::Mutex<Vec<TradeRecord>>>,
     alerts: Arc<tokio::sync::RwLock<VecDeque<Alert>>>,
     start_time: Instant,
     latency_tracker: LatencyTracker,
     websocket_connected: Arc<tokio::sync::RwLock<bool>>,
+    /// Set while P&L sits below the drawdown threshold, so we alert once per breach
+    drawdown_alerted: AtomicBool,
+    /// Set while the market feed is stalled, so we alert once per stall
+    feed_stall_alerted: AtomicBool,
+    /// Set while the exchange balance is short of expected, so we alert once per shortfall
+    balance_alerted: AtomicBool,
+    /// (market, limit) -> when its breach was last alerted, for `risk_alert_cooldown_secs`
+    risk_alerted: DashMap<(String, RiskRejectReason), Instant>,
+    /// Every alert that pages is fanned out to each of these
+    sinks: Vec<Box<dyn AlertSink>>,
+    /// Configured client the sinks and daily reporter send through
+    http_client: reqwest::Client,
+    /// Shared with the arb engines and risk manager, which feed it
+    near_misses: Arc<NearMissRecorder>,
+    /// Detection and executor state behind `/health`
+    health: Arc<HealthState>,
+    /// Shared with the arb engines, which count why they turned gaps away
+    rejections: Arc<RejectionStats>,
+    /// Shared with the risk manager; markets here are skipped until resumed
+    paused_markets: Arc<DashSet<String>>,
+    /// Markets past `stale_feed_age_ms` at the last feed health check, stalest first
+    stale_markets: Arc<parking_lot::Mutex<Vec<StaleMarket>>>,
+    /// Markets being traded; only these can be paused from the dashboard
+    known_markets: Arc<DashSet<String>>,
 }
 
 impl Monitor {
     pub async fn new(config: &Config) -> Result<Self> {
+        let http_client = crate::utils::build_http_client(&config.execution)?;
+
         Ok(Self {
             config: Arc::new(config.clone()),
             metrics: Arc::new(tokio::sync::RwLock::new(Self::empty_metrics())),
             recent_trades: Arc::new(tokio::sync::RwLock::new(VecDeque::with_capacity(
                 MAX_RECENT_TRADES,
             ))),
-            alerts: Arc::new(tokio::sync::RwLock::new(VecDeque::with_capacity(500))),
+            day_trades: Arc::new(parking_lot::Mutex::new(Vec::new())),
+            alerts: Arc::new(tokio::sync::RwLock::new(VecDeque::with_capacity(
+                MAX_ALERTS,
+            ))),
             start_time: Instant::now(),
             latency_tracker: LatencyTracker::new(),
             websocket_connected: Arc::new(tokio::sync::RwLock::new(false)),
+            drawdown_alerted: AtomicBool::new(false),
+            feed_stall_alerted: AtomicBool::new(false),
+            balance_alerted: AtomicBool::new(false),
+            risk_alerted: DashMap::new(),
+            sinks: sinks::sinks_from_config(&config.alerts, &http_client),
+            http_client,
+            near_misses: Arc::new(NearMissRecorder::new(&config.monitoring)),
+            health: Arc::new(HealthState::new(config)),
+            rejections: Arc::new(RejectionStats::new()),
+            paused_markets: Arc::new(DashSet::new()),
+            stale_markets: Arc::new(parking_lot::Mutex::new(Vec::new())),
+            known_markets: Arc::new(DashSet::new()),
         })
     }
 
@@ -112,18 +348,37 @@ impl Monitor {
             arb_detections: 0,
             arb_executions: 0,
             arb_missed: 0,
+            arb_expired: 0,
             total_pnl: rust_decimal::Decimal::ZERO,
+            total_fees_paid: Decimal::ZERO,
             avg_latency_ms: 0.0,
             p50_latency_ns: 0,
             p99_latency_ns: 0,
             websocket_connected: false,
             active_positions: 0,
             active_arbs: 0,
+            dropped_updates: 0,
+            fills: 0,
+            partial_fills: 0,
+            fill_rate: 0.0,
+            avg_slippage_bps: 0.0,
+            exchange_fills: 0,
+            exchange_fill_volume: Decimal::ZERO,
+            ws_reconnects: 0,
+            ws_last_message_age_ms: 0,
+            ws_messages_per_sec: 0.0,
+            ws_sequence_gaps: 0,
+            exchange_balance: None,
+            sim_balance: None,
+            sim_pnl: None,
+            capital_efficiency: CapitalEfficiency::default(),
+            filled_expected_cost: Decimal::ZERO,
+            filled_realized_cost: Decimal::ZERO,
         }
     }
 
     #[inline]
-    pub async fn record_arbitrage_detected(&self, arb_op: &ArbitrageOpportunity) {
+    pub async fn record_arbitrage_detected(&self, arb_op: &dyn Opportunity) {
         let mut metrics = self.metrics.write().await;
         metrics.arb_detections += 1;
 
@@ -131,45 +386,50 @@ impl Monitor {
             alert_type: AlertType::ArbitrageDetected,
             message: format!(
                 "Arbitrage detected: {} ({:.2}% edge)",
-                arb_op.market_id,
-                arb_op.total_edge * rust_decimal::Decimal::ONE_HUNDRED
+                arb_op.market_id(),
+                arb_op.edge() * rust_decimal::Decimal::ONE_HUNDRED
             ),
             timestamp: Utc::now().timestamp(),
-            severity: if arb_op.total_edge > Decimal::from_str("0.04").unwrap() {
+            severity: if arb_op.edge() > Decimal::from_str("0.04").unwrap() {
                 AlertSeverity::Info
             } else {
                 AlertSeverity::Warning
             },
         };
 
-        let mut alerts = self.alerts.write().await;
-        alerts.push_back(alert.clone());
-
-        while alerts.len() > 500 {
-            alerts.pop_front();
-        }
-
-        drop(alerts);
+        self.push_alert(alert.clone()).await;
 
         info!(
             "🎯 Arbitrage #{} detected: {} ({:.2}% edge, ${:.2} profit)",
             metrics.arb_detections,
-            arb_op.market_id,
-            arb_op.total_edge * rust_decimal::Decimal::ONE_HUNDRED,
-            arb_op.net_profit
+            arb_op.market_id(),
+            arb_op.edge() * rust_decimal::Decimal::ONE_HUNDRED,
+            arb_op.expected_profit()
         );
 
-        if self.config.alerts.enable_telegram
-            && arb_op.position_size >= self.config.alerts.alert_on_trade_usd.into()
-        {
-            self.send_telegram_alert(&alert).await;
+        if arb_op.position_size() >= self.config.alerts.alert_on_trade_usd.into() {
+            self.dispatch_alert(&alert).await;
         }
     }
 
+    /// An opportunity that sat `age_ms` between detection and execution, too
+    /// long for its prices to still hold
+    pub async fn record_arbitrage_expired(&self, arb_op: &dyn Opportunity, age_ms: i64) {
+        self.metrics.write().await.arb_expired += 1;
+        info!(
+            event = "arb_expired",
+            market_id = %arb_op.market_id(),
+            age_ms,
+            "⌛ Dropped {} detected {}ms ago: prices have likely moved",
+            arb_op.market_id(),
+            age_ms
+        );
+    }
+
     #[inline]
     pub async fn record_arbitrage_executed(
         &mut self,
-        arb_op: &ArbitrageOpportunity,
+        arb_op: &dyn Opportunity,
         result: &ExecutionResult,
         execution_time: std::time::Duration,
     ) {
@@ -177,57 +437,85 @@ impl Monitor {
 
         if result.success {
             metrics.arb_executions += 1;
-            metrics.total_pnl += result.total_cost;
+            metrics.total_pnl += result.realized_pnl;
         } else {
             metrics.arb_missed += 1;
         }
+        // Partial fills pay fees on the sets they did complete
+        metrics.total_fees_paid += result.fees_paid;
+        Self::record_fill_quality(&mut metrics, result);
 
         let latency = execution_time.as_nanos() as u64;
         self.latency_tracker.record(latency);
 
         let trade_record = TradeRecord {
             timestamp: Utc::now().timestamp(),
-            market_id: arb_op.market_id.clone(),
-            arb_type: format!("{:?}", arb_op.arb_type),
-            position_size: arb_op.position_size,
-            expected_profit: arb_op.net_profit,
-            actual_profit: result.total_cost,
+            market_id: arb_op.market_id().to_string(),
+            arb_type: format!("{:?}", arb_op.arb_type()),
+            position_size: arb_op.position_size(),
+            expected_profit: arb_op.expected_profit(),
+            actual_profit: result.realized_pnl,
+            fees: result.fees_paid,
             execution_time_ms: execution_time.as_millis() as u64,
             success: result.success,
         };
 
+        self.day_trades.lock().push(trade_record.clone());
+
         let mut recent_trades = self.recent_trades.write().await;
         recent_trades.push_back(trade_record);
-
-        while recent_trades.len() > MAX_RECENT_TRADES {
-            recent_trades.pop_front();
-        }
-
+        evict_history(
+            &mut recent_trades,
+            MAX_RECENT_TRADES,
+            retention_cutoff(&self.config.monitoring),
+        );
         drop(recent_trades);
 
         info!(
             "✅ Arbitrage executed: {} in {:.2}ms (success: {})",
-            arb_op.market_id,
+            arb_op.market_id(),
             execution_time.as_secs_f64() * 1000.0,
             result.success
         );
 
-        if self.config.alerts.enable_telegram
-            && arb_op.position_size >= self.config.alerts.alert_on_trade_usd.into()
-        {
+        if arb_op.position_size() >= self.config.alerts.alert_on_trade_usd.into() {
             let alert = Alert {
                 alert_type: AlertType::TradeExecuted,
                 message: format!(
                     "Trade executed: ${:.2} profit in {:.2}ms on {}",
-                    arb_op.net_profit,
+                    arb_op.expected_profit(),
                     execution_time.as_millis(),
-                    arb_op.market_id
+                    arb_op.market_id()
                 ),
                 timestamp: Utc::now().timestamp(),
                 severity: AlertSeverity::Info,
             };
 
-            self.send_telegram_alert(&alert).await;
+            self.dispatch_alert(&alert).await;
+        }
+    }
+
+    /// Fold one execution into the fill counters, fill rate and slippage average
+    fn record_fill_quality(metrics: &mut Metrics, result: &ExecutionResult) {
+        if result.filled {
+            metrics.fills += 1;
+        } else if result.partial_fill {
+            metrics.partial_fills += 1;
+        }
+
+        let attempts = metrics.arb_executions + metrics.arb_missed;
+        if attempts > 0 {
+            metrics.fill_rate = metrics.fills as f64 / attempts as f64;
+        }
+
+        if result.expected_cost > Decimal::ZERO {
+            metrics.filled_expected_cost += result.expected_cost;
+            metrics.filled_realized_cost += result.realized_cost;
+
+            let slippage = (metrics.filled_realized_cost - metrics.filled_expected_cost)
+                / metrics.filled_expected_cost
+                * Decimal::from(10_000);
+            metrics.avg_slippage_bps = slippage.to_f64().unwrap_or(0.0);
         }
     }
 
@@ -244,19 +532,12 @@ impl Monitor {
                 severity: AlertSeverity::Warning,
             };
 
-            let mut alerts = self.alerts.write().await;
-            alerts.push_back(alert.clone());
-
-            while alerts.len() > 500 {
-                alerts.pop_front();
-            }
-
-            drop(alerts);
+            self.push_alert(alert.clone()).await;
 
             warn!("⚠️  Latency spike: {}ms", current_latency_ms);
 
             if self.config.alerts.alert_on_latency_spike {
-                self.send_telegram_alert(&alert).await;
+                self.dispatch_alert(&alert).await;
             }
         }
     }
@@ -270,19 +551,318 @@ impl Monitor {
             severity: AlertSeverity::Error,
         };
 
-        let mut alerts = self.alerts.write().await;
-        alerts.push_back(alert.clone());
+        self.push_alert(alert.clone()).await;
+
+        error!("❌ {}", error_message);
+
+        if self.config.alerts.alert_on_error {
+            self.dispatch_alert(&alert).await;
+        }
+    }
+
+    #[inline]
+    pub async fn alert_risk_limit_breached(&self, market_id: &str, reason: RiskRejectReason) {
+        let severity = match reason {
+            RiskRejectReason::DailyLossLimit => AlertSeverity::Critical,
+            RiskRejectReason::InventoryDrift => AlertSeverity::Error,
+            RiskRejectReason::MarketExposure
+            | RiskRejectReason::EventExposure
+            | RiskRejectReason::MarketBlacklisted
+            | RiskRejectReason::StrategyBudget
+            | RiskRejectReason::GlobalCapital => AlertSeverity::Warning,
+            RiskRejectReason::MaxConcurrentArbs
+            | RiskRejectReason::InsufficientLiquidity
+            | RiskRejectReason::MarketCooldown
+            | RiskRejectReason::MarketPaused
+            | RiskRejectReason::TradeSpacing => AlertSeverity::Info,
+        };
 
-        while alerts.len() > 500 {
-            alerts.pop_front();
+        if !self.risk_alert_due(market_id, reason) {
+            return;
         }
 
-        drop(alerts);
+        let alert = Alert {
+            alert_type: AlertType::RiskLimitBreached,
+            message: format!("Risk limit breached ({}) for {}", reason, market_id),
+            timestamp: Utc::now().timestamp(),
+            severity: severity.clone(),
+        };
 
-        error!("❌ {}", error_message);
+        self.push_alert(alert.clone()).await;
 
-        if self.config.alerts.enable_telegram && self.config.alerts.alert_on_error {
-            self.send_telegram_alert(&alert).await;
+        // Routine limits (concurrency, liquidity) fire constantly; only page on real breaches
+        if matches!(severity, AlertSeverity::Error | AlertSeverity::Critical) {
+            warn!("🛑 Risk limit breached ({}) for {}", reason, market_id);
+            self.dispatch_alert(&alert).await;
+        }
+    }
+
+    /// Whether `reason` in `market_id` is outside its alert cooldown; marks it
+    /// alerted if so. Rejections repeat on every detection while a limit holds.
+    fn risk_alert_due(&self, market_id: &str, reason: RiskRejectReason) -> bool {
+        let cooldown = Duration::from_secs(self.config.alerts.risk_alert_cooldown_secs);
+        let now = Instant::now();
+        let key = (market_id.to_string(), reason);
+
+        if self
+            .risk_alerted
+            .get(&key)
+            .is_some_and(|last| now.duration_since(*last) < cooldown)
+        {
+            return false;
+        }
+        if self.risk_alerted.len() >= MAX_ALERTS {
+            self.risk_alerted
+                .retain(|_, last| now.duration_since(*last) < cooldown);
+        }
+        self.risk_alerted.insert(key, now);
+        true
+    }
+
+    #[inline]
+    pub async fn check_pnl_drawdown(&self, total_pnl: Decimal) {
+        if !self.config.alerts.alert_on_pnl_drawdown {
+            return;
+        }
+
+        let threshold = Decimal::from(self.config.alerts.pnl_drawdown_threshold_usd);
+
+        if total_pnl > -threshold {
+            self.drawdown_alerted.store(false, Ordering::Relaxed);
+            return;
+        }
+
+        if self.drawdown_alerted.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let alert = Alert {
+            alert_type: AlertType::PnlDrawdown,
+            message: format!(
+                "P&L drawdown: ${:.2} below -${} threshold",
+                total_pnl, threshold
+            ),
+            timestamp: Utc::now().timestamp(),
+            severity: AlertSeverity::Critical,
+        };
+
+        self.push_alert(alert.clone()).await;
+
+        warn!("📉 P&L drawdown: ${:.2}", total_pnl);
+
+        self.dispatch_alert(&alert).await;
+    }
+
+    /// Handle that publishes daily summaries from this monitor's execution history
+    pub fn daily_reporter(&self) -> DailyReporter {
+        DailyReporter::new(
+            Arc::clone(&self.config),
+            Arc::clone(&self.day_trades),
+            Arc::clone(&self.alerts),
+            &self.http_client,
+        )
+    }
+
+    /// Handle that publishes capital efficiency into this monitor's metrics,
+    /// measured from when the monitor started
+    pub fn capital_efficiency_feed(&self) -> CapitalEfficiencyFeed {
+        let session_start = Utc::now().timestamp() - self.start_time.elapsed().as_secs() as i64;
+        CapitalEfficiencyFeed::new(Arc::clone(&self.metrics), session_start)
+    }
+
+    /// Recorded trades within the retention window, oldest first
+    pub async fn get_recent_trades(&self) -> Vec<TradeRecord> {
+        let mut trades = newest_since(
+            &*self.recent_trades.read().await,
+            retention_cutoff(&self.config.monitoring),
+            MAX_RECENT_TRADES,
+        );
+        trades.reverse();
+        trades
+    }
+
+    /// Most recent alerts within the retention window, newest first
+    pub async fn get_recent_alerts(&self, limit: usize) -> Vec<Alert> {
+        newest_since(
+            &*self.alerts.read().await,
+            retention_cutoff(&self.config.monitoring),
+            limit,
+        )
+    }
+
+    /// Keep `alert` in the recent history without sending it anywhere
+    pub async fn push_alert(&self, alert: Alert) {
+        push_alert(
+            &mut *self.alerts.write().await,
+            alert,
+            &self.config.monitoring,
+        );
+    }
+
+    /// Count a fill reported by the user channel, alerting on large ones
+    pub async fn record_user_fill(&self, fill: &UserFill) {
+        let notional = fill.price * fill.size;
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.exchange_fills += 1;
+            metrics.exchange_fill_volume += notional;
+            metrics.total_fees_paid += fill.fee;
+        }
+
+        info!(
+            event = "user_fill",
+            market_id = %fill.market_id,
+            asset_id = %fill.asset_id,
+            side = ?fill.side,
+            price = %fill.price,
+            size = %fill.size,
+            "🧾 Fill: {:?} {} {} @ ${:.4} (${:.2})",
+            fill.side,
+            fill.size,
+            fill.outcome,
+            fill.price,
+            notional
+        );
+
+        if notional >= self.config.alerts.alert_on_trade_usd.into() {
+            let alert = Alert {
+                alert_type: AlertType::TradeExecuted,
+                message: format!(
+                    "Fill: {:?} {} {} @ ${:.4} on {}",
+                    fill.side, fill.size, fill.outcome, fill.price, fill.market_id
+                ),
+                timestamp: Utc::now().timestamp(),
+                severity: AlertSeverity::Info,
+            };
+
+            self.dispatch_alert(&alert).await;
+        }
+    }
+
+    #[inline]
+    pub async fn record_dropped_updates(&self, count: u64) {
+        self.metrics.write().await.dropped_updates += count;
+    }
+
+    #[inline]
+    pub async fn set_websocket_connected(&self, connected: bool) {
+        *self.websocket_connected.write().await = connected;
+        self.metrics.write().await.websocket_connected = connected;
+    }
+
+    /// Publish market feed health, warning once when it has been silent for longer
+    /// than `ws_stall_threshold_ms` (connected but no data)
+    pub async fn record_feed_health(&self, health: &FeedHealth, now: Instant) {
+        let age_ms = health
+            .last_message_age_at(now)
+            .map_or(0, |age| age.as_millis() as u64);
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.ws_reconnects = health.reconnects();
+            metrics.ws_last_message_age_ms = age_ms;
+            metrics.ws_messages_per_sec = health.messages_per_sec();
+            metrics.ws_sequence_gaps = health.sequence_gaps();
+        }
+
+        let threshold_ms = self.config.alerts.ws_stall_threshold_ms;
+        if age_ms <= threshold_ms {
+            self.feed_stall_alerted.store(false, Ordering::Relaxed);
+            return;
+        }
+
+        if self.feed_stall_alerted.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let alert = Alert {
+            alert_type: AlertType::FeedStalled,
+            message: format!(
+                "Market feed stalled: no data for {}ms (threshold {}ms)",
+                age_ms, threshold_ms
+            ),
+            timestamp: Utc::now().timestamp(),
+            severity: AlertSeverity::Warning,
+        };
+
+        self.push_alert(alert.clone()).await;
+
+        warn!("📡 Market feed stalled: no data for {}ms", age_ms);
+
+        self.dispatch_alert(&alert).await;
+    }
+
+    /// Execution halted by the circuit breaker after `failures` failures in a
+    /// row; always paged, since nothing trades until it recovers
+    pub async fn alert_circuit_breaker_tripped(&self, failures: u32, cooldown_secs: u64) {
+        let alert = Alert {
+            alert_type: AlertType::CircuitBreakerTripped,
+            message: format!(
+                "Execution halted after {} consecutive failures; probing again in {}s",
+                failures, cooldown_secs
+            ),
+            timestamp: Utc::now().timestamp(),
+            severity: AlertSeverity::Critical,
+        };
+
+        self.push_alert(alert.clone()).await;
+
+        error!(
+            "🔌 Circuit breaker tripped after {} consecutive failures, halting execution for {}s",
+            failures, cooldown_secs
+        );
+
+        self.dispatch_alert(&alert).await;
+    }
+
+    /// Record a balance read from the exchange, alerting once when it falls more than
+    /// `balance_deviation_threshold_usd` short of the capital we expected to have.
+    /// A wallet holding more than expected is normal (the bankroll is only a cap).
+    pub async fn check_balance_deviation(&self, balance: Decimal, expected: Decimal) {
+        self.metrics.write().await.exchange_balance = Some(balance);
+
+        let threshold = Decimal::from(self.config.alerts.balance_deviation_threshold_usd);
+        let shortfall = expected - balance;
+
+        if shortfall <= threshold {
+            self.balance_alerted.store(false, Ordering::Relaxed);
+            return;
+        }
+
+        if self.balance_alerted.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let alert = Alert {
+            alert_type: AlertType::BalanceDeviation,
+            message: format!(
+                "Exchange balance ${:.2} is ${:.2} short of expected ${:.2}",
+                balance, shortfall, expected
+            ),
+            timestamp: Utc::now().timestamp(),
+            severity: AlertSeverity::Critical,
+        };
+
+        self.push_alert(alert.clone()).await;
+
+        warn!(
+            "💸 Exchange balance ${:.2} is ${:.2} short of expected ${:.2}",
+            balance, shortfall, expected
+        );
+
+        self.dispatch_alert(&alert).await;
+    }
+
+    /// Copy the simulator's balance and P&L into the metrics, so the dashboard
+    /// shows how a simulation run is doing. Does nothing outside simulation mode.
+    pub async fn refresh_simulation_metrics(&self, executor: &dyn Executor) {
+        if self.config.trading.trading_mode != TradingMode::Simulation {
+            return;
+        }
+
+        if let Some((balance, pnl)) = executor.simulated_account().await {
+            let mut metrics = self.metrics.write().await;
+            metrics.sim_balance = Some(balance);
+            metrics.sim_pnl = Some(pnl);
         }
     }
 
@@ -303,38 +883,71 @@ impl Monitor {
         drop(metrics);
     }
 
+    /// Register an extra alert channel alongside the configured ones
+    pub fn add_sink(&mut self, sink: Box<dyn AlertSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// Near-miss recorder for detection and risk checks to feed
     #[inline]
-    async fn send_telegram_alert(&self, alert: &Alert) {
-        if !self.config.alerts.enable_telegram {
-            return;
-        }
+    pub fn near_misses(&self) -> Arc<NearMissRecorder> {
+        Arc::clone(&self.near_misses)
+    }
 
-        let bot_token = &self.config.alerts.telegram_bot_token;
-        let chat_id = &self.config.alerts.telegram_chat_id;
+    /// Rejection counters for detection to feed
+    #[inline]
+    pub fn rejections(&self) -> Arc<RejectionStats> {
+        Arc::clone(&self.rejections)
+    }
 
-        if bot_token.is_empty() || chat_id.is_empty() {
-            return;
-        }
+    /// Allow `market` to be paused from the dashboard
+    pub fn register_market(&self, market: &Market) {
+        self.known_markets.insert(market.market.clone());
+    }
 
-        let severity_icon = match alert.severity {
-            AlertSeverity::Info => "ℹ️",
-            AlertSeverity::Warning => "⚠️",
-            AlertSeverity::Error => "❌",
-            AlertSeverity::Critical => "🚨",
-        };
+    /// Markets the dashboard may pause, for markets registered after startup
+    #[inline]
+    pub fn known_markets(&self) -> Arc<DashSet<String>> {
+        Arc::clone(&self.known_markets)
+    }
 
-        let message = format!("{} HFTPM Alert\n\n{}", severity_icon, alert.message);
+    /// Markets paused from the dashboard, for the risk manager to skip
+    #[inline]
+    pub fn paused_markets(&self) -> Arc<DashSet<String>> {
+        Arc::clone(&self.paused_markets)
+    }
 
-        let url = format!(
-            "https://api.telegram.org/bot{}/sendMessage?chat_id={}&text={}",
-            bot_token,
-            chat_id,
-            urlencoding::encode(&message)
-        );
+    /// Replace the stale-feed list with `stale` (`OrderBookManager::stale_markets`)
+    pub fn record_stale_markets(&self, stale: Vec<(String, i64)>) {
+        *self.stale_markets.lock() = stale
+            .into_iter()
+            .map(|(market_id, age_ms)| StaleMarket { market_id, age_ms })
+            .collect();
+    }
 
-        if let Err(e) = reqwest::get(&url).await {
-            error!("Failed to send Telegram alert: {:?}", e);
-        }
+    /// Markets listed by the last `record_stale_markets`, stalest first
+    pub fn stale_markets(&self) -> Vec<StaleMarket> {
+        self.stale_markets.lock().clone()
+    }
+
+    #[inline]
+    pub fn record_detection_pass(&self) {
+        self.health.record_detection_pass();
+    }
+
+    #[inline]
+    pub fn record_executor_health(&self, healthy: bool) {
+        self.health.record_executor_health(healthy);
+    }
+
+    /// What `/health` would answer now
+    pub async fn health_report(&self) -> HealthReport {
+        self.health.report(&*self.metrics.read().await)
+    }
+
+    #[inline]
+    async fn dispatch_alert(&self, alert: &Alert) {
+        join_all(self.sinks.iter().map(|sink| sink.send(alert))).await;
     }
 
     pub async fn start_dashboard(&self) {
@@ -346,13 +959,39 @@ impl Monitor {
         let metrics = Arc::clone(&self.metrics);
         let recent_trades = Arc::clone(&self.recent_trades);
         let alerts = Arc::clone(&self.alerts);
+        let monitoring = config.monitoring.clone();
+        let effective_config = Arc::new(config.redacted_json());
+        let near_misses = Arc::clone(&self.near_misses);
+        let health = Arc::clone(&self.health);
+        let rejections = Arc::clone(&self.rejections);
+        let paused_markets = Arc::clone(&self.paused_markets);
+        let stale_markets = Arc::clone(&self.stale_markets);
+        let known_markets = Arc::clone(&self.known_markets);
 
         let app = Router::new()
             .route("/metrics", get(Self::metrics_handler))
             .route("/trades", get(Self::trades_handler))
             .route("/alerts", get(Self::alerts_handler))
             .route("/health", get(Self::health_handler))
-            .with_state((metrics, recent_trades, alerts));
+            .route("/config", get(Self::config_handler))
+            .route("/near_misses", get(Self::near_misses_handler))
+            .route("/rejections", get(Self::rejections_handler))
+            .route("/markets/:id/pause", post(Self::pause_market_handler))
+            .route("/markets/:id/resume", post(Self::resume_market_handler))
+            .route("/stale_books", get(Self::stale_books_handler))
+            .with_state((
+                metrics,
+                recent_trades,
+                alerts,
+                monitoring,
+                effective_config,
+                near_misses,
+                health,
+                rejections,
+                paused_markets,
+                stale_markets,
+                known_markets,
+            ));
 
         let listener =
             tokio::net::TcpListener::bind(format!("0.0.0.0:{}", config.monitoring.dashboard_port))
@@ -364,57 +1003,117 @@ impl Monitor {
             config.monitoring.dashboard_port
         );
 
+        // Peer addresses let the mutating routes tell local callers apart
+        let app = app.into_make_service_with_connect_info::<SocketAddr>();
         tokio::spawn(async move {
             axum::serve(listener, app).await.unwrap();
         });
     }
 
-    #[allow(clippy::type_complexity)]
     async fn metrics_handler(
-        State((metrics, _, _)): State<(
-            Arc<tokio::sync::RwLock<Metrics>>,
-            Arc<tokio::sync::RwLock<VecDeque<TradeRecord>>>,
-            Arc<tokio::sync::RwLock<VecDeque<Alert>>>,
-        )>,
+        State((metrics, _, _, _, _, _, _, _, _, _, _)): State<DashboardState>,
     ) -> Json<Metrics> {
         Json(metrics.read().await.clone())
     }
 
-    #[allow(clippy::type_complexity)]
     async fn trades_handler(
-        State((_, recent_trades, _)): State<(
-            Arc<tokio::sync::RwLock<Metrics>>,
-            Arc<tokio::sync::RwLock<VecDeque<TradeRecord>>>,
-            Arc<tokio::sync::RwLock<VecDeque<Alert>>>,
-        )>,
+        State((_, recent_trades, _, monitoring, _, _, _, _, _, _, _)): State<DashboardState>,
         Query(query): Query<LimitQuery>,
     ) -> Json<Vec<TradeRecord>> {
-        let trades = recent_trades.read().await;
-        let limit = query.limit.unwrap_or(50).min(MAX_RECENT_TRADES);
-
-        Json(trades.iter().rev().take(limit).cloned().collect())
+        Json(newest_since(
+            &*recent_trades.read().await,
+            retention_cutoff(&monitoring),
+            query.capped(MAX_RECENT_TRADES),
+        ))
     }
 
-    #[allow(clippy::type_complexity)]
     async fn alerts_handler(
-        State((_, _, alerts)): State<(
-            Arc<tokio::sync::RwLock<Metrics>>,
-            Arc<tokio::sync::RwLock<VecDeque<TradeRecord>>>,
-            Arc<tokio::sync::RwLock<VecDeque<Alert>>>,
-        )>,
+        State((_, _, alerts, monitoring, _, _, _, _, _, _, _)): State<DashboardState>,
         Query(query): Query<LimitQuery>,
     ) -> Json<Vec<Alert>> {
-        let alerts_list = alerts.read().await;
-        let limit = query.limit.unwrap_or(50);
+        Json(newest_since(
+            &*alerts.read().await,
+            retention_cutoff(&monitoring),
+            query.capped(MAX_ALERTS),
+        ))
+    }
+
+    async fn config_handler(
+        State((_, _, _, _, effective_config, _, _, _, _, _, _)): State<DashboardState>,
+    ) -> Json<serde_json::Value> {
+        Json(effective_config.as_ref().clone())
+    }
+
+    async fn near_misses_handler(
+        State((_, _, _, _, _, near_misses, _, _, _, _, _)): State<DashboardState>,
+        Query(query): Query<LimitQuery>,
+    ) -> Json<Vec<NearMiss>> {
+        Json(near_misses.recent(query.capped(near_miss::MAX_NEAR_MISSES)))
+    }
+
+    async fn rejections_handler(
+        State((_, _, _, _, _, _, _, rejections, _, _, _)): State<DashboardState>,
+    ) -> Json<RejectionReport> {
+        Json(rejections.report())
+    }
 
-        Json(alerts_list.iter().rev().take(limit).cloned().collect())
+    /// Pause detection and execution on one market; answers with every paused market
+    async fn pause_market_handler(
+        State((_, _, _, monitoring, _, _, _, _, paused_markets, _, known_markets)): State<
+            DashboardState,
+        >,
+        ConnectInfo(peer): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        Path(market_id): Path<String>,
+    ) -> Result<Json<Vec<String>>, StatusCode> {
+        if !dashboard_write_allowed(&monitoring, &headers, peer) {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        if !known_markets.contains(&market_id) {
+            return Err(StatusCode::NOT_FOUND);
+        }
+        if paused_markets.insert(market_id.clone()) {
+            warn!("⏸️ Market {} paused from the dashboard", market_id);
+        }
+        Ok(Json(sorted_markets(&paused_markets)))
     }
 
-    async fn health_handler() -> Json<serde_json::Value> {
-        Json(serde_json::json!({
-            "status": "healthy",
-            "timestamp": Utc::now().to_rfc3339(),
-        }))
+    async fn resume_market_handler(
+        State((_, _, _, monitoring, _, _, _, _, paused_markets, _, known_markets)): State<
+            DashboardState,
+        >,
+        ConnectInfo(peer): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        Path(market_id): Path<String>,
+    ) -> Result<Json<Vec<String>>, StatusCode> {
+        if !dashboard_write_allowed(&monitoring, &headers, peer) {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        if paused_markets.remove(&market_id).is_some() {
+            info!("▶️ Market {} resumed from the dashboard", market_id);
+        } else if !known_markets.contains(&market_id) {
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Ok(Json(sorted_markets(&paused_markets)))
+    }
+
+    async fn stale_books_handler(
+        State((_, _, _, _, _, _, _, _, _, stale_markets, _)): State<DashboardState>,
+    ) -> Json<Vec<StaleMarket>> {
+        Json(stale_markets.lock().clone())
+    }
+
+    /// 200 when every subsystem is healthy, 503 otherwise; the body says which
+    async fn health_handler(
+        State((metrics, _, _, _, _, _, health, _, _, _, _)): State<DashboardState>,
+    ) -> (StatusCode, Json<HealthReport>) {
+        let report = health.report(&*metrics.read().await);
+        let status = if report.is_healthy() {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        (status, Json(report))
     }
 
     #[inline]
@@ -0,0 +1,136 @@
+use crate::utils::MonitoringConfig;
+use chrono::Utc;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Near misses kept for the dashboard; the oldest are dropped first
+pub(crate) const MAX_NEAR_MISSES: usize = 500;
+
+/// A market is recorded missing the same threshold at most once per this;
+/// detection re-runs on every book update and would otherwise flood the list
+const NEAR_MISS_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Threshold a near miss fell short of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NearMissKind {
+    /// Net edge under `min_edge` (or the short-window min edge)
+    Edge,
+    /// Thinnest leg under `min_liquidity`
+    Liquidity,
+    /// Exposure after the trade over `max_exposure_per_market`
+    MarketExposure,
+    /// Exposure after the trade over `max_exposure_per_event`
+    EventExposure,
+}
+
+/// An opportunity rejected by a threshold it almost met
+#[derive(Debug, Clone, Serialize)]
+pub struct NearMiss {
+    pub market_id: String,
+    pub kind: NearMissKind,
+    /// What the opportunity had (edge, liquidity, exposure)
+    pub value: Decimal,
+    /// What it needed
+    pub threshold: Decimal,
+    pub timestamp: i64,
+}
+
+/// Rejected opportunities within `near_miss_band` of passing, for seeing what
+/// loosening a threshold would let through.
+///
+/// Detection and risk checks feed it at their rejection points; the dashboard
+/// serves it at `/near_misses`. With a band of 0 nothing is recorded. Each
+/// (market, kind) is recorded at most once per `NEAR_MISS_COOLDOWN`.
+pub struct NearMissRecorder {
+    band: Decimal,
+    near_misses: Mutex<VecDeque<NearMiss>>,
+    /// (market, kind) -> when it was last recorded
+    last_recorded: Mutex<HashMap<(String, NearMissKind), Instant>>,
+}
+
+impl NearMissRecorder {
+    pub fn new(config: &MonitoringConfig) -> Self {
+        Self {
+            band: config.near_miss_band,
+            near_misses: Mutex::new(VecDeque::with_capacity(MAX_NEAR_MISSES)),
+            last_recorded: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.band > Decimal::ZERO
+    }
+
+    /// `value` fell short of a `threshold` it had to reach; kept if within the band
+    pub fn record_below(
+        &self,
+        market_id: &str,
+        kind: NearMissKind,
+        value: Decimal,
+        threshold: Decimal,
+    ) {
+        let floor = threshold * (Decimal::ONE - self.band);
+        if self.is_enabled() && value < threshold && value >= floor {
+            self.push(market_id, kind, value, threshold);
+        }
+    }
+
+    /// `value` went over a `threshold` it had to stay under; kept if within the band
+    pub fn record_above(
+        &self,
+        market_id: &str,
+        kind: NearMissKind,
+        value: Decimal,
+        threshold: Decimal,
+    ) {
+        let ceiling = threshold * (Decimal::ONE + self.band);
+        if self.is_enabled() && value > threshold && value <= ceiling {
+            self.push(market_id, kind, value, threshold);
+        }
+    }
+
+    /// Up to `limit` near misses, newest first
+    pub fn recent(&self, limit: usize) -> Vec<NearMiss> {
+        self.near_misses
+            .lock()
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    fn push(&self, market_id: &str, kind: NearMissKind, value: Decimal, threshold: Decimal) {
+        let now = Instant::now();
+        let mut last_recorded = self.last_recorded.lock();
+        let key = (market_id.to_string(), kind);
+        if last_recorded
+            .get(&key)
+            .is_some_and(|last| now.duration_since(*last) < NEAR_MISS_COOLDOWN)
+        {
+            return;
+        }
+        if last_recorded.len() >= MAX_NEAR_MISSES {
+            last_recorded.retain(|_, last| now.duration_since(*last) < NEAR_MISS_COOLDOWN);
+        }
+        last_recorded.insert(key, now);
+        drop(last_recorded);
+
+        let mut near_misses = self.near_misses.lock();
+        if near_misses.len() >= MAX_NEAR_MISSES {
+            near_misses.pop_front();
+        }
+        near_misses.push_back(NearMiss {
+            market_id: market_id.to_string(),
+            kind,
+            value,
+            threshold,
+            timestamp: Utc::now().timestamp(),
+        });
+    }
+}
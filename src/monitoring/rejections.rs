@@ -0,0 +1,133 @@
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Check that turned a detected price gap away
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectReason {
+    /// Thinnest leg under `min_liquidity`
+    Liquidity,
+    /// No profit after fees, or net edge under `min_edge`
+    Edge,
+    /// Net profit under `min_net_profit_usd`
+    Profit,
+    /// Market blacklisted, paused or cooling down
+    Blacklist,
+}
+
+impl RejectReason {
+    const COUNT: usize = 4;
+
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Rejection counts for one market, or summed over all of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RejectionCounts {
+    pub liquidity: u64,
+    pub edge: u64,
+    pub profit: u64,
+    pub blacklist: u64,
+}
+
+impl RejectionCounts {
+    #[inline]
+    pub fn get(&self, reason: RejectReason) -> u64 {
+        match reason {
+            RejectReason::Liquidity => self.liquidity,
+            RejectReason::Edge => self.edge,
+            RejectReason::Profit => self.profit,
+            RejectReason::Blacklist => self.blacklist,
+        }
+    }
+
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.liquidity + self.edge + self.profit + self.blacklist
+    }
+
+    fn load(counters: &[AtomicU64; RejectReason::COUNT]) -> Self {
+        let count = |reason: RejectReason| counters[reason.index()].load(Ordering::Relaxed);
+        Self {
+            liquidity: count(RejectReason::Liquidity),
+            edge: count(RejectReason::Edge),
+            profit: count(RejectReason::Profit),
+            blacklist: count(RejectReason::Blacklist),
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.liquidity += other.liquidity;
+        self.edge += other.edge;
+        self.profit += other.profit;
+        self.blacklist += other.blacklist;
+    }
+}
+
+/// What `/rejections` serves
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectionReport {
+    pub totals: RejectionCounts,
+    pub markets: BTreeMap<String, RejectionCounts>,
+}
+
+/// Per-market counts of why detection turned price gaps away, for telling
+/// whether thresholds or thin books are what keeps us out of a market.
+///
+/// Detection bumps a counter at each rejection point; once a market has been
+/// seen that's a single atomic increment. The dashboard serves it at `/rejections`.
+#[derive(Default)]
+pub struct RejectionStats {
+    markets: DashMap<String, [AtomicU64; RejectReason::COUNT]>,
+}
+
+impl RejectionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn record(&self, market_id: &str, reason: RejectReason) {
+        if let Some(counters) = self.markets.get(market_id) {
+            counters[reason.index()].fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.markets.entry(market_id.to_string()).or_default()[reason.index()]
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts for `market_id`; zero if it was never rejected
+    pub fn for_market(&self, market_id: &str) -> RejectionCounts {
+        self.markets
+            .get(market_id)
+            .map(|counters| RejectionCounts::load(&counters))
+            .unwrap_or_default()
+    }
+
+    /// Counts summed over every market
+    pub fn totals(&self) -> RejectionCounts {
+        let mut totals = RejectionCounts::default();
+        for counters in self.markets.iter() {
+            totals.add(&RejectionCounts::load(counters.value()));
+        }
+        totals
+    }
+
+    pub fn report(&self) -> RejectionReport {
+        let markets: BTreeMap<String, RejectionCounts> = self
+            .markets
+            .iter()
+            .map(|entry| (entry.key().clone(), RejectionCounts::load(entry.value())))
+            .collect();
+        let mut totals = RejectionCounts::default();
+        for counts in markets.values() {
+            totals.add(counts);
+        }
+        RejectionReport { totals, markets }
+    }
+}
@@ -0,0 +1,228 @@
+use super::sinks::{self, AlertSink};
+use super::{push_alert, Alert, AlertSeverity, AlertType, TradeRecord};
+use crate::risk::DailyPnlTracker;
+use crate::utils::Config;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+/// File under `daily_report_dir` each summary is appended to, one JSON object per line
+const REPORT_FILE: &str = "daily_summaries.jsonl";
+
+/// P&L of one market over a report day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarketPnl {
+    pub market_id: String,
+    pub pnl: Decimal,
+}
+
+/// One UTC day of trading, emitted when the day rolls over
+#[derive(Debug, Clone, Serialize)]
+pub struct DailySummary {
+    /// UTC date, `%Y-%m-%d`
+    pub date: String,
+    pub trades: u64,
+    /// Executions with positive realized P&L
+    pub wins: u64,
+    pub win_rate: f64,
+    pub realized_pnl: Decimal,
+    /// Open positions marked to market at the rollover
+    pub unrealized_pnl: Decimal,
+    pub fees_paid: Decimal,
+    pub best_market: Option<MarketPnl>,
+    pub worst_market: Option<MarketPnl>,
+    /// Notional traded by the volume farmer over the day
+    pub volume_farmed: Decimal,
+    /// Liquidity rewards the market maker estimates it earned over the day
+    pub mm_rewards_estimate: Decimal,
+}
+
+impl DailySummary {
+    /// Aggregate the executions in `trades` that fall on `date` (UTC); the others
+    /// are ignored. Strategy figures start at zero for the caller to fill in.
+    pub fn from_trades<'a>(date: &str, trades: impl IntoIterator<Item = &'a TradeRecord>) -> Self {
+        let mut summary = Self {
+            date: date.to_string(),
+            trades: 0,
+            wins: 0,
+            win_rate: 0.0,
+            realized_pnl: Decimal::ZERO,
+            unrealized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            best_market: None,
+            worst_market: None,
+            volume_farmed: Decimal::ZERO,
+            mm_rewards_estimate: Decimal::ZERO,
+        };
+        let mut market_pnl: HashMap<&str, Decimal> = HashMap::new();
+
+        for trade in trades.into_iter().filter(|t| trade_date(t) == date) {
+            summary.trades += 1;
+            if trade.actual_profit > Decimal::ZERO {
+                summary.wins += 1;
+            }
+            summary.realized_pnl += trade.actual_profit;
+            summary.fees_paid += trade.fees;
+            *market_pnl.entry(trade.market_id.as_str()).or_default() += trade.actual_profit;
+        }
+
+        if summary.trades > 0 {
+            summary.win_rate = summary.wins as f64 / summary.trades as f64;
+        }
+
+        // Ties go to the lexically first market so the report is deterministic
+        let mut markets: Vec<(&str, Decimal)> = market_pnl.into_iter().collect();
+        markets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let to_market_pnl = |(market_id, pnl): &(&str, Decimal)| MarketPnl {
+            market_id: market_id.to_string(),
+            pnl: *pnl,
+        };
+        summary.best_market = markets.first().map(to_market_pnl);
+        summary.worst_market = markets.last().map(to_market_pnl);
+
+        summary
+    }
+}
+
+impl std::fmt::Display for DailySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "📅 Daily summary {}: {} trades ({:.1}% won), realized ${:.2}, unrealized ${:.2}, fees ${:.2}, volume farmed ${:.2}, MM rewards ~${:.2}",
+            self.date,
+            self.trades,
+            self.win_rate * 100.0,
+            self.realized_pnl,
+            self.unrealized_pnl,
+            self.fees_paid,
+            self.volume_farmed,
+            self.mm_rewards_estimate
+        )?;
+
+        if let (Some(best), Some(worst)) = (&self.best_market, &self.worst_market) {
+            write!(
+                f,
+                ", best {} (${:.2}), worst {} (${:.2})",
+                best.market_id, best.pnl, worst.market_id, worst.pnl
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[inline]
+fn trade_date(trade: &TradeRecord) -> String {
+    DateTime::<Utc>::from_timestamp(trade.timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Publishes a `DailySummary` at each UTC rollover. Shares the execution history
+/// with `Monitor`, so it can run on the strategy loop while `Monitor` is borrowed
+/// by the WebSocket loop.
+pub struct DailyReporter {
+    config: Arc<Config>,
+    day_trades: Arc<Mutex<Vec<TradeRecord>>>,
+    alerts: Arc<tokio::sync::RwLock<VecDeque<Alert>>>,
+    sinks: Vec<Box<dyn AlertSink>>,
+}
+
+impl DailyReporter {
+    pub(super) fn new(
+        config: Arc<Config>,
+        day_trades: Arc<Mutex<Vec<TradeRecord>>>,
+        alerts: Arc<tokio::sync::RwLock<VecDeque<Alert>>>,
+        http_client: &reqwest::Client,
+    ) -> Self {
+        let sinks = sinks::sinks_from_config(&config.alerts, http_client);
+        Self {
+            config,
+            day_trades,
+            alerts,
+            sinks,
+        }
+    }
+
+    /// Summarize the day `closed` covers and drop its trades from the history.
+    /// `volume_farmed` and `mm_rewards_estimate` are the strategies' figures for that day.
+    pub fn summarize(
+        &self,
+        closed: &DailyPnlTracker,
+        volume_farmed: Decimal,
+        mm_rewards_estimate: Decimal,
+    ) -> DailySummary {
+        let mut day_trades = self.day_trades.lock();
+        let mut summary = DailySummary::from_trades(&closed.date, day_trades.iter());
+        day_trades.retain(|trade| trade_date(trade) > closed.date);
+        drop(day_trades);
+
+        summary.unrealized_pnl = closed.unrealized_pnl;
+        // Fills reported by the user channel have no trade record
+        summary.fees_paid += closed.fill_fees;
+        summary.volume_farmed = volume_farmed;
+        summary.mm_rewards_estimate = mm_rewards_estimate;
+        summary
+    }
+
+    /// Log `summary`, append it to the report file and, if enabled, send it to
+    /// the alert sinks
+    pub async fn publish(&self, summary: &DailySummary) {
+        info!("{}", summary);
+
+        let dir = &self.config.monitoring.daily_report_dir;
+        if !dir.is_empty() {
+            if let Err(e) = append_report(Path::new(dir), summary).await {
+                warn!("Failed to write daily summary: {:?}", e);
+            }
+        }
+
+        if self.config.monitoring.daily_report_alert {
+            let alert = Alert {
+                alert_type: AlertType::DailySummary,
+                message: summary.to_string(),
+                timestamp: Utc::now().timestamp(),
+                severity: AlertSeverity::Info,
+            };
+
+            push_alert(
+                &mut *self.alerts.write().await,
+                alert.clone(),
+                &self.config.monitoring,
+            );
+
+            join_all(self.sinks.iter().map(|sink| sink.send(&alert))).await;
+        }
+    }
+}
+
+/// Append `summary` as one JSON line to `dir/daily_summaries.jsonl`
+pub async fn append_report(dir: &Path, summary: &DailySummary) -> Result<()> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let path = dir.join(REPORT_FILE);
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    // One write per line, so a concurrent append can't land mid-line
+    let line = format!("{}\n", serde_json::to_string(summary)?);
+    file.write_all(line.as_bytes())
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
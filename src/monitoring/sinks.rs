@@ -0,0 +1,137 @@
+use super::{Alert, AlertSeverity};
+use crate::utils::AlertsConfig;
+use async_trait::async_trait;
+use reqwest::Client;
+use tracing::error;
+
+/// A channel alerts are delivered to. `Monitor` fans every alert out to each sink;
+/// delivery failures are logged by the sink rather than surfaced to the trading loop.
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn send(&self, alert: &Alert);
+}
+
+/// Build the sinks enabled in `[alerts]`, all sending through `client`
+pub fn sinks_from_config(config: &AlertsConfig, client: &Client) -> Vec<Box<dyn AlertSink>> {
+    let mut sinks: Vec<Box<dyn AlertSink>> = Vec::new();
+
+    if config.enable_telegram
+        && !config.telegram_bot_token.is_empty()
+        && !config.telegram_chat_id.is_empty()
+    {
+        sinks.push(Box::new(TelegramSink::new(
+            client.clone(),
+            &config.telegram_bot_token,
+            &config.telegram_chat_id,
+        )));
+    }
+    if config.enable_discord && !config.discord_webhook_url.is_empty() {
+        sinks.push(Box::new(DiscordSink::new(
+            client.clone(),
+            &config.discord_webhook_url,
+        )));
+    }
+    if config.enable_webhook && !config.webhook_url.is_empty() {
+        sinks.push(Box::new(WebhookSink::new(
+            client.clone(),
+            &config.webhook_url,
+        )));
+    }
+
+    sinks
+}
+
+/// Human-readable alert text shared by the chat sinks
+fn format_message(alert: &Alert) -> String {
+    let severity_icon = match alert.severity {
+        AlertSeverity::Info => "ℹ️",
+        AlertSeverity::Warning => "⚠️",
+        AlertSeverity::Error => "❌",
+        AlertSeverity::Critical => "🚨",
+    };
+
+    format!("{} HFTPM Alert\n\n{}", severity_icon, alert.message)
+}
+
+/// Telegram bot `sendMessage`
+pub struct TelegramSink {
+    client: Client,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramSink {
+    pub fn new(client: Client, bot_token: &str, chat_id: &str) -> Self {
+        Self {
+            client,
+            bot_token: bot_token.to_string(),
+            chat_id: chat_id.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for TelegramSink {
+    async fn send(&self, alert: &Alert) {
+        let url = format!(
+            "https://api.telegram.org/bot{}/sendMessage?chat_id={}&text={}",
+            self.bot_token,
+            self.chat_id,
+            urlencoding::encode(&format_message(alert))
+        );
+
+        if let Err(e) = self.client.get(&url).send().await {
+            error!("Failed to send Telegram alert: {:?}", e);
+        }
+    }
+}
+
+/// Discord incoming webhook (`{"content": ...}`)
+pub struct DiscordSink {
+    client: Client,
+    webhook_url: String,
+}
+
+impl DiscordSink {
+    pub fn new(client: Client, webhook_url: &str) -> Self {
+        Self {
+            client,
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for DiscordSink {
+    async fn send(&self, alert: &Alert) {
+        let body = serde_json::json!({ "content": format_message(alert) });
+
+        if let Err(e) = self.client.post(&self.webhook_url).json(&body).send().await {
+            error!("Failed to send Discord alert: {:?}", e);
+        }
+    }
+}
+
+/// Generic webhook: POSTs the alert itself as JSON
+pub struct WebhookSink {
+    client: Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(client: Client, url: &str) -> Self {
+        Self {
+            client,
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    async fn send(&self, alert: &Alert) {
+        if let Err(e) = self.client.post(&self.url).json(alert).send().await {
+            error!("Failed to send webhook alert: {:?}", e);
+        }
+    }
+}
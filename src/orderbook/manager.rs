@@ -1,13 +1,18 @@
-use crate::utils::Config;
+use crate::arb_engine::ArbSide;
+use crate::error::{HftpmError, Result};
+use crate::gamma_api::Market;
+use crate::utils::{saturating_i64, Config};
 use crate::websocket::types::BookSnapshot;
-use anyhow::{Context, Result};
 use dashmap::DashMap;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::sync::Arc;
 use tracing::debug;
 
+/// Ask levels `(price, size)`, cheapest first
+pub type AskLadder = Vec<(Decimal, Decimal)>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBook {
     pub market_id: String,
@@ -16,6 +21,10 @@ pub struct OrderBook {
     pub asks: BTreeMap<Decimal, Decimal>,
     pub timestamp: i64,
     pub hash: String,
+    /// Exchange timestamp of the last applied snapshot. `timestamp` also moves on
+    /// every price change, so snapshots are ordered against this instead.
+    #[serde(default)]
+    pub snapshot_timestamp: i64,
 }
 
 impl OrderBook {
@@ -27,6 +36,7 @@ impl OrderBook {
             asks: BTreeMap::new(),
             timestamp,
             hash,
+            snapshot_timestamp: timestamp,
         }
     }
 
@@ -35,6 +45,7 @@ impl OrderBook {
         self.bids = snapshot.bids.iter().cloned().collect();
         self.asks = snapshot.asks.iter().cloned().collect();
         self.timestamp = snapshot.timestamp;
+        self.snapshot_timestamp = snapshot.timestamp;
         self.hash = snapshot.hash.clone();
 
         debug!(
@@ -86,6 +97,39 @@ impl OrderBook {
             .map(|(price, size)| (*price, *size))
     }
 
+    /// The best `levels` asks as `(price, size)`, cheapest first
+    #[inline]
+    pub fn top_asks(&self, levels: usize) -> AskLadder {
+        self.asks
+            .iter()
+            .take(levels)
+            .map(|(price, size)| (*price, *size))
+            .collect()
+    }
+
+    /// Arithmetic midpoint of the best bid and ask
+    #[inline]
+    pub fn midpoint(&self) -> Option<Decimal> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+        Some((bid + ask) / Decimal::TWO)
+    }
+
+    /// Size-weighted microprice, `(bid * ask_size + ask * bid_size) / (bid_size + ask_size)`.
+    /// Leans toward the ask when bids are heavier, and vice versa.
+    #[inline]
+    pub fn microprice(&self) -> Option<Decimal> {
+        let (bid, bid_size) = self.best_bid()?;
+        let (ask, ask_size) = self.best_ask()?;
+        let total_size = bid_size + ask_size;
+
+        if total_size.is_zero() {
+            return self.midpoint();
+        }
+
+        Some((bid * ask_size + ask * bid_size) / total_size)
+    }
+
     #[inline]
     pub fn spread(&self) -> Option<Decimal> {
         if let (Some((bid_price, _)), Some((ask_price, _))) = (self.best_bid(), self.best_ask()) {
@@ -96,6 +140,45 @@ impl OrderBook {
         None
     }
 
+    /// Spread in basis points of the midpoint
+    #[inline]
+    pub fn spread_bps(&self) -> Option<Decimal> {
+        let spread = self.spread()?;
+        let midpoint = self.midpoint()?;
+        spread
+            .checked_div(midpoint)
+            .map(|ratio| ratio * Decimal::from(10_000))
+    }
+
+    /// Share of the top `levels` on each side that is bid: 1 is all bids, 0 all asks
+    #[inline]
+    pub fn imbalance(&self, levels: usize) -> Option<Decimal> {
+        let bid_depth: Decimal = self.bids.values().rev().take(levels).sum();
+        let ask_depth: Decimal = self.asks.values().take(levels).sum();
+        bid_depth.checked_div(bid_depth + ask_depth)
+    }
+
+    /// `(bid_depth, ask_depth)` resting within `bps` of the midpoint
+    #[inline]
+    pub fn depth_within_bps(&self, bps: u32) -> Option<(Decimal, Decimal)> {
+        let midpoint = self.midpoint()?;
+        let band = midpoint * Decimal::from(bps) / Decimal::from(10_000);
+        Some((
+            self.bid_depth_at(midpoint - band),
+            self.ask_depth_at(midpoint + band),
+        ))
+    }
+
+    /// Best bid at or through the best ask (crossed or locked), which a real
+    /// book can't sustain - usually out-of-order updates awaiting the next snapshot
+    #[inline]
+    pub fn is_crossed(&self) -> bool {
+        matches!(
+            (self.best_bid(), self.best_ask()),
+            (Some((bid, _)), Some((ask, _))) if bid >= ask
+        )
+    }
+
     #[inline]
     pub fn bid_depth_at(&self, price: Decimal) -> Decimal {
         self.bids.range(price..).map(|(_, size)| *size).sum()
@@ -116,14 +199,17 @@ impl OrderBook {
         self.asks.values().sum()
     }
 
+    /// Milliseconds since the book last changed
     #[inline]
-    pub fn is_stale(&self, max_age_ms: u64) -> bool {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as i64;
+    pub fn age_ms(&self) -> i64 {
+        chrono::Utc::now()
+            .timestamp_millis()
+            .saturating_sub(self.timestamp)
+    }
 
-        now - self.timestamp > max_age_ms as i64
+    #[inline]
+    pub fn is_stale(&self, max_age_ms: u64) -> bool {
+        self.age_ms() > saturating_i64(max_age_ms)
     }
 }
 
@@ -145,6 +231,60 @@ impl MarketBooks {
         }
     }
 
+    /// `(asset_id, best_ask_price, best_ask_size)` for every book with an ask
+    #[inline]
+    pub fn best_asks(&self) -> Vec<(String, Decimal, Decimal)> {
+        self.books
+            .iter()
+            .filter_map(|book| {
+                book.best_ask()
+                    .map(|(price, size)| (book.asset_id.clone(), price, size))
+            })
+            .collect()
+    }
+
+    /// `(asset_id, top asks)` for every book with an ask, `levels` deep
+    #[inline]
+    pub fn ask_ladders(&self, levels: usize) -> Vec<(String, AskLadder)> {
+        self.books
+            .iter()
+            .filter(|book| !book.asks.is_empty())
+            .map(|book| (book.asset_id.clone(), book.top_asks(levels)))
+            .collect()
+    }
+
+    /// `(asset_id, best_bid_price, best_bid_size)` for every book with a bid
+    #[inline]
+    pub fn best_bids(&self) -> Vec<(String, Decimal, Decimal)> {
+        self.books
+            .iter()
+            .filter_map(|book| {
+                book.best_bid()
+                    .map(|(price, size)| (book.asset_id.clone(), price, size))
+            })
+            .collect()
+    }
+
+    /// Asset id of the first crossed/locked book in this market, if any
+    #[inline]
+    pub fn crossed_asset_id(&self) -> Option<&str> {
+        self.books
+            .iter()
+            .find(|book| book.is_crossed())
+            .map(|book| book.asset_id.as_str())
+    }
+
+    /// The other token of a binary market, whose share completes a set with
+    /// one of `asset_id`
+    #[inline]
+    pub fn complement_of(&self, asset_id: &str) -> Option<&str> {
+        match (&self.asset_id_yes, &self.asset_id_no) {
+            (Some(yes), Some(no)) if yes == asset_id => Some(no),
+            (Some(yes), Some(no)) if no == asset_id => Some(yes),
+            _ => None,
+        }
+    }
+
     #[inline]
     pub fn is_binary(&self) -> bool {
         self.books.len() == 2 && self.asset_id_yes.is_some() && self.asset_id_no.is_some()
@@ -180,27 +320,168 @@ impl MarketBooks {
     }
 }
 
+/// What `update_book` did with a snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookUpdate {
+    Applied,
+    /// Same hash and timestamp as the book already held: a redelivery
+    SkippedDuplicate,
+    /// Older than the snapshot already applied: delivered out of order
+    SkippedStale,
+}
+
 pub struct OrderBookManager {
     #[allow(dead_code)]
     config: Arc<Config>,
     market_books: DashMap<String, MarketBooks>,
+    /// Market -> tick size announced by `tick_size_change` events
+    tick_sizes: DashMap<String, Decimal>,
+    /// Tick size of markets that haven't announced a change (`trading.tick_size`)
+    default_tick_size: Decimal,
+    /// Market -> its token ids in outcome order, from `register_market`
+    market_assets: DashMap<String, Vec<String>>,
 }
 
 impl OrderBookManager {
     pub fn new(config: &Config) -> Result<Self> {
+        let default_tick_size: Decimal = config.trading.tick_size.parse().map_err(|_| {
+            HftpmError::Config(format!("Invalid tick_size {:?}", config.trading.tick_size))
+        })?;
+        if default_tick_size <= Decimal::ZERO || default_tick_size >= Decimal::ONE {
+            return Err(HftpmError::Config(format!(
+                "tick_size must be between 0 and 1, got {}",
+                default_tick_size
+            )));
+        }
+
         Ok(Self {
             config: Arc::new(config.clone()),
             market_books: DashMap::new(),
+            tick_sizes: DashMap::new(),
+            default_tick_size,
+            market_assets: DashMap::new(),
         })
     }
 
+    /// Record a market's tokens so YES/NO and warmup can be worked out from them.
+    /// Gamma lists `clobTokenIds` in outcome order, so a binary market's first
+    /// token is the first outcome ("Yes", "Up"). Markets that aren't registered
+    /// never count as binary.
+    pub fn register_market(&self, market: &Market) {
+        if market.assets_ids.is_empty() {
+            return;
+        }
+
+        self.market_assets
+            .insert(market.market.clone(), market.assets_ids.clone());
+
+        if let Some(mut market_books) = self.market_books.get_mut(&market.market) {
+            Self::assign_outcomes(&mut market_books, &market.assets_ids);
+        }
+    }
+
+    #[inline]
+    fn assign_outcomes(market_books: &mut MarketBooks, assets_ids: &[String]) {
+        if let [yes, no] = assets_ids {
+            market_books.asset_id_yes = Some(yes.clone());
+            market_books.asset_id_no = Some(no.clone());
+        }
+    }
+
+    /// `(loaded, expected)` books for a market. Unregistered markets expect
+    /// whatever has loaded.
+    pub fn market_readiness(&self, market_id: &str) -> (usize, usize) {
+        match self.registered_assets(market_id) {
+            Some(assets) => {
+                let missing = self.missing_assets_of(market_id, &assets).len();
+                (assets.len() - missing, assets.len())
+            }
+            None => {
+                let loaded = self
+                    .with_market_books(market_id, |market_books| market_books.books.len())
+                    .unwrap_or(0);
+                (loaded, loaded)
+            }
+        }
+    }
+
+    /// Registered assets of a market with no book yet, in outcome order
+    pub fn missing_assets(&self, market_id: &str) -> Vec<String> {
+        self.registered_assets(market_id)
+            .map(|assets| self.missing_assets_of(market_id, &assets))
+            .unwrap_or_default()
+    }
+
+    // Copied out so the registry lock is never held while taking a book lock
+    #[inline]
+    fn registered_assets(&self, market_id: &str) -> Option<Vec<String>> {
+        self.market_assets
+            .get(market_id)
+            .map(|assets| assets.clone())
+    }
+
+    fn missing_assets_of(&self, market_id: &str, assets: &[String]) -> Vec<String> {
+        self.with_market_books(market_id, |market_books| {
+            assets
+                .iter()
+                .filter(|asset| !market_books.books.iter().any(|b| &b.asset_id == *asset))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_else(|| assets.to_vec())
+    }
+
+    /// `(ready, registered)` markets, where ready means every book has loaded
+    pub fn warmup_progress(&self) -> (usize, usize) {
+        let registered: Vec<(String, Vec<String>)> = self
+            .market_assets
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        let ready = registered
+            .iter()
+            .filter(|(market_id, assets)| self.missing_assets_of(market_id, assets).is_empty())
+            .count();
+
+        (ready, registered.len())
+    }
+
+    #[inline]
+    pub fn set_tick_size(&self, market_id: &str, tick_size: Decimal) {
+        self.tick_sizes.insert(market_id.to_string(), tick_size);
+    }
+
+    /// Current tick size for a market: the last announced change, else the configured one
+    #[inline]
+    pub fn tick_size(&self, market_id: &str) -> Decimal {
+        self.tick_sizes
+            .get(market_id)
+            .map_or(self.default_tick_size, |tick| *tick)
+    }
+
+    /// Round `price` onto a valid tick for the market, away from the other side
+    /// of the book: buys round down and sells up, so rounding never makes an
+    /// order pay more or receive less than `price`. Kept within `[tick, 1 - tick]`
+    /// so the order is never rejected as off-tick.
+    #[inline]
+    pub fn round_to_tick(&self, market_id: &str, price: Decimal, side: ArbSide) -> Decimal {
+        let tick = self.tick_size(market_id);
+        let strategy = match side {
+            ArbSide::Buy => RoundingStrategy::ToNegativeInfinity,
+            ArbSide::Sell => RoundingStrategy::ToPositiveInfinity,
+        };
+        let ticks = (price / tick).round_dp_with_strategy(0, strategy);
+
+        (ticks * tick).clamp(tick, Decimal::ONE - tick).normalize()
+    }
+
     #[inline]
     pub fn update_book(
         &self,
         market_id: &str,
         asset_id: &str,
         snapshot: &BookSnapshot,
-    ) -> Result<()> {
+    ) -> Result<BookUpdate> {
         // Skip stale check for now - initial WebSocket snapshots can be several minutes old
         // and rejecting them means we have no market data at all
         // TODO: Re-enable with smarter logic that accepts first snapshot per market regardless of age
@@ -212,7 +493,34 @@ impl OrderBookManager {
         let mut market_books = self
             .market_books
             .entry(market_id.to_string())
-            .or_insert_with(|| MarketBooks::new(market_id.to_string()));
+            .or_insert_with(|| {
+                let mut market_books = MarketBooks::new(market_id.to_string());
+                if let Some(assets) = self.market_assets.get(market_id) {
+                    Self::assign_outcomes(&mut market_books, &assets);
+                }
+                market_books
+            });
+
+        if let Some(current) = market_books
+            .books
+            .iter()
+            .find(|book| book.asset_id == asset_id)
+        {
+            if snapshot.timestamp < current.snapshot_timestamp {
+                debug!(
+                    "⏭️  Out-of-order snapshot for {} asset {}: {} < {}",
+                    market_id, asset_id, snapshot.timestamp, current.snapshot_timestamp
+                );
+                return Ok(BookUpdate::SkippedStale);
+            }
+            // Without a hash a same-millisecond snapshot can't be told apart, so apply it
+            if !snapshot.hash.is_empty()
+                && snapshot.hash == current.hash
+                && snapshot.timestamp == current.snapshot_timestamp
+            {
+                return Ok(BookUpdate::SkippedDuplicate);
+            }
+        }
 
         let mut new_book = OrderBook::new(
             market_id.to_string(),
@@ -223,7 +531,6 @@ impl OrderBookManager {
 
         new_book.update_from_snapshot(snapshot);
 
-        // First, update the books vector
         if let Some(idx) = market_books
             .books
             .iter()
@@ -234,32 +541,12 @@ impl OrderBookManager {
             market_books.books.push(new_book);
         }
 
-        // Then, if we have exactly 2 books, determine yes/no asset IDs
-        if market_books.books.len() == 2 {
-            let asset_id_0 = market_books.books[0].asset_id.clone();
-            let asset_id_1 = market_books.books[1].asset_id.clone();
-            let bids_0_len = market_books.books[0].bids.keys().len();
-            let asks_0_len = market_books.books[0].asks.keys().len();
-
-            if bids_0_len > asks_0_len {
-                market_books.asset_id_yes = Some(asset_id_0);
-                market_books.asset_id_no = Some(asset_id_1);
-            } else {
-                market_books.asset_id_yes = Some(asset_id_1);
-                market_books.asset_id_no = Some(asset_id_0);
-            }
-        }
-
-        let updated = true;
-
-        if updated {
-            debug!(
-                "✅ Updated order book for market {} asset {}",
-                market_id, asset_id
-            );
-        }
+        debug!(
+            "✅ Updated order book for market {} asset {}",
+            market_id, asset_id
+        );
 
-        Ok(())
+        Ok(BookUpdate::Applied)
     }
 
     #[inline]
@@ -274,7 +561,7 @@ impl OrderBookManager {
         let mut market_books = self
             .market_books
             .get_mut(market_id)
-            .context("Market not found")?;
+            .ok_or_else(|| HftpmError::NotFound(format!("Market {}", market_id)))?;
 
         for book in &mut market_books.books {
             if book.asset_id == asset_id {
@@ -291,6 +578,17 @@ impl OrderBookManager {
         self.market_books.get(market_id).map(|books| books.clone())
     }
 
+    /// Run `f` against a market's books without cloning them.
+    /// Holds the map's shard read lock for the duration of `f`, so keep it short.
+    #[inline]
+    pub fn with_market_books<R>(
+        &self,
+        market_id: &str,
+        f: impl FnOnce(&MarketBooks) -> R,
+    ) -> Option<R> {
+        self.market_books.get(market_id).map(|books| f(&books))
+    }
+
     /// Get a specific order book by market_id and asset_id
     #[inline]
     pub fn get_book(&self, market_id: &str, asset_id: &str) -> Option<OrderBook> {
@@ -302,40 +600,97 @@ impl OrderBookManager {
             .cloned()
     }
 
+    /// Run `f` against one asset's book without cloning it (same locking caveat
+    /// as `with_market_books`)
     #[inline]
-    pub fn get_best_asks_for_market(
+    pub fn with_book<R>(
         &self,
         market_id: &str,
-    ) -> Option<Vec<(String, Decimal, Decimal)>> {
-        let market_books = self.get_market_books(market_id)?;
-
-        Some(
+        asset_id: &str,
+        f: impl FnOnce(&OrderBook) -> R,
+    ) -> Option<R> {
+        self.with_market_books(market_id, |market_books| {
             market_books
                 .books
                 .iter()
-                .filter_map(|book| {
-                    book.best_ask()
-                        .map(|(price, size)| (book.asset_id.clone(), price, size))
-                })
-                .collect(),
-        )
+                .find(|book| book.asset_id == asset_id)
+                .map(f)
+        })?
     }
 
     #[inline]
-    pub fn get_bid_ask_sum(&self, market_id: &str) -> Option<Decimal> {
-        let market_books = self.get_market_books(market_id)?;
+    pub fn midpoint(&self, market_id: &str, asset_id: &str) -> Option<Decimal> {
+        self.with_book(market_id, asset_id, OrderBook::midpoint)?
+    }
 
-        if market_books.is_binary() {
-            market_books.get_binary_book_sum()
-        } else {
-            Some(market_books.get_total_ask_sum())
-        }
+    #[inline]
+    pub fn spread_bps(&self, market_id: &str, asset_id: &str) -> Option<Decimal> {
+        self.with_book(market_id, asset_id, OrderBook::spread_bps)?
+    }
+
+    /// Bid share of the depth in the top `levels` of the book, see `OrderBook::imbalance`
+    #[inline]
+    pub fn book_imbalance(
+        &self,
+        market_id: &str,
+        asset_id: &str,
+        levels: usize,
+    ) -> Option<Decimal> {
+        self.with_book(market_id, asset_id, |book| book.imbalance(levels))?
+    }
+
+    /// `(bid_depth, ask_depth)` within `bps` of the midpoint
+    #[inline]
+    pub fn depth_within_bps(
+        &self,
+        market_id: &str,
+        asset_id: &str,
+        bps: u32,
+    ) -> Option<(Decimal, Decimal)> {
+        self.with_book(market_id, asset_id, |book| book.depth_within_bps(bps))?
+    }
+
+    #[inline]
+    pub fn get_best_asks_for_market(
+        &self,
+        market_id: &str,
+    ) -> Option<Vec<(String, Decimal, Decimal)>> {
+        self.with_market_books(market_id, |market_books| market_books.best_asks())
+    }
+
+    #[inline]
+    pub fn get_ask_ladders_for_market(
+        &self,
+        market_id: &str,
+        levels: usize,
+    ) -> Option<Vec<(String, AskLadder)>> {
+        self.with_market_books(market_id, |market_books| market_books.ask_ladders(levels))
+    }
+
+    /// Asset id of a crossed/locked book in this market, if any
+    #[inline]
+    pub fn crossed_asset_id(&self, market_id: &str) -> Option<String> {
+        self.with_market_books(market_id, |market_books| {
+            market_books.crossed_asset_id().map(str::to_string)
+        })?
+    }
+
+    #[inline]
+    pub fn get_bid_ask_sum(&self, market_id: &str) -> Option<Decimal> {
+        self.with_market_books(market_id, |market_books| {
+            if market_books.is_binary() {
+                market_books.get_binary_book_sum()
+            } else {
+                Some(market_books.get_total_ask_sum())
+            }
+        })?
     }
 
     #[inline]
     pub fn get_min_liquidity_at_best_asks(&self, market_id: &str) -> Option<Decimal> {
-        let market_books = self.get_market_books(market_id)?;
-        Some(market_books.min_liquidity_at_best_asks())
+        self.with_market_books(market_id, |market_books| {
+            market_books.min_liquidity_at_best_asks()
+        })
     }
 
     #[inline]
@@ -346,8 +701,34 @@ impl OrderBookManager {
             .collect()
     }
 
+    /// Age of a market's oldest book, which is as stale as its prices get
     #[inline]
-    pub fn cleanup_stale_books(&self, max_age_ms: u64) {
+    pub fn market_age_ms(&self, market_id: &str) -> Option<i64> {
+        self.with_market_books(market_id, |market_books| {
+            market_books.books.iter().map(OrderBook::age_ms).max()
+        })?
+    }
+
+    /// Markets with at least one book older than `max_age_ms`, with the age of
+    /// their oldest book, stalest first. Read-only, unlike `cleanup_stale_books`.
+    pub fn stale_markets(&self, max_age_ms: u64) -> Vec<(String, i64)> {
+        let max_age_ms = saturating_i64(max_age_ms);
+        let mut stale: Vec<(String, i64)> = self
+            .market_books
+            .iter()
+            .filter_map(|entry| {
+                let age_ms = entry.value().books.iter().map(OrderBook::age_ms).max()?;
+                (age_ms > max_age_ms).then(|| (entry.key().clone(), age_ms))
+            })
+            .collect();
+        stale.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        stale
+    }
+
+    /// Remove every market with at least one book older than `max_age_ms`.
+    /// Returns the IDs of the removed markets.
+    #[inline]
+    pub fn cleanup_stale_books(&self, max_age_ms: u64) -> Vec<String> {
         let mut stale_markets = Vec::new();
 
         for entry in self.market_books.iter() {
@@ -362,9 +743,11 @@ impl OrderBookManager {
             }
         }
 
-        for market_id in stale_markets {
-            self.market_books.remove(&market_id);
+        for market_id in &stale_markets {
+            self.market_books.remove(market_id);
             debug!("🗑️  Cleaned up stale market book: {}", market_id);
         }
+
+        stale_markets
     }
 }
@@ -1,3 +1,5 @@
 pub mod manager;
+pub mod rest;
 
-pub use manager::{MarketBooks, OrderBook, OrderBookManager};
+pub use manager::{AskLadder, BookUpdate, MarketBooks, OrderBook, OrderBookManager};
+pub use rest::ClobBookResponse;
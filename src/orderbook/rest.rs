@@ -0,0 +1,79 @@
+use super::manager::{OrderBook, OrderBookManager};
+use crate::error::{HftpmError, Result};
+use crate::websocket::types::{BookSnapshot, OrderSummary};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Full order book response from the CLOB `/book` endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClobBookResponse {
+    pub market: String,
+    pub asset_id: String,
+    #[serde(default)]
+    pub bids: Vec<OrderSummary>,
+    #[serde(default)]
+    pub asks: Vec<OrderSummary>,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+impl ClobBookResponse {
+    /// Convert into the same snapshot type the WebSocket path produces.
+    /// Levels that fail to parse are skipped.
+    pub fn to_snapshot(&self) -> BookSnapshot {
+        BookSnapshot {
+            asset_id: self.asset_id.clone(),
+            market: self.market.clone(),
+            bids: OrderSummary::parse_levels(&self.bids),
+            asks: OrderSummary::parse_levels(&self.asks),
+            timestamp: self
+                .timestamp
+                .as_ref()
+                .and_then(|ts| ts.parse().ok())
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+            hash: self.hash.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl OrderBookManager {
+    /// Fetch a full book for `token_id` from the CLOB REST API and apply it
+    /// through the same `update_book` path as WebSocket snapshots.
+    /// Returns `None` if the exchange has no book for this token.
+    pub async fn fetch_book_rest(
+        &self,
+        client: &Client,
+        base_url: &str,
+        token_id: &str,
+    ) -> Result<Option<OrderBook>> {
+        let url = format!("{}/book?token_id={}", base_url, token_id);
+
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| HftpmError::Network(format!("Failed to fetch order book: {}", e)))?;
+
+        if !response.status().is_success() {
+            debug!(
+                "No REST book for {}: status {}",
+                token_id,
+                response.status()
+            );
+            return Ok(None);
+        }
+
+        let book: ClobBookResponse = response
+            .json()
+            .await
+            .map_err(|e| HftpmError::Parse(format!("Failed to parse order book: {}", e)))?;
+
+        let snapshot = book.to_snapshot();
+        self.update_book(&snapshot.market, &snapshot.asset_id, &snapshot)?;
+
+        Ok(self.get_book(&snapshot.market, &snapshot.asset_id))
+    }
+}
@@ -1,10 +1,12 @@
 //! Parallel Market Scanner - Utilizes all CPU cores for maximum throughput
 //!
+//! Work is split across `latency.scanner_workers` threads (one per core by default).
 //! With 16 vCores, we can scan 5000 markets in parallel:
 //! - Each core handles ~312 markets
 //! - Detection latency reduced by ~16x
 //! - Can process 100,000+ orderbook updates/sec
 
+use crate::arb_engine::{ArbSide, OpportunityLeg};
 use crate::gamma_api::Market;
 use crate::orderbook::OrderBookManager;
 use crate::utils::Config;
@@ -13,14 +15,11 @@ use anyhow::Result;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 
-/// Number of worker threads (should match vCores)
-const NUM_WORKERS: usize = 16;
-
 /// Cross-market arbitrage opportunity
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrossMarketOpportunity {
@@ -33,7 +32,12 @@ pub struct CrossMarketOpportunity {
     pub position_size: Decimal,
     pub expected_profit: Decimal,
     pub confidence: Decimal,
+    /// Unix ms; execution drops it once it is older than `max_opportunity_age_ms`
     pub detected_at: i64,
+    /// One buy in each market, `position_size` each, that together lock in the edge
+    pub legs: Vec<OpportunityLeg>,
+    /// Thinner of the two legs' top-of-book size
+    pub min_liquidity: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +62,38 @@ pub struct MultiOutcomeOpportunity {
     pub outcomes: Vec<OutcomePrice>,
 }
 
+impl std::fmt::Display for CrossMarketOpportunity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} [{} {} / {} {}]: {:.2}% edge, ${:.2} profit, ${:.2} position",
+            self.arb_type,
+            self.market_a_id,
+            self.market_a_question,
+            self.market_b_id,
+            self.market_b_question,
+            self.edge * dec!(100),
+            self.expected_profit,
+            self.position_size
+        )
+    }
+}
+
+impl std::fmt::Display for MultiOutcomeOpportunity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MultiOutcome [{}]: {} outcomes sum ${:.4}, {:.2}% edge, ${:.2} profit, ${:.2} position",
+            self.market_id,
+            self.num_outcomes,
+            self.total_price,
+            self.edge * dec!(100),
+            self.expected_profit,
+            self.position_size
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutcomePrice {
     pub asset_id: String,
@@ -66,13 +102,33 @@ pub struct OutcomePrice {
     pub ask_size: Decimal,
 }
 
+/// Top of a binary market's books, as the cross-market checks price it
+struct BinaryTop {
+    yes_asset_id: String,
+    no_asset_id: String,
+    /// Best YES ask and its size
+    yes_ask: (Decimal, Decimal),
+    /// Best YES bid and its size; buying NO costs one minus it
+    yes_bid: (Decimal, Decimal),
+}
+
 /// Market correlation for cross-market arbitrage
 #[derive(Debug, Clone)]
 pub struct MarketCorrelation {
     pub market_a: String,
     pub market_b: String,
     pub correlation_type: CorrelationType,
-    pub strength: f64, // 0.0 to 1.0
+    pub strength: f64,      // 0.0 to 1.0
+    pub parse_quality: f64, // 0.0 to 1.0, how cleanly the targets/entities matched
+}
+
+impl MarketCorrelation {
+    /// Confidence that the legs are really related: strength scaled by parse quality
+    pub fn confidence(&self) -> Decimal {
+        Decimal::try_from(self.strength * self.parse_quality)
+            .unwrap_or_default()
+            .round_dp(2)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -92,11 +148,18 @@ pub struct ScannerStats {
     pub total_edge_found: Decimal,
     pub avg_scan_time_ms: f64,
     pub scans_per_second: f64,
+    /// Configured worker threads
+    pub workers: usize,
+    /// Threads actually used by the last correlation graph build
+    pub correlation_threads: usize,
+    pub correlation_build_ms: f64,
 }
 
-/// The parallel market scanner
+/// The parallel market scanner. Clones share its state.
+#[derive(Clone)]
 pub struct ParallelScanner {
     config: Arc<Config>,
+    workers: usize,
     markets: Arc<RwLock<Vec<Market>>>,
     correlations: Arc<RwLock<Vec<MarketCorrelation>>>,
     stats: Arc<RwLock<ScannerStats>>,
@@ -106,18 +169,21 @@ pub struct ParallelScanner {
 
 impl ParallelScanner {
     pub fn new(config: &Config, markets: Vec<Market>) -> Self {
-        info!(
-            "🔬 Parallel Scanner initialized with {} workers",
-            NUM_WORKERS
-        );
+        let workers = config.latency.scanner_workers.max(1);
+
+        info!("🔬 Parallel Scanner initialized with {} workers", workers);
         info!("   Markets to scan: {}", markets.len());
-        info!("   Markets per worker: ~{}", markets.len() / NUM_WORKERS);
+        info!("   Markets per worker: ~{}", markets.len() / workers);
 
         Self {
             config: Arc::new(config.clone()),
+            workers,
             markets: Arc::new(RwLock::new(markets)),
             correlations: Arc::new(RwLock::new(Vec::new())),
-            stats: Arc::new(RwLock::new(ScannerStats::default())),
+            stats: Arc::new(RwLock::new(ScannerStats {
+                workers,
+                ..Default::default()
+            })),
             relationship_cache: Arc::new(RwLock::new(HashMap::new())),
         }
     }
@@ -125,15 +191,143 @@ impl ParallelScanner {
     /// Build correlation graph between markets (runs once at startup)
     /// With 64GB RAM, we can store relationships between all 5000+ markets
     pub async fn build_correlation_graph(&self) {
-        let markets = self.markets.read().await;
-        let mut correlations = Vec::new();
+        // A snapshot, so the markets lock isn't held while the workers run
+        let markets = self.markets.read().await.clone();
 
         info!("🔗 Building market correlation graph...");
         let start = std::time::Instant::now();
 
+        // The worker threads block, so they're kept off the async runtime
+        let scanner = self.clone();
+        let (correlations, threads) =
+            tokio::task::spawn_blocking(move || scanner.correlate_markets(&markets))
+                .await
+                .expect("correlation graph build panicked");
+
+        let elapsed = start.elapsed();
+        info!(
+            "✅ Built correlation graph: {} relationships in {:?} ({} threads)",
+            correlations.len(),
+            elapsed,
+            threads
+        );
+
+        {
+            let mut stats = self.stats.write().await;
+            stats.correlation_threads = threads;
+            stats.correlation_build_ms = elapsed.as_secs_f64() * 1000.0;
+        }
+
+        // Store correlations
+        {
+            let mut corr_lock = self.correlations.write().await;
+            *corr_lock = correlations;
+        } // Drop write lock before acquiring read lock
+
+        // Build relationship cache for O(1) lookups
+        let corr_read = self.correlations.read().await;
+        let mut cache = self.relationship_cache.write().await;
+        cache.clear();
+        for corr in corr_read.iter() {
+            Self::cache_relationship(&mut cache, corr);
+        }
+    }
+
+    /// Swap in a refreshed market set, patching the correlation graph in place.
+    /// Correlations touching dropped markets are removed and only pairs involving
+    /// newly added markets are checked, so short-window churn doesn't force a rebuild.
+    pub async fn update_markets(&self, new_markets: Vec<Market>) {
+        let mut markets = self.markets.write().await;
+
+        let old_ids: HashSet<&str> = markets.iter().map(|m| m.market.as_str()).collect();
+        let new_ids: HashSet<&str> = new_markets.iter().map(|m| m.market.as_str()).collect();
+        let removed: HashSet<String> = old_ids
+            .difference(&new_ids)
+            .map(|id| id.to_string())
+            .collect();
+        let added: HashSet<&str> = new_ids.difference(&old_ids).copied().collect();
+
+        if removed.is_empty() && added.is_empty() {
+            *markets = new_markets;
+            return;
+        }
+
+        // Check each added market against the rest of its event, keeping the
+        // market-list order a full rebuild would use (detection isn't symmetric)
+        let mut event_groups: HashMap<&str, Vec<&Market>> = HashMap::new();
+        for market in &new_markets {
+            if let Some(event_id) = market.event_id() {
+                event_groups.entry(event_id).or_default().push(market);
+            }
+        }
+
+        let mut new_correlations = Vec::new();
+        for group in event_groups.values().filter(|g| g.len() >= 2) {
+            for i in 0..group.len() {
+                for j in (i + 1)..group.len() {
+                    let (market_a, market_b) = (group[i], group[j]);
+                    if !added.contains(market_a.market.as_str())
+                        && !added.contains(market_b.market.as_str())
+                    {
+                        continue;
+                    }
+                    if let Some(correlation) = self.detect_correlation(market_a, market_b) {
+                        new_correlations.push(correlation);
+                    }
+                }
+            }
+        }
+
+        let mut correlations = self.correlations.write().await;
+        let mut cache = self.relationship_cache.write().await;
+
+        let before = correlations.len();
+        correlations
+            .retain(|c| !removed.contains(&c.market_a) && !removed.contains(&c.market_b));
+        let dropped = before - correlations.len();
+
+        if !removed.is_empty() {
+            cache.retain(|market_id, related| {
+                related.retain(|id| !removed.contains(id));
+                !removed.contains(market_id) && !related.is_empty()
+            });
+        }
+
+        for corr in &new_correlations {
+            Self::cache_relationship(&mut cache, corr);
+        }
+
+        info!(
+            "🔄 Markets refreshed: +{} / -{} markets, +{} / -{} relationships ({} total)",
+            added.len(),
+            removed.len(),
+            new_correlations.len(),
+            dropped,
+            correlations.len() + new_correlations.len()
+        );
+
+        correlations.extend(new_correlations);
+        *markets = new_markets;
+    }
+
+    /// Record a correlation in both directions of the relationship cache
+    fn cache_relationship(cache: &mut HashMap<String, Vec<String>>, corr: &MarketCorrelation) {
+        cache
+            .entry(corr.market_a.clone())
+            .or_default()
+            .push(corr.market_b.clone());
+        cache
+            .entry(corr.market_b.clone())
+            .or_default()
+            .push(corr.market_a.clone());
+    }
+
+    /// Group `markets` by event and correlate each multi-market event. Blocks on
+    /// the worker threads. Returns the correlations and threads used.
+    fn correlate_markets(&self, markets: &[Market]) -> (Vec<MarketCorrelation>, usize) {
         // Group markets by event_id (markets in the same event are related)
         let mut event_groups: HashMap<String, Vec<&Market>> = HashMap::new();
-        for market in markets.iter() {
+        for market in markets {
             // Use event_id for grouping - this is the key for cross-market correlation!
             if let Some(event_id) = market.event_id() {
                 event_groups
@@ -153,53 +347,60 @@ impl ParallelScanner {
             multi_market_events.len()
         );
 
-        // Find related markets within each event
-        for (event_id, group) in &event_groups {
-            if group.len() < 2 {
-                continue;
-            }
-
+        for (event_id, group) in &multi_market_events {
             debug!("🔍 Event {} has {} related markets", event_id, group.len());
-
-            for i in 0..group.len() {
-                for j in (i + 1)..group.len() {
-                    let market_a = group[i];
-                    let market_b = group[j];
-
-                    // Check for logical relationships in questions
-                    if let Some(correlation) = self.detect_correlation(market_a, market_b) {
-                        correlations.push(correlation);
-                    }
-                }
-            }
         }
 
-        let elapsed = start.elapsed();
-        info!(
-            "✅ Built correlation graph: {} relationships in {:?}",
-            correlations.len(),
-            elapsed
-        );
+        // Find related markets within each event
+        let groups: Vec<&Vec<&Market>> = multi_market_events.iter().map(|(_, g)| *g).collect();
+        self.correlate_groups(&groups)
+    }
 
-        // Store correlations
-        {
-            let mut corr_lock = self.correlations.write().await;
-            *corr_lock = correlations;
-        } // Drop write lock before acquiring read lock
+    /// Check every pair within each event group, spread across the worker threads.
+    /// Rows `(group, i)` are dealt round-robin so the triangular pair loops of large
+    /// events don't all land on one worker. Returns the correlations and threads used.
+    fn correlate_groups(&self, groups: &[&Vec<&Market>]) -> (Vec<MarketCorrelation>, usize) {
+        let rows: Vec<(usize, usize)> = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(g, group)| (0..group.len() - 1).map(move |i| (g, i)))
+            .collect();
 
-        // Build relationship cache for O(1) lookups
-        let corr_read = self.correlations.read().await;
-        let mut cache = self.relationship_cache.write().await;
-        for corr in corr_read.iter() {
-            cache
-                .entry(corr.market_a.clone())
-                .or_default()
-                .push(corr.market_b.clone());
-            cache
-                .entry(corr.market_b.clone())
-                .or_default()
-                .push(corr.market_a.clone());
+        let correlate_row = |&(g, i): &(usize, usize)| {
+            let group = groups[g];
+            group[i + 1..]
+                .iter()
+                .filter_map(|market_b| self.detect_correlation(group[i], market_b))
+                .collect::<Vec<_>>()
+        };
+
+        let threads = self.workers.min(rows.len()).max(1);
+        if threads == 1 {
+            return (rows.iter().flat_map(correlate_row).collect(), threads);
         }
+
+        let correlations = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let rows = &rows;
+                    let correlate_row = &correlate_row;
+                    scope.spawn(move || {
+                        rows.iter()
+                            .skip(worker)
+                            .step_by(threads)
+                            .flat_map(correlate_row)
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("correlation worker panicked"))
+                .collect()
+        });
+
+        (correlations, threads)
     }
 
     /// Detect correlation between two markets based on question text
@@ -241,6 +442,7 @@ impl ParallelScanner {
                     market_b: child.market.clone(),
                     correlation_type: CorrelationType::Parent,
                     strength: 0.95,
+                    parse_quality: 1.0, // Both price targets parsed
                 });
             }
 
@@ -257,38 +459,44 @@ impl ParallelScanner {
                     market_b: child.market.clone(),
                     correlation_type: CorrelationType::Parent,
                     strength: 0.95,
+                    parse_quality: 1.0, // Both price targets parsed
                 });
             }
         }
 
         // Check for simple parent-child (e.g., "win championship" vs "win semifinal")
-        let parent_keywords = ["championship", "final", "winner", "win"];
-        let child_keywords = ["semifinal", "quarter", "round", "game"];
+        let heuristics = &self.config.correlation;
+        let mentions = |question: &str, keywords: &[String]| {
+            keywords.iter().any(|k| question.contains(k.as_str()))
+        };
 
-        let a_is_parent = parent_keywords.iter().any(|k| q_a.contains(k))
-            && !child_keywords.iter().any(|k| q_a.contains(k));
-        let b_is_child = child_keywords.iter().any(|k| q_b.contains(k));
+        let a_is_parent = mentions(&q_a, &heuristics.parent_keywords)
+            && !mentions(&q_a, &heuristics.child_keywords);
+        let b_is_child = mentions(&q_b, &heuristics.child_keywords);
 
         if a_is_parent && b_is_child {
             let common_words = self.find_common_significant_words(&q_a, &q_b);
-            if common_words >= 2 {
+            if common_words >= heuristics.min_parent_common_words {
                 return Some(MarketCorrelation {
                     market_a: market_a.market.clone(),
                     market_b: market_b.market.clone(),
                     correlation_type: CorrelationType::Parent,
                     strength: 0.8,
+                    parse_quality: (common_words as f64 / 3.0).min(1.0),
                 });
             }
         }
 
         // Check for mutually exclusive markets (e.g., "Team A wins" vs "Team B wins")
-        if self.find_common_significant_words(&q_a, &q_b) >= 3 {
+        let common_words = self.find_common_significant_words(&q_a, &q_b);
+        if common_words >= heuristics.min_sibling_common_words {
             // Markets about the same event with different subjects might be opposite
             return Some(MarketCorrelation {
                 market_a: market_a.market.clone(),
                 market_b: market_b.market.clone(),
                 correlation_type: CorrelationType::Sibling,
                 strength: 0.6,
+                parse_quality: (common_words as f64 / 4.0).min(1.0),
             });
         }
 
@@ -318,19 +526,16 @@ impl ParallelScanner {
 
     /// Count common significant words between two questions
     fn find_common_significant_words(&self, q_a: &str, q_b: &str) -> usize {
-        let stop_words = [
-            "will", "the", "a", "an", "to", "be", "in", "on", "at", "by", "for", "or", "and", "of",
-        ];
+        let heuristics = &self.config.correlation;
+        let significant = |w: &&str| {
+            w.len() >= heuristics.min_word_len && !heuristics.stop_words.iter().any(|s| s == w)
+        };
 
-        let words_a: std::collections::HashSet<_> = q_a
-            .split_whitespace()
-            .filter(|w| w.len() > 2 && !stop_words.contains(w))
-            .collect();
+        let words_a: std::collections::HashSet<_> =
+            q_a.split_whitespace().filter(significant).collect();
 
-        let words_b: std::collections::HashSet<_> = q_b
-            .split_whitespace()
-            .filter(|w| w.len() > 2 && !stop_words.contains(w))
-            .collect();
+        let words_b: std::collections::HashSet<_> =
+            q_b.split_whitespace().filter(significant).collect();
 
         words_a.intersection(&words_b).count()
     }
@@ -342,6 +547,14 @@ impl ParallelScanner {
         market: &Market,
         orderbook_manager: &OrderBookManager,
     ) -> Option<MultiOutcomeOpportunity> {
+        if let Some(asset_id) = orderbook_manager.crossed_asset_id(&market.market) {
+            debug!(
+                "⏭️  Skipping {}: book for {} is crossed/locked",
+                market.market, asset_id
+            );
+            return None;
+        }
+
         // Get best asks for all outcomes in this market
         let best_asks = orderbook_manager.get_best_asks_for_market(&market.market)?;
 
@@ -349,6 +562,17 @@ impl ParallelScanner {
             return None; // Not enough outcomes
         }
 
+        // Every outcome is a leg; past the limit the capital and leg risk outweigh the edge
+        if !self.config.trading.within_outcome_limit(best_asks.len()) {
+            debug!(
+                "⏭️  Skipping {}: {} outcomes > max {}",
+                market.market,
+                best_asks.len(),
+                self.config.trading.max_outcomes_for_arb
+            );
+            return None;
+        }
+
         // Calculate total cost to buy all outcomes
         let total_price: Decimal = best_asks.iter().map(|(_, price, _)| *price).sum();
 
@@ -379,6 +603,10 @@ impl ParallelScanner {
         // Expected profit = position * edge (after fees already factored in)
         let expected_profit = position_size * edge;
 
+        if expected_profit < self.config.trading.min_net_profit_usd {
+            return None; // Not worth the gas and fees
+        }
+
         // Build outcome details
         let outcomes: Vec<OutcomePrice> = best_asks
             .iter()
@@ -421,7 +649,7 @@ impl ParallelScanner {
     }
 
     /// Parallel scan for multi-outcome arbitrage opportunities
-    /// Divides markets across NUM_WORKERS threads
+    /// Markets are scanned in place; the worker count is reported for sizing
     pub async fn scan_multi_outcome_parallel(
         &self,
         orderbook_manager: &OrderBookManager,
@@ -444,7 +672,7 @@ impl ParallelScanner {
         debug!(
             "Scanning {} multi-outcome markets across {} workers",
             multi_markets.len(),
-            NUM_WORKERS
+            self.workers
         );
 
         // Scan all markets (OrderBookManager is thread-safe via DashMap)
@@ -457,7 +685,7 @@ impl ParallelScanner {
         }
 
         // Sort by expected profit
-        all_opportunities.sort_by(|a, b| b.expected_profit.cmp(&a.expected_profit));
+        all_opportunities.sort_by_key(|o| std::cmp::Reverse(o.expected_profit));
 
         // Update stats
         let elapsed = start.elapsed();
@@ -485,11 +713,11 @@ impl ParallelScanner {
         &self,
         orderbook_manager: &OrderBookManager,
     ) -> Vec<CrossMarketOpportunity> {
-        let correlations = self.correlations.read().await;
+        let correlations = self.actionable_correlations().await;
         let _start = std::time::Instant::now();
 
         if correlations.is_empty() {
-            debug!("No correlations built yet, skipping cross-market scan");
+            debug!("No confident correlations yet, skipping cross-market scan");
             return Vec::new();
         }
 
@@ -498,12 +726,7 @@ impl ParallelScanner {
         // Check each correlated pair for pricing inconsistencies
         for corr in correlations.iter() {
             if let Some(opp) = self
-                .check_cross_market_opportunity(
-                    &corr.market_a,
-                    &corr.market_b,
-                    &corr.correlation_type,
-                    orderbook_manager,
-                )
+                .check_cross_market_opportunity(corr, orderbook_manager)
                 .await
             {
                 if opp.edge >= self.config.trading.min_edge {
@@ -513,7 +736,7 @@ impl ParallelScanner {
         }
 
         // Sort by expected profit
-        opportunities.sort_by(|a, b| b.expected_profit.cmp(&a.expected_profit));
+        opportunities.sort_by_key(|o| std::cmp::Reverse(o.expected_profit));
 
         // Update stats
         let mut stats = self.stats.write().await;
@@ -522,25 +745,64 @@ impl ParallelScanner {
         opportunities
     }
 
+    /// Correlations confident enough to trade on (`min_cross_market_confidence`).
+    /// Text-inferred relationships are noisy; a weak match may not link the legs at all.
+    pub async fn actionable_correlations(&self) -> Vec<MarketCorrelation> {
+        let min_confidence = self.config.trading.min_cross_market_confidence;
+        self.correlations
+            .read()
+            .await
+            .iter()
+            .filter(|c| c.confidence() >= min_confidence)
+            .cloned()
+            .collect()
+    }
+
+    /// Best ask and best bid of `market_id`'s YES book, read in place, with the
+    /// YES and NO asset ids. `None` if either price or the NO book is missing,
+    /// or any of the market's books is crossed.
+    fn yes_top_of_book(orderbook_manager: &OrderBookManager, market_id: &str) -> Option<BinaryTop> {
+        orderbook_manager.with_market_books(market_id, |books| {
+            if books.crossed_asset_id().is_some() {
+                debug!("⏭️  Skipping {}: crossed/locked book", market_id);
+                return None;
+            }
+            let yes = books.books.first()?;
+            Some(BinaryTop {
+                yes_asset_id: yes.asset_id.clone(),
+                no_asset_id: books.complement_of(&yes.asset_id)?.to_string(),
+                yes_ask: yes.best_ask()?,
+                yes_bid: yes.best_bid()?,
+            })
+        })?
+    }
+
     /// Check if two correlated markets have a pricing inconsistency
     async fn check_cross_market_opportunity(
         &self,
-        market_a_id: &str,
-        market_b_id: &str,
-        correlation_type: &CorrelationType,
+        correlation: &MarketCorrelation,
         orderbook_manager: &OrderBookManager,
     ) -> Option<CrossMarketOpportunity> {
-        // Get prices for both markets
-        let books_a = orderbook_manager.get_market_books(market_a_id)?;
-        let books_b = orderbook_manager.get_market_books(market_b_id)?;
-
-        // Get best ask prices for YES outcomes (cost to buy YES)
-        let yes_ask_a = books_a.books.first()?.best_ask()?.0;
-        let yes_ask_b = books_b.books.first()?.best_ask()?.0;
-
-        // Get best bid prices for YES outcomes (what we'd get selling YES / buying NO)
-        let _yes_bid_a = books_a.books.first()?.best_bid()?.0;
-        let yes_bid_b = books_b.books.first()?.best_bid()?.0;
+        let market_a_id = correlation.market_a.as_str();
+        let market_b_id = correlation.market_b.as_str();
+        let confidence = correlation.confidence();
+
+        // YES best ask (cost to buy YES) and best bid (what we'd get selling YES /
+        // buying NO) for both markets
+        let top_a = Self::yes_top_of_book(orderbook_manager, market_a_id)?;
+        let top_b = Self::yes_top_of_book(orderbook_manager, market_b_id)?;
+        let ((yes_ask_a, yes_ask_size_a), (yes_ask_b, yes_ask_size_b)) =
+            (top_a.yes_ask, top_b.yes_ask);
+        let (yes_bid_b, yes_bid_size_b) = top_b.yes_bid;
+        let position = Decimal::from(self.config.trading.max_arb_size);
+        let buy = |market_id: &str, asset_id: &str, outcome: &str, price: Decimal| OpportunityLeg {
+            market_id: market_id.to_string(),
+            asset_id: asset_id.to_string(),
+            outcome: outcome.to_string(),
+            side: ArbSide::Buy,
+            price,
+            size: position,
+        };
 
         // Skip markets that look resolved (price at 0 or 1)
         if yes_ask_a <= dec!(0.01)
@@ -551,7 +813,7 @@ impl ParallelScanner {
             return None;
         }
 
-        match correlation_type {
+        match correlation.correlation_type {
             CorrelationType::Parent => {
                 // A is parent of B means: If B happens, A must happen
                 // Example: "Bitcoin $150k" (B) implies "Bitcoin $100k" (A)
@@ -569,12 +831,10 @@ impl ParallelScanner {
                 if cost_to_lock < dec!(0.98) {
                     // 2% minimum edge for fees
                     let edge = dec!(1.0) - cost_to_lock;
-                    let position = Decimal::from(self.config.trading.max_arb_size);
                     let fee = position * dec!(0.02); // ~2% Polymarket fee
                     let profit = (position * edge) - fee;
 
-                    if profit > dec!(0.50) {
-                        // Minimum $0.50 profit
+                    if profit >= self.config.trading.min_net_profit_usd {
                         return Some(CrossMarketOpportunity {
                             market_a_id: market_a_id.to_string(),
                             market_b_id: market_b_id.to_string(),
@@ -584,8 +844,13 @@ impl ParallelScanner {
                             edge,
                             position_size: position,
                             expected_profit: profit,
-                            confidence: dec!(0.8),
-                            detected_at: chrono::Utc::now().timestamp(),
+                            confidence,
+                            detected_at: chrono::Utc::now().timestamp_millis(),
+                            legs: vec![
+                                buy(market_a_id, &top_a.yes_asset_id, "Yes", yes_ask_a),
+                                buy(market_b_id, &top_b.no_asset_id, "No", dec!(1.0) - yes_bid_b),
+                            ],
+                            min_liquidity: yes_ask_size_a.min(yes_bid_size_b),
                         });
                     }
                 }
@@ -597,11 +862,10 @@ impl ParallelScanner {
 
                 if cost < dec!(0.98) {
                     let edge = dec!(1.0) - cost;
-                    let position = Decimal::from(self.config.trading.max_arb_size);
                     let fee = position * dec!(0.02);
                     let profit = (position * edge) - fee;
 
-                    if profit > dec!(0.50) {
+                    if profit >= self.config.trading.min_net_profit_usd {
                         return Some(CrossMarketOpportunity {
                             market_a_id: market_a_id.to_string(),
                             market_b_id: market_b_id.to_string(),
@@ -610,9 +874,14 @@ impl ParallelScanner {
                             arb_type: CrossArbType::MutualExclusion,
                             edge,
                             position_size: position,
-                            confidence: dec!(0.9),
+                            confidence,
                             expected_profit: profit,
-                            detected_at: chrono::Utc::now().timestamp(),
+                            detected_at: chrono::Utc::now().timestamp_millis(),
+                            legs: vec![
+                                buy(market_a_id, &top_a.yes_asset_id, "Yes", yes_ask_a),
+                                buy(market_b_id, &top_b.yes_asset_id, "Yes", yes_ask_b),
+                            ],
+                            min_liquidity: yes_ask_size_a.min(yes_ask_size_b),
                         });
                     }
                 }
@@ -632,18 +901,29 @@ impl ParallelScanner {
     pub async fn num_correlations(&self) -> usize {
         self.correlations.read().await.len()
     }
+
+    /// Markets correlated with `market_id` (from the relationship cache)
+    pub async fn related_markets(&self, market_id: &str) -> Vec<String> {
+        self.relationship_cache
+            .read()
+            .await
+            .get(market_id)
+            .cloned()
+            .unwrap_or_default()
+    }
 }
 
 impl std::fmt::Display for ScannerStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Scanner: {} markets | {} multi-outcome | {} cross-market | {:.0}/sec | {:.2}ms avg",
+            "Scanner: {} markets | {} multi-outcome | {} cross-market | {:.0}/sec | {:.2}ms avg | {} workers",
             self.markets_scanned,
             self.multi_outcome_opps,
             self.cross_market_opps,
             self.scans_per_second,
-            self.avg_scan_time_ms
+            self.avg_scan_time_ms,
+            self.workers
         )
     }
 }
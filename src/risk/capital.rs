@@ -0,0 +1,121 @@
+use super::{RiskDecision, RiskRejectReason};
+use crate::utils::{Config, Strategy};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Capital committed by each strategy, checked against its own budget and a
+/// cap shared by all of them.
+///
+/// In Hybrid mode arbitrage, market making and volume farming draw on the same
+/// account. Each reserves before placing or simulating an order and releases
+/// when the position or order closes, so together they can't commit more than
+/// the account holds.
+pub struct CapitalAllocator {
+    /// Strategy -> USD budget; strategies without one are bounded by the global cap only
+    budgets: HashMap<Strategy, Decimal>,
+    global_cap: Decimal,
+    committed: Mutex<HashMap<Strategy, Decimal>>,
+}
+
+impl CapitalAllocator {
+    pub fn new(config: &Config) -> Self {
+        let budgets_config = &config.strategy_budgets;
+        let budgets = [
+            (Strategy::Arbitrage, budgets_config.arbitrage),
+            (Strategy::MarketMaking, budgets_config.market_making),
+            (Strategy::VolumeFarming, budgets_config.volume_farming),
+        ]
+        .into_iter()
+        .filter(|(_, budget)| *budget > 0)
+        .map(|(strategy, budget)| (strategy, Decimal::from(budget)))
+        .collect();
+
+        let global_cap = match budgets_config.global_cap {
+            0 => config.trading.bankroll,
+            cap => cap,
+        };
+
+        Self {
+            budgets,
+            global_cap: Decimal::from(global_cap),
+            committed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `strategy` could commit `amount` more without breaching its
+    /// budget or the global cap
+    pub fn check(&self, strategy: &Strategy, amount: Decimal) -> RiskDecision {
+        self.decide(&self.committed.lock(), strategy, amount)
+    }
+
+    /// Commit `amount` for `strategy` if it fits, as one step so concurrent
+    /// strategies can't both squeeze into the same headroom
+    pub fn try_reserve(&self, strategy: &Strategy, amount: Decimal) -> RiskDecision {
+        let mut committed = self.committed.lock();
+        let decision = self.decide(&committed, strategy, amount);
+        if decision.is_allowed() {
+            *committed.entry(strategy.clone()).or_default() += amount;
+        }
+        decision
+    }
+
+    /// Commit `amount` for `strategy` unconditionally, for orders that already
+    /// went out after a `check`
+    pub fn commit(&self, strategy: &Strategy, amount: Decimal) {
+        *self.committed.lock().entry(strategy.clone()).or_default() += amount;
+    }
+
+    /// Return `amount` of `strategy`'s committed capital; never goes below zero
+    pub fn release(&self, strategy: &Strategy, amount: Decimal) {
+        if let Some(current) = self.committed.lock().get_mut(strategy) {
+            *current = (*current - amount).max(Decimal::ZERO);
+        }
+    }
+
+    #[inline]
+    pub fn committed(&self, strategy: &Strategy) -> Decimal {
+        self.committed
+            .lock()
+            .get(strategy)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn total_committed(&self) -> Decimal {
+        self.committed.lock().values().sum()
+    }
+
+    #[inline]
+    pub fn global_cap(&self) -> Decimal {
+        self.global_cap
+    }
+
+    fn decide(
+        &self,
+        committed: &HashMap<Strategy, Decimal>,
+        strategy: &Strategy,
+        amount: Decimal,
+    ) -> RiskDecision {
+        let own = committed.get(strategy).copied().unwrap_or_default() + amount;
+        if let Some(budget) = self.budgets.get(strategy) {
+            if own > *budget {
+                debug!("{:?} budget: ${:.2} > ${:.2}", strategy, own, budget);
+                return RiskDecision::Reject(RiskRejectReason::StrategyBudget);
+            }
+        }
+
+        let total = committed.values().sum::<Decimal>() + amount;
+        if total > self.global_cap {
+            debug!(
+                "Global capital cap: ${:.2} > ${:.2}",
+                total, self.global_cap
+            );
+            return RiskDecision::Reject(RiskRejectReason::GlobalCapital);
+        }
+
+        RiskDecision::Allow
+    }
+}
@@ -0,0 +1,229 @@
+//! Expected value per dollar of capital per second, so opportunities from
+//! different strategies can be compared when they compete for the same cap.
+//!
+//! Each strategy's edge is spread over how long it ties the capital up: an arb
+//! earns its net profit by resolution, market making earns its reward share
+//! plus spread while quotes rest, and volume farming earns airdrop credit on a
+//! position held until the outcome resolves. Arbs also lose value as they age,
+//! halving every `EV_HALF_LIFE_SECS` since the book they were priced on moves.
+
+use super::CapitalAllocator;
+use crate::arb_engine::{ShortWindowArbOpportunity, ShortWindowArbTracker};
+use crate::market_maker::{MMOpportunity, MarketMaker};
+use crate::utils::sim::SimClock;
+use crate::utils::Strategy;
+use crate::volume_farmer::{TrashOpportunity, VolumeFarmer};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use tracing::{debug, warn};
+
+/// Age over which a detected opportunity's score halves
+pub const EV_HALF_LIFE_SECS: i64 = 10;
+/// Time for a resting MM quote pair to fill on both sides and capture the spread
+pub const MM_ROUND_TRIP_SECS: i64 = 3600;
+/// Trash contracts are held to resolution; assume a day
+pub const VF_HOLD_SECS: i64 = 86400;
+/// Airdrop value per dollar of notional volume (same guess as `VFStats`)
+pub const VF_AIRDROP_RATE: Decimal = dec!(0.001);
+
+const SECS_PER_DAY: i64 = 86400;
+
+pub trait ScoreEv {
+    /// Expected USD earned per USD committed per second held, as of `now_ms`
+    fn score_ev(&self, now_ms: i64) -> Decimal;
+
+    /// USD the opportunity commits
+    fn capital_required(&self) -> Decimal;
+}
+
+impl ScoreEv for ShortWindowArbOpportunity {
+    fn score_ev(&self, now_ms: i64) -> Decimal {
+        let capital = self.capital_required();
+        if capital <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        let hold_secs = Decimal::from(self.minutes_to_expiry.max(1) * 60);
+        let age_ms = now_ms - self.detected_at;
+
+        self.expected_profit / capital / hold_secs * decay(age_ms)
+    }
+
+    fn capital_required(&self) -> Decimal {
+        self.position_size * self.vwap_sum
+    }
+}
+
+impl ScoreEv for MMOpportunity {
+    fn score_ev(&self, _now_ms: i64) -> Decimal {
+        let capital = self.capital_required();
+        if capital <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        let spread: Decimal = self
+            .ladder
+            .iter()
+            .map(|rung| (rung.ask_price - rung.bid_price) * rung.size)
+            .sum();
+        let per_sec = self.estimated_reward / Decimal::from(SECS_PER_DAY)
+            + spread / Decimal::from(MM_ROUND_TRIP_SECS);
+
+        per_sec / capital
+    }
+
+    /// Only bids lock cash; asks sell the outcome
+    fn capital_required(&self) -> Decimal {
+        self.ladder
+            .iter()
+            .map(|rung| rung.bid_price * rung.size)
+            .sum()
+    }
+}
+
+impl ScoreEv for TrashOpportunity {
+    fn score_ev(&self, _now_ms: i64) -> Decimal {
+        let capital = self.capital_required();
+        if capital <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        self.notional_volume * VF_AIRDROP_RATE / capital / Decimal::from(VF_HOLD_SECS)
+    }
+
+    fn capital_required(&self) -> Decimal {
+        self.cost_for_volume
+    }
+}
+
+/// An opportunity from any strategy, competing for capital
+#[derive(Debug, Clone)]
+pub enum Candidate {
+    ShortWindowArb(ShortWindowArbOpportunity),
+    MarketMaking(MMOpportunity),
+    VolumeFarming(TrashOpportunity),
+}
+
+impl Candidate {
+    #[inline]
+    pub fn strategy(&self) -> Strategy {
+        match self {
+            Self::ShortWindowArb(_) => Strategy::Arbitrage,
+            Self::MarketMaking(_) => Strategy::MarketMaking,
+            Self::VolumeFarming(_) => Strategy::VolumeFarming,
+        }
+    }
+
+    #[inline]
+    pub fn market_id(&self) -> &str {
+        match self {
+            Self::ShortWindowArb(opp) => &opp.market_id,
+            Self::MarketMaking(opp) => &opp.market_id,
+            Self::VolumeFarming(opp) => &opp.market_id,
+        }
+    }
+}
+
+impl ScoreEv for Candidate {
+    fn score_ev(&self, now_ms: i64) -> Decimal {
+        match self {
+            Self::ShortWindowArb(opp) => opp.score_ev(now_ms),
+            Self::MarketMaking(opp) => opp.score_ev(now_ms),
+            Self::VolumeFarming(opp) => opp.score_ev(now_ms),
+        }
+    }
+
+    fn capital_required(&self) -> Decimal {
+        match self {
+            Self::ShortWindowArb(opp) => opp.capital_required(),
+            Self::MarketMaking(opp) => opp.capital_required(),
+            Self::VolumeFarming(opp) => opp.capital_required(),
+        }
+    }
+}
+
+/// Highest score as of `now_ms` first; ties keep their order
+pub fn rank_by_ev(candidates: Vec<Candidate>, now_ms: i64) -> Vec<Candidate> {
+    let mut scored: Vec<(Decimal, Candidate)> = candidates
+        .into_iter()
+        .map(|candidate| (candidate.score_ev(now_ms), candidate))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Hand `candidates` to their strategies highest score first. Each strategy
+/// reserves from `capital` as it places, so once the cap is used up the
+/// lower-scoring candidates are the ones turned away. Short-window arbs
+/// reserve here, since the tracker doesn't hold an allocator; their capital is
+/// released when the trade resolves. Arbs age on `clock`, the simulators'
+/// clock. Returns the candidates that were funded.
+pub async fn fund_by_ev(
+    candidates: Vec<Candidate>,
+    capital: &CapitalAllocator,
+    clock: &SimClock,
+    sw_arb_tracker: &mut ShortWindowArbTracker,
+    market_maker: &mut MarketMaker,
+    volume_farmer: &mut VolumeFarmer,
+) -> Vec<Candidate> {
+    let mut funded = Vec::new();
+    let now_ms = clock.now_ms();
+
+    for candidate in rank_by_ev(candidates, now_ms) {
+        let placed = match &candidate {
+            Candidate::ShortWindowArb(opp) => {
+                let cost = opp.capital_required();
+                if capital.try_reserve(&Strategy::Arbitrage, cost).is_allowed() {
+                    let entered = sw_arb_tracker.simulate_entry(opp).is_some();
+                    if !entered {
+                        capital.release(&Strategy::Arbitrage, cost);
+                    }
+                    entered
+                } else {
+                    false
+                }
+            }
+            Candidate::MarketMaking(opp) => {
+                match market_maker
+                    .simulate_mm_orders(std::slice::from_ref(opp))
+                    .await
+                {
+                    Ok(results) => !results.is_empty(),
+                    Err(e) => {
+                        warn!("MM placement failed on {}: {:?}", opp.asset_id, e);
+                        false
+                    }
+                }
+            }
+            Candidate::VolumeFarming(opp) => match volume_farmer.simulate_trash_trade(opp).await {
+                Ok(trade) => trade.is_some(),
+                Err(e) => {
+                    warn!("Trash trade failed on {}: {:?}", opp.asset_id, e);
+                    false
+                }
+            },
+        };
+
+        if placed {
+            funded.push(candidate);
+        } else {
+            debug!(
+                "💤 {:?} candidate on {} not funded (score {:.8})",
+                candidate.strategy(),
+                candidate.market_id(),
+                candidate.score_ev(now_ms)
+            );
+        }
+    }
+
+    funded
+}
+
+/// `0.5^(age / EV_HALF_LIFE_SECS)`; fresh or future-dated opportunities keep full value
+fn decay(age_ms: i64) -> Decimal {
+    if age_ms <= 0 {
+        return Decimal::ONE;
+    }
+    let half_lives = age_ms as f64 / (EV_HALF_LIFE_SECS * 1000) as f64;
+    Decimal::from_f64(0.5f64.powf(half_lives))
+        .map(|factor| factor.round_dp(12))
+        .unwrap_or(Decimal::ZERO)
+}
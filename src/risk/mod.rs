@@ -1,10 +1,23 @@
-use crate::arb_engine::ArbitrageOpportunity;
-use crate::executor::ExecutionResult;
-use crate::utils::Config;
+pub mod capital;
+pub mod ev;
+
+pub use capital::CapitalAllocator;
+pub use ev::{Candidate, ScoreEv};
+
+use crate::arb_engine::{ArbSide, Opportunity};
+use crate::executor::{ExchangeOpenOrder, ExchangePosition, ExecutionResult, SettledFill};
+use crate::gamma_api::Market;
+use crate::monitoring::{NearMissKind, NearMissRecorder};
+use crate::orderbook::OrderBookManager;
+use crate::utils::{Config, Strategy};
+use crate::websocket::{UserFill, UserSide};
 use anyhow::Result;
 use chrono::Utc;
+use dashmap::{DashMap, DashSet};
+use parking_lot::Mutex;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
@@ -43,16 +56,109 @@ pub struct DailyPnlTracker {
     pub total_pnl: Decimal,
     pub trade_count: u64,
     pub arb_count: u64,
+    /// Fees on fills the user channel reported, already taken out of `realized_pnl`.
+    /// Executions' fees are carried by their `ExecutionResult`s instead.
+    pub fill_fees: Decimal,
+}
+
+/// Why the risk manager refused an arbitrage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RiskRejectReason {
+    MaxConcurrentArbs,
+    DailyLossLimit,
+    MarketExposure,
+    EventExposure,
+    InventoryDrift,
+    InsufficientLiquidity,
+    MarketCooldown,
+    MarketBlacklisted,
+    MarketPaused,
+    TradeSpacing,
+    StrategyBudget,
+    GlobalCapital,
+}
+
+impl std::fmt::Display for RiskRejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiskRejectReason::MaxConcurrentArbs => write!(f, "max concurrent arbs"),
+            RiskRejectReason::DailyLossLimit => write!(f, "daily loss limit"),
+            RiskRejectReason::MarketExposure => write!(f, "market exposure"),
+            RiskRejectReason::EventExposure => write!(f, "event exposure"),
+            RiskRejectReason::InventoryDrift => write!(f, "inventory drift"),
+            RiskRejectReason::InsufficientLiquidity => write!(f, "insufficient liquidity"),
+            RiskRejectReason::MarketCooldown => write!(f, "market cooldown"),
+            RiskRejectReason::MarketBlacklisted => write!(f, "market blacklisted"),
+            RiskRejectReason::MarketPaused => write!(f, "market paused"),
+            RiskRejectReason::TradeSpacing => write!(f, "too soon after last trade"),
+            RiskRejectReason::StrategyBudget => write!(f, "strategy budget"),
+            RiskRejectReason::GlobalCapital => write!(f, "global capital cap"),
+        }
+    }
 }
 
+/// An arbitrage leg resting on the exchange, until it fills or is cancelled
+#[derive(Debug)]
+struct RestingLeg {
+    /// Size still unfilled
+    remaining: Decimal,
+    /// Legs of the same arbitrage still resting, shared between them
+    arb_legs: Arc<AtomicUsize>,
+}
+
+/// Outcome of the pre-trade risk checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskDecision {
+    Allow,
+    Reject(RiskRejectReason),
+}
+
+impl RiskDecision {
+    #[inline]
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, RiskDecision::Allow)
+    }
+}
+
+/// Pre-trade limits and position book-keeping.
+///
+/// All state is interior-mutable (`DashMap`s, atomics, a mutex around daily P&L),
+/// so one `Arc<RiskManager>` can be shared by the WebSocket loop and the periodic
+/// strategy loop and both enforce limits against the same view.
 pub struct RiskManager {
     config: Arc<Config>,
-    positions: HashMap<String, Position>,
-    market_exposure: HashMap<String, Decimal>,
-    event_exposure: HashMap<String, Decimal>,
-    daily_pnl: DailyPnlTracker,
-    active_arbs: usize,
-    last_cleanup: i64,
+    positions: DashMap<String, Position>,
+    market_exposure: DashMap<String, Decimal>,
+    /// Event id (or market id, for markets without one) -> exposure
+    event_exposure: DashMap<String, Decimal>,
+    /// Market -> the event it belongs to, from `register_market`
+    market_events: DashMap<String, String>,
+    daily_pnl: Mutex<DailyPnlTracker>,
+    /// The day most recently reset away, until the daily summary collects it
+    closed_day: Mutex<Option<DailyPnlTracker>>,
+    active_arbs: AtomicUsize,
+    /// Slots in `active_arbs` taken by `try_reserve_arb_slot` and not yet
+    /// handed to a recorded arbitrage or released
+    reserved_arbs: AtomicUsize,
+    /// Order id -> submitted arbitrage leg still resting; the arbitrage stops
+    /// counting toward `active_arbs` once its last leg leaves
+    resting_legs: DashMap<String, RestingLeg>,
+    last_cleanup: AtomicI64,
+    /// Market -> unix timestamp (secs) when its cooldown ends
+    cooldowns: DashMap<String, i64>,
+    /// Market -> timestamps of recent failures (within the failure window)
+    recent_failures: DashMap<String, VecDeque<i64>>,
+    /// Markets blacklisted at runtime after repeated failures
+    runtime_blacklist: DashSet<String>,
+    /// Markets an operator paused from the dashboard; private unless shared via
+    /// `with_paused_markets`
+    paused_markets: Arc<DashSet<String>>,
+    /// Market -> unix timestamp (ms) of its most recent trade
+    last_trade: DashMap<String, i64>,
+    /// Capital committed per strategy, shared with the market maker and volume farmer
+    capital: Arc<CapitalAllocator>,
+    /// Where rejections that nearly passed are recorded, if anywhere
+    near_misses: Option<Arc<NearMissRecorder>>,
 }
 
 impl RiskManager {
@@ -61,60 +167,109 @@ impl RiskManager {
 
         Self {
             config: Arc::new(config.clone()),
-            positions: HashMap::new(),
-            market_exposure: HashMap::new(),
-            event_exposure: HashMap::new(),
-            daily_pnl: DailyPnlTracker {
+            positions: DashMap::new(),
+            market_exposure: DashMap::new(),
+            event_exposure: DashMap::new(),
+            market_events: DashMap::new(),
+            daily_pnl: Mutex::new(DailyPnlTracker {
                 date: today.clone(),
                 realized_pnl: Decimal::ZERO,
                 unrealized_pnl: Decimal::ZERO,
                 total_pnl: Decimal::ZERO,
                 trade_count: 0,
                 arb_count: 0,
-            },
-            active_arbs: 0,
-            last_cleanup: Utc::now().timestamp(),
+                fill_fees: Decimal::ZERO,
+            }),
+            closed_day: Mutex::new(None),
+            active_arbs: AtomicUsize::new(0),
+            reserved_arbs: AtomicUsize::new(0),
+            resting_legs: DashMap::new(),
+            last_cleanup: AtomicI64::new(Utc::now().timestamp()),
+            cooldowns: DashMap::new(),
+            recent_failures: DashMap::new(),
+            runtime_blacklist: DashSet::new(),
+            paused_markets: Arc::new(DashSet::new()),
+            last_trade: DashMap::new(),
+            capital: Arc::new(CapitalAllocator::new(config)),
+            near_misses: None,
         }
     }
 
+    /// Record exposure and liquidity rejections that nearly passed in `near_misses`
+    pub fn with_near_misses(mut self, near_misses: Arc<NearMissRecorder>) -> Self {
+        self.near_misses = Some(near_misses);
+        self
+    }
+
+    /// Skip markets in `paused_markets` (the monitor's, toggled from the dashboard)
+    pub fn with_paused_markets(mut self, paused_markets: Arc<DashSet<String>>) -> Self {
+        self.paused_markets = paused_markets;
+        self
+    }
+
+    /// Run all risk checks, rejecting with the first limit the arbitrage would breach
     #[inline]
-    pub fn can_execute_arbitrage(&mut self, arb_op: &ArbitrageOpportunity) -> Result<bool> {
+    pub fn can_execute_arbitrage(&self, arb_op: &dyn Opportunity) -> Result<RiskDecision> {
         let today = Utc::now().format("%Y-%m-%d").to_string();
 
-        if self.daily_pnl.date != today {
-            self.reset_daily_pnl(&today);
+        let daily_pnl = {
+            let mut daily_pnl = self.daily_pnl.lock();
+            if daily_pnl.date != today {
+                *self.closed_day.lock() = Some(Self::reset_daily_pnl(&mut daily_pnl, &today));
+            }
+            daily_pnl.total_pnl
+        };
+
+        let market_ids = arb_op.market_ids();
+
+        if market_ids.iter().any(|id| self.is_market_blacklisted(id)) {
+            return Ok(RiskDecision::Reject(RiskRejectReason::MarketBlacklisted));
+        }
+
+        if market_ids.iter().any(|id| self.is_market_paused(id)) {
+            return Ok(RiskDecision::Reject(RiskRejectReason::MarketPaused));
+        }
+
+        if let Some(id) = market_ids.iter().find(|id| self.is_market_in_cooldown(id)) {
+            debug!("Market in cooldown: {}", id);
+            return Ok(RiskDecision::Reject(RiskRejectReason::MarketCooldown));
         }
 
-        if self.active_arbs >= self.config.risk.max_concurrent_arbs {
+        if let Some(id) = market_ids.iter().find(|id| self.is_trade_too_soon(id)) {
+            debug!(
+                "Traded {} under {}s ago",
+                id, self.config.risk.min_seconds_between_trades_per_market
+            );
+            return Ok(RiskDecision::Reject(RiskRejectReason::TradeSpacing));
+        }
+
+        let active_arbs = self.active_arbs.load(Ordering::Acquire);
+        if active_arbs >= self.config.risk.max_concurrent_arbs {
             debug!(
                 "Max concurrent arbs reached: {} >= {}",
-                self.active_arbs, self.config.risk.max_concurrent_arbs
+                active_arbs, self.config.risk.max_concurrent_arbs
             );
-            return Ok(false);
+            return Ok(RiskDecision::Reject(RiskRejectReason::MaxConcurrentArbs));
         }
 
-        if self.daily_pnl.total_pnl < -Decimal::from(self.config.risk.daily_loss_limit) {
+        if daily_pnl < -Decimal::from(self.config.risk.daily_loss_limit) {
             warn!(
                 "⚠️  Daily loss limit reached: ${:.2} < ${}",
-                self.daily_pnl.total_pnl, self.config.risk.daily_loss_limit
+                daily_pnl, self.config.risk.daily_loss_limit
             );
-            return Ok(false);
+            return Ok(RiskDecision::Reject(RiskRejectReason::DailyLossLimit));
         }
 
-        let current_market_exposure = self
-            .market_exposure
-            .get(&arb_op.market_id)
-            .copied()
-            .unwrap_or(Decimal::ZERO);
-
-        let new_market_exposure = current_market_exposure + arb_op.position_size;
+        // Selling a held set only frees exposure
+        if arb_op.side() == ArbSide::Buy {
+            if let Some(reason) = self.exposure_breach(&market_ids, arb_op.position_size()) {
+                return Ok(RiskDecision::Reject(reason));
+            }
 
-        if new_market_exposure > self.config.risk.max_exposure_per_market.into() {
-            debug!(
-                "Market exposure limit: ${:.2} > ${}",
-                new_market_exposure, self.config.risk.max_exposure_per_market
-            );
-            return Ok(false);
+            let cost = arb_op.legs().iter().map(|leg| leg.signed_cost()).sum();
+            if let RiskDecision::Reject(reason) = self.capital.check(&Strategy::Arbitrage, cost) {
+                return Ok(RiskDecision::Reject(reason));
+            }
         }
 
         let new_inventory = self.calculate_inventory_change(arb_op)?;
@@ -127,59 +282,178 @@ impl RiskManager {
                 (self.calculate_current_inventory().net_delta + new_inventory.net_delta).abs(),
                 self.config.risk.inventory_drift_threshold
             );
-            return Ok(false);
+            return Ok(RiskDecision::Reject(RiskRejectReason::InventoryDrift));
+        }
+
+        let min_liquidity = Decimal::from(self.config.trading.min_liquidity);
+        if arb_op.min_liquidity() < min_liquidity {
+            debug!("Insufficient liquidity: ${}", arb_op.min_liquidity());
+            if let Some(near_misses) = &self.near_misses {
+                near_misses.record_below(
+                    arb_op.market_id(),
+                    NearMissKind::Liquidity,
+                    arb_op.min_liquidity(),
+                    min_liquidity,
+                );
+            }
+            return Ok(RiskDecision::Reject(RiskRejectReason::InsufficientLiquidity));
+        }
+
+        Ok(RiskDecision::Allow)
+    }
+
+    /// The exposure limit that buying `size` more in each of `market_ids` would
+    /// breach. Markets of one event share its cap, so a cross-market pair in the
+    /// same event adds both legs to it.
+    fn exposure_breach(&self, market_ids: &[&str], size: Decimal) -> Option<RiskRejectReason> {
+        let max_market_exposure = Decimal::from(self.config.risk.max_exposure_per_market);
+
+        for market_id in market_ids {
+            let new_market_exposure = self.get_market_exposure(market_id) + size;
+            if new_market_exposure > max_market_exposure {
+                debug!(
+                    "Market exposure limit: ${:.2} > ${}",
+                    new_market_exposure, self.config.risk.max_exposure_per_market
+                );
+                if let Some(near_misses) = &self.near_misses {
+                    near_misses.record_above(
+                        market_id,
+                        NearMissKind::MarketExposure,
+                        new_market_exposure,
+                        max_market_exposure,
+                    );
+                }
+                return Some(RiskRejectReason::MarketExposure);
+            }
+        }
+
+        let mut added: Vec<(String, &str, Decimal)> = Vec::new();
+        for market_id in market_ids {
+            let event_id = self.event_key(market_id);
+            match added.iter_mut().find(|(event, _, _)| *event == event_id) {
+                Some((_, _, event_size)) => *event_size += size,
+                None => added.push((event_id, market_id, size)),
+            }
+        }
+
+        let max_event_exposure = Decimal::from(self.config.risk.max_exposure_per_event);
+        for (event_id, market_id, event_size) in added {
+            let new_event_exposure = self.get_event_exposure(&event_id) + event_size;
+            if new_event_exposure > max_event_exposure {
+                debug!(
+                    "Event exposure limit for {}: ${:.2} > ${}",
+                    event_id, new_event_exposure, self.config.risk.max_exposure_per_event
+                );
+                if let Some(near_misses) = &self.near_misses {
+                    near_misses.record_above(
+                        market_id,
+                        NearMissKind::EventExposure,
+                        new_event_exposure,
+                        max_event_exposure,
+                    );
+                }
+                return Some(RiskRejectReason::EventExposure);
+            }
+        }
+
+        None
+    }
+
+    /// Take one of the `max_concurrent_arbs` slots for an arbitrage about to be
+    /// executed. Check and take are one compare-and-swap, so concurrent
+    /// executions can't both pass the limit. The slot is kept by the
+    /// `record_arbitrage_*` call that follows, or given back with
+    /// `release_arb_slot` if nothing is recorded.
+    pub fn try_reserve_arb_slot(&self) -> bool {
+        let max = self.config.risk.max_concurrent_arbs;
+        let reserved = self
+            .active_arbs
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .is_ok();
+        if reserved {
+            self.reserved_arbs.fetch_add(1, Ordering::AcqRel);
         }
+        reserved
+    }
 
-        if arb_op.min_liquidity < self.config.trading.min_liquidity.into() {
-            debug!("Insufficient liquidity: ${}", arb_op.min_liquidity);
-            return Ok(false);
+    /// Give back a slot from `try_reserve_arb_slot` that no arbitrage holds
+    pub fn release_arb_slot(&self) {
+        if Self::take_one(&self.reserved_arbs) {
+            Self::take_one(&self.active_arbs);
         }
+    }
 
-        Ok(true)
+    /// Count a recorded arbitrage toward `active_arbs`, in a reserved slot if
+    /// there is one; returns the new count
+    fn hold_arb_slot(&self) -> usize {
+        if Self::take_one(&self.reserved_arbs) {
+            self.active_arbs.load(Ordering::Acquire)
+        } else {
+            self.active_arbs.fetch_add(1, Ordering::AcqRel) + 1
+        }
     }
 
+    /// Decrement `counter` unless it is already zero; whether it was
+    #[inline]
+    fn take_one(counter: &AtomicUsize) -> bool {
+        counter
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Open positions for bought legs and close them out for sold ones. The arb's
+    /// own realized P&L is what counts toward the day, not the sold legs' entry prices.
     #[inline]
     pub fn record_arbitrage_execution(
-        &mut self,
-        arb_op: &ArbitrageOpportunity,
+        &self,
+        arb_op: &dyn Opportunity,
         result: &ExecutionResult,
     ) -> Result<()> {
         if result.success || result.partial_fill {
-            self.active_arbs += 1;
+            let active_arbs = self.hold_arb_slot();
+            self.record_trade_time(arb_op);
 
-            for edge in &arb_op.edges {
-                self.add_position(
-                    arb_op.market_id.clone(),
-                    edge.asset_id.clone(),
-                    edge.outcome.clone(),
-                    PositionType::Long,
-                    edge.size,
-                    edge.price,
-                    edge.expected_cost,
-                )?;
-
-                *self
-                    .market_exposure
-                    .entry(arb_op.market_id.clone())
-                    .or_insert(Decimal::ZERO) += edge.size;
-
-                *self
-                    .event_exposure
-                    .entry(arb_op.market_id.clone())
-                    .or_insert(Decimal::ZERO) += edge.size;
+            for leg in arb_op.legs() {
+                match leg.side {
+                    ArbSide::Buy => {
+                        self.add_position(
+                            leg.market_id.clone(),
+                            leg.asset_id.clone(),
+                            leg.outcome.clone(),
+                            PositionType::Long,
+                            leg.size,
+                            leg.price,
+                            leg.signed_cost(),
+                        )?;
+
+                        self.add_exposure(&leg.market_id, leg.size);
+                        self.capital.commit(&Strategy::Arbitrage, leg.signed_cost());
+                    }
+                    ArbSide::Sell => {
+                        self.reduce_position(&leg.market_id, &leg.asset_id, leg.size, leg.price);
+                    }
+                }
             }
 
-            self.daily_pnl.arb_count += 1;
-            self.daily_pnl.trade_count += 1;
+            {
+                let mut daily_pnl = self.daily_pnl.lock();
+                daily_pnl.arb_count += 1;
+                daily_pnl.trade_count += 1;
 
-            if result.filled {
-                self.daily_pnl.realized_pnl += result.total_cost;
+                if result.filled {
+                    daily_pnl.realized_pnl += result.realized_pnl;
+                }
             }
 
             info!(
                 "📊 Recorded arbitrage execution: ${:.2} profit, {} active arbs",
-                arb_op.net_profit, self.active_arbs
+                arb_op.expected_profit(),
+                active_arbs
             );
+        } else {
+            self.release_arb_slot();
         }
 
         self.cleanup_stale_positions();
@@ -187,13 +461,409 @@ impl RiskManager {
         Ok(())
     }
 
+    /// Record an arbitrage whose orders now rest on the exchange. Exposure is reserved
+    /// for every submitted buy leg, but positions only open (or close, for sells) as
+    /// the user channel reports fills (see `record_fill`).
+    pub fn record_arbitrage_submission(&self, arb_op: &dyn Opportunity, result: &ExecutionResult) {
+        let legs = arb_op.legs();
+        let submitted: Vec<_> = legs
+            .iter()
+            .filter_map(|leg| {
+                result
+                    .orders
+                    .iter()
+                    .find(|order| order.success && order.asset_id == leg.asset_id)
+                    .map(|order| (leg, order.order_id.as_ref()))
+            })
+            .collect();
+
+        if submitted.is_empty() {
+            self.release_arb_slot();
+            return;
+        }
+
+        // Legs without an order id can't be matched to their fills, so the
+        // arbitrage only holds its slot while tracked legs rest
+        let tracked: Vec<_> = submitted
+            .iter()
+            .filter_map(|(leg, order_id)| order_id.map(|id| (id.clone(), leg.size)))
+            .collect();
+        let active_arbs = if tracked.is_empty() {
+            self.release_arb_slot();
+            self.active_arbs.load(Ordering::Acquire)
+        } else {
+            let arb_legs = Arc::new(AtomicUsize::new(tracked.len()));
+            for (order_id, remaining) in tracked {
+                self.resting_legs.insert(
+                    order_id,
+                    RestingLeg {
+                        remaining,
+                        arb_legs: arb_legs.clone(),
+                    },
+                );
+            }
+            self.hold_arb_slot()
+        };
+        self.record_trade_time(arb_op);
+        for (leg, _) in submitted.iter().filter(|(leg, _)| leg.side == ArbSide::Buy) {
+            self.add_exposure(&leg.market_id, leg.size);
+            self.capital.commit(&Strategy::Arbitrage, leg.signed_cost());
+        }
+
+        {
+            let mut daily_pnl = self.daily_pnl.lock();
+            daily_pnl.arb_count += 1;
+            daily_pnl.trade_count += 1;
+        }
+
+        info!(
+            "📊 Recorded arbitrage submission: {}/{} legs resting, {} active arbs",
+            submitted.len(),
+            legs.len(),
+            active_arbs
+        );
+    }
+
+    /// Settle an arbitrage recorded with `record_arbitrage_submission` whose
+    /// orders all filled (or didn't) at once, as the simulator's deferred fills
+    /// do. Positions open or close for the filled sizes, the exposure and
+    /// capital reserved for the unfilled rest is released, and the arb's
+    /// realized P&L counts toward the day.
+    pub fn record_arbitrage_settlement(&self, settled: &SettledFill) {
+        let order = &settled.order;
+        for ((leg, filled), order_id) in order
+            .legs()
+            .iter()
+            .zip(&settled.filled_sizes)
+            .zip(order.order_ids())
+        {
+            match leg.side {
+                ArbSide::Buy => {
+                    if !filled.is_zero() {
+                        if let Err(e) = self.add_position(
+                            leg.market_id.clone(),
+                            leg.asset_id.clone(),
+                            leg.outcome.clone(),
+                            PositionType::Long,
+                            *filled,
+                            leg.price,
+                            leg.price * *filled,
+                        ) {
+                            warn!("Failed to record buy fill for {}: {:?}", leg.asset_id, e);
+                        }
+                    }
+                    let unfilled = leg.size - *filled;
+                    if unfilled > Decimal::ZERO {
+                        self.release_exposure(&leg.market_id, unfilled);
+                        self.capital
+                            .release(&Strategy::Arbitrage, leg.price * unfilled);
+                    }
+                }
+                ArbSide::Sell => {
+                    if !filled.is_zero() {
+                        self.reduce_position(&leg.market_id, &leg.asset_id, *filled, leg.price);
+                    }
+                }
+            }
+            self.record_order_closed(order_id);
+        }
+
+        if settled.result.filled {
+            self.daily_pnl.lock().realized_pnl += settled.result.realized_pnl;
+        }
+
+        info!(
+            "📊 Recorded arbitrage settlement for {}: ${:.2} realized",
+            order.market_id(),
+            settled.result.realized_pnl
+        );
+    }
+
+    /// Apply a fill reported by the exchange. Buys grow the position (exposure was
+    /// reserved when the order was submitted); sells shrink it, realize P&L against
+    /// the average entry price and release exposure.
+    pub fn record_fill(&self, fill: &UserFill) {
+        match fill.side {
+            UserSide::Buy => {
+                if let Err(e) = self.add_position(
+                    fill.market_id.clone(),
+                    fill.asset_id.clone(),
+                    fill.outcome.clone(),
+                    PositionType::Long,
+                    fill.size,
+                    fill.price,
+                    fill.price * fill.size,
+                ) {
+                    warn!("Failed to record buy fill for {}: {:?}", fill.asset_id, e);
+                }
+            }
+            UserSide::Sell => {
+                let Some(realized) =
+                    self.reduce_position(&fill.market_id, &fill.asset_id, fill.size, fill.price)
+                else {
+                    warn!(
+                        "Sell fill for {} without a tracked position, ignoring",
+                        fill.asset_id
+                    );
+                    return;
+                };
+
+                let mut daily_pnl = self.daily_pnl.lock();
+                daily_pnl.realized_pnl += realized;
+                daily_pnl.total_pnl = daily_pnl.realized_pnl + daily_pnl.unrealized_pnl;
+            }
+        }
+
+        if !fill.fee.is_zero() {
+            let mut daily_pnl = self.daily_pnl.lock();
+            daily_pnl.fill_fees += fill.fee;
+            daily_pnl.realized_pnl -= fill.fee;
+            daily_pnl.total_pnl = daily_pnl.realized_pnl + daily_pnl.unrealized_pnl;
+        }
+
+        debug!(
+            "🧾 Fill {:?} {} {} @ {:.4} (order {})",
+            fill.side, fill.size, fill.asset_id, fill.price, fill.order_id
+        );
+
+        let filled = match self.resting_legs.get_mut(&fill.order_id) {
+            Some(mut leg) => {
+                leg.remaining -= fill.size;
+                leg.remaining <= Decimal::ZERO
+            }
+            None => false,
+        };
+        if filled {
+            self.record_order_closed(&fill.order_id);
+        }
+    }
+
+    /// An order left the book: filled, cancelled or rejected. Once an
+    /// arbitrage's last leg is gone it no longer counts toward `max_concurrent_arbs`.
+    pub fn record_order_closed(&self, order_id: &str) {
+        let Some((_, leg)) = self.resting_legs.remove(order_id) else {
+            return;
+        };
+        if leg.arb_legs.fetch_sub(1, Ordering::AcqRel) == 1 {
+            let _ = self
+                .active_arbs
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                    Some(n.saturating_sub(1))
+                });
+            debug!("Last leg of arbitrage closed with order {}", order_id);
+        }
+    }
+
+    /// Sell up to `size` of the position in `asset_id` at `price`, releasing its
+    /// exposure. Returns the P&L realized against the average entry price, or
+    /// `None` if no position is tracked.
+    fn reduce_position(
+        &self,
+        market_id: &str,
+        asset_id: &str,
+        size: Decimal,
+        price: Decimal,
+    ) -> Option<Decimal> {
+        let mut position = self.positions.get_mut(asset_id)?;
+
+        let size = size.min(position.size);
+        let realized = (price - position.avg_price) * size;
+        self.capital
+            .release(&Strategy::Arbitrage, position.avg_price * size);
+        position.size -= size;
+        position.total_cost = position.avg_price * position.size;
+        let closed = position.size.is_zero();
+        drop(position);
+
+        if closed {
+            self.positions.remove(asset_id);
+        }
+        self.release_exposure(market_id, size);
+
+        Some(realized)
+    }
+
+    /// Release the exposure reserved for the unfilled remainder of a cancelled buy order
+    pub fn release_exposure(&self, market_id: &str, size: Decimal) {
+        let event_id = self.event_key(market_id);
+        for (exposure, key) in [
+            (&self.market_exposure, market_id),
+            (&self.event_exposure, event_id.as_str()),
+        ] {
+            if let Some(mut current) = exposure.get_mut(key) {
+                *current = (*current - size).max(Decimal::ZERO);
+            }
+        }
+    }
+
+    /// Count `market`'s exposure toward its event's `max_exposure_per_event`,
+    /// shared with the event's other markets. Exposure recorded before the
+    /// market was registered moves under the event.
+    pub fn register_market(&self, market: &Market) {
+        let Some(event_id) = market.event_id() else {
+            return;
+        };
+        self.market_events
+            .insert(market.market.clone(), event_id.to_string());
+
+        if let Some((_, exposure)) = self.event_exposure.remove(&market.market) {
+            *self
+                .event_exposure
+                .entry(event_id.to_string())
+                .or_insert(Decimal::ZERO) += exposure;
+        }
+    }
+
+    /// Start from the exposure already held on the exchange (live mode startup).
+    /// Filled positions become tracked positions; resting buy orders only count
+    /// towards exposure since they can still fill.
+    pub fn seed_positions(
+        &self,
+        positions: &[ExchangePosition],
+        open_orders: &[ExchangeOpenOrder],
+    ) -> Result<()> {
+        for position in positions {
+            self.add_position(
+                position.market_id.clone(),
+                position.asset_id.clone(),
+                position.outcome.clone(),
+                PositionType::Long,
+                position.size,
+                position.avg_price,
+                position.size * position.avg_price,
+            )?;
+
+            self.add_exposure(&position.market_id, position.size);
+            self.capital
+                .commit(&Strategy::Arbitrage, position.size * position.avg_price);
+        }
+
+        for order in open_orders.iter().filter(|o| o.is_buy) {
+            self.add_exposure(&order.market_id, order.remaining_size);
+            self.capital
+                .commit(&Strategy::Arbitrage, order.remaining_size * order.price);
+        }
+
+        info!(
+            "🔁 Seeded {} positions and {} open orders, {} markets exposed",
+            positions.len(),
+            open_orders.len(),
+            self.market_exposure.len()
+        );
+
+        Ok(())
+    }
+
+    /// Mark open positions to the current best bid and refresh daily P&L
+    /// Returns the updated total (realized + unrealized) daily P&L
+    pub fn mark_to_market(&self, orderbook_manager: &OrderBookManager) -> Decimal {
+        let mut unrealized = Decimal::ZERO;
+
+        for mut position in self.positions.iter_mut() {
+            let bid = orderbook_manager
+                .get_book(&position.market_id, &position.asset_id)
+                .and_then(|book| book.best_bid())
+                .map(|(price, _)| price);
+
+            if let Some(bid) = bid {
+                position.current_pnl = (bid - position.avg_price) * position.size;
+            }
+            unrealized += position.current_pnl;
+        }
+
+        let mut daily_pnl = self.daily_pnl.lock();
+        daily_pnl.unrealized_pnl = unrealized;
+        daily_pnl.total_pnl = daily_pnl.realized_pnl + unrealized;
+
+        daily_pnl.total_pnl
+    }
+
+    /// Put a market in cooldown after a failed execution or losing trade.
+    /// Blacklists it for the session once it hits the repeated-failure threshold.
+    pub fn record_market_failure(&self, market_id: &str) {
+        let now = Utc::now().timestamp();
+        let risk = &self.config.risk;
+
+        self.cooldowns
+            .insert(market_id.to_string(), now + risk.market_cooldown_secs as i64);
+
+        let failure_count = {
+            let mut failures = self.recent_failures.entry(market_id.to_string()).or_default();
+            failures.push_back(now);
+            while failures
+                .front()
+                .is_some_and(|&t| now - t > risk.failure_window_secs as i64)
+            {
+                failures.pop_front();
+            }
+            failures.len()
+        };
+
+        if risk.blacklist_on_repeated_failure
+            && failure_count >= risk.max_failures_before_blacklist
+            && self.runtime_blacklist.insert(market_id.to_string())
+        {
+            warn!(
+                "🚫 Blacklisting {} after {} failures in {}s",
+                market_id, failure_count, risk.failure_window_secs
+            );
+        } else {
+            info!(
+                "🧊 Market {} in cooldown for {}s",
+                market_id, risk.market_cooldown_secs
+            );
+        }
+    }
+
+    #[inline]
+    pub fn is_market_in_cooldown(&self, market_id: &str) -> bool {
+        self.cooldowns
+            .get(market_id)
+            .is_some_and(|until| Utc::now().timestamp() < *until)
+    }
+
+    /// Whether `market_id` traded within `min_seconds_between_trades_per_market`.
+    /// Unlike the cooldown this applies after every trade, profitable or not.
+    #[inline]
+    pub fn is_trade_too_soon(&self, market_id: &str) -> bool {
+        let spacing_ms = self.config.risk.min_seconds_between_trades_per_market as i64 * 1000;
+        self.last_trade
+            .get(market_id)
+            .is_some_and(|last| Utc::now().timestamp_millis() - *last < spacing_ms)
+    }
+
+    fn record_trade_time(&self, arb_op: &dyn Opportunity) {
+        let now = Utc::now().timestamp_millis();
+        for market_id in arb_op.market_ids() {
+            self.last_trade.insert(market_id.to_string(), now);
+        }
+    }
+
     #[inline]
     pub fn is_market_blacklisted(&self, market_id: &str) -> bool {
-        self.config
-            .markets
-            .blacklisted_markets
-            .iter()
-            .any(|blacklisted| market_id.contains(blacklisted))
+        self.runtime_blacklist.contains(market_id)
+            || self
+                .config
+                .markets
+                .blacklisted_markets
+                .iter()
+                .any(|blacklisted| market_id.contains(blacklisted))
+    }
+
+    /// Stop detecting and executing on `market_id` until it's resumed; false if
+    /// it was already paused
+    pub fn pause_market(&self, market_id: &str) -> bool {
+        self.paused_markets.insert(market_id.to_string())
+    }
+
+    /// Undo `pause_market`; false if the market wasn't paused
+    pub fn resume_market(&self, market_id: &str) -> bool {
+        self.paused_markets.remove(market_id).is_some()
+    }
+
+    #[inline]
+    pub fn is_market_paused(&self, market_id: &str) -> bool {
+        self.paused_markets.contains(market_id)
     }
 
     #[inline]
@@ -201,21 +871,51 @@ impl RiskManager {
         self.calculate_current_inventory()
     }
 
+    /// Per-strategy capital commitments, for strategies that place orders outside
+    /// the arbitrage path
     #[inline]
-    pub fn get_daily_pnl(&self) -> &DailyPnlTracker {
-        &self.daily_pnl
+    pub fn capital(&self) -> &Arc<CapitalAllocator> {
+        &self.capital
     }
 
+    /// Bankroll not yet deployed in open positions; never negative
     #[inline]
-    pub fn get_position(&self, asset_id: &str) -> Option<&Position> {
-        self.positions.get(asset_id)
+    pub fn available_bankroll(&self) -> Decimal {
+        let bankroll = Decimal::from(self.config.trading.bankroll);
+        (bankroll - self.calculate_current_inventory().total_exposure).max(Decimal::ZERO)
+    }
+
+    /// Reset daily P&L if the UTC day has rolled over, returning the day that
+    /// closed (whether reset here or by a risk check since the last call)
+    pub fn roll_daily_pnl(&self) -> Option<DailyPnlTracker> {
+        self.roll_daily_pnl_at(&Utc::now().format("%Y-%m-%d").to_string())
+    }
+
+    /// `roll_daily_pnl` for an explicit UTC date (`%Y-%m-%d`)
+    pub fn roll_daily_pnl_at(&self, today: &str) -> Option<DailyPnlTracker> {
+        let mut daily_pnl = self.daily_pnl.lock();
+        let mut closed_day = self.closed_day.lock();
+        if daily_pnl.date != today {
+            *closed_day = Some(Self::reset_daily_pnl(&mut daily_pnl, today));
+        }
+        closed_day.take()
+    }
+
+    #[inline]
+    pub fn get_daily_pnl(&self) -> DailyPnlTracker {
+        self.daily_pnl.lock().clone()
+    }
+
+    #[inline]
+    pub fn get_position(&self, asset_id: &str) -> Option<Position> {
+        self.positions.get(asset_id).map(|p| p.clone())
     }
 
     #[inline]
     pub fn get_market_exposure(&self, market_id: &str) -> Decimal {
         self.market_exposure
             .get(market_id)
-            .copied()
+            .map(|e| *e)
             .unwrap_or(Decimal::ZERO)
     }
 
@@ -223,14 +923,17 @@ impl RiskManager {
     pub fn get_event_exposure(&self, event_id: &str) -> Decimal {
         self.event_exposure
             .get(event_id)
-            .copied()
+            .map(|e| *e)
             .unwrap_or(Decimal::ZERO)
     }
 
+    /// Open a position, or grow the one already held in `asset_id`: size and
+    /// cost add up and `avg_price` becomes the cost-weighted average, so a
+    /// position built over several fills keeps its full cost basis
     #[inline]
     #[allow(clippy::too_many_arguments)]
     fn add_position(
-        &mut self,
+        &self,
         market_id: String,
         asset_id: String,
         outcome: String,
@@ -239,45 +942,78 @@ impl RiskManager {
         price: Decimal,
         cost: Decimal,
     ) -> Result<()> {
-        let entry_time = Utc::now().timestamp();
-
-        let position = Position {
-            market_id: market_id.clone(),
-            asset_id: asset_id.clone(),
-            outcome: outcome.clone(),
-            position_type: position_type.clone(),
-            size,
-            avg_price: price,
-            total_cost: cost,
-            entry_time,
-            current_pnl: Decimal::ZERO,
-        };
+        let mut position = self
+            .positions
+            .entry(asset_id.clone())
+            .or_insert_with(|| Position {
+                market_id,
+                asset_id: asset_id.clone(),
+                outcome: outcome.clone(),
+                position_type,
+                size: Decimal::ZERO,
+                avg_price: Decimal::ZERO,
+                total_cost: Decimal::ZERO,
+                entry_time: Utc::now().timestamp(),
+                current_pnl: Decimal::ZERO,
+            });
 
-        self.positions.insert(asset_id.clone(), position);
+        position.size += size;
+        position.total_cost += cost;
+        position.avg_price = if position.size.is_zero() {
+            price
+        } else {
+            position.total_cost / position.size
+        };
 
         debug!(
-            "➕ Added position: {} {} @ {:.4} = ${:.2}",
-            asset_id, outcome, price, cost
+            "➕ Added position: {} {} @ {:.4} = ${:.2} (now {} @ {:.4})",
+            asset_id, outcome, price, cost, position.size, position.avg_price
         );
 
         Ok(())
     }
 
     #[inline]
-    fn calculate_inventory_change(&self, arb_op: &ArbitrageOpportunity) -> Result<Inventory> {
+    fn add_exposure(&self, market_id: &str, size: Decimal) {
+        *self
+            .market_exposure
+            .entry(market_id.to_string())
+            .or_insert(Decimal::ZERO) += size;
+
+        *self
+            .event_exposure
+            .entry(self.event_key(market_id))
+            .or_insert(Decimal::ZERO) += size;
+    }
+
+    /// Key `market_id`'s exposure counts under in `event_exposure`: its event,
+    /// or the market itself if it has none registered
+    #[inline]
+    fn event_key(&self, market_id: &str) -> String {
+        self.market_events
+            .get(market_id)
+            .map(|event_id| event_id.clone())
+            .unwrap_or_else(|| market_id.to_string())
+    }
+
+    #[inline]
+    fn calculate_inventory_change(&self, arb_op: &dyn Opportunity) -> Result<Inventory> {
         let mut net_delta = Decimal::ZERO;
         let mut total_exposure = Decimal::ZERO;
 
-        for edge in &arb_op.edges {
-            net_delta += edge.size;
-            total_exposure += edge.size * edge.price;
+        for leg in arb_op.legs() {
+            match leg.side {
+                ArbSide::Buy => net_delta += leg.size,
+                ArbSide::Sell => net_delta -= leg.size,
+            }
+            total_exposure += leg.signed_cost();
         }
 
         Ok(Inventory {
             net_delta,
             total_exposure,
-            market_count: 1,
-            last_update: arb_op.timestamp,
+            market_count: arb_op.market_ids().len(),
+            last_update: Utc::now().timestamp(),
         })
     }
 
@@ -286,7 +1022,7 @@ impl RiskManager {
         let mut net_delta = Decimal::ZERO;
         let mut total_exposure = Decimal::ZERO;
 
-        for position in self.positions.values() {
+        for position in self.positions.iter() {
             net_delta += position.size;
             total_exposure += position.total_cost;
         }
@@ -300,69 +1036,93 @@ impl RiskManager {
     }
 
     #[inline]
-    fn cleanup_stale_positions(&mut self) {
+    fn cleanup_stale_positions(&self) {
         let now = Utc::now().timestamp();
-        let timeout_secs = self.config.risk.position_timeout_seconds;
-        let mut stale_asset_ids = Vec::new();
-
-        for (asset_id, position) in &self.positions {
-            if now - position.entry_time > timeout_secs as i64 {
-                stale_asset_ids.push(asset_id.clone());
+        let timeout_secs = self.config.risk.position_timeout_seconds as i64;
 
-                *self.market_exposure.get_mut(&position.market_id).unwrap() -= position.size;
+        let stale_asset_ids: Vec<String> = self
+            .positions
+            .iter()
+            .filter(|p| now - p.entry_time > timeout_secs)
+            .map(|p| p.key().clone())
+            .collect();
 
-                self.active_arbs = self.active_arbs.saturating_sub(1);
+        // Another task may clean up the same position concurrently; only the remover accounts for it
+        for asset_id in stale_asset_ids {
+            let Some((_, position)) = self.positions.remove(&asset_id) else {
+                continue;
+            };
 
-                info!(
-                    "🗑️  Cleaned up stale position: {} (age: {}s)",
-                    asset_id,
-                    now - position.entry_time
-                );
+            if let Some(mut exposure) = self.market_exposure.get_mut(&position.market_id) {
+                *exposure -= position.size;
             }
-        }
+            self.capital
+                .release(&Strategy::Arbitrage, position.total_cost);
 
-        for asset_id in stale_asset_ids {
-            self.positions.remove(&asset_id);
+            let _ = self
+                .active_arbs
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| Some(n.saturating_sub(1)));
+
+            info!(
+                "🗑️  Cleaned up stale position: {} (age: {}s)",
+                asset_id,
+                now - position.entry_time
+            );
         }
 
-        self.last_cleanup = now;
+        self.last_cleanup.store(now, Ordering::Relaxed);
     }
 
+    /// Start a fresh tracker for `today`, returning the one it replaces
     #[inline]
-    fn reset_daily_pnl(&mut self, today: &str) {
+    fn reset_daily_pnl(daily_pnl: &mut DailyPnlTracker, today: &str) -> DailyPnlTracker {
         info!(
             "🔄 Resetting daily PnL: ${:.2} -> $0.00 ({} trades)",
-            self.daily_pnl.total_pnl, self.daily_pnl.trade_count
+            daily_pnl.total_pnl, daily_pnl.trade_count
         );
 
-        self.daily_pnl = DailyPnlTracker {
-            date: today.to_string(),
-            realized_pnl: Decimal::ZERO,
-            unrealized_pnl: Decimal::ZERO,
-            total_pnl: Decimal::ZERO,
-            trade_count: 0,
-            arb_count: 0,
-        };
+        std::mem::replace(
+            daily_pnl,
+            DailyPnlTracker {
+                date: today.to_string(),
+                realized_pnl: Decimal::ZERO,
+                unrealized_pnl: Decimal::ZERO,
+                total_pnl: Decimal::ZERO,
+                trade_count: 0,
+                arb_count: 0,
+                fill_fees: Decimal::ZERO,
+            },
+        )
     }
 
     #[inline]
     pub fn should_stop_trading(&self) -> bool {
-        self.daily_pnl.total_pnl < -Decimal::from(self.config.risk.daily_loss_limit)
+        self.daily_pnl.lock().total_pnl < -Decimal::from(self.config.risk.daily_loss_limit)
     }
 
     #[inline]
     pub fn get_risk_summary(&self) -> RiskSummary {
+        let inventory = self.calculate_current_inventory();
+        let daily_pnl = self.daily_pnl.lock().clone();
+
         RiskSummary {
             active_positions: self.positions.len(),
-            active_arbitrages: self.active_arbs,
-            total_exposure: self.calculate_current_inventory().total_exposure,
-            net_delta: self.calculate_current_inventory().net_delta,
-            daily_pnl: self.daily_pnl.total_pnl,
-            daily_trades: self.daily_pnl.trade_count,
-            market_exposure: self.market_exposure.clone(),
-            event_exposure: self.event_exposure.clone(),
+            active_arbitrages: self.active_arbs.load(Ordering::Acquire),
+            total_exposure: inventory.total_exposure,
+            net_delta: inventory.net_delta,
+            daily_pnl: daily_pnl.total_pnl,
+            daily_trades: daily_pnl.trade_count,
+            market_exposure: Self::snapshot(&self.market_exposure),
+            event_exposure: Self::snapshot(&self.event_exposure),
         }
     }
+
+    fn snapshot(exposure: &DashMap<String, Decimal>) -> HashMap<String, Decimal> {
+        exposure
+            .iter()
+            .map(|e| (e.key().clone(), *e.value()))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
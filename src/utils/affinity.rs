@@ -0,0 +1,47 @@
+use super::LatencyConfig;
+use tracing::{info, warn};
+
+/// Pin the calling thread to `target_cpu_core` when `enable_cpu_pinning` is set.
+/// Returns whether the thread is now pinned. Pinning is Linux-only; elsewhere
+/// it logs and leaves the thread alone.
+pub fn pin_current_thread(config: &LatencyConfig) -> bool {
+    if !config.enable_cpu_pinning {
+        return false;
+    }
+
+    pin_to_core(config.target_cpu_core)
+}
+
+#[cfg(target_os = "linux")]
+fn pin_to_core(core: usize) -> bool {
+    // Only the cores this process may run on (respects taskset/cgroup limits)
+    let available = core_affinity::get_core_ids().unwrap_or_default();
+    let Some(core_id) = available.iter().copied().find(|core_id| core_id.id == core) else {
+        warn!(
+            "📌 Can't pin to CPU core {}: not in the available set {:?}",
+            core,
+            available
+                .iter()
+                .map(|core_id| core_id.id)
+                .collect::<Vec<_>>()
+        );
+        return false;
+    };
+
+    if core_affinity::set_for_current(core_id) {
+        info!("📌 Pinned trading loop to CPU core {}", core);
+        true
+    } else {
+        warn!("📌 Failed to pin trading loop to CPU core {}", core);
+        false
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_to_core(core: usize) -> bool {
+    warn!(
+        "📌 CPU pinning is only supported on Linux, not pinning to core {}",
+        core
+    );
+    false
+}
@@ -0,0 +1,186 @@
+use crate::arb_engine::SimulatedShortWindowTrade;
+use crate::executor::SimulatedTrade;
+use crate::monitoring::TradeRecord;
+use anyhow::{Context, Result};
+use rust_decimal::Decimal;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Decimal places used for every Decimal column
+const DECIMAL_PLACES: u32 = 4;
+
+/// A record that can be written as one CSV row with a stable column order
+pub trait CsvRow {
+    const HEADER: &'static [&'static str];
+
+    fn row(&self) -> Vec<String>;
+}
+
+#[inline]
+fn fmt_decimal(value: Decimal) -> String {
+    format!("{:.*}", DECIMAL_PLACES as usize, value.round_dp(DECIMAL_PLACES))
+}
+
+#[inline]
+fn fmt_opt_decimal(value: Option<Decimal>) -> String {
+    value.map(fmt_decimal).unwrap_or_default()
+}
+
+impl CsvRow for TradeRecord {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp",
+        "market_id",
+        "arb_type",
+        "position_size",
+        "expected_profit",
+        "actual_profit",
+        "execution_time_ms",
+        "success",
+    ];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            self.market_id.clone(),
+            self.arb_type.clone(),
+            fmt_decimal(self.position_size),
+            fmt_decimal(self.expected_profit),
+            fmt_decimal(self.actual_profit),
+            self.execution_time_ms.to_string(),
+            self.success.to_string(),
+        ]
+    }
+}
+
+impl CsvRow for SimulatedTrade {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp",
+        "market_id",
+        "arb_type",
+        "legs",
+        "asset_ids",
+        "total_cost",
+        "expected_payout",
+        "net_profit",
+        "execution_time_ms",
+        "pending_redemption",
+    ];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            self.market_id.clone(),
+            self.arb_type.clone(),
+            self.edges.len().to_string(),
+            self.edges
+                .iter()
+                .map(|e| e.asset_id.as_str())
+                .collect::<Vec<_>>()
+                .join(";"),
+            fmt_decimal(self.total_cost),
+            fmt_decimal(self.expected_payout),
+            fmt_decimal(self.net_profit),
+            self.execution_time_ms.to_string(),
+            self.pending_redemption.to_string(),
+        ]
+    }
+}
+
+impl CsvRow for SimulatedShortWindowTrade {
+    const HEADER: &'static [&'static str] = &[
+        "id",
+        "market_id",
+        "market_question",
+        "entry_time",
+        "expected_resolution_time",
+        "minutes_to_expiry",
+        "leg_prices",
+        "sum_prices",
+        "position_size",
+        "entry_cost",
+        "expected_profit",
+        "status",
+        "actual_profit",
+        "resolution_time",
+    ];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.market_id.clone(),
+            self.market_question.clone(),
+            self.entry_time.to_string(),
+            self.expected_resolution_time.to_string(),
+            self.minutes_to_expiry.to_string(),
+            self.leg_prices
+                .iter()
+                .map(|p| fmt_decimal(*p))
+                .collect::<Vec<_>>()
+                .join(";"),
+            fmt_decimal(self.sum_prices),
+            fmt_decimal(self.position_size),
+            fmt_decimal(self.entry_cost),
+            fmt_decimal(self.expected_profit),
+            format!("{:?}", self.status),
+            fmt_opt_decimal(self.actual_profit),
+            self.resolution_time
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+/// Write a header plus one row per record
+pub fn write_csv<W: Write, T: CsvRow>(writer: W, rows: &[T]) -> Result<()> {
+    let mut csv_writer = csv::Writer::from_writer(writer);
+
+    csv_writer.write_record(T::HEADER)?;
+    for row in rows {
+        csv_writer.write_record(row.row())?;
+    }
+    csv_writer.flush()?;
+
+    Ok(())
+}
+
+/// Write records to a CSV file, creating parent directories as needed
+pub fn export_csv<T: CsvRow>(path: &Path, rows: &[T]) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    write_csv(file, rows)
+}
+
+/// `trades.csv` + `short_window` -> `trades_short_window.csv`
+pub fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "csv".to_string());
+
+    path.with_file_name(format!("{}_{}.{}", stem, suffix, extension))
+}
+
+/// Value of `--export-csv <path>` (or `--export-csv=<path>`) from CLI args
+pub fn export_csv_arg<I: IntoIterator<Item = String>>(args: I) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--export-csv" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--export-csv=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    None
+}
@@ -1,18 +1,35 @@
+pub mod affinity;
+pub mod export;
+pub mod parse;
+pub mod sim;
+
 use anyhow::{Context, Result};
 use config::{Config as ConfigLoader, Environment};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::time::Instant;
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum TradingMode {
     Live,
     Simulation,
+    /// Authenticate, build and sign orders against the live exchange, but never submit them
+    #[serde(rename = "paper_live")]
+    PaperLive,
+}
+
+/// Reference price the market maker quotes around
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MidpointMode {
+    #[default]
+    Arithmetic, // (best_bid + best_ask) / 2
+    Microprice, // Size-weighted: leans toward the side with less resting size
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Strategy {
     Arbitrage,     // Original YES+NO < 1.0 detection (rare opportunities)
@@ -33,14 +50,63 @@ pub struct Config {
     pub monitoring: MonitoringConfig,
     pub alerts: AlertsConfig,
     pub latency: LatencyConfig,
+    #[serde(default)]
+    pub quality_scoring: QualityScoringConfig,
+    #[serde(default)]
+    pub simulation: SimulationConfig,
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    #[serde(default)]
+    pub correlation: CorrelationConfig,
+    #[serde(default)]
+    pub strategy_budgets: StrategyBudgetsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub wss_url: String,
+    /// Authenticated channel reporting our own orders and trades (live mode)
+    #[serde(default = "default_user_wss_url")]
+    pub user_wss_url: String,
+    /// Market-channel connections to spread the subscribed markets across
+    #[serde(default = "default_ws_connections")]
+    pub ws_connections: usize,
+    /// Largest market-channel frame to parse, in bytes. Bigger frames are
+    /// reported and the books they carried are fetched over REST instead
+    #[serde(default = "default_ws_max_message_bytes")]
+    pub ws_max_message_bytes: usize,
     pub rest_url: String,
     pub gamma_url: String,
     pub polygon_rpc_url: String,
+    /// Poll `/book` over REST for a market-channel connection's assets while it is down
+    #[serde(default)]
+    pub rest_fallback_enabled: bool,
+    /// How long a connection must be down before REST polling takes over
+    #[serde(default = "default_rest_fallback_after_secs")]
+    pub rest_fallback_after_secs: u64,
+    /// Interval between REST polls while the fallback is active
+    #[serde(default = "default_rest_fallback_poll_ms")]
+    pub rest_fallback_poll_ms: u64,
+}
+
+fn default_user_wss_url() -> String {
+    "wss://ws-subscriptions-clob.polymarket.com/ws/user".to_string()
+}
+
+fn default_ws_connections() -> usize {
+    1
+}
+
+fn default_ws_max_message_bytes() -> usize {
+    5 * 1024 * 1024
+}
+
+fn default_rest_fallback_after_secs() -> u64 {
+    30
+}
+
+fn default_rest_fallback_poll_ms() -> u64 {
+    2000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +123,69 @@ pub struct CredentialsConfig {
     pub signature_type: u8,
 }
 
+impl CredentialsConfig {
+    /// Check `funder_address` against the EOA the private key signs as, and
+    /// rewrite it in checksum form. A mismatch is fatal for an EOA; for proxy and
+    /// Safe wallets the two differ by design, so it's logged for a human to check.
+    fn validate_wallet(&mut self) -> Result<()> {
+        use alloy::primitives::Address;
+        use alloy::signers::local::PrivateKeySigner;
+
+        let eoa = self
+            .private_key
+            .trim()
+            .parse::<PrivateKeySigner>()
+            .map_err(|_| anyhow::anyhow!("private_key is not a 32-byte hex key"))?
+            .address();
+
+        let raw = self.funder_address.trim();
+        let hex = raw.strip_prefix("0x").unwrap_or(raw);
+        if hex.len() != 40 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            anyhow::bail!(
+                "funder_address {:?} is not an address (expected 0x and 40 hex digits)",
+                raw
+            );
+        }
+        // All one case carries no checksum; mixed case must be a valid EIP-55 one
+        let mixed_case = hex.bytes().any(|b| b.is_ascii_lowercase())
+            && hex.bytes().any(|b| b.is_ascii_uppercase());
+        let funder = if mixed_case {
+            Address::parse_checksummed(format!("0x{}", hex), None)
+                .map_err(|_| anyhow::anyhow!("funder_address {} has a bad checksum (typo?)", raw))?
+        } else {
+            hex.parse::<Address>()
+                .map_err(|_| anyhow::anyhow!("funder_address {:?} is not an address", raw))?
+        };
+        self.funder_address = funder.to_checksum(None);
+
+        match self.signature_type {
+            0 if funder != eoa => anyhow::bail!(
+                "signature_type 0 (EOA) signs and funds its own orders, but funder_address {} \
+                 is not the private key's address {}",
+                funder,
+                eoa
+            ),
+            0 => {}
+            1 | 2 if funder == eoa => warn!(
+                "⚠️  signature_type {} (proxy/Safe) but funder_address {} is the EOA itself; \
+                 it should be the Polymarket proxy wallet holding the funds",
+                self.signature_type, funder
+            ),
+            1 | 2 => warn!(
+                "⚠️  Orders signed by EOA {} will be funded by {} (signature_type {}); \
+                 make sure that is your Polymarket proxy wallet",
+                eoa, funder, self.signature_type
+            ),
+            other => anyhow::bail!(
+                "Invalid signature_type {} (expected 0=EOA, 1=Proxy, 2=Gnosis Safe)",
+                other
+            ),
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingConfig {
     pub trading_mode: TradingMode,
@@ -70,6 +199,22 @@ pub struct TradingConfig {
     pub tick_size: String,
     pub order_type: String,
     pub slippage_tolerance: rust_decimal::Decimal,
+    /// Legs priced below this (or above 1 - this) belong to a resolved book and can't be filled
+    #[serde(default = "default_min_executable_price")]
+    pub min_executable_price: rust_decimal::Decimal,
+    /// Cross-market opportunities whose correlation confidence falls below this are dropped
+    #[serde(default = "default_min_cross_market_confidence")]
+    pub min_cross_market_confidence: rust_decimal::Decimal,
+    /// Absolute profit floor (USD, after fees) for every detector, whatever the edge %
+    #[serde(default = "default_min_net_profit_usd")]
+    pub min_net_profit_usd: rust_decimal::Decimal,
+    /// Multi-outcome markets with more outcomes than this are never arbed (0 = no limit)
+    #[serde(default = "default_max_outcomes_for_arb")]
+    pub max_outcomes_for_arb: usize,
+    /// Only take a multi-outcome arb when every leg's size rests at or below
+    /// its limit price, so one execution fills all of them
+    #[serde(default)]
+    pub multi_outcome_require_full_fill: bool,
     // Market Making parameters (RN1 strategy)
     #[serde(default = "default_spread_bps")]
     pub mm_spread_bps: u64, // Spread in basis points (e.g., 100 = 1%)
@@ -79,6 +224,22 @@ pub struct TradingConfig {
     pub mm_max_orders_per_market: usize, // Max open orders per market
     #[serde(default = "default_refresh_secs")]
     pub mm_order_refresh_secs: u64, // How often to refresh orders
+    #[serde(default)]
+    pub mm_midpoint_mode: MidpointMode, // Arithmetic midpoint or size-weighted microprice
+    #[serde(default = "default_ladder_levels")]
+    pub mm_ladder_levels: usize, // Bid/ask rungs quoted per asset (1 = single quote)
+    #[serde(default = "default_ladder_step_bps")]
+    pub mm_ladder_step_bps: u64, // Extra distance from the midpoint per rung, in bps of midpoint
+    #[serde(default = "default_ladder_size_decay")]
+    pub mm_ladder_size_decay: rust_decimal::Decimal, // Each rung's size = previous rung's * decay
+    #[serde(default = "default_reward_pool_usd")]
+    pub mm_reward_pool_usd: rust_decimal::Decimal, // Daily liquidity reward pool per market (USD)
+    #[serde(default = "default_reward_max_spread")]
+    pub mm_reward_max_spread: rust_decimal::Decimal, // Orders further than this from midpoint earn no rewards
+    #[serde(default = "default_min_spread_ticks")]
+    pub mm_min_spread_ticks: u32, // Skip books whose spread is under this many of the market's ticks
+    #[serde(default)]
+    pub mm_price_improve_ticks: u32, // Peg the inner rung this many ticks inside the best bid/ask (0 = off)
     // Volume Farming parameters (trash farming)
     #[serde(default = "default_max_price")]
     pub vf_max_price: rust_decimal::Decimal, // Max price for trash contracts (e.g., 0.05)
@@ -96,14 +257,97 @@ pub struct TradingConfig {
     /// Max position size for short-window arb (conservative)
     #[serde(default = "default_short_window_max_size")]
     pub short_window_max_size: u64,
+    /// Assumed probability that a detected short-window opportunity actually fills
+    #[serde(default = "default_short_window_fill_probability")]
+    pub short_window_fill_probability: rust_decimal::Decimal,
+    /// Assumed fraction of time capital is deployed (idle between cycles otherwise)
+    #[serde(default = "default_short_window_capital_utilization")]
+    pub short_window_capital_utilization: rust_decimal::Decimal,
+    /// Ceiling on the reported annualized return (as a fraction, 10.0 = 1000%)
+    #[serde(default = "default_short_window_max_annualized_return")]
+    pub short_window_max_annualized_return: rust_decimal::Decimal,
+    /// Min edge by minutes to expiry; empty uses `short_window_min_edge` everywhere
+    #[serde(default)]
+    pub short_window_edge_curve: Vec<EdgeCurvePoint>,
+    /// How `short_window_edge_curve` fills the gaps between its points
+    #[serde(default)]
+    pub short_window_edge_curve_mode: EdgeCurveMode,
+    /// Flag binary markets whose ask sum strays from its rolling mean. These are
+    /// directional signals, not arbs, and are never executed by the arb engine.
+    #[serde(default)]
+    pub enable_mean_reversion_signals: bool,
+    /// Sums per market in the rolling mean
+    #[serde(default = "default_mean_reversion_window")]
+    pub mean_reversion_window: usize,
+    /// How far (in $) the sum must stray from its rolling mean to signal
+    #[serde(default = "default_mean_reversion_band")]
+    pub mean_reversion_band: rust_decimal::Decimal,
 }
 
+/// One point of the short-window edge curve
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EdgeCurvePoint {
+    pub minutes: i64,
+    pub min_edge: rust_decimal::Decimal,
+}
+
+/// Interpolation between `EdgeCurvePoint`s
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeCurveMode {
+    #[default]
+    Linear, // Straight line between neighbouring points
+    Step, // Edge of the last point at or below minutes_to_expiry
+}
+
+fn default_min_executable_price() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::new(1, 2) // 0.01 = 1¢, same bound the cross-market scanner uses
+}
+fn default_min_cross_market_confidence() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::new(75, 2) // 0.75 = price-target implications pass, loose text matches don't
+}
+fn default_min_net_profit_usd() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::new(50, 2) // $0.50, the floor the cross-market scanner used
+}
+fn default_max_outcomes_for_arb() -> usize {
+    0 // Any number of outcomes
+}
 fn default_short_window_min_edge() -> rust_decimal::Decimal {
     rust_decimal::Decimal::new(8, 3) // 0.008 = 0.8% (lower than standard 1.2%)
 }
 fn default_short_window_max_size() -> u64 {
     50 // $50 max per short-window arb (conservative)
 }
+fn default_market_cooldown_secs() -> u64 {
+    300 // 5 minute cooldown after a failure
+}
+fn default_min_seconds_between_trades_per_market() -> u64 {
+    0 // No spacing: a market can be re-entered on the next detection
+}
+fn default_blacklist_on_repeated_failure() -> bool {
+    true
+}
+fn default_max_failures_before_blacklist() -> usize {
+    3 // 3 strikes
+}
+fn default_failure_window_secs() -> u64 {
+    3600 // ...within an hour
+}
+fn default_short_window_fill_probability() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::new(25, 2) // 25% of detections actually fill
+}
+fn default_short_window_capital_utilization() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::new(5, 1) // Capital deployed half the time
+}
+fn default_short_window_max_annualized_return() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::from(10) // Cap at 1000%
+}
+fn default_mean_reversion_window() -> usize {
+    30
+}
+fn default_mean_reversion_band() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::new(2, 2) // $0.02 either side of the mean
+}
 
 fn default_spread_bps() -> u64 {
     200
@@ -117,6 +361,24 @@ fn default_max_orders() -> usize {
 fn default_refresh_secs() -> u64 {
     30
 } // Refresh every 30s
+fn default_ladder_levels() -> usize {
+    1
+} // Single bid + ask
+fn default_ladder_step_bps() -> u64 {
+    50
+} // 0.5% further out per rung
+fn default_ladder_size_decay() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::ONE
+} // Same size on every rung
+fn default_reward_pool_usd() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::from(50)
+} // $50/day per market
+fn default_reward_max_spread() -> rust_decimal::Decimal {
+    rust_decimal::Decimal::new(3, 2)
+} // 3¢ either side of the midpoint
+fn default_min_spread_ticks() -> u32 {
+    2
+} // A one-tick spread leaves no price strictly inside to improve on
 fn default_max_price() -> rust_decimal::Decimal {
     rust_decimal::Decimal::new(5, 2)
 } // 0.05
@@ -136,6 +398,20 @@ pub struct RiskConfig {
     pub max_gas_gwei: u64,
     pub position_timeout_seconds: u64,
     pub inventory_drift_threshold: rust_decimal::Decimal,
+    /// Seconds a market is skipped after a failed execution or losing trade
+    #[serde(default = "default_market_cooldown_secs")]
+    pub market_cooldown_secs: u64,
+    /// Seconds after any trade in a market before it can be traded again (0 disables)
+    #[serde(default = "default_min_seconds_between_trades_per_market")]
+    pub min_seconds_between_trades_per_market: u64,
+    /// Blacklist a market for the session after repeated failures
+    #[serde(default = "default_blacklist_on_repeated_failure")]
+    pub blacklist_on_repeated_failure: bool,
+    /// Failures within `failure_window_secs` that trigger the runtime blacklist
+    #[serde(default = "default_max_failures_before_blacklist")]
+    pub max_failures_before_blacklist: usize,
+    #[serde(default = "default_failure_window_secs")]
+    pub failure_window_secs: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -160,6 +436,19 @@ pub struct MarketsConfig {
     /// Require order book to be enabled (safety: always true for MM)
     #[serde(default = "default_enforce_orderbook")]
     pub enforce_enable_order_book: bool,
+    /// Max short-window event slugs probed at once against the Gamma API
+    #[serde(default = "default_max_concurrent_event_fetches")]
+    pub max_concurrent_event_fetches: usize,
+    /// Last successful market fetch, used at startup if Gamma is down (empty disables)
+    #[serde(default = "default_market_cache_path")]
+    pub market_cache_path: String,
+    /// Refuse to start from a market cache older than this
+    #[serde(default = "default_market_cache_max_age_secs")]
+    pub market_cache_max_age_secs: u64,
+    /// How often markets are refetched so new listings and short-window rollovers
+    /// are picked up (0 disables)
+    #[serde(default = "default_market_refresh_interval_secs")]
+    pub market_refresh_interval_secs: u64,
 }
 
 fn default_short_window_enabled() -> bool {
@@ -171,12 +460,24 @@ fn default_short_window_minutes() -> u64 {
 fn default_min_minutes_to_expiry() -> u64 {
     2
 } // Skip if <2 min to expiry (avoid settlement risk)
+fn default_max_concurrent_event_fetches() -> usize {
+    16
+} // Enough to probe a full horizon in a few round trips
 fn default_min_volume_24h_short() -> u64 {
     100
 } // Lower volume bar for short-window markets
 fn default_enforce_orderbook() -> bool {
     true
 }
+fn default_market_cache_path() -> String {
+    "data/markets_cache.json".to_string()
+}
+fn default_market_cache_max_age_secs() -> u64 {
+    21600
+} // 6h: older listings have likely resolved or closed
+fn default_market_refresh_interval_secs() -> u64 {
+    300
+} // 5m: each 15m short-window market is seen before it expires
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionConfig {
@@ -187,8 +488,71 @@ pub struct ExecutionConfig {
     pub retry_backoff_ms: u64,
     pub http_timeout_secs: u64,
     pub connection_pool_size: usize,
+    /// Time allowed to establish a connection, within `http_timeout_secs`
+    #[serde(default = "default_http_connect_timeout_secs")]
+    pub http_connect_timeout_secs: u64,
+    /// How often the stale-book sweeper runs
+    #[serde(default = "default_stale_book_sweep_interval_secs")]
+    pub stale_book_sweep_interval_secs: u64,
+    /// Books older than this are evicted so detection never runs on dead prices
+    #[serde(default = "default_stale_book_max_age_ms")]
+    pub stale_book_max_age_ms: u64,
+    /// Track fills/cancels from the authenticated user channel instead of
+    /// waiting a fixed time after submission and cancelling (live mode only)
+    #[serde(default = "default_enable_user_channel")]
+    pub enable_user_channel: bool,
+    /// Budget for live order submissions; excess orders queue instead of bursting
+    #[serde(default = "default_max_orders_per_sec")]
+    pub max_orders_per_sec: u32,
+    /// Live: how often the exchange balance is re-read and checked against the
+    /// capital we think we have
+    #[serde(default = "default_balance_reconcile_interval_secs")]
+    pub balance_reconcile_interval_secs: u64,
+    /// Opportunities detected longer ago than this are dropped instead of executed (0 = no limit)
+    #[serde(default = "default_max_opportunity_age_ms")]
+    pub max_opportunity_age_ms: u64,
+    /// Consecutive execution failures, within `circuit_breaker_window_secs`,
+    /// that halt execution (0 = never halt)
+    #[serde(default = "default_circuit_breaker_failures")]
+    pub circuit_breaker_failures: u32,
+    #[serde(default = "default_circuit_breaker_window_secs")]
+    pub circuit_breaker_window_secs: u64,
+    /// How long execution stays halted before a single probe is let through
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
 }
 
+fn default_http_connect_timeout_secs() -> u64 {
+    3
+}
+fn default_stale_book_sweep_interval_secs() -> u64 {
+    5
+} // Sweep every 5s
+fn default_stale_book_max_age_ms() -> u64 {
+    600_000
+} // 10 min (initial WS snapshots can be several minutes old)
+fn default_enable_user_channel() -> bool {
+    true
+}
+fn default_max_orders_per_sec() -> u32 {
+    10
+}
+fn default_balance_reconcile_interval_secs() -> u64 {
+    60
+}
+fn default_max_opportunity_age_ms() -> u64 {
+    500
+} // Short-window books move within a few hundred ms
+fn default_circuit_breaker_failures() -> u32 {
+    5
+}
+fn default_circuit_breaker_window_secs() -> u64 {
+    60
+}
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    300
+} // 5 min: long enough for an outage or halt to clear
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitoringConfig {
     pub log_level: String,
@@ -197,6 +561,37 @@ pub struct MonitoringConfig {
     pub enable_tracing: bool,
     pub log_file: String,
     pub metrics_retention_hours: u64,
+    /// Directory each day's summary is appended to; empty to skip the file
+    #[serde(default = "default_daily_report_dir")]
+    pub daily_report_dir: String,
+    /// Also send the daily summary to the alert sinks
+    #[serde(default)]
+    pub daily_report_alert: bool,
+    /// Record rejected opportunities this close to a threshold (0.1 = within 10%); 0 disables
+    #[serde(default)]
+    pub near_miss_band: rust_decimal::Decimal,
+    /// `/health` reports degraded once detection has been idle this long; 0 disables the check
+    #[serde(default = "default_health_max_detection_idle_secs")]
+    pub health_max_detection_idle_secs: u64,
+    /// Markets with a book unchanged this long are listed at `/stale_books`
+    #[serde(default = "default_stale_feed_age_ms")]
+    pub stale_feed_age_ms: u64,
+    /// Bearer token the dashboard's pause/resume routes require; when empty
+    /// they only answer requests from this machine
+    #[serde(default)]
+    pub dashboard_token: String,
+}
+
+fn default_health_max_detection_idle_secs() -> u64 {
+    300
+}
+
+fn default_stale_feed_age_ms() -> u64 {
+    60_000
+}
+
+fn default_daily_report_dir() -> String {
+    "reports".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -204,12 +599,43 @@ pub struct AlertsConfig {
     pub enable_telegram: bool,
     pub telegram_bot_token: String,
     pub telegram_chat_id: String,
+    /// Post alerts to a Discord channel via an incoming webhook
+    #[serde(default)]
+    pub enable_discord: bool,
+    #[serde(default)]
+    pub discord_webhook_url: String,
+    /// POST each alert as JSON to a generic webhook receiver
+    #[serde(default)]
+    pub enable_webhook: bool,
+    #[serde(default)]
+    pub webhook_url: String,
     pub alert_on_trade_usd: u64,
     pub alert_on_error: bool,
     pub alert_on_latency_spike: bool,
     pub latency_spike_threshold_ms: u64,
     pub alert_on_pnl_drawdown: bool,
     pub pnl_drawdown_threshold_usd: u64,
+    /// Warn when the market feed has been silent this long while connected
+    #[serde(default = "default_ws_stall_threshold_ms")]
+    pub ws_stall_threshold_ms: u64,
+    /// Alert when the exchange balance falls this far short of the capital we
+    /// think is available
+    #[serde(default = "default_balance_deviation_threshold_usd")]
+    pub balance_deviation_threshold_usd: u64,
+    /// The same risk limit breached again in the same market within this is
+    /// not alerted again (0 = alert every breach)
+    #[serde(default = "default_risk_alert_cooldown_secs")]
+    pub risk_alert_cooldown_secs: u64,
+}
+
+fn default_ws_stall_threshold_ms() -> u64 {
+    30_000
+}
+fn default_balance_deviation_threshold_usd() -> u64 {
+    25
+}
+fn default_risk_alert_cooldown_secs() -> u64 {
+    300
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -219,46 +645,392 @@ pub struct LatencyConfig {
     pub use_jemalloc: bool,
     pub max_orderbook_updates_per_sec: usize,
     pub enable_zero_copy: bool,
+    /// Worker threads for the parallel scanner (defaults to the number of CPUs)
+    #[serde(default = "default_scanner_workers")]
+    pub scanner_workers: usize,
+    /// Minimum gap between detection runs on a market whose best asks haven't
+    /// moved (0 = detect on every update)
+    #[serde(default)]
+    pub detection_throttle_ms: u64,
+}
+
+fn default_scanner_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Weights and normalizers for the RN1 opportunity quality score (0-10)
+/// Each component is `min(metric / divisor, 10)`, then weighted and summed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityScoringConfig {
+    pub edge_weight: rust_decimal::Decimal,
+    pub liquidity_weight: rust_decimal::Decimal,
+    pub size_weight: rust_decimal::Decimal,
+    pub profit_weight: rust_decimal::Decimal,
+    /// Edge is scaled by this multiplier (edge of 0.05 * 100 = 5 points)
+    pub edge_multiplier: rust_decimal::Decimal,
+    /// Liquidity ($) that scores 1 point
+    pub liquidity_divisor: rust_decimal::Decimal,
+    /// Position size ($) that scores 1 point
+    pub size_divisor: rust_decimal::Decimal,
+    /// Net profit ($) that scores 1 point
+    pub profit_divisor: rust_decimal::Decimal,
+    /// Minimum score required to execute
+    pub min_quality_score: rust_decimal::Decimal,
+}
+
+impl Default for QualityScoringConfig {
+    fn default() -> Self {
+        Self {
+            edge_weight: rust_decimal::Decimal::new(4, 1),
+            liquidity_weight: rust_decimal::Decimal::new(3, 1),
+            size_weight: rust_decimal::Decimal::new(2, 1),
+            profit_weight: rust_decimal::Decimal::new(1, 1),
+            edge_multiplier: rust_decimal::Decimal::from(100),
+            liquidity_divisor: rust_decimal::Decimal::from(1000),
+            size_divisor: rust_decimal::Decimal::from(500),
+            profit_divisor: rust_decimal::Decimal::from(50),
+            min_quality_score: rust_decimal::Decimal::from(5), // Top 50% threshold
+        }
+    }
+}
+
+/// How `SimulationExecutor` models execution. The defaults fill instantly and
+/// always, which flatters simulated results compared with live trading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    /// Delay between detection and the fill, during which the book can move
+    pub fill_latency_ms: u64,
+    /// Extra delay drawn uniformly from `0..=fill_latency_jitter_ms`
+    pub fill_latency_jitter_ms: u64,
+    /// Chance (0-1) that an opportunity fills at all
+    pub fill_probability: f64,
+    /// Fee charged on the payout of filled sets
+    pub fee_rate: rust_decimal::Decimal,
+    /// Hold each complete set's $1 until its market resolves instead of
+    /// crediting it at entry, so capital is tied up as it would be live
+    pub redeem_at_resolution: bool,
+    /// Resolution delay for markets without a known end date
+    pub default_resolution_delay_secs: u64,
+    /// Where sw_arb_sim keeps its tracker between runs (empty disables)
+    pub short_window_state_path: String,
+    /// Saved tracker state older than this is ignored and a fresh session starts
+    pub short_window_state_max_age_secs: u64,
+    /// Chance (0-1) that an expired short-window trade was picked off near
+    /// expiry: only its losing leg filled, so it loses instead of redeeming $1
+    pub adversarial_fill_probability: f64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            fill_latency_ms: 0,
+            fill_latency_jitter_ms: 0,
+            fill_probability: 1.0,
+            fee_rate: rust_decimal::Decimal::new(2, 2),
+            redeem_at_resolution: false,
+            default_resolution_delay_secs: 900,
+            short_window_state_path: "logs/sw_arb_state.json".to_string(),
+            short_window_state_max_age_secs: 86400,
+            adversarial_fill_probability: 0.0,
+        }
+    }
+}
+
+/// Capital each strategy may have committed at once (USD). A budget of 0 leaves
+/// the strategy bounded by `global_cap` alone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrategyBudgetsConfig {
+    pub arbitrage: u64,
+    pub market_making: u64,
+    pub volume_farming: u64,
+    /// Committed capital across all strategies (0 = the bankroll)
+    pub global_cap: u64,
+}
+
+/// Question-text heuristics the parallel scanner uses to relate markets within
+/// an event. Keywords and stop words are matched against lowercased questions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorrelationConfig {
+    /// Words marking a question as the parent event ("win the championship")
+    pub parent_keywords: Vec<String>,
+    /// Words marking a question as a stage of its parent ("win the semifinal")
+    pub child_keywords: Vec<String>,
+    /// Words ignored when counting the words two questions share
+    pub stop_words: Vec<String>,
+    /// Shortest word that counts as significant
+    pub min_word_len: usize,
+    /// Shared significant words needed to link a parent and a child
+    pub min_parent_common_words: usize,
+    /// Shared significant words needed to call two markets siblings
+    pub min_sibling_common_words: usize,
+}
+
+impl Default for CorrelationConfig {
+    fn default() -> Self {
+        let words = |words: &[&str]| words.iter().map(|w| w.to_string()).collect();
+        Self {
+            parent_keywords: words(&["championship", "final", "winner", "win"]),
+            child_keywords: words(&["semifinal", "quarter", "round", "game"]),
+            stop_words: words(&[
+                "will", "the", "a", "an", "to", "be", "in", "on", "at", "by", "for", "or", "and",
+                "of",
+            ]),
+            min_word_len: 3,
+            min_parent_common_words: 2,
+            min_sibling_common_words: 3,
+        }
+    }
+}
+
+/// UTC hours each strategy runs in. A window `(start, end)` covers hours
+/// `start..end` and wraps past midnight when `end < start`, so `(22, 6)` is
+/// overnight. A strategy without windows runs around the clock.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    pub arbitrage_hours: Vec<(u32, u32)>,
+    pub market_making_hours: Vec<(u32, u32)>,
+    pub volume_farming_hours: Vec<(u32, u32)>,
+}
+
+impl TradingConfig {
+    /// Whether a multi-outcome arb with `outcomes` legs is within `max_outcomes_for_arb`
+    #[inline]
+    pub fn within_outcome_limit(&self, outcomes: usize) -> bool {
+        self.max_outcomes_for_arb == 0 || outcomes <= self.max_outcomes_for_arb
+    }
+
+    /// Min net edge a short-window arb expiring in `minutes_to_expiry` must clear.
+    /// Outside the curve the nearest end point applies.
+    pub fn short_window_min_edge_for(&self, minutes_to_expiry: i64) -> rust_decimal::Decimal {
+        let curve = &self.short_window_edge_curve;
+        let (Some(first), Some(last)) = (curve.first(), curve.last()) else {
+            return self.short_window_min_edge;
+        };
+        if minutes_to_expiry <= first.minutes {
+            return first.min_edge;
+        }
+        if minutes_to_expiry >= last.minutes {
+            return last.min_edge;
+        }
+
+        let upper = curve
+            .iter()
+            .position(|point| point.minutes > minutes_to_expiry)
+            .unwrap_or(curve.len() - 1);
+        let (lower, upper) = (&curve[upper - 1], &curve[upper]);
+        match self.short_window_edge_curve_mode {
+            EdgeCurveMode::Step => lower.min_edge,
+            EdgeCurveMode::Linear => {
+                let progress = rust_decimal::Decimal::from(minutes_to_expiry - lower.minutes)
+                    / rust_decimal::Decimal::from(upper.minutes - lower.minutes);
+                lower.min_edge + (upper.min_edge - lower.min_edge) * progress
+            }
+        }
+    }
+}
+
+impl ScheduleConfig {
+    /// Whether `strategy` runs during UTC `hour` (0-23). Hybrid is the
+    /// combination of the others, so it has no window of its own.
+    pub fn is_active(&self, strategy: &Strategy, hour: u32) -> bool {
+        let windows = match strategy {
+            Strategy::Arbitrage => &self.arbitrage_hours,
+            Strategy::MarketMaking => &self.market_making_hours,
+            Strategy::VolumeFarming => &self.volume_farming_hours,
+            Strategy::Hybrid => return true,
+        };
+
+        windows.is_empty()
+            || windows.iter().any(|&(start, end)| {
+                if start <= end {
+                    (start..end).contains(&hour)
+                } else {
+                    hour >= start || hour < end
+                }
+            })
+    }
+
+    #[inline]
+    pub fn is_active_now(&self, strategy: &Strategy) -> bool {
+        use chrono::Timelike;
+        self.is_active(strategy, chrono::Utc::now().hour())
+    }
+
+    fn validate(&self) -> Result<()> {
+        let windows = self
+            .arbitrage_hours
+            .iter()
+            .chain(&self.market_making_hours)
+            .chain(&self.volume_farming_hours);
+        for &(start, end) in windows {
+            if start > 24 || end > 24 {
+                anyhow::bail!("Schedule window ({}, {}) is outside 0-24h", start, end);
+            }
+        }
+        Ok(())
+    }
 }
 
+/// Secrets outside `CredentialsConfig`, as (section, field), masked by `Config::redacted_json`.
+/// The RPC URL is on the list because providers embed the API key in it.
+const REDACTED_FIELDS: [(&str, &str); 5] = [
+    ("server", "polygon_rpc_url"),
+    ("monitoring", "dashboard_token"),
+    ("alerts", "telegram_bot_token"),
+    ("alerts", "discord_webhook_url"),
+    ("alerts", "webhook_url"),
+];
+const REDACTED: &str = "<redacted>";
+
 impl Config {
+    /// The effective config, defaults included, as JSON safe to share. Credentials
+    /// are never serialized; the other secrets read `<redacted>` when set.
+    pub fn redacted_json(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        for (section, field) in REDACTED_FIELDS {
+            let Some(secret) = value.get_mut(section).and_then(|s| s.get_mut(field)) else {
+                continue;
+            };
+            if secret.as_str().is_some_and(|s| !s.is_empty()) {
+                *secret = REDACTED.into();
+            }
+        }
+        value
+    }
+
+    /// Live trading with CLOB API credentials and the user channel switched on
+    pub fn user_channel_enabled(&self) -> bool {
+        self.execution.enable_user_channel
+            && self.trading.trading_mode == TradingMode::Live
+            && !self.credentials.api_key.is_empty()
+            && !self.credentials.api_secret.is_empty()
+            && !self.credentials.api_passphrase.is_empty()
+    }
+
+    /// Load from `config/`, overlaid with the `HFTPM_PROFILE` profile if set
     pub fn load() -> Result<Self> {
+        Self::load_profile(None)
+    }
+
+    /// Load from `config/`, overlaid with `profile` (falling back to `HFTPM_PROFILE`)
+    pub fn load_profile(profile: Option<&str>) -> Result<Self> {
+        let env_profile = std::env::var(PROFILE_ENV).ok();
+        let profile = profile.or(env_profile.as_deref()).filter(|p| !p.is_empty());
+        Self::load_from(Path::new("config"), profile)
+    }
+
+    /// Load `<dir>/config`, then `<dir>/<profile>` over it so a profile only
+    /// lists the fields it changes, then `<dir>/secrets`, then `HFTPM__*` env vars
+    pub fn load_from(dir: &Path, profile: Option<&str>) -> Result<Self> {
         use config::File;
 
-        let settings = ConfigLoader::builder()
+        let mut builder = ConfigLoader::builder()
             // Load base config
-            .add_source(File::with_name("config/config").required(true))
+            .add_source(File::from(dir.join("config")).required(true));
+
+        if let Some(profile) = profile {
+            info!("📋 Config profile: {}", profile);
+            // A named profile that doesn't exist is a typo, not an empty overlay
+            builder = builder.add_source(File::from(dir.join(profile)).required(true));
+        }
+
+        let settings = builder
             // Load secrets (optional, can use env vars instead)
-            .add_source(File::with_name("config/secrets").required(false))
+            .add_source(File::from(dir.join("secrets")).required(false))
             // Environment variables override files
             .add_source(Environment::default().prefix("HFTPM").separator("__"))
             .build()?;
 
-        let config: Config = settings
+        let mut config: Config = settings
             .try_deserialize()
             .context("Failed to deserialize config")?;
 
-        if config.server.wss_url.is_empty() || config.server.rest_url.is_empty() {
+        config.validate()?;
+
+        info!("✅ Configuration loaded successfully");
+        Ok(config)
+    }
+
+    /// Reject a config that can't run, normalizing what it can.
+    ///
+    /// In the modes that sign orders (live and paper-live) the wallet is checked
+    /// too: the private key must parse, `funder_address` must be a well-formed
+    /// address (rewritten in EIP-55 checksum form), and an EOA (`signature_type`
+    /// 0) must fund its own orders. Simulation never signs, so placeholder
+    /// credentials are left alone.
+    pub fn validate(&mut self) -> Result<()> {
+        if self.server.wss_url.is_empty() || self.server.rest_url.is_empty() {
             anyhow::bail!("Server URLs must be configured");
         }
 
-        if config.credentials.private_key.is_empty() {
+        if self.credentials.private_key.is_empty() {
             anyhow::bail!("Private key must be set");
         }
 
-        if config.credentials.api_key.is_empty() {
+        if self.credentials.api_key.is_empty() {
             anyhow::bail!("API key must be set");
         }
 
-        if config.credentials.funder_address.is_empty() {
+        if self.credentials.funder_address.is_empty() {
             anyhow::bail!("Funder address must be set");
         }
 
-        info!("✅ Configuration loaded successfully");
-        Ok(config)
+        self.schedule.validate()?;
+
+        if self.trading.trading_mode != TradingMode::Simulation {
+            self.credentials.validate_wallet()?;
+        }
+
+        Ok(())
     }
 }
 
+/// Env var naming the config profile to overlay when none is passed
+pub const PROFILE_ENV: &str = "HFTPM_PROFILE";
+
+/// Value of `--profile <name>` (or `--profile=<name>`) from CLI args
+pub fn profile_arg<I: IntoIterator<Item = String>>(args: I) -> Option<String> {
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next();
+        }
+        if let Some(profile) = arg.strip_prefix("--profile=") {
+            return Some(profile.to_string());
+        }
+    }
+
+    None
+}
+
+/// A `u64` config value (seconds, minutes) as `i64` for timestamp math, saturating
+/// instead of wrapping negative when it's out of range
+#[inline]
+pub fn saturating_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+/// HTTP client with the configured request and connect timeouts and idle
+/// connection pool, for every REST call the bot makes
+pub fn build_http_client(config: &ExecutionConfig) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(config.http_timeout_secs))
+        .connect_timeout(std::time::Duration::from_secs(
+            config.http_connect_timeout_secs,
+        ))
+        .pool_max_idle_per_host(config.connection_pool_size)
+        .build()
+        .context("Failed to create HTTP client")
+}
+
 pub fn setup_tracing(log_level: &str, log_file: &str) {
     use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
@@ -0,0 +1,38 @@
+//! Parsing of the decimal strings the CLOB sends for book levels.
+//!
+//! Every price and size from the WebSocket feed and the REST book endpoint goes
+//! through here, so a malformed level is rejected the same way everywhere
+//! instead of being read as zero.
+
+use anyhow::{bail, Context, Result};
+use rust_decimal::Decimal;
+
+/// Price of an outcome share, which must lie in (0, 1]
+pub fn parse_price(raw: &str) -> Result<Decimal> {
+    let price = parse_decimal(raw).with_context(|| format!("Invalid price {:?}", raw))?;
+    if price <= Decimal::ZERO || price > Decimal::ONE {
+        bail!("Price {} outside (0, 1]", price);
+    }
+    Ok(price)
+}
+
+/// Size of a level in shares; zero is allowed since it removes the level
+pub fn parse_size(raw: &str) -> Result<Decimal> {
+    let size = parse_decimal(raw).with_context(|| format!("Invalid size {:?}", raw))?;
+    if size.is_sign_negative() && !size.is_zero() {
+        bail!("Negative size {}", size);
+    }
+    Ok(size)
+}
+
+/// A `(price, size)` book level
+#[inline]
+pub fn parse_level(price: &str, size: &str) -> Result<(Decimal, Decimal)> {
+    Ok((parse_price(price)?, parse_size(size)?))
+}
+
+/// Exact parse that keeps the scale as sent ("0.50" stays 0.50), rejecting
+/// values that would need rounding to fit a Decimal
+fn parse_decimal(raw: &str) -> Result<Decimal> {
+    Ok(Decimal::from_str_exact(raw.trim())?)
+}
@@ -0,0 +1,164 @@
+//! Clock, ID and randomness sources for the simulation paths.
+//!
+//! Production runs use the wall clock, random v4 UUIDs and a randomly seeded
+//! generator. Replays and tests can swap in a fixed clock and seeded generators
+//! so the same inputs produce byte-identical trade logs.
+
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Millisecond clock: the system clock, or a fixed time that only moves when advanced
+#[derive(Debug, Clone, Default)]
+pub struct SimClock {
+    fixed_ms: Option<Arc<AtomicI64>>,
+}
+
+impl SimClock {
+    pub fn real() -> Self {
+        Self { fixed_ms: None }
+    }
+
+    /// Clock frozen at `start_ms`; clones share the same time
+    pub fn fixed(start_ms: i64) -> Self {
+        Self {
+            fixed_ms: Some(Arc::new(AtomicI64::new(start_ms))),
+        }
+    }
+
+    #[inline]
+    pub fn now_ms(&self) -> i64 {
+        match &self.fixed_ms {
+            Some(ms) => ms.load(Ordering::Relaxed),
+            None => chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    #[inline]
+    pub fn now_secs(&self) -> i64 {
+        self.now_ms().div_euclid(1000)
+    }
+
+    /// Move a fixed clock forward (no-op on the real clock)
+    pub fn advance_ms(&self, ms: i64) {
+        if let Some(fixed) = &self.fixed_ms {
+            fixed.fetch_add(ms, Ordering::Relaxed);
+        }
+    }
+
+    /// Wait `ms`: sleeps on the real clock, advances a fixed one instantly
+    pub async fn sleep_ms(&self, ms: u64) {
+        match &self.fixed_ms {
+            Some(_) => self.advance_ms(ms as i64),
+            None => tokio::time::sleep(Duration::from_millis(ms)).await,
+        }
+    }
+}
+
+/// Order/trade ID generator: random v4 UUIDs, or a reproducible sequence from a seed
+#[derive(Debug, Clone, Default)]
+pub struct SimIdGen {
+    seeded_state: Option<Arc<AtomicU64>>,
+}
+
+impl SimIdGen {
+    pub fn random() -> Self {
+        Self { seeded_state: None }
+    }
+
+    /// Same seed, same sequence of IDs; clones draw from the same sequence
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seeded_state: Some(Arc::new(AtomicU64::new(seed))),
+        }
+    }
+
+    /// Next ID as `{prefix}_{uuid}`
+    pub fn next_id(&self, prefix: &str) -> String {
+        let uuid = match &self.seeded_state {
+            Some(state) => {
+                let mut bytes = [0u8; 16];
+                bytes[..8].copy_from_slice(&splitmix64(state).to_le_bytes());
+                bytes[8..].copy_from_slice(&splitmix64(state).to_le_bytes());
+                uuid::Builder::from_random_bytes(bytes).into_uuid()
+            }
+            None => uuid::Uuid::new_v4(),
+        };
+
+        format!("{}_{}", prefix, uuid)
+    }
+}
+
+/// Uniform draws for simulated fills; clones draw from the same sequence
+#[derive(Debug, Clone)]
+pub struct SimRng {
+    state: Arc<AtomicU64>,
+}
+
+impl SimRng {
+    /// Seeded from a random UUID (production)
+    pub fn random() -> Self {
+        Self::seeded(uuid::Uuid::new_v4().as_u64_pair().0)
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            state: Arc::new(AtomicU64::new(seed)),
+        }
+    }
+
+    /// Uniform in `[0, 1)`
+    pub fn next_f64(&self) -> f64 {
+        (splitmix64(&self.state) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `0..=max`
+    pub fn next_u64_upto(&self, max: u64) -> u64 {
+        match max.checked_add(1) {
+            Some(bound) => splitmix64(&self.state) % bound,
+            None => splitmix64(&self.state),
+        }
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+/// SplitMix64 step; cheap and good enough for IDs, not for anything adversarial
+#[inline]
+fn splitmix64(state: &AtomicU64) -> u64 {
+    let mut z = state
+        .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Clock, ID and randomness source handed to the simulators
+#[derive(Debug, Clone, Default)]
+pub struct SimEnv {
+    pub clock: SimClock,
+    pub ids: SimIdGen,
+    pub rng: SimRng,
+}
+
+impl SimEnv {
+    /// Wall clock, random IDs and random draws (production)
+    pub fn real() -> Self {
+        Self::default()
+    }
+
+    /// Fixed clock starting at `start_ms`, IDs and draws seeded from `seed`
+    pub fn deterministic(seed: u64, start_ms: i64) -> Self {
+        Self {
+            clock: SimClock::fixed(start_ms),
+            ids: SimIdGen::seeded(seed),
+            // A separate stream, so fill draws don't shift the ID sequence
+            rng: SimRng::seeded(!seed),
+        }
+    }
+}
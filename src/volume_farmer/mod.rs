@@ -8,15 +8,17 @@
 //! Example: Buying $10 of contracts at $0.01 = $1,000 notional volume
 //! The $10 loss is worth it if airdrop allocation > $10
 
-use crate::gamma_api::Market;
+use crate::gamma_api::{self, Market};
 use crate::orderbook::OrderBookManager;
-use crate::utils::Config;
+use crate::risk::{CapitalAllocator, RiskDecision};
+use crate::utils::sim::SimEnv;
+use crate::utils::{Config, Strategy};
 use anyhow::Result;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
 use tracing::{debug, info, warn};
 
 /// A trash farming opportunity
@@ -61,13 +63,25 @@ pub struct VolumeFarmer {
     trades: Vec<TrashTrade>,
     daily_spend: Decimal,
     total_volume: Decimal,
-    last_reset: Instant,
+    /// Unix seconds of the last daily budget reset
+    last_reset: i64,
     simulated_balance: Decimal,
     initial_balance: Decimal,
+    sim_env: SimEnv,
+    /// Trash positions are held to resolution, so their cost stays committed
+    /// until `release_resolved` sees the market end
+    capital: Arc<CapitalAllocator>,
+    /// Market id -> capital committed to trash positions held in it
+    committed: HashMap<String, Decimal>,
 }
 
 impl VolumeFarmer {
     pub fn new(config: &Config) -> Self {
+        Self::with_sim_env(config, SimEnv::real())
+    }
+
+    /// Volume farmer whose trades and budget resets follow `sim_env`'s clock
+    pub fn with_sim_env(config: &Config, sim_env: SimEnv) -> Self {
         let initial_balance = Decimal::from(config.trading.bankroll);
 
         info!("🗑️  Volume Farmer initialized");
@@ -83,12 +97,22 @@ impl VolumeFarmer {
             trades: Vec::new(),
             daily_spend: Decimal::ZERO,
             total_volume: Decimal::ZERO,
-            last_reset: Instant::now(),
+            last_reset: sim_env.clock.now_secs(),
             simulated_balance: initial_balance,
             initial_balance,
+            sim_env,
+            capital: Arc::new(CapitalAllocator::new(config)),
+            committed: HashMap::new(),
         }
     }
 
+    /// Draw on `capital`, shared with the other strategies, instead of a
+    /// private allocator
+    pub fn with_capital(mut self, capital: Arc<CapitalAllocator>) -> Self {
+        self.capital = capital;
+        self
+    }
+
     /// Find trash farming opportunities (cheap contracts to buy for volume)
     pub fn find_opportunities(
         &self,
@@ -103,6 +127,11 @@ impl VolumeFarmer {
             for (i, asset_id) in market.assets_ids.iter().enumerate() {
                 // Get best ask for this asset (cheapest we can buy)
                 if let Some(book) = orderbook_manager.get_book(&market.market, asset_id) {
+                    if book.is_crossed() {
+                        debug!("⏭️  Skipping {}: book is crossed/locked", asset_id);
+                        continue;
+                    }
+
                     // Find asks at or below our max price
                     for (price, size) in book.asks.iter() {
                         if *price <= max_price && *price > Decimal::ZERO {
@@ -141,7 +170,7 @@ impl VolumeFarmer {
         }
 
         // Sort by volume multiplier (highest first = best deals)
-        opportunities.sort_by(|a, b| b.volume_multiplier.cmp(&a.volume_multiplier));
+        opportunities.sort_by_key(|o| std::cmp::Reverse(o.volume_multiplier));
 
         opportunities
     }
@@ -175,11 +204,16 @@ impl VolumeFarmer {
         let actual_cost = opportunity.cost_for_volume.min(remaining_budget);
         let actual_volume = actual_cost * opportunity.volume_multiplier;
 
+        if let RiskDecision::Reject(reason) = self
+            .capital
+            .try_reserve(&Strategy::VolumeFarming, actual_cost)
+        {
+            debug!("Skipping trash trade: {}", reason);
+            return Ok(None);
+        }
+
         // Execute the trade
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+        let timestamp = self.sim_env.clock.now_secs();
 
         let trade = TrashTrade {
             timestamp,
@@ -195,9 +229,19 @@ impl VolumeFarmer {
         self.simulated_balance -= actual_cost;
         self.daily_spend += actual_cost;
         self.total_volume += actual_volume;
+        *self
+            .committed
+            .entry(trade.market_id.clone())
+            .or_insert(Decimal::ZERO) += actual_cost;
         self.trades.push(trade.clone());
 
         info!(
+            event = "trash_trade",
+            market_id = %trade.market_id,
+            asset_id = %trade.asset_id,
+            price = %trade.price,
+            cost = %actual_cost,
+            volume = %actual_volume,
             "🗑️  [SIM] TRASH TRADE: {} @ ${:.4} | Cost: ${:.2} | Volume: ${:.0} ({}x) | Balance: ${:.2}",
             opportunity.outcome_name,
             opportunity.price,
@@ -210,16 +254,47 @@ impl VolumeFarmer {
         Ok(Some(trade))
     }
 
+    /// Release the capital committed to positions in markets that have ended or
+    /// dropped out of `markets` (closed or swept), returning how much was freed
+    pub fn release_resolved(&mut self, markets: &[Market]) -> Decimal {
+        let now = self.sim_env.clock.now_secs();
+        let open = |market_id: &str| {
+            markets.iter().any(|market| {
+                market.market == market_id
+                    && !market.closed
+                    && market
+                        .end_date
+                        .as_deref()
+                        .and_then(gamma_api::parse_end_date)
+                        .is_none_or(|end| end.timestamp() > now)
+            })
+        };
+
+        let mut released = Decimal::ZERO;
+        self.committed.retain(|market_id, cost| {
+            if open(market_id) {
+                return true;
+            }
+            debug!("🗑️  Releasing ${:.2} held in {}", cost, market_id);
+            released += *cost;
+            false
+        });
+        if released > Decimal::ZERO {
+            self.capital.release(&Strategy::VolumeFarming, released);
+        }
+        released
+    }
+
     /// Reset daily budget (call at midnight UTC)
     pub fn reset_daily_budget(&mut self) {
         self.daily_spend = Decimal::ZERO;
-        self.last_reset = Instant::now();
+        self.last_reset = self.sim_env.clock.now_secs();
         info!("🗑️  Daily budget reset");
     }
 
     /// Check if we should reset (24h passed)
     pub fn should_reset_budget(&self) -> bool {
-        self.last_reset.elapsed().as_secs() >= 86400
+        self.sim_env.clock.now_secs() - self.last_reset >= 86400
     }
 
     /// Get current statistics
@@ -244,6 +319,11 @@ impl VolumeFarmer {
         }
     }
 
+    /// Get all trash trades (for persistence/export)
+    pub fn get_trades(&self) -> &[TrashTrade] {
+        &self.trades
+    }
+
     /// Get remaining daily budget
     pub fn remaining_budget(&self) -> Decimal {
         let budget = Decimal::from(self.config.trading.vf_daily_budget);
@@ -1,53 +1,216 @@
-use super::types::{BookSnapshot, WsMessage};
-use crate::arb_engine::ArbEngine;
-use crate::executor::OrderExecutor;
+use super::detection_throttle::DetectionThrottle;
+use super::health::FeedHealth;
+use super::in_flight::InFlightMarkets;
+use super::rate_limit::UpdateRateLimiter;
+use super::rest_fallback::{RestFallback, RestPoller};
+use super::shard::{self, MarketShard, ShardStats};
+use super::types::{
+    BookMessage, BookSnapshot, OrderSummary, PriceChangeMessage, TradeStatus, UserEvent,
+    UserOrderUpdate, UserSide, WsMessage,
+};
+use crate::arb_engine::{self, ArbEngine, ArbType, Opportunity};
+use crate::error::HftpmError;
+use crate::executor::{BreakerPermit, CircuitBreaker, Executor};
 use crate::gamma_api::Market;
 use crate::monitoring::Monitor;
-use crate::orderbook::OrderBookManager;
-use crate::risk::RiskManager;
-use crate::utils::{Config, LatencyTracker, ScopedTimer};
+use crate::orderbook::{BookUpdate, OrderBookManager};
+use crate::parallel_scanner::CrossMarketOpportunity;
+use crate::risk::{RiskDecision, RiskManager, RiskRejectReason};
+use crate::utils::parse::parse_level;
+use crate::utils::{build_http_client, Config, LatencyTracker, ScopedTimer, Strategy, TradingMode};
 
 use anyhow::{Context, Result};
+use futures::stream::SplitStream;
 use futures::{SinkExt, StreamExt};
-use std::collections::HashSet;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
 use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use tracing::{debug, error, info, instrument, warn};
 
-const PING_INTERVAL: Duration = Duration::from_secs(10);
-const RECONNECT_DELAY: Duration = Duration::from_millis(1000);
-const MAX_MESSAGE_SIZE: usize = 5 * 1024 * 1024; // 5MB for initial orderbook snapshots
+pub(super) const PING_INTERVAL: Duration = Duration::from_secs(10);
+pub(super) const RECONNECT_DELAY: Duration = Duration::from_millis(1000);
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const STATS_INTERVAL: Duration = Duration::from_secs(60);
+/// How often the executor's exchange connectivity is checked for `/health`
+const EXECUTOR_HEALTH_INTERVAL: Duration = Duration::from_secs(30);
+/// Read batches (as touched market lists) queued between the shards and detection
+const TOUCHED_QUEUE_CAPACITY: usize = 1024;
+/// Cross-market opportunities queued between the scanner and execution
+pub const CROSS_MARKET_QUEUE_CAPACITY: usize = 64;
+/// Refreshed market sets queued between the strategy loop and the shards
+pub const MARKET_UPDATE_QUEUE_CAPACITY: usize = 4;
+
+type WsReader = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
 
 pub struct WebSocketClient {
     config: Arc<Config>,
     markets: Arc<Vec<Market>>,
+    /// Condition id -> index into `markets`, for resolving book updates to their market
+    market_index: HashMap<String, usize>,
     latency_tracker: LatencyTracker,
     subscribed_markets: HashSet<String>,
-    simulation_executor: Option<Arc<crate::executor::SimulationExecutor>>,
+    /// Live or simulated, chosen once by the caller
+    executor: Arc<dyn Executor>,
+    /// Asset ids subscribed on each market-channel connection
+    shard_asset_ids: Vec<Vec<String>>,
+    /// Where each connection's shard and REST poller pick up its asset ids
+    shard_subscriptions: Vec<watch::Sender<Vec<String>>>,
+    shard_stats: Vec<Arc<Mutex<ShardStats>>>,
+    /// Shared by the shards' oversized-frame refetches and REST fallback polling
+    http_client: reqwest::Client,
+    /// Shard dropped-update total already reported to `Monitor`
+    reported_dropped_updates: u64,
+    detection_throttle: DetectionThrottle,
+    /// Markets with an execution under way, so a repeat detection can't submit twice
+    in_flight: InFlightMarkets,
+    /// Cross-market opportunities found by the scanner, executed here so they
+    /// go through the same checks as everything else
+    cross_market_rx: Option<mpsc::Receiver<CrossMarketOpportunity>>,
+    /// Refreshed market sets, which replace `markets` and resubscribe the shards
+    market_updates_rx: Option<mpsc::Receiver<Vec<Market>>>,
+    /// Halts execution while the exchange keeps failing every order
+    circuit_breaker: Mutex<CircuitBreaker>,
 }
 
 impl WebSocketClient {
-    pub async fn new(config: &Config, markets: &[Market]) -> Result<Self> {
-        let simulation_executor =
-            if config.trading.trading_mode == crate::utils::TradingMode::Simulation {
-                Some(Arc::new(crate::executor::SimulationExecutor::new(config)))
-            } else {
-                None
-            };
+    pub async fn new(
+        config: &Config,
+        markets: &[Market],
+        executor: Arc<dyn Executor>,
+    ) -> Result<Self> {
+        // Fewer markets than connections leaves the extra shards with nothing to read
+        let tracked = &markets[..markets.len().min(config.trading.max_order_books)];
+        let shard_asset_ids: Vec<Vec<String>> =
+            shard::shard_asset_ids(tracked, config.server.ws_connections)
+                .into_iter()
+                .filter(|asset_ids| !asset_ids.is_empty())
+                .collect();
 
         Ok(Self {
             config: Arc::new(config.clone()),
             markets: Arc::new(markets.to_vec()),
+            market_index: index_markets(markets),
             latency_tracker: LatencyTracker::new(),
             subscribed_markets: HashSet::new(),
-            simulation_executor,
+            executor,
+            shard_stats: shard_asset_ids
+                .iter()
+                .map(|_| Arc::new(Mutex::new(ShardStats::default())))
+                .collect(),
+            shard_subscriptions: shard_asset_ids
+                .iter()
+                .map(|asset_ids| watch::channel(asset_ids.clone()).0)
+                .collect(),
+            shard_asset_ids,
+            http_client: build_http_client(&config.execution)?,
+            reported_dropped_updates: 0,
+            detection_throttle: DetectionThrottle::new(Duration::from_millis(
+                config.latency.detection_throttle_ms,
+            )),
+            in_flight: InFlightMarkets::new(),
+            cross_market_rx: None,
+            market_updates_rx: None,
+            circuit_breaker: Mutex::new(CircuitBreaker::new(
+                config.execution.circuit_breaker_failures,
+                Duration::from_secs(config.execution.circuit_breaker_window_secs),
+                Duration::from_secs(config.execution.circuit_breaker_cooldown_secs),
+            )),
         })
     }
 
+    /// Execute the cross-market opportunities sent on `cross_market_rx`
+    pub fn with_cross_market_feed(
+        mut self,
+        cross_market_rx: mpsc::Receiver<CrossMarketOpportunity>,
+    ) -> Self {
+        self.cross_market_rx = Some(cross_market_rx);
+        self
+    }
+
+    /// Track the market sets sent on `market_updates_rx` (see `update_markets`)
+    pub fn with_market_updates(mut self, market_updates_rx: mpsc::Receiver<Vec<Market>>) -> Self {
+        self.market_updates_rx = Some(market_updates_rx);
+        self
+    }
+
+    /// Replace the tracked markets with `markets`, so detection resolves their
+    /// books, and redeal their asset ids over the connections opened at startup.
+    /// A connection whose share changed reconnects with its new subscription;
+    /// the others keep reading. The user channel picks the set up when it next
+    /// connects.
+    pub fn update_markets(&mut self, markets: &[Market]) {
+        let tracked = &markets[..markets.len().min(self.config.trading.max_order_books)];
+        self.markets = Arc::new(markets.to_vec());
+        self.market_index = index_markets(markets);
+        self.subscribed_markets = tracked.iter().map(|m| m.id.clone()).collect();
+
+        // No connection was opened for an empty startup set, so there's nothing to redeal
+        if self.shard_subscriptions.is_empty() {
+            warn!(
+                "No market-channel connections to subscribe {} refreshed markets on",
+                tracked.len()
+            );
+            return;
+        }
+
+        self.shard_asset_ids = shard::shard_asset_ids(tracked, self.shard_subscriptions.len());
+        let resubscribed = self
+            .shard_asset_ids
+            .iter()
+            .zip(&self.shard_subscriptions)
+            .filter(|(asset_ids, subscription)| {
+                subscription.send_if_modified(|current| {
+                    let changed = current != *asset_ids;
+                    if changed {
+                        current.clone_from(asset_ids);
+                    }
+                    changed
+                })
+            })
+            .count();
+        info!(
+            "📡 Tracking {} markets, {} of {} connection(s) resubscribing",
+            tracked.len(),
+            resubscribed,
+            self.shard_subscriptions.len()
+        );
+    }
+
+    /// Asset ids subscribed on each market-channel connection
+    pub fn shard_asset_ids(&self) -> &[Vec<String>] {
+        &self.shard_asset_ids
+    }
+
+    /// Reconnects, message rate and detected gaps of the market channel, across
+    /// all connections
+    pub fn feed_health(&self) -> FeedHealth {
+        let stats: Vec<_> = self.shard_stats.iter().map(|stats| stats.lock()).collect();
+        FeedHealth::combined(stats.iter().map(|stats| &stats.health))
+    }
+
+    /// Market-channel messages skipped for having an unhandled `event_type`
+    pub fn unknown_messages(&self) -> u64 {
+        self.shard_stats
+            .iter()
+            .map(|stats| stats.lock().unknown_messages)
+            .sum()
+    }
+
+    /// Market-channel frames over `ws_max_message_bytes`
+    pub fn oversized_messages(&self) -> u64 {
+        self.shard_stats
+            .iter()
+            .map(|stats| stats.lock().oversized_messages)
+            .sum()
+    }
+
     pub async fn subscribe_all_markets(&mut self) -> Result<()> {
-        // Just mark markets as needing subscription - actual subscription happens in connect_and_run
+        // Just mark markets as needing subscription - actual subscription happens per shard
         for market in self
             .markets
             .iter()
@@ -62,53 +225,191 @@ impl WebSocketClient {
         Ok(())
     }
 
-    fn build_subscription_message(&self) -> String {
-        let asset_ids: Vec<String> = self
+    /// Authenticated `user` channel subscription for our own orders and trades
+    fn build_user_subscription_message(&self) -> String {
+        let markets: Vec<&str> = self
             .markets
             .iter()
             .take(self.config.trading.max_order_books)
-            .flat_map(|m| m.assets_ids.clone())
+            .map(|m| m.market.as_str())
             .collect();
+        let credentials = &self.config.credentials;
 
         serde_json::json!({
-            "assets_ids": asset_ids,
-            "type": "market"
+            "auth": {
+                "apiKey": credentials.api_key,
+                "secret": credentials.api_secret,
+                "passphrase": credentials.api_passphrase,
+            },
+            "markets": markets,
+            "type": "user"
         })
         .to_string()
     }
 
-    #[instrument(skip(self, orderbook_manager, arb_engine, risk_manager, executor, monitor))]
+    /// Connect and subscribe to the user channel, keeping it alive with pings
+    async fn connect_user_channel(&self) -> Result<WsReader> {
+        let url = &self.config.server.user_wss_url;
+        let (ws_stream, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(|e| match e {
+                // A refused handshake here means the API credentials were rejected
+                tokio_tungstenite::tungstenite::Error::Http(response) => {
+                    HftpmError::from_status(response.status(), "User channel handshake")
+                }
+                e => HftpmError::Network(format!("Failed to connect to user channel: {}", e)),
+            })?;
+
+        let (mut write, read) = ws_stream.split();
+        write
+            .send(Message::Text(self.build_user_subscription_message()))
+            .await
+            .context("Failed to send user channel subscription")?;
+        info!("✅ User channel connected to {}", url);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PING_INTERVAL);
+            loop {
+                interval.tick().await;
+                if write.send(Message::Ping(vec![])).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(read)
+    }
+
+    /// Next user-channel frame; never resolves when the channel is off
+    async fn next_user_message(
+        user_read: &mut Option<WsReader>,
+    ) -> Option<Result<Message, tokio_tungstenite::tungstenite::Error>> {
+        match user_read {
+            Some(read) => read.next().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Next cross-market opportunity from the scanner; never resolves without a feed
+    async fn next_cross_market(
+        cross_market_rx: &mut Option<mpsc::Receiver<CrossMarketOpportunity>>,
+    ) -> Option<CrossMarketOpportunity> {
+        match cross_market_rx {
+            Some(rx) => rx.recv().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Next refreshed market set; never resolves without a feed
+    async fn next_market_update(
+        market_updates_rx: &mut Option<mpsc::Receiver<Vec<Market>>>,
+    ) -> Option<Vec<Market>> {
+        match market_updates_rx {
+            Some(rx) => rx.recv().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Start one read task per market-channel connection. Each reconnects on its
+    /// own, and with a new subscription when `update_markets` changes its share,
+    /// and stops once `touched_tx`'s receiver is dropped. With the REST
+    /// fallback on, each also gets a poller that serves its books while it is down.
+    fn spawn_shards(
+        &self,
+        orderbook_manager: &Arc<OrderBookManager>,
+        touched_tx: mpsc::Sender<Vec<String>>,
+    ) {
+        // The update budget is split evenly, so the total stays as configured
+        let updates_per_shard =
+            self.config.latency.max_orderbook_updates_per_sec / self.shard_asset_ids.len().max(1);
+
+        for (id, (subscription, stats)) in self
+            .shard_subscriptions
+            .iter()
+            .zip(&self.shard_stats)
+            .enumerate()
+        {
+            let shard = MarketShard {
+                id,
+                url: self.config.server.wss_url.clone(),
+                rest_url: self.config.server.rest_url.clone(),
+                http_client: self.http_client.clone(),
+                max_message_bytes: self.config.server.ws_max_message_bytes,
+                asset_ids: subscription.borrow().clone(),
+                subscription: subscription.subscribe(),
+                orderbook_manager: orderbook_manager.clone(),
+                rate_limiter: UpdateRateLimiter::new(updates_per_shard),
+                stats: stats.clone(),
+                touched_tx: touched_tx.clone(),
+                refetch: Arc::default(),
+            };
+            tokio::spawn(shard.run());
+
+            let server = &self.config.server;
+            if server.rest_fallback_enabled {
+                let poller = RestPoller {
+                    shard_id: id,
+                    base_url: server.rest_url.clone(),
+                    client: self.http_client.clone(),
+                    subscription: subscription.subscribe(),
+                    orderbook_manager: orderbook_manager.clone(),
+                    stats: stats.clone(),
+                    touched_tx: touched_tx.clone(),
+                    fallback: RestFallback::new(Duration::from_secs(
+                        server.rest_fallback_after_secs,
+                    )),
+                    poll_interval: Duration::from_millis(server.rest_fallback_poll_ms.max(1)),
+                };
+                tokio::spawn(poller.run());
+            }
+        }
+    }
+
+    #[instrument(skip(self, orderbook_manager, arb_engine, risk_manager, monitor))]
     pub async fn run(
         &mut self,
-        orderbook_manager: &OrderBookManager,
+        orderbook_manager: &Arc<OrderBookManager>,
         arb_engine: &mut ArbEngine,
-        risk_manager: &mut RiskManager,
-        executor: &OrderExecutor,
+        risk_manager: &RiskManager,
         monitor: &mut Monitor,
     ) -> Result<()> {
         info!(
-            "🚀 Starting WebSocket connection to {}",
+            "🚀 Starting {} WebSocket connection(s) to {}",
+            self.shard_asset_ids.len(),
             self.config.server.wss_url
         );
 
+        let (touched_tx, mut touched_rx) = mpsc::channel(TOUCHED_QUEUE_CAPACITY);
+        self.spawn_shards(orderbook_manager, touched_tx);
+
         loop {
             match self
-                .connect_and_run(
+                .process_updates(
+                    &mut touched_rx,
                     orderbook_manager,
                     arb_engine,
                     risk_manager,
-                    executor,
                     monitor,
                 )
                 .await
             {
                 Ok(_) => {
-                    warn!("WebSocket closed unexpectedly, reconnecting...");
+                    warn!("Update loop stopped unexpectedly, restarting...");
                     tokio::time::sleep(RECONNECT_DELAY).await;
                 }
+                Err(e) if is_fatal(&e) => {
+                    error!("WebSocket loop stopping on fatal error: {:?}", e);
+                    return Err(e);
+                }
+                // Every shard has exited, so a restart would only fail the same way
+                Err(e) if touched_rx.is_closed() => {
+                    error!("WebSocket loop stopping, market feed is gone: {:?}", e);
+                    return Err(e);
+                }
+                // The shards reconnect on their own; only detection is restarted here
                 Err(e) => {
                     error!(
-                        "WebSocket error: {:?}, reconnecting in {:?}...",
+                        "Update loop error: {:?}, restarting in {:?}...",
                         e, RECONNECT_DELAY
                     );
                     tokio::time::sleep(RECONNECT_DELAY).await;
@@ -117,234 +418,363 @@ impl WebSocketClient {
         }
     }
 
-    async fn connect_and_run(
+    /// Detect on the markets the shards report, alongside the user channel and
+    /// the feed health check. Only returns on an error.
+    async fn process_updates(
         &mut self,
+        touched_rx: &mut mpsc::Receiver<Vec<String>>,
         orderbook_manager: &OrderBookManager,
         arb_engine: &mut ArbEngine,
-        risk_manager: &mut RiskManager,
-        executor: &OrderExecutor,
+        risk_manager: &RiskManager,
         monitor: &mut Monitor,
     ) -> Result<()> {
-        let url = &self.config.server.wss_url;
-        let (ws_stream, _) = tokio_tungstenite::connect_async(url)
-            .await
-            .context("Failed to connect to WebSocket")?;
+        self.refresh_available_balance(arb_engine).await;
 
-        info!("✅ WebSocket connected to {}", url);
-
-        let (mut write, mut read) = ws_stream.split();
-
-        // Send subscription message immediately after connecting
-        let subscribe_msg = self.build_subscription_message();
-        info!(
-            "📡 Sending subscription for {} asset IDs...",
-            self.markets
-                .iter()
-                .take(self.config.trading.max_order_books)
-                .flat_map(|m| m.assets_ids.clone())
-                .count()
-        );
-        write
-            .send(Message::Text(subscribe_msg))
-            .await
-            .context("Failed to send subscription message")?;
-        info!("✅ Subscription message sent");
+        // Our fills and cancellations, read alongside book data. A dropped or
+        // failed connection is retried without holding up detection.
+        let user_channel_enabled = self.config.user_channel_enabled();
+        let mut user_read = None;
+        let mut user_reconnect_interval = tokio::time::interval(RECONNECT_DELAY);
 
-        // Channel for sending messages to the write half
-        let (tx, mut rx) = mpsc::channel::<Message>(100);
+        let mut last_stats = Instant::now();
+        let mut health_interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+        let mut executor_health_interval = tokio::time::interval(EXECUTOR_HEALTH_INTERVAL);
+        // Simulated balances can't drift from what we expect, so only reconcile live
+        let reconcile_balance = self.config.trading.trading_mode != TradingMode::Simulation;
+        let mut balance_interval = tokio::time::interval(Duration::from_secs(
+            self.config.execution.balance_reconcile_interval_secs.max(1),
+        ));
+        // Trailing detection for markets whose last updates the throttle skipped
+        let throttled = self.detection_throttle.interval();
+        let mut trailing_interval =
+            tokio::time::interval(throttled.unwrap_or(HEALTH_CHECK_INTERVAL));
+        trailing_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
-        // Clone tx for ping task
-        let ping_tx = tx.clone();
+        loop {
+            let touched = tokio::select! {
+                touched = touched_rx.recv() => match touched {
+                    Some(touched) => touched,
+                    None => anyhow::bail!("All market feed connections stopped"),
+                },
+                user_message = Self::next_user_message(&mut user_read), if user_read.is_some() => {
+                    match user_message {
+                        Some(Ok(Message::Text(text))) => {
+                            self.handle_user_message(&text, risk_manager, monitor).await;
+                        }
+                        Some(Ok(Message::Close(frame))) => {
+                            warn!("User channel closed: {:?}, reconnecting...", frame);
+                            user_read = None;
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            error!("User channel read failed: {:?}, reconnecting...", e);
+                            user_read = None;
+                        }
+                        None => {
+                            warn!("User channel ended, reconnecting...");
+                            user_read = None;
+                        }
+                    }
+                    continue;
+                }
+                _ = user_reconnect_interval.tick(), if user_channel_enabled && user_read.is_none() => {
+                    match self.connect_user_channel().await {
+                        Ok(read) => user_read = Some(read),
+                        Err(e) if is_fatal(&e) => return Err(e),
+                        Err(e) => error!(
+                            "User channel connection failed: {:?}, retrying in {:?}...",
+                            e, RECONNECT_DELAY
+                        ),
+                    }
+                    continue;
+                }
+                // Runs even when the feed is silent, which is when it matters
+                _ = health_interval.tick() => {
+                    self.settle_fills(orderbook_manager, risk_manager, monitor).await;
+                    self.check_feed_health(monitor).await;
+                    monitor.record_stale_markets(
+                        orderbook_manager.stale_markets(self.config.monitoring.stale_feed_age_ms),
+                    );
+                    monitor
+                        .refresh_simulation_metrics(self.executor.as_ref())
+                        .await;
+                    arb_engine.prune_idle_markets(Instant::now());
+                    if last_stats.elapsed() >= STATS_INTERVAL {
+                        self.log_stats();
+                        last_stats = Instant::now();
+                    }
+                    continue;
+                }
+                opportunity = Self::next_cross_market(&mut self.cross_market_rx) => {
+                    match opportunity {
+                        Some(opportunity) => {
+                            monitor.record_arbitrage_detected(&opportunity).await;
+                            self.execute_arbitrage(
+                                &opportunity,
+                                orderbook_manager,
+                                risk_manager,
+                                monitor,
+                            )
+                            .await?;
+                        }
+                        None => {
+                            debug!("Cross-market feed closed");
+                            self.cross_market_rx = None;
+                        }
+                    }
+                    continue;
+                }
+                markets = Self::next_market_update(&mut self.market_updates_rx) => {
+                    match markets {
+                        Some(markets) => self.update_markets(&markets),
+                        None => {
+                            debug!("Market update feed closed");
+                            self.market_updates_rx = None;
+                        }
+                    }
+                    continue;
+                }
+                _ = executor_health_interval.tick() => {
+                    let healthy = self.executor.health_check().await.unwrap_or(false);
+                    monitor.record_executor_health(healthy);
+                    continue;
+                }
+                _ = balance_interval.tick(), if reconcile_balance => {
+                    self.reconcile_balance(arb_engine, risk_manager, monitor).await;
+                    continue;
+                }
+                _ = trailing_interval.tick(), if throttled.is_some() => {
+                    for market_id in self.detection_throttle.due() {
+                        self.detect_and_execute(
+                            &market_id,
+                            orderbook_manager,
+                            arb_engine,
+                            risk_manager,
+                            monitor,
+                        )
+                        .await?;
+                    }
+                    continue;
+                }
+            };
 
-        // Spawn ping task
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(PING_INTERVAL);
-            loop {
-                interval.tick().await;
-                if ping_tx.send(Message::Ping(vec![])).await.is_err() {
-                    break;
+            // Fold in whatever else the shards queued meanwhile, so a market touched
+            // by several batches is detected once
+            let mut seen: HashSet<String> = touched.iter().cloned().collect();
+            let mut pending = touched;
+            while let Ok(more) = touched_rx.try_recv() {
+                for market_id in more {
+                    if seen.insert(market_id.clone()) {
+                        pending.push(market_id);
+                    }
                 }
             }
-        });
 
-        // Spawn write task
-        tokio::spawn(async move {
-            while let Some(msg) = rx.recv().await {
-                if let Err(e) = write.send(msg).await {
-                    error!("Failed to send WebSocket message: {:?}", e);
-                    break;
+            // Simulated arbs placed earlier fill against the books as they are now
+            self.settle_fills(orderbook_manager, risk_manager, monitor)
+                .await;
+
+            for market_id in &pending {
+                let best_asks = orderbook_manager
+                    .get_best_asks_for_market(market_id)
+                    .unwrap_or_default();
+                if !self.detection_throttle.should_detect(market_id, &best_asks) {
+                    continue;
                 }
-            }
-        });
 
-        let mut start_time = Instant::now();
-        let mut message_count = 0u64;
-        let mut last_stats = Instant::now();
+                self.detect_and_execute(
+                    market_id,
+                    orderbook_manager,
+                    arb_engine,
+                    risk_manager,
+                    monitor,
+                )
+                .await?;
+            }
+        }
+    }
 
-        while let Some(message) = read.next().await {
-            let message = message.context("Failed to read WebSocket message")?;
+    /// Publish the combined health of the shards to `Monitor`
+    async fn check_feed_health(&mut self, monitor: &Monitor) {
+        let now = Instant::now();
+        let (health, connected, dropped_updates) = {
+            let mut stats: Vec<_> = self.shard_stats.iter().map(|stats| stats.lock()).collect();
+            for shard in stats.iter_mut() {
+                shard.health.roll_window(now);
+            }
+            (
+                FeedHealth::combined(stats.iter().map(|shard| &shard.health)),
+                stats.iter().all(|shard| shard.connected),
+                stats.iter().map(|shard| shard.dropped_updates).sum::<u64>(),
+            )
+        };
 
-            match message {
-                Message::Text(text) => {
-                    let _timer = ScopedTimer::new("ws_message_processing", None);
+        monitor.set_websocket_connected(connected).await;
+        monitor.record_feed_health(&health, now).await;
 
-                    let text_bytes = text.as_bytes();
+        let dropped = dropped_updates - self.reported_dropped_updates;
+        if dropped > 0 {
+            monitor.record_dropped_updates(dropped).await;
+            self.reported_dropped_updates = dropped_updates;
+        }
+    }
 
-                    if text_bytes.len() > MAX_MESSAGE_SIZE {
-                        warn!("Message too large: {} bytes", text_bytes.len());
-                        continue;
-                    }
+    fn log_stats(&self) {
+        let health = self.feed_health();
+        let max_rate = self.config.latency.max_orderbook_updates_per_sec as f64;
+        if health.messages_per_sec() > max_rate {
+            warn!(
+                "⚠️  High message rate: {:.2} msg/s (limit: {})",
+                health.messages_per_sec(),
+                max_rate
+            );
+        }
 
-                    // Try to parse as array first, then fall back to single message
-                    let messages: Vec<WsMessage> = if text.trim_start().starts_with('[') {
-                        match serde_json::from_str::<Vec<WsMessage>>(&text) {
-                            Ok(msgs) => msgs,
-                            Err(e) => {
-                                warn!(
-                                    "Failed to parse message array: {} | Sample: {}",
-                                    e,
-                                    &text[..text.len().min(300)]
-                                );
-                                continue;
-                            }
-                        }
-                    } else {
-                        match serde_json::from_str::<WsMessage>(&text) {
-                            Ok(msg) => vec![msg],
-                            Err(e) => {
-                                warn!(
-                                    "Failed to parse message: {} | Sample: {}",
-                                    e,
-                                    &text[..text.len().min(300)]
-                                );
-                                continue;
-                            }
-                        }
-                    };
+        let shard_rates: Vec<String> = self
+            .shard_stats
+            .iter()
+            .map(|stats| format!("{:.2}", stats.lock().health.messages_per_sec()))
+            .collect();
+        info!(
+            "📊 WebSocket stats: {:.2} msg/s (per connection: [{}]), avg latency: {:.2}ms, dropped updates: {}, unknown messages: {}, oversized messages: {}, throttled detections: {}",
+            health.messages_per_sec(),
+            shard_rates.join(", "),
+            self.latency_tracker.avg_latency_ms(),
+            self.reported_dropped_updates,
+            self.unknown_messages(),
+            self.oversized_messages(),
+            self.detection_throttle.skipped()
+        );
+    }
 
-                    for ws_msg in messages {
-                        message_count += 1;
+    /// Parse a user-channel frame and apply each event
+    async fn handle_user_message(&self, text: &str, risk_manager: &RiskManager, monitor: &Monitor) {
+        match UserEvent::parse_batch(text) {
+            Ok(events) => {
+                for event in &events {
+                    self.apply_user_event(event, risk_manager, monitor).await;
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to parse user channel message: {} | Sample: {}",
+                    e,
+                    &text[..text.len().min(300)]
+                );
+            }
+        }
+    }
 
-                        if ws_msg.is_book_snapshot() {
-                            debug!("📖 Book snapshot for market: {}", ws_msg.market);
-                            self.handle_book_snapshot(
-                                &ws_msg,
-                                orderbook_manager,
-                                arb_engine,
-                                risk_manager,
-                                executor,
-                                monitor,
-                            )
-                            .await?;
-                        } else if ws_msg.is_price_change() {
-                            debug!("💹 Price change for market: {}", ws_msg.market);
-                            self.handle_price_change(
-                                &ws_msg,
-                                orderbook_manager,
-                                arb_engine,
-                                risk_manager,
-                                executor,
-                                monitor,
-                            )
-                            .await?;
-                        } else {
-                            debug!("❓ Unknown message type for market: {}", ws_msg.market);
-                        }
+    /// Matched trades become fills in `RiskManager` and `Monitor`; cancelled buys
+    /// release the exposure reserved for their unfilled remainder
+    async fn apply_user_event(
+        &self,
+        event: &UserEvent,
+        risk_manager: &RiskManager,
+        monitor: &Monitor,
+    ) {
+        match event {
+            // A trade is re-sent as it settles; count it once, when matched
+            UserEvent::Trade(trade) => match trade.status {
+                TradeStatus::Matched => {
+                    for fill in trade.fills_for(&self.config.credentials.api_key) {
+                        risk_manager.record_fill(&fill);
+                        monitor.record_user_fill(&fill).await;
+                    }
+                }
+                TradeStatus::Failed => {
+                    monitor
+                        .alert_error(&format!(
+                            "Trade {} on {} failed to settle",
+                            trade.id, trade.market
+                        ))
+                        .await;
+                }
+                status => debug!("Trade {} on {}: {:?}", trade.id, trade.market, status),
+            },
+            UserEvent::Order(order) => match order.update {
+                UserOrderUpdate::Cancellation => {
+                    let unfilled = order.original_size - order.size_matched;
+                    if order.side == UserSide::Buy && unfilled > rust_decimal::Decimal::ZERO {
+                        risk_manager.release_exposure(&order.market, unfilled);
+                        risk_manager
+                            .capital()
+                            .release(&crate::utils::Strategy::Arbitrage, order.price * unfilled);
                     }
+                    risk_manager.record_order_closed(&order.id);
+                    info!(
+                        "🗑️  Order {} cancelled ({} of {} filled)",
+                        order.id, order.size_matched, order.original_size
+                    );
+                }
+                update => debug!(
+                    "Order {} {:?}: {} of {} filled",
+                    order.id, update, order.size_matched, order.original_size
+                ),
+            },
+        }
+    }
 
-                    let elapsed = start_time.elapsed();
-                    if elapsed.as_secs() >= 1 {
-                        let msgs_per_sec = message_count as f64 / elapsed.as_secs_f64();
-                        if msgs_per_sec > self.config.latency.max_orderbook_updates_per_sec as f64 {
-                            warn!(
-                                "⚠️  High message rate: {:.2} msg/s (limit: {})",
-                                msgs_per_sec, self.config.latency.max_orderbook_updates_per_sec
-                            );
-                        }
+    /// Apply every message in a read batch to the order books, returning the
+    /// markets that were touched (deduplicated, in first-seen order).
+    ///
+    /// Detection runs once per touched market after the whole batch is applied,
+    /// rather than once per level change.
+    pub fn apply_message_batch(
+        messages: &[WsMessage],
+        orderbook_manager: &OrderBookManager,
+    ) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut touched = Vec::new();
 
-                        if last_stats.elapsed().as_secs() >= 60 {
-                            info!(
-                                "📊 WebSocket stats: {:.2} msg/s, avg latency: {:.2}ms",
-                                msgs_per_sec,
-                                self.latency_tracker.avg_latency_ms()
-                            );
-                            last_stats = Instant::now();
-                            message_count = 0;
-                            start_time = Instant::now();
-                        }
-                    }
+        for ws_msg in messages {
+            let applied = match ws_msg {
+                WsMessage::Book(book) => {
+                    debug!("📖 Book snapshot for market: {}", book.market);
+                    Self::apply_book_snapshot(book, orderbook_manager)
                 }
-                Message::Ping(data) => {
-                    let _ = tx.send(Message::Pong(data)).await;
+                WsMessage::PriceChange(change) => {
+                    debug!("💹 Price change for market: {}", change.market);
+                    Self::apply_price_change(change, orderbook_manager)
                 }
-                Message::Pong(_) => {
-                    debug!("Received pong");
+                WsMessage::TickSizeChange(change) => {
+                    info!(
+                        "📏 Tick size for {} changed: {} -> {}",
+                        change.market, change.old_tick_size, change.new_tick_size
+                    );
+                    orderbook_manager.set_tick_size(&change.market, change.new_tick_size);
+                    false
                 }
-                Message::Close(frame) => {
-                    info!("WebSocket closed: {:?}", frame);
-                    return Ok(());
+                WsMessage::LastTradePrice(trade) => {
+                    debug!(
+                        "🔁 Trade on {}: {} {} @ {}",
+                        trade.asset_id, trade.side, trade.size, trade.price
+                    );
+                    false
+                }
+                WsMessage::Unknown => false,
+            };
+
+            if let Some(market) = ws_msg.market().filter(|_| applied) {
+                if seen.insert(market) {
+                    touched.push(market.to_string());
                 }
-                _ => {}
             }
         }
 
-        Ok(())
+        touched
     }
 
     #[inline]
-    #[instrument(skip(self, orderbook_manager, arb_engine, risk_manager, executor, monitor))]
-    async fn handle_book_snapshot(
-        &self,
-        ws_msg: &WsMessage,
-        orderbook_manager: &OrderBookManager,
-        arb_engine: &mut ArbEngine,
-        risk_manager: &mut RiskManager,
-        executor: &OrderExecutor,
-        monitor: &mut Monitor,
-    ) -> Result<()> {
+    fn apply_book_snapshot(ws_msg: &BookMessage, orderbook_manager: &OrderBookManager) -> bool {
         let _timer = ScopedTimer::new("book_snapshot", None);
 
         let market_id = ws_msg.market.clone();
         let asset_id = ws_msg.asset_id.clone();
         let timestamp = ws_msg.parse_timestamp();
 
-        let bids = ws_msg
-            .bids
-            .as_ref()
-            .map(|bids| {
-                bids.iter()
-                    .filter_map(|o| {
-                        o.price.parse::<rust_decimal::Decimal>().ok().map(|p| {
-                            (
-                                p,
-                                o.size
-                                    .parse::<rust_decimal::Decimal>()
-                                    .unwrap_or(rust_decimal::Decimal::ZERO),
-                            )
-                        })
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        let asks = ws_msg
-            .asks
-            .as_ref()
-            .map(|asks| {
-                asks.iter()
-                    .filter_map(|o| {
-                        o.price.parse::<rust_decimal::Decimal>().ok().map(|p| {
-                            (
-                                p,
-                                o.size
-                                    .parse::<rust_decimal::Decimal>()
-                                    .unwrap_or(rust_decimal::Decimal::ZERO),
-                            )
-                        })
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
+        let bids = OrderSummary::parse_levels(&ws_msg.bids);
+        let asks = OrderSummary::parse_levels(&ws_msg.asks);
 
         let book = BookSnapshot {
             asset_id: asset_id.clone(),
@@ -355,96 +785,92 @@ impl WebSocketClient {
             hash: ws_msg.hash.clone().unwrap_or_default(),
         };
 
-        // Try to update book, skip if market not found
+        // Try to update book, skip if market not found. Redelivered and
+        // out-of-order snapshots leave the book as it was, so nothing to detect
         match orderbook_manager.update_book(&market_id, &asset_id, &book) {
-            Ok(_) => {}
+            Ok(update) => update == BookUpdate::Applied,
             Err(e) => {
                 debug!(
                     "⏭️  Skipping book update for unknown market {}: {}",
                     market_id, e
                 );
-                return Ok(());
+                false
             }
         }
+    }
 
-        // Only detect arbitrage if market exists (avoid crash on stale/skipped books)
-        match arb_engine.detect_arbitrage(orderbook_manager, &market_id, risk_manager) {
-            Ok(Some(arb_op)) => {
-                monitor.record_arbitrage_detected(&arb_op).await;
+    #[inline]
+    fn apply_price_change(
+        ws_msg: &PriceChangeMessage,
+        orderbook_manager: &OrderBookManager,
+    ) -> bool {
+        let _timer = ScopedTimer::new("price_change", None);
 
-                // Check quality threshold before executing
-                if arb_engine.should_execute_opportunity(&arb_op) {
-                    self.execute_arbitrage(&arb_op, risk_manager, executor, monitor)
-                        .await?;
-                } else {
-                    debug!("⏭️  Skipping low-quality arbitrage");
+        let market_id = &ws_msg.market;
+        let mut applied = false;
+
+        for change in &ws_msg.price_changes {
+            let (price, size) = match parse_level(&change.price, &change.size) {
+                Ok(level) => level,
+                Err(e) => {
+                    debug!("⏭️  Skipping price change on {}: {:#}", market_id, e);
+                    continue;
+                }
+            };
+
+            // Try to update price, skip if market not found
+            match orderbook_manager.update_price(
+                market_id,
+                &change.asset_id,
+                price,
+                size,
+                change.side.as_str(),
+            ) {
+                Ok(_) => applied = true,
+                Err(e) => {
+                    debug!(
+                        "⏭️  Skipping price update for unknown market {}: {}",
+                        market_id, e
+                    );
                 }
-            }
-            Ok(None) => {}
-            Err(e) => {
-                debug!("⏭️  Skipping arbitrage detection for {}: {}", market_id, e);
             }
         }
 
-        Ok(())
+        applied
     }
 
+    /// Detect on one market and, if the arb clears the quality bar and risk
+    /// checks, send it to the executor
     #[inline]
-    #[instrument(skip(self, orderbook_manager, arb_engine, risk_manager, executor, monitor))]
-    async fn handle_price_change(
+    #[instrument(skip(self, orderbook_manager, arb_engine, risk_manager, monitor))]
+    pub async fn detect_and_execute(
         &self,
-        ws_msg: &WsMessage,
+        market_id: &str,
         orderbook_manager: &OrderBookManager,
         arb_engine: &mut ArbEngine,
-        risk_manager: &mut RiskManager,
-        executor: &OrderExecutor,
+        risk_manager: &RiskManager,
         monitor: &mut Monitor,
     ) -> Result<()> {
-        let _timer = ScopedTimer::new("price_change", None);
+        // The loop is alive even when the schedule keeps it from trading
+        monitor.record_detection_pass();
 
-        let market_id = ws_msg.market.clone();
-
-        if let Some(price_changes) = &ws_msg.price_changes {
-            for change in price_changes {
-                let price = change
-                    .price
-                    .parse::<rust_decimal::Decimal>()
-                    .context("Failed to parse price")?;
-
-                let size = change
-                    .size
-                    .parse::<rust_decimal::Decimal>()
-                    .context("Failed to parse size")?;
-
-                // Try to update price, skip if market not found
-                match orderbook_manager.update_price(
-                    &market_id,
-                    &change.asset_id,
-                    price,
-                    size,
-                    change.side.as_str(),
-                ) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        debug!(
-                            "⏭️  Skipping price update for unknown market {}: {}",
-                            market_id, e
-                        );
-                        continue;
-                    }
-                }
-            }
+        // Outside the arbitrage window books stay current, but nothing is traded
+        if !self.config.schedule.is_active_now(&Strategy::Arbitrage) {
+            return Ok(());
         }
 
         // Only detect arbitrage if market exists (avoid crash on stale/skipped books)
-        match arb_engine.detect_arbitrage(orderbook_manager, &market_id, risk_manager) {
+        let market = self.market_index.get(market_id).map(|&i| &self.markets[i]);
+
+        match arb_engine.detect_arbitrage(orderbook_manager, market_id, market, risk_manager) {
             Ok(Some(arb_op)) => {
                 monitor.record_arbitrage_detected(&arb_op).await;
 
                 // Check quality threshold before executing
                 if arb_engine.should_execute_opportunity(&arb_op) {
-                    self.execute_arbitrage(&arb_op, risk_manager, executor, monitor)
+                    self.execute_arbitrage(&arb_op, orderbook_manager, risk_manager, monitor)
                         .await?;
+                    self.refresh_available_balance(arb_engine).await;
                 } else {
                     debug!("⏭️  Skipping low-quality arbitrage");
                 }
@@ -455,42 +881,182 @@ impl WebSocketClient {
             }
         }
 
+        // Directional, so only kept for a strategy to pick up via
+        // `mean_reversion_signals`; never executed here
+        arb_engine.detect_mean_reversion(orderbook_manager, market_id);
+
         Ok(())
     }
 
+    /// Hand the executor's balance (simulated, or the exchange's in live mode)
+    /// to the arb engine's sizing
+    async fn refresh_available_balance(&self, arb_engine: &mut ArbEngine) {
+        match self.executor.available_balance().await {
+            Ok(balance) => arb_engine.set_available_balance(balance),
+            Err(e) => warn!("Failed to refresh available balance: {:?}", e),
+        }
+    }
+
+    /// Re-read the exchange balance so sizing tracks transfers and fills we didn't
+    /// see, and alert if it has fallen short of the capital risk thinks is free
+    async fn reconcile_balance(
+        &self,
+        arb_engine: &mut ArbEngine,
+        risk_manager: &RiskManager,
+        monitor: &Monitor,
+    ) {
+        match self.executor.available_balance().await {
+            Ok(balance) => {
+                arb_engine.set_available_balance(balance);
+                monitor
+                    .check_balance_deviation(balance, risk_manager.available_bankroll())
+                    .await;
+            }
+            Err(e) => warn!("Failed to reconcile exchange balance: {:?}", e),
+        }
+    }
+
+    /// Run `arb_op` through the in-flight, risk, fill and age checks and, if it
+    /// passes them all, send it to the executor and record the outcome
     #[inline]
-    async fn execute_arbitrage(
+    pub async fn execute_arbitrage(
         &self,
-        arb_op: &crate::arb_engine::ArbitrageOpportunity,
-        risk_manager: &mut RiskManager,
-        executor: &OrderExecutor,
+        arb_op: &dyn crate::arb_engine::Opportunity,
+        orderbook_manager: &OrderBookManager,
+        risk_manager: &RiskManager,
         monitor: &mut Monitor,
     ) -> Result<()> {
         let _timer = ScopedTimer::new("arb_execution", None);
 
-        if !risk_manager.can_execute_arbitrage(arb_op)? {
-            debug!("⚠️  Risk manager rejected arbitrage: {:?}", arb_op);
+        // An echo of an opportunity already being executed: exposure doesn't
+        // reflect the first execution yet, so risk checks would let it through
+        let Some(claim) = self.in_flight.try_claim(arb_op.market_id()) else {
+            debug!(
+                "⏭️  Execution already in flight for {}, skipping duplicate",
+                arb_op.market_id()
+            );
+            return Ok(());
+        };
+
+        if let RiskDecision::Reject(reason) = risk_manager.can_execute_arbitrage(arb_op)? {
+            debug!("⚠️  Risk manager rejected arbitrage ({}): {:?}", reason, arb_op);
+            monitor
+                .alert_risk_limit_breached(arb_op.market_id(), reason)
+                .await;
+            return Ok(());
+        }
+
+        // Each outcome is its own order: unless the books still cover all of
+        // them, part of the set fills and the rest is left unhedged
+        if self.config.trading.multi_outcome_require_full_fill
+            && matches!(arb_op.arb_type(), ArbType::MultiOutcome)
+            && !arb_engine::legs_fill_at_limit(arb_op, orderbook_manager)
+        {
+            debug!(
+                "⏭️  Not every leg of {} fills at its limit price, skipping",
+                arb_op.market_id()
+            );
+            return Ok(());
+        }
+
+        // Risk checks and scoring take time; past a point the detected prices can't be trusted
+        let max_age_ms = self.config.execution.max_opportunity_age_ms;
+        if let Some(detected_at) = arb_op.detected_at_ms() {
+            let age_ms = chrono::Utc::now().timestamp_millis() - detected_at;
+            if max_age_ms > 0 && age_ms > max_age_ms as i64 {
+                monitor.record_arbitrage_expired(arb_op, age_ms).await;
+                return Ok(());
+            }
+        }
+
+        // The risk check above only read the concurrency count; taking the
+        // slot is what keeps two executions from both passing the limit
+        if !risk_manager.try_reserve_arb_slot() {
+            monitor
+                .alert_risk_limit_breached(arb_op.market_id(), RiskRejectReason::MaxConcurrentArbs)
+                .await;
             return Ok(());
         }
 
+        // The exchange has been failing every order: sit out the cooldown
+        match self.circuit_breaker.lock().allow(Instant::now()) {
+            BreakerPermit::Allowed => {}
+            BreakerPermit::Probe => info!(
+                "🔌 Circuit breaker half-open, probing with {}",
+                arb_op.market_id()
+            ),
+            BreakerPermit::Rejected => {
+                debug!("🔌 Circuit breaker open, skipping {}", arb_op.market_id());
+                risk_manager.release_arb_slot();
+                return Ok(());
+            }
+        }
+
         let execution_start = Instant::now();
 
-        // Execute based on trading mode
-        let result = if self.config.trading.trading_mode == crate::utils::TradingMode::Simulation {
-            self.simulation_executor
-                .as_ref()
-                .unwrap()
-                .simulate_arbitrage(arb_op)
-                .await
-        } else {
-            executor.execute_arbitrage(arb_op).await
-        };
+        let result = self
+            .executor
+            .execute_arbitrage(arb_op, orderbook_manager)
+            .await;
+
+        // Simulated orders resting until their fill latency passes: they count
+        // when they settle (see `settle_fills`)
+        let awaiting_fill = !self.config.user_channel_enabled()
+            && matches!(&result, Ok(exec_result) if exec_result.is_resting());
+
+        if !awaiting_fill {
+            // Nothing filled counts as a failure: it's what a rejecting exchange looks like
+            let failed = match &result {
+                Ok(exec_result) => !exec_result.success && !exec_result.partial_fill,
+                Err(_) => true,
+            };
+            self.record_breaker_outcome(failed, monitor).await;
+        }
 
         match result {
             Ok(exec_result) => {
                 let execution_time = execution_start.elapsed();
 
-                risk_manager.record_arbitrage_execution(arb_op, &exec_result)?;
+                // Nothing reached the exchange, so there is nothing to hold or count
+                if self.config.trading.trading_mode == TradingMode::PaperLive {
+                    info!(
+                        event = "arb_paper_executed",
+                        market_id = %arb_op.market_id(),
+                        "📝 [PAPER] Signed orders for {} in {:.2}ms: {}",
+                        arb_op.market_id(),
+                        execution_time.as_secs_f64() * 1000.0,
+                        arb_op
+                    );
+                    risk_manager.release_arb_slot();
+                    return Ok(());
+                }
+
+                // Reserve exposure only; positions open as fills are reported
+                if awaiting_fill {
+                    risk_manager.record_arbitrage_submission(arb_op, &exec_result);
+                    // Still in flight until the fill settles
+                    claim.hold();
+                    info!(
+                        event = "arb_placed",
+                        market_id = %arb_op.market_id(),
+                        "🕒 [SIM] Orders placed in {:.2}ms, filling once the latency passes: {}",
+                        execution_time.as_secs_f64() * 1000.0,
+                        arb_op
+                    );
+                    return Ok(());
+                }
+
+                // With the user channel, positions open as fills are reported
+                if self.config.user_channel_enabled() {
+                    risk_manager.record_arbitrage_submission(arb_op, &exec_result);
+                } else {
+                    risk_manager.record_arbitrage_execution(arb_op, &exec_result)?;
+                }
+                if !exec_result.success && !exec_result.partial_fill {
+                    risk_manager.record_market_failure(arb_op.market_id());
+                }
+                let total_pnl = risk_manager.mark_to_market(orderbook_manager);
+                monitor.check_pnl_drawdown(total_pnl).await;
 
                 monitor
                     .record_arbitrage_executed(arb_op, &exec_result, execution_time)
@@ -505,14 +1071,20 @@ impl WebSocketClient {
                         .await;
                 }
 
-                let mode_indicator =
-                    if self.config.trading.trading_mode == crate::utils::TradingMode::Simulation {
-                        "[SIM]"
-                    } else {
-                        "[LIVE]"
-                    };
+                let mode_indicator = match self.config.trading.trading_mode {
+                    TradingMode::Simulation => "[SIM]",
+                    TradingMode::PaperLive => "[PAPER]",
+                    TradingMode::Live => "[LIVE]",
+                };
 
                 info!(
+                    event = "arb_executed",
+                    mode = mode_indicator,
+                    market_id = %arb_op.market_id(),
+                    edge = %arb_op.edge(),
+                    profit = %arb_op.expected_profit(),
+                    filled = exec_result.filled,
+                    latency_ms = execution_time.as_millis() as u64,
                     "✅ {} Arbitrage executed in {:.2}ms: {}",
                     mode_indicator,
                     execution_time.as_secs_f64() * 1000.0,
@@ -520,13 +1092,94 @@ impl WebSocketClient {
                 );
             }
             Err(e) => {
-                error!("❌ Arbitrage execution failed: {:?}", e);
+                risk_manager.release_arb_slot();
+                risk_manager.record_market_failure(arb_op.market_id());
+                error!(
+                    event = "arb_execution_failed",
+                    market_id = %arb_op.market_id(),
+                    "❌ Arbitrage execution failed: {:?}",
+                    e
+                );
                 monitor
                     .alert_error(&format!("Arbitrage execution failed: {:?}", e))
                     .await;
+
+                // Bad credentials fail every trade the same way: stop instead
+                if e.is_fatal() {
+                    return Err(e.into());
+                }
             }
         }
 
         Ok(())
     }
+
+    /// Record the simulated arbs whose fill latency has passed: positions, P&L
+    /// and fill metrics follow what filled, and what didn't gives back the
+    /// exposure reserved when it was placed
+    pub async fn settle_fills(
+        &self,
+        orderbook_manager: &OrderBookManager,
+        risk_manager: &RiskManager,
+        monitor: &mut Monitor,
+    ) {
+        let settled = self.executor.settle_fills(orderbook_manager).await;
+        if settled.is_empty() {
+            return;
+        }
+
+        for fill in &settled {
+            let result = &fill.result;
+            let failed = !result.success && !result.partial_fill;
+            self.record_breaker_outcome(failed, monitor).await;
+
+            risk_manager.record_arbitrage_settlement(fill);
+            self.in_flight.release(fill.order.market_id());
+            if failed {
+                risk_manager.record_market_failure(fill.order.market_id());
+            }
+            monitor
+                .record_arbitrage_executed(
+                    &fill.order,
+                    result,
+                    Duration::from_millis(result.execution_time_ms),
+                )
+                .await;
+        }
+
+        let total_pnl = risk_manager.mark_to_market(orderbook_manager);
+        monitor.check_pnl_drawdown(total_pnl).await;
+    }
+
+    /// Count an execution toward the circuit breaker, alerting if it trips
+    async fn record_breaker_outcome(&self, failed: bool, monitor: &Monitor) {
+        if failed {
+            let tripped = self.circuit_breaker.lock().record_failure(Instant::now());
+            if tripped {
+                monitor
+                    .alert_circuit_breaker_tripped(
+                        self.config.execution.circuit_breaker_failures,
+                        self.config.execution.circuit_breaker_cooldown_secs,
+                    )
+                    .await;
+            }
+        } else if self.circuit_breaker.lock().record_success() {
+            info!("✅ Circuit breaker closed, execution resumed");
+        }
+    }
+}
+
+/// Whether `err` carries an `HftpmError` that retrying can't fix
+fn is_fatal(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<HftpmError>()
+        .is_some_and(HftpmError::is_fatal)
+}
+
+/// Condition id -> index into `markets`
+fn index_markets(markets: &[Market]) -> HashMap<String, usize> {
+    markets
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.market.clone(), i))
+        .collect()
 }
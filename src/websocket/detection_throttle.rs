@@ -0,0 +1,128 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Per-market gate in front of `detect_arbitrage`.
+///
+/// A hot book can change thousands of times a second while its best asks, the only
+/// input that matters for a sum-<$1 arb, stay put. Detection runs when a market's
+/// best asks move, otherwise at most once per `interval`. Updates skipped in between
+/// are picked up by `due` once the interval is up, so the last of a burst is never
+/// left undetected. A zero interval detects on every update.
+pub struct DetectionThrottle {
+    interval: Duration,
+    last_detection: HashMap<String, LastDetection>,
+    skipped: u64,
+}
+
+/// When detection last ran on a market and the best asks it saw
+struct LastDetection {
+    ran_at: Instant,
+    asks: Vec<(String, Decimal)>,
+    /// An update was skipped since, so a trailing run is owed
+    pending: bool,
+}
+
+impl DetectionThrottle {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_detection: HashMap::new(),
+            skipped: 0,
+        }
+    }
+
+    /// Whether to run detection for `market_id` given its current best asks
+    pub fn should_detect(
+        &mut self,
+        market_id: &str,
+        best_asks: &[(String, Decimal, Decimal)],
+    ) -> bool {
+        self.should_detect_at(market_id, best_asks, Instant::now())
+    }
+
+    /// `should_detect` against an explicit clock reading
+    pub fn should_detect_at(
+        &mut self,
+        market_id: &str,
+        best_asks: &[(String, Decimal, Decimal)],
+        now: Instant,
+    ) -> bool {
+        if self.interval.is_zero() {
+            return true;
+        }
+
+        let ask_prices = || -> Vec<(String, Decimal)> {
+            best_asks
+                .iter()
+                .map(|(asset_id, price, _)| (asset_id.clone(), *price))
+                .collect()
+        };
+
+        match self.last_detection.get_mut(market_id) {
+            Some(last) => {
+                let asks_moved = last.asks.len() != best_asks.len()
+                    || last.asks.iter().zip(best_asks).any(
+                        |((asset, price), (new_asset, new_price, _))| {
+                            asset != new_asset || price != new_price
+                        },
+                    );
+
+                if !asks_moved && now.duration_since(last.ran_at) < self.interval {
+                    self.skipped += 1;
+                    last.pending = true;
+                    return false;
+                }
+
+                last.ran_at = now;
+                last.pending = false;
+                if asks_moved {
+                    last.asks = ask_prices();
+                }
+            }
+            None => {
+                self.last_detection.insert(
+                    market_id.to_string(),
+                    LastDetection {
+                        ran_at: now,
+                        asks: ask_prices(),
+                        pending: false,
+                    },
+                );
+            }
+        }
+
+        true
+    }
+
+    /// Markets with skipped updates whose interval is up, to detect on now even
+    /// if no further update arrives. Each is counted as a run.
+    pub fn due(&mut self) -> Vec<String> {
+        self.due_at(Instant::now())
+    }
+
+    /// `due` against an explicit clock reading
+    pub fn due_at(&mut self, now: Instant) -> Vec<String> {
+        let interval = self.interval;
+        self.last_detection
+            .iter_mut()
+            .filter(|(_, last)| last.pending && now.duration_since(last.ran_at) >= interval)
+            .map(|(market_id, last)| {
+                last.ran_at = now;
+                last.pending = false;
+                market_id.clone()
+            })
+            .collect()
+    }
+
+    /// How often `due` needs polling; `None` when every update is detected
+    pub fn interval(&self) -> Option<Duration> {
+        (!self.interval.is_zero()).then_some(self.interval)
+    }
+
+    /// Detection runs saved by the throttle
+    #[inline]
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+}
@@ -0,0 +1,167 @@
+use super::types::WsMessage;
+use crate::orderbook::OrderBookManager;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Window over which `messages_per_sec` is averaged
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Market feed liveness. A socket can stay connected while data stops arriving,
+/// so `websocket_connected` alone can't tell a quiet feed from a stalled one.
+pub struct FeedHealth {
+    reconnects: u64,
+    sequence_gaps: u64,
+    last_message: Option<Instant>,
+    window_start: Option<Instant>,
+    window_messages: u64,
+    messages_per_sec: f64,
+}
+
+impl FeedHealth {
+    pub fn new() -> Self {
+        Self {
+            reconnects: 0,
+            sequence_gaps: 0,
+            last_message: None,
+            window_start: None,
+            window_messages: 0,
+            messages_per_sec: 0.0,
+        }
+    }
+
+    /// Health across several connections: counts and rates add up, and the
+    /// message age is that of the stalest connection
+    pub fn combined<'a>(connections: impl IntoIterator<Item = &'a FeedHealth>) -> Self {
+        let mut combined = Self::new();
+
+        for health in connections {
+            combined.reconnects += health.reconnects;
+            combined.sequence_gaps += health.sequence_gaps;
+            combined.messages_per_sec += health.messages_per_sec;
+            combined.last_message = match (combined.last_message, health.last_message) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+
+        combined
+    }
+
+    /// Start the stall clock at connect, so a feed that never sends anything is
+    /// still reported as stalled
+    pub fn mark_connected_at(&mut self, now: Instant) {
+        self.last_message = Some(now);
+    }
+
+    /// Count `count` messages received in one read
+    pub fn record_messages(&mut self, count: usize) {
+        self.record_messages_at(count, Instant::now());
+    }
+
+    /// `record_messages` against an explicit clock reading
+    pub fn record_messages_at(&mut self, count: usize, now: Instant) {
+        self.last_message = Some(now);
+        self.window_messages += count as u64;
+        self.roll_window(now);
+    }
+
+    /// Close the rate window once it spans `RATE_WINDOW`; a feed that goes silent
+    /// decays to zero messages/sec on the next health check rather than freezing
+    pub fn roll_window(&mut self, now: Instant) {
+        let start = *self.window_start.get_or_insert(now);
+        let elapsed = now.duration_since(start);
+
+        if elapsed >= RATE_WINDOW {
+            self.messages_per_sec = self.window_messages as f64 / elapsed.as_secs_f64();
+            self.window_messages = 0;
+            self.window_start = Some(now);
+        }
+    }
+
+    #[inline]
+    pub fn record_reconnect(&mut self) {
+        self.reconnects += 1;
+    }
+
+    #[inline]
+    pub fn record_sequence_gaps(&mut self, gaps: u64) {
+        self.sequence_gaps += gaps;
+    }
+
+    /// Time since the last message (or connect), `None` before either
+    #[inline]
+    pub fn last_message_age_at(&self, now: Instant) -> Option<Duration> {
+        self.last_message.map(|last| now.duration_since(last))
+    }
+
+    /// Average rate over the last completed window
+    #[inline]
+    pub fn messages_per_sec(&self) -> f64 {
+        self.messages_per_sec
+    }
+
+    #[inline]
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects
+    }
+
+    #[inline]
+    pub fn sequence_gaps(&self) -> u64 {
+        self.sequence_gaps
+    }
+}
+
+impl Default for FeedHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Assets whose book no longer matches the exchange after `messages` were applied.
+///
+/// Every price change carries the exchange's best bid/ask once it is applied. The
+/// market channel has no sequence numbers, so a local top of book that disagrees
+/// with the last reported one means an update was dropped. Assets whose last event
+/// in the batch is a snapshot are in sync by construction and are skipped.
+pub fn count_sequence_gaps(messages: &[WsMessage], orderbook_manager: &OrderBookManager) -> u64 {
+    let mut last_reported: HashMap<(&str, &str), Option<(&str, &str)>> = HashMap::new();
+
+    for ws_msg in messages {
+        match ws_msg {
+            WsMessage::Book(book) => {
+                last_reported.insert((book.market.as_str(), book.asset_id.as_str()), None);
+            }
+            WsMessage::PriceChange(change) => {
+                for level in &change.price_changes {
+                    last_reported.insert(
+                        (change.market.as_str(), level.asset_id.as_str()),
+                        Some((level.best_bid.as_str(), level.best_ask.as_str())),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    last_reported
+        .into_iter()
+        .filter_map(|((market_id, asset_id), reported)| {
+            let (best_bid, best_ask) = reported?;
+            orderbook_manager.with_book(market_id, asset_id, |book| {
+                !top_matches(best_bid, book.best_bid().map(|(p, _)| p))
+                    || !top_matches(best_ask, book.best_ask().map(|(p, _)| p))
+            })
+        })
+        .filter(|&gap| gap)
+        .count() as u64
+}
+
+/// A missing or unparseable report, or an empty local side, can't be checked
+#[inline]
+fn top_matches(reported: &str, local: Option<Decimal>) -> bool {
+    match (reported.parse::<Decimal>(), local) {
+        (Ok(reported), Some(local)) => reported == local,
+        _ => true,
+    }
+}
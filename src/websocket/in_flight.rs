@@ -0,0 +1,59 @@
+use dashmap::DashSet;
+
+/// Markets with an execution in progress.
+///
+/// A book snapshot and the price_change right behind it can both detect the same
+/// arb before the first execution has updated exposure. Claiming the market before
+/// executing turns the second detection into a no-op instead of a second set of
+/// orders.
+#[derive(Default)]
+pub struct InFlightMarkets {
+    markets: DashSet<String>,
+}
+
+impl InFlightMarkets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claim `market_id` for one execution, or `None` if one is already running.
+    /// The claim is released when the returned guard drops.
+    pub fn try_claim<'a>(&'a self, market_id: &str) -> Option<InFlightClaim<'a>> {
+        self.markets
+            .insert(market_id.to_string())
+            .then(|| InFlightClaim {
+                markets: &self.markets,
+                market_id: market_id.to_string(),
+            })
+    }
+
+    #[inline]
+    pub fn is_in_flight(&self, market_id: &str) -> bool {
+        self.markets.contains(market_id)
+    }
+
+    /// Release a claim kept past its guard with `InFlightClaim::hold`
+    pub fn release(&self, market_id: &str) {
+        self.markets.remove(market_id);
+    }
+}
+
+/// A market's execution slot, held for the duration of the execution
+pub struct InFlightClaim<'a> {
+    markets: &'a DashSet<String>,
+    market_id: String,
+}
+
+impl InFlightClaim<'_> {
+    /// Keep the market claimed after the guard drops, for an execution that
+    /// finishes later; `InFlightMarkets::release` ends it
+    pub fn hold(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for InFlightClaim<'_> {
+    fn drop(&mut self) {
+        self.markets.remove(&self.market_id);
+    }
+}
@@ -1,5 +1,17 @@
 pub mod client;
+pub mod detection_throttle;
+pub mod health;
+pub mod in_flight;
+pub mod rate_limit;
+pub mod rest_fallback;
+pub mod shard;
 pub mod types;
 
 pub use client::WebSocketClient;
+pub use detection_throttle::DetectionThrottle;
+pub use health::FeedHealth;
+pub use in_flight::{InFlightClaim, InFlightMarkets};
+pub use rate_limit::{UpdateRateLimiter, MARKET_PROCESS_INTERVAL};
+pub use rest_fallback::{poll_books, FallbackTransition, RestFallback};
+pub use shard::{shard_asset_ids, OversizedMessage};
 pub use types::*;
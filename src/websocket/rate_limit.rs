@@ -0,0 +1,198 @@
+use super::types::WsMessage;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Once over budget, a market's updates are processed at most this often
+pub const MARKET_PROCESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Token bucket over order book updates (one token per snapshot or level change).
+///
+/// While tokens last every update is processed. Once the bucket is empty, a batch
+/// is coalesced before processing: earlier snapshots of an asset are dropped in
+/// favour of the latest one, and earlier changes to the same price level are
+/// dropped in favour of the latest size. Updates to a market processed less than
+/// `MARKET_PROCESS_INTERVAL` ago are held back and coalesced with the batches
+/// that follow, so a busy market is processed once per interval however its
+/// updates are split across reads. The resulting book state is identical, only
+/// the redundant intermediate work is skipped.
+pub struct UpdateRateLimiter {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+    processed_updates: u64,
+    dropped_updates: u64,
+    /// Market -> when its updates were last let through
+    last_processed: HashMap<String, Instant>,
+    /// Coalesced updates waiting out their market's interval, oldest first
+    held: Vec<WsMessage>,
+}
+
+impl UpdateRateLimiter {
+    pub fn new(max_updates_per_sec: usize) -> Self {
+        let capacity = max_updates_per_sec.max(1) as f64;
+
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity,
+            last_refill: Instant::now(),
+            processed_updates: 0,
+            dropped_updates: 0,
+            last_processed: HashMap::new(),
+            held: Vec::new(),
+        }
+    }
+
+    /// Pass a read batch through the limiter, coalescing it if over budget
+    pub fn throttle(&mut self, messages: Vec<WsMessage>) -> Vec<WsMessage> {
+        self.throttle_at(messages, Instant::now())
+    }
+
+    /// `throttle` against an explicit clock reading. Held-back updates go out
+    /// ahead of `messages` once their market is due; pass an empty batch to
+    /// release them when nothing else arrives (see `next_release`).
+    pub fn throttle_at(&mut self, messages: Vec<WsMessage>, now: Instant) -> Vec<WsMessage> {
+        self.refill(now);
+
+        let mut messages = messages;
+        if !self.held.is_empty() {
+            let mut held = std::mem::take(&mut self.held);
+            held.extend(messages);
+            messages = held;
+        }
+
+        let incoming = messages.iter().map(update_count).sum::<usize>();
+
+        let messages = if (incoming as f64) <= self.tokens {
+            messages
+        } else {
+            let coalesced = coalesce(messages);
+            let kept = coalesced.iter().map(update_count).sum::<usize>();
+            let dropped = incoming - kept;
+
+            if dropped > 0 {
+                debug!("🚦 Rate limit: coalesced {} redundant book updates", dropped);
+            }
+            self.dropped_updates += dropped as u64;
+
+            let (due, held): (Vec<_>, Vec<_>) = coalesced
+                .into_iter()
+                .partition(|ws_msg| self.is_due(ws_msg, now));
+            self.held = held;
+            due
+        };
+
+        for market in messages.iter().filter_map(market_of) {
+            self.last_processed.insert(market.to_string(), now);
+        }
+
+        let processed = messages.iter().map(update_count).sum::<usize>();
+        self.tokens = (self.tokens - processed as f64).max(0.0);
+        self.processed_updates += processed as u64;
+
+        messages
+    }
+
+    /// When the earliest held-back update's market is next due, if any are held
+    pub fn next_release(&self) -> Option<Instant> {
+        self.held
+            .iter()
+            .filter_map(market_of)
+            .filter_map(|market| self.last_processed.get(market))
+            .min()
+            .map(|last| *last + MARKET_PROCESS_INTERVAL)
+    }
+
+    /// Whether `ws_msg`'s market was last processed at least an interval before `now`
+    fn is_due(&self, ws_msg: &WsMessage, now: Instant) -> bool {
+        market_of(ws_msg)
+            .and_then(|market| self.last_processed.get(market))
+            .is_none_or(|last| now.duration_since(*last) >= MARKET_PROCESS_INTERVAL)
+    }
+
+    #[inline]
+    pub fn processed_updates(&self) -> u64 {
+        self.processed_updates
+    }
+
+    #[inline]
+    pub fn dropped_updates(&self) -> u64 {
+        self.dropped_updates
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+/// Number of book updates carried by a message
+#[inline]
+fn update_count(ws_msg: &WsMessage) -> usize {
+    match ws_msg {
+        WsMessage::Book(_) => 1,
+        WsMessage::PriceChange(change) => change.price_changes.len(),
+        WsMessage::TickSizeChange(_) | WsMessage::LastTradePrice(_) | WsMessage::Unknown => 0,
+    }
+}
+
+/// Market a book update applies to
+#[inline]
+fn market_of(ws_msg: &WsMessage) -> Option<&str> {
+    match ws_msg {
+        WsMessage::Book(book) => Some(&book.market),
+        WsMessage::PriceChange(change) => Some(&change.market),
+        WsMessage::TickSizeChange(_) | WsMessage::LastTradePrice(_) | WsMessage::Unknown => None,
+    }
+}
+
+/// Keep only the latest snapshot per asset and the latest change per price level,
+/// preserving the order of whatever survives
+fn coalesce(messages: Vec<WsMessage>) -> Vec<WsMessage> {
+    let mut snapshot_assets: HashSet<(String, String)> = HashSet::new();
+    let mut seen_levels: HashSet<(String, String, String, String)> = HashSet::new();
+    let mut kept = Vec::with_capacity(messages.len());
+
+    // Walk newest-first so the first occurrence of each key is the one to keep
+    for ws_msg in messages.into_iter().rev() {
+        match ws_msg {
+            WsMessage::Book(book) => {
+                if snapshot_assets.insert((book.market.clone(), book.asset_id.clone())) {
+                    kept.push(WsMessage::Book(book));
+                }
+            }
+            WsMessage::PriceChange(mut change) => {
+                let changes = std::mem::take(&mut change.price_changes);
+                let mut fresh: Vec<_> = changes
+                    .into_iter()
+                    .rev()
+                    .filter(|level| {
+                        let superseded_by_snapshot = snapshot_assets
+                            .contains(&(change.market.clone(), level.asset_id.clone()));
+
+                        !superseded_by_snapshot
+                            && seen_levels.insert((
+                                change.market.clone(),
+                                level.asset_id.clone(),
+                                level.side.clone(),
+                                level.price.clone(),
+                            ))
+                    })
+                    .collect();
+
+                if !fresh.is_empty() {
+                    fresh.reverse();
+                    change.price_changes = fresh;
+                    kept.push(WsMessage::PriceChange(change));
+                }
+            }
+            other => kept.push(other),
+        }
+    }
+
+    kept.reverse();
+    kept
+}
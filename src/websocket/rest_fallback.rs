@@ -0,0 +1,171 @@
+use super::shard::ShardStats;
+use crate::orderbook::{OrderBook, OrderBookManager};
+
+use futures::stream::{self, StreamExt};
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+/// `/book` requests in flight at once per connection while polling
+const REST_POLL_CONCURRENCY: usize = 8;
+
+/// A change in whether a connection's books come from REST polling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackTransition {
+    Activated,
+    Deactivated,
+}
+
+/// Decides when REST polling stands in for a market-channel connection: once it
+/// has been down for `activate_after`, until it reconnects
+pub struct RestFallback {
+    activate_after: Duration,
+    down_since: Option<Instant>,
+    active: bool,
+}
+
+impl RestFallback {
+    pub fn new(activate_after: Duration) -> Self {
+        Self {
+            activate_after,
+            down_since: None,
+            active: false,
+        }
+    }
+
+    /// Feed the connection state observed at `now`, returning the transition it
+    /// causes, if any. Downtime counts from the first disconnected observation.
+    pub fn update(&mut self, connected: bool, now: Instant) -> Option<FallbackTransition> {
+        if connected {
+            self.down_since = None;
+            if self.active {
+                self.active = false;
+                return Some(FallbackTransition::Deactivated);
+            }
+            return None;
+        }
+
+        let down_since = *self.down_since.get_or_insert(now);
+        if !self.active && now.duration_since(down_since) >= self.activate_after {
+            self.active = true;
+            return Some(FallbackTransition::Activated);
+        }
+
+        None
+    }
+
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+/// Fetch every book in `asset_ids` over REST into `orderbook_manager`,
+/// returning the markets whose books were fetched (deduplicated). Failed
+/// fetches are logged and skipped; the next poll retries them.
+pub async fn poll_books(
+    orderbook_manager: &OrderBookManager,
+    client: &reqwest::Client,
+    base_url: &str,
+    asset_ids: &[String],
+) -> Vec<String> {
+    let fetches: Vec<_> = asset_ids
+        .iter()
+        .map(|asset_id| fetch_or_skip(orderbook_manager, client, base_url, asset_id))
+        .collect();
+    let books: Vec<Option<OrderBook>> = stream::iter(fetches)
+        .buffer_unordered(REST_POLL_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut seen = HashSet::new();
+    books
+        .into_iter()
+        .flatten()
+        .filter(|book| seen.insert(book.market_id.clone()))
+        .map(|book| book.market_id)
+        .collect()
+}
+
+async fn fetch_or_skip(
+    orderbook_manager: &OrderBookManager,
+    client: &reqwest::Client,
+    base_url: &str,
+    asset_id: &str,
+) -> Option<OrderBook> {
+    match orderbook_manager
+        .fetch_book_rest(client, base_url, asset_id)
+        .await
+    {
+        Ok(book) => book,
+        Err(e) => {
+            debug!("REST poll for {} failed: {}", asset_id, e);
+            None
+        }
+    }
+}
+
+/// Polls one connection's books over REST while its WebSocket is down, feeding
+/// the touched markets to the detection loop like the shard itself would
+pub(super) struct RestPoller {
+    pub(super) shard_id: usize,
+    pub(super) base_url: String,
+    pub(super) client: reqwest::Client,
+    /// The connection's current asset ids, as the shard subscribes them
+    pub(super) subscription: watch::Receiver<Vec<String>>,
+    pub(super) orderbook_manager: Arc<OrderBookManager>,
+    pub(super) stats: Arc<Mutex<ShardStats>>,
+    pub(super) touched_tx: mpsc::Sender<Vec<String>>,
+    pub(super) fallback: RestFallback,
+    pub(super) poll_interval: Duration,
+}
+
+impl RestPoller {
+    /// Check the connection every `poll_interval`, polling while the fallback is
+    /// active, until the detection loop goes away
+    pub(super) async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if self.touched_tx.is_closed() {
+                return;
+            }
+
+            let connected = self.stats.lock().connected;
+            match self.fallback.update(connected, Instant::now()) {
+                Some(FallbackTransition::Activated) => warn!(
+                    "📮 WebSocket shard {} down for {:?}, polling {} books over REST",
+                    self.shard_id,
+                    self.fallback.activate_after,
+                    self.subscription.borrow().len()
+                ),
+                Some(FallbackTransition::Deactivated) => info!(
+                    "✅ WebSocket shard {} back, stopping REST polling",
+                    self.shard_id
+                ),
+                None => {}
+            }
+
+            if !self.fallback.is_active() {
+                continue;
+            }
+
+            // Cloned so the receiver isn't borrowed across the fetches
+            let asset_ids = self.subscription.borrow().clone();
+            let touched = poll_books(
+                &self.orderbook_manager,
+                &self.client,
+                &self.base_url,
+                &asset_ids,
+            )
+            .await;
+            if !touched.is_empty() && self.touched_tx.send(touched).await.is_err() {
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use super::client::{WebSocketClient, PING_INTERVAL, RECONNECT_DELAY};
+use super::health::{self, FeedHealth};
+use super::rate_limit::UpdateRateLimiter;
+use super::rest_fallback::poll_books;
+use super::types::WsMessage;
+use crate::gamma_api::Market;
+use crate::orderbook::OrderBookManager;
+use crate::utils::ScopedTimer;
+
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tracing::{debug, error, info, warn};
+
+/// Sleep until held-back updates are due, or forever if none are held
+async fn sleep_until_release(release: Option<Instant>) {
+    match release {
+        Some(at) => tokio::time::sleep_until(at.into()).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Deal markets round-robin across `shards` connections, returning each
+/// connection's asset ids. A market's outcomes stay on one connection so its
+/// books are updated in the order the exchange sent them.
+pub fn shard_asset_ids(markets: &[Market], shards: usize) -> Vec<Vec<String>> {
+    let mut sharded = vec![Vec::new(); shards.max(1)];
+    let shard_count = sharded.len();
+
+    for (i, market) in markets.iter().enumerate() {
+        sharded[i % shard_count].extend(market.assets_ids.iter().cloned());
+    }
+
+    sharded
+}
+
+/// Counters one market-channel connection shares with `WebSocketClient`
+#[derive(Default)]
+pub struct ShardStats {
+    pub connected: bool,
+    pub health: FeedHealth,
+    /// Messages with an `event_type` we don't handle
+    pub unknown_messages: u64,
+    /// Book updates coalesced away by the shard's rate limiter
+    pub dropped_updates: u64,
+    /// Frames over `ws_max_message_bytes`, whose books were refetched over REST
+    pub oversized_messages: u64,
+}
+
+/// A market-channel frame too large to parse, with the ids it mentions so the
+/// books it carried can be named and refetched
+#[derive(Debug, Clone, PartialEq)]
+pub struct OversizedMessage {
+    pub bytes: usize,
+    pub limit: usize,
+    /// Markets and assets found by scanning the raw text (deduplicated, in
+    /// first-seen order); the frame may be cut short, so this can be partial
+    pub market_ids: Vec<String>,
+    pub asset_ids: Vec<String>,
+}
+
+impl OversizedMessage {
+    /// `Some` if `text` is over `limit` bytes
+    pub fn check(text: &str, limit: usize) -> Option<Self> {
+        (text.len() > limit).then(|| Self {
+            bytes: text.len(),
+            limit,
+            market_ids: scan_string_field(text, "market"),
+            asset_ids: scan_string_field(text, "asset_id"),
+        })
+    }
+}
+
+/// Every string value of `"field"` in `text`, without parsing the JSON around it
+fn scan_string_field(text: &str, field: &str) -> Vec<String> {
+    let key = format!("\"{}\"", field);
+    let mut seen = HashSet::new();
+    let mut values = Vec::new();
+
+    for (start, _) in text.match_indices(&key) {
+        let rest = text[start + key.len()..].trim_start();
+        let Some(rest) = rest.strip_prefix(':') else {
+            continue;
+        };
+        let Some(rest) = rest.trim_start().strip_prefix('"') else {
+            continue;
+        };
+        let Some(end) = rest.find('"') else {
+            continue;
+        };
+        let value = &rest[..end];
+        if seen.insert(value) {
+            values.push(value.to_string());
+        }
+    }
+
+    values
+}
+
+/// Books waiting to be refetched after oversized frames. One worker drains it
+/// at a time, and a book already queued isn't queued again.
+#[derive(Default)]
+pub(super) struct RefetchQueue {
+    asset_ids: Vec<String>,
+    queued: HashSet<String>,
+    /// Set while a worker is draining the queue
+    running: bool,
+}
+
+impl RefetchQueue {
+    /// Queue `asset_ids`; whether a worker has to be started for them
+    fn push(&mut self, asset_ids: Vec<String>) -> bool {
+        for asset_id in asset_ids {
+            if self.queued.insert(asset_id.clone()) {
+                self.asset_ids.push(asset_id);
+            }
+        }
+        !std::mem::replace(&mut self.running, true)
+    }
+
+    /// Everything queued so far, or `None` (and the worker stops) once empty
+    fn take(&mut self) -> Option<Vec<String>> {
+        if self.asset_ids.is_empty() {
+            self.running = false;
+            return None;
+        }
+        self.queued.clear();
+        Some(std::mem::take(&mut self.asset_ids))
+    }
+}
+
+/// Why a connection's read loop returned without an error
+enum ShardExit {
+    Closed,
+    /// The connection's asset ids changed and it has to subscribe again
+    Resubscribe,
+}
+
+/// One market-channel connection. Its read loop applies updates straight to the
+/// shared order books and hands the touched markets to the detection loop, so a
+/// backlog on one connection doesn't hold up the others.
+pub(super) struct MarketShard {
+    pub(super) id: usize,
+    pub(super) url: String,
+    /// REST base for refetching the books in an oversized frame
+    pub(super) rest_url: String,
+    pub(super) http_client: reqwest::Client,
+    pub(super) max_message_bytes: usize,
+    /// Asset ids subscribed on the current connection
+    pub(super) asset_ids: Vec<String>,
+    /// Asset ids to subscribe; a change reconnects with the new set
+    pub(super) subscription: watch::Receiver<Vec<String>>,
+    pub(super) orderbook_manager: Arc<OrderBookManager>,
+    pub(super) rate_limiter: UpdateRateLimiter,
+    pub(super) stats: Arc<Mutex<ShardStats>>,
+    pub(super) touched_tx: mpsc::Sender<Vec<String>>,
+    pub(super) refetch: Arc<Mutex<RefetchQueue>>,
+}
+
+impl MarketShard {
+    /// Read until the detection loop goes away, reconnecting on close or error
+    /// and when the subscription changes
+    pub(super) async fn run(mut self) {
+        loop {
+            let result = self.connect_and_read().await;
+            if matches!(result, Ok(ShardExit::Resubscribe)) {
+                self.stats.lock().connected = false;
+                continue;
+            }
+
+            {
+                let mut stats = self.stats.lock();
+                stats.connected = false;
+                stats.health.record_reconnect();
+            }
+            if self.touched_tx.is_closed() {
+                return;
+            }
+
+            match result {
+                Ok(_) => warn!(
+                    "WebSocket shard {} closed unexpectedly, reconnecting...",
+                    self.id
+                ),
+                Err(e) => error!(
+                    "WebSocket shard {} error: {:?}, reconnecting in {:?}...",
+                    self.id, e, RECONNECT_DELAY
+                ),
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    async fn connect_and_read(&mut self) -> Result<ShardExit> {
+        self.asset_ids = self.subscription.borrow_and_update().clone();
+        let (ws_stream, _) = tokio_tungstenite::connect_async(&self.url)
+            .await
+            .context("Failed to connect to WebSocket")?;
+
+        info!("✅ WebSocket shard {} connected to {}", self.id, self.url);
+
+        let (mut write, mut read) = ws_stream.split();
+
+        // Send subscription message immediately after connecting
+        info!(
+            "📡 Shard {} sending subscription for {} asset IDs...",
+            self.id,
+            self.asset_ids.len()
+        );
+        let subscribe_msg = serde_json::json!({
+            "assets_ids": self.asset_ids,
+            "type": "market"
+        })
+        .to_string();
+        write
+            .send(Message::Text(subscribe_msg))
+            .await
+            .context("Failed to send subscription message")?;
+
+        {
+            let mut stats = self.stats.lock();
+            stats.connected = true;
+            stats.health.mark_connected_at(Instant::now());
+        }
+
+        // Channel for sending messages to the write half
+        let (tx, mut rx) = mpsc::channel::<Message>(100);
+
+        let ping_tx = tx.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PING_INTERVAL);
+            loop {
+                interval.tick().await;
+                if ping_tx.send(Message::Ping(vec![])).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                if let Err(e) = write.send(msg).await {
+                    error!("Failed to send WebSocket message: {:?}", e);
+                    break;
+                }
+            }
+        });
+
+        loop {
+            // Wake for held-back updates even if the feed goes quiet
+            let release = self.rate_limiter.next_release();
+            let message = tokio::select! {
+                message = read.next() => message,
+                _ = sleep_until_release(release) => {
+                    self.apply_messages(Vec::new()).await?;
+                    continue;
+                }
+                Ok(()) = self.subscription.changed() => {
+                    info!(
+                        "📡 Shard {} resubscribing for {} asset IDs after a market refresh",
+                        self.id,
+                        self.subscription.borrow().len()
+                    );
+                    // Closing lets the write and ping tasks wind down with the socket
+                    let _ = tx.send(Message::Close(None)).await;
+                    return Ok(ShardExit::Resubscribe);
+                }
+            };
+            let Some(message) = message else {
+                break;
+            };
+
+            match message.context("Failed to read WebSocket message")? {
+                Message::Text(text) => self.process_text(&text).await?,
+                Message::Ping(data) => {
+                    let _ = tx.send(Message::Pong(data)).await;
+                }
+                Message::Pong(_) => {
+                    debug!("Received pong");
+                }
+                Message::Close(frame) => {
+                    info!("WebSocket shard {} closed: {:?}", self.id, frame);
+                    return Ok(ShardExit::Closed);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(ShardExit::Closed)
+    }
+
+    /// Apply one text frame to the books and queue its markets for detection
+    async fn process_text(&mut self, text: &str) -> Result<()> {
+        let _timer = ScopedTimer::new("ws_message_processing", None);
+
+        if let Some(oversized) = OversizedMessage::check(text, self.max_message_bytes) {
+            self.recover_oversized(oversized);
+            return Ok(());
+        }
+
+        let messages = match WsMessage::parse_batch(text) {
+            Ok(messages) => messages,
+            Err(e) => {
+                warn!(
+                    "Failed to parse message: {} | Sample: {}",
+                    e,
+                    &text[..text.len().min(300)]
+                );
+                return Ok(());
+            }
+        };
+
+        let received = messages.len();
+        let unknown = messages
+            .iter()
+            .filter(|msg| matches!(msg, WsMessage::Unknown))
+            .count() as u64;
+
+        {
+            let mut stats = self.stats.lock();
+            stats.health.record_messages(received);
+            stats.unknown_messages += unknown;
+        }
+
+        self.apply_messages(messages).await
+    }
+
+    /// Throttle parsed messages, apply them to the books and queue their
+    /// markets for detection. An empty batch releases held-back updates.
+    async fn apply_messages(&mut self, messages: Vec<WsMessage>) -> Result<()> {
+        // Coalesce redundant updates once over this shard's share of the budget
+        let dropped_before = self.rate_limiter.dropped_updates();
+        let messages = self.rate_limiter.throttle(messages);
+        let dropped = self.rate_limiter.dropped_updates() - dropped_before;
+
+        // Apply the whole batch first, then detect once per touched market
+        let touched = WebSocketClient::apply_message_batch(&messages, &self.orderbook_manager);
+
+        let gaps = health::count_sequence_gaps(&messages, &self.orderbook_manager);
+        if gaps > 0 {
+            debug!("🕳️  {} book(s) out of sync with the feed", gaps);
+        }
+
+        {
+            let mut stats = self.stats.lock();
+            stats.health.record_sequence_gaps(gaps);
+            stats.dropped_updates += dropped;
+        }
+
+        if !touched.is_empty() && self.touched_tx.send(touched).await.is_err() {
+            anyhow::bail!("Detection loop stopped");
+        }
+
+        Ok(())
+    }
+
+    /// Report a frame too large to parse and refetch its books over REST, so a
+    /// large snapshot batch doesn't leave those books missing until their next
+    /// update. If the frame names no assets every book on this connection is
+    /// refetched. Books already waiting on a refetch aren't fetched twice, and
+    /// one worker per connection does the fetching however many frames arrive.
+    /// Frames that keep exceeding the limit call for a higher
+    /// `ws_max_message_bytes` or more `ws_connections` to split the subscription.
+    fn recover_oversized(&self, oversized: OversizedMessage) {
+        warn!(
+            "📦 Shard {} got a {} byte message (limit {}), refetching books over REST for markets: {:?}",
+            self.id, oversized.bytes, oversized.limit, oversized.market_ids
+        );
+        self.stats.lock().oversized_messages += 1;
+
+        let asset_ids = if oversized.asset_ids.is_empty() {
+            self.asset_ids.clone()
+        } else {
+            oversized.asset_ids
+        };
+        if !self.refetch.lock().push(asset_ids) {
+            return;
+        }
+
+        let refetch = self.refetch.clone();
+        let orderbook_manager = self.orderbook_manager.clone();
+        let rest_url = self.rest_url.clone();
+        let client = self.http_client.clone();
+        let touched_tx = self.touched_tx.clone();
+
+        tokio::spawn(async move {
+            // Taken in its own statement so the lock is released before fetching
+            loop {
+                let queued = refetch.lock().take();
+                let Some(asset_ids) = queued else {
+                    break;
+                };
+                let touched = poll_books(&orderbook_manager, &client, &rest_url, &asset_ids).await;
+                if !touched.is_empty() {
+                    let _ = touched_tx.send(touched).await;
+                }
+            }
+        });
+    }
+}
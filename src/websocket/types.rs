@@ -1,23 +1,69 @@
+use crate::utils::parse::parse_level;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use tracing::debug;
 
+/// Market-channel message, discriminated by its `event_type`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WsMessage {
-    #[serde(rename = "event_type", default)]
-    pub event_type: String,
-    #[serde(rename = "asset_id", default)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum WsMessage {
+    Book(BookMessage),
+    PriceChange(PriceChangeMessage),
+    TickSizeChange(TickSizeChangeMessage),
+    LastTradePrice(LastTradePriceMessage),
+    /// Any other `event_type`; counted rather than parsed
+    #[serde(other)]
+    Unknown,
+}
+
+/// Full snapshot of one asset's book
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookMessage {
     pub asset_id: String,
-    #[serde(rename = "market")]
     pub market: String,
     #[serde(default)]
     pub timestamp: Option<String>,
     #[serde(default)]
     pub hash: Option<String>,
     #[serde(default)]
-    pub bids: Option<Vec<OrderSummary>>,
+    pub bids: Vec<OrderSummary>,
+    #[serde(default)]
+    pub asks: Vec<OrderSummary>,
+}
+
+/// Level changes, possibly across several assets of one market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceChangeMessage {
+    pub market: String,
+    #[serde(default)]
+    pub price_changes: Vec<PriceChange>,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// The asset's minimum price increment changed (prices near 0 or 1 trade finer)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickSizeChangeMessage {
+    pub asset_id: String,
+    pub market: String,
+    pub old_tick_size: rust_decimal::Decimal,
+    pub new_tick_size: rust_decimal::Decimal,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// A trade printed on the asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastTradePriceMessage {
+    pub asset_id: String,
+    pub market: String,
+    pub price: rust_decimal::Decimal,
+    pub size: rust_decimal::Decimal,
+    pub side: String,
     #[serde(default)]
-    pub asks: Option<Vec<OrderSummary>>,
+    pub fee_rate_bps: Option<String>,
     #[serde(default)]
-    pub price_changes: Option<Vec<PriceChange>>,
+    pub timestamp: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,15 +96,230 @@ pub struct BookSnapshot {
     pub hash: String,
 }
 
+/// Message on the authenticated `user` channel: our own orders and trades
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event_type", rename_all = "lowercase")]
+pub enum UserEvent {
+    Order(UserOrder),
+    Trade(UserTrade),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum UserSide {
+    Buy,
+    Sell,
+}
+
+/// What happened to one of our orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum UserOrderUpdate {
+    Placement,
+    Update,
+    Cancellation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserOrder {
+    pub id: String,
+    pub market: String,
+    pub asset_id: String,
+    #[serde(default)]
+    pub outcome: String,
+    pub side: UserSide,
+    pub price: rust_decimal::Decimal,
+    pub original_size: rust_decimal::Decimal,
+    pub size_matched: rust_decimal::Decimal,
+    #[serde(rename = "type")]
+    pub update: UserOrderUpdate,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Settlement progress of a trade; a match is reported once per status change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TradeStatus {
+    Matched,
+    Mined,
+    Confirmed,
+    Retrying,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserTrade {
+    pub id: String,
+    pub market: String,
+    pub asset_id: String,
+    #[serde(default)]
+    pub outcome: String,
+    /// Taker's side
+    pub side: UserSide,
+    pub price: rust_decimal::Decimal,
+    pub size: rust_decimal::Decimal,
+    pub status: TradeStatus,
+    pub taker_order_id: String,
+    /// Fee rate charged to the taker order
+    #[serde(default)]
+    pub fee_rate_bps: Option<String>,
+    /// API key owning the taker order
+    #[serde(default)]
+    pub trade_owner: String,
+    #[serde(default)]
+    pub maker_orders: Vec<MakerOrder>,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Resting order the taker matched against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MakerOrder {
+    pub order_id: String,
+    pub asset_id: String,
+    #[serde(default)]
+    pub outcome: String,
+    pub owner: String,
+    pub price: rust_decimal::Decimal,
+    pub matched_amount: rust_decimal::Decimal,
+}
+
+/// One of our orders filling, from either side of a trade
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFill {
+    pub trade_id: String,
+    pub order_id: String,
+    pub market_id: String,
+    pub asset_id: String,
+    pub outcome: String,
+    pub side: UserSide,
+    pub price: rust_decimal::Decimal,
+    pub size: rust_decimal::Decimal,
+    /// Fee the exchange charged us on this fill (zero for maker fills)
+    pub fee: rust_decimal::Decimal,
+}
+
+impl UserEvent {
+    /// Parse a user-channel frame (a single event or an array of them)
+    pub fn parse_batch(text: &str) -> serde_json::Result<Vec<UserEvent>> {
+        if text.trim_start().starts_with('[') {
+            serde_json::from_str(text)
+        } else {
+            serde_json::from_str(text).map(|event| vec![event])
+        }
+    }
+}
+
+impl UserTrade {
+    /// Our fills in this trade: the taker leg if we sent the taker order, plus any
+    /// of our resting orders it matched (at the maker's price).
+    pub fn fills_for(&self, api_key: &str) -> Vec<UserFill> {
+        let mut fills = Vec::new();
+
+        if self.trade_owner == api_key {
+            fills.push(UserFill {
+                trade_id: self.id.clone(),
+                order_id: self.taker_order_id.clone(),
+                market_id: self.market.clone(),
+                asset_id: self.asset_id.clone(),
+                outcome: self.outcome.clone(),
+                side: self.side,
+                price: self.price,
+                size: self.size,
+                fee: self.taker_fee(),
+            });
+        }
+
+        let opposite = match self.side {
+            UserSide::Buy => UserSide::Sell,
+            UserSide::Sell => UserSide::Buy,
+        };
+
+        fills.extend(
+            self.maker_orders
+                .iter()
+                .filter(|maker| maker.owner == api_key)
+                .map(|maker| UserFill {
+                    trade_id: self.id.clone(),
+                    order_id: maker.order_id.clone(),
+                    market_id: self.market.clone(),
+                    asset_id: maker.asset_id.clone(),
+                    outcome: maker.outcome.clone(),
+                    // Same token: the maker took the other side. Complementary token
+                    // (e.g. BUY YES matched with BUY NO to mint a set): same side.
+                    side: if maker.asset_id == self.asset_id {
+                        opposite
+                    } else {
+                        self.side
+                    },
+                    price: maker.price,
+                    size: maker.matched_amount,
+                    // Fees are charged to the taker
+                    fee: rust_decimal::Decimal::ZERO,
+                }),
+        );
+
+        fills
+    }
+
+    /// Fee on the taker leg at `fee_rate_bps`, charged on the cheaper side of
+    /// the price: `rate * min(price, 1 - price) * size`. Zero if no rate was sent.
+    pub fn taker_fee(&self) -> rust_decimal::Decimal {
+        use rust_decimal::Decimal;
+
+        let Some(bps) = self
+            .fee_rate_bps
+            .as_deref()
+            .and_then(|bps| bps.parse::<Decimal>().ok())
+        else {
+            return Decimal::ZERO;
+        };
+        let rate = bps / Decimal::from(10_000);
+        rate * self.price.min(Decimal::ONE - self.price) * self.size
+    }
+}
+
 impl WsMessage {
-    pub fn is_book_snapshot(&self) -> bool {
-        self.event_type == "book" || (self.bids.is_some() || self.asks.is_some())
+    /// Parse a market-channel frame (a single message or an array of them)
+    pub fn parse_batch(text: &str) -> serde_json::Result<Vec<WsMessage>> {
+        if text.trim_start().starts_with('[') {
+            serde_json::from_str(text)
+        } else {
+            serde_json::from_str(text).map(|msg| vec![msg])
+        }
     }
 
-    pub fn is_price_change(&self) -> bool {
-        self.event_type == "price_change" || self.price_changes.is_some()
+    /// Market the message belongs to (`None` for unknown messages)
+    pub fn market(&self) -> Option<&str> {
+        match self {
+            WsMessage::Book(book) => Some(&book.market),
+            WsMessage::PriceChange(change) => Some(&change.market),
+            WsMessage::TickSizeChange(change) => Some(&change.market),
+            WsMessage::LastTradePrice(trade) => Some(&trade.market),
+            WsMessage::Unknown => None,
+        }
     }
+}
+
+impl OrderSummary {
+    /// Parse a side of a book, skipping levels with a malformed or
+    /// out-of-range price or size rather than reading them as empty
+    pub fn parse_levels(levels: &[Self]) -> Vec<(Decimal, Decimal)> {
+        levels
+            .iter()
+            .filter_map(|level| match parse_level(&level.price, &level.size) {
+                Ok(level) => Some(level),
+                Err(e) => {
+                    debug!("⏭️  Skipping book level: {:#}", e);
+                    None
+                }
+            })
+            .collect()
+    }
+}
 
+impl BookMessage {
     pub fn parse_timestamp(&self) -> i64 {
         self.timestamp
             .as_ref()
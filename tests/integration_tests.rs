@@ -1,13 +1,19 @@
 #[cfg(test)]
 mod tests {
     use hfptm::{
-        arb_engine::ArbType,
+        arb_engine::{ArbEdge, ArbSide, ArbType, ArbitrageOpportunity, Opportunity},
+        executor::{ExchangeOpenOrder, ExchangePosition, ExecutionResult, Executor},
+        monitoring::{Alert, AlertSeverity, AlertType, Monitor},
+        risk::{RiskDecision, RiskRejectReason},
         utils::{
             AlertsConfig, CredentialsConfig, ExecutionConfig, LatencyConfig, MarketsConfig,
-            MonitoringConfig, RiskConfig, ServerConfig, TradingConfig,
+            MidpointMode, MonitoringConfig, RiskConfig, ServerConfig, TradingConfig,
         },
-        websocket::BookSnapshot,
-        ArbEngine, Config, LatencyTracker, OrderBookManager, RiskManager,
+        websocket::{
+            BookSnapshot, UpdateRateLimiter, WebSocketClient, WsMessage, MARKET_PROCESS_INTERVAL,
+        },
+        gamma_api::Market,
+        ArbEngine, Config, HftpmError, LatencyTracker, MarketMaker, OrderBookManager, RiskManager,
     };
     use rust_decimal::Decimal;
     use rust_decimal_macros::dec;
@@ -28,6 +34,119 @@ mod tests {
         assert!(result.is_ok() || result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_config_profile_overrides_only_its_fields() {
+        let dir = std::env::temp_dir().join(format!("hfptm_profile_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("config/config.toml", dir.join("config.toml")).unwrap();
+        std::fs::write(
+            dir.join("staging.toml"),
+            "[trading]\nmax_arb_size = 25\n\n[risk]\ndaily_loss_limit = 10\n",
+        )
+        .unwrap();
+
+        let base = Config::load_from(&dir, None).unwrap();
+        let staging = Config::load_from(&dir, Some("staging")).unwrap();
+
+        assert_eq!(base.trading.max_arb_size, 150);
+        assert_eq!(staging.trading.max_arb_size, 25);
+        assert_eq!(staging.risk.daily_loss_limit, 10);
+        // Everything the profile leaves out comes from the base file
+        assert_eq!(staging.trading.bankroll, base.trading.bankroll);
+        assert_eq!(staging.trading.min_edge, base.trading.min_edge);
+        assert_eq!(staging.server.wss_url, base.server.wss_url);
+        assert_eq!(
+            staging.risk.max_exposure_per_market,
+            base.risk.max_exposure_per_market
+        );
+
+        // A misspelled profile fails loudly instead of silently running the base config
+        assert!(Config::load_from(&dir, Some("stagign")).is_err());
+
+        assert_eq!(
+            hfptm::utils::profile_arg(["--profile".to_string(), "prod".to_string()]),
+            Some("prod".to_string())
+        );
+        assert_eq!(
+            hfptm::utils::profile_arg(["--profile=dev".to_string()]),
+            Some("dev".to_string())
+        );
+        assert_eq!(hfptm::utils::profile_arg(Vec::new()), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_validates_funder_against_private_key() {
+        use alloy::signers::local::PrivateKeySigner;
+        use hfptm::utils::TradingMode;
+
+        let live = |signature_type: u8, funder_address: &str| {
+            let mut config = create_test_config();
+            config.trading.trading_mode = TradingMode::Live;
+            config.credentials.signature_type = signature_type;
+            config.credentials.funder_address = funder_address.to_string();
+            config
+        };
+        let eoa = create_test_config()
+            .credentials
+            .private_key
+            .parse::<PrivateKeySigner>()
+            .unwrap()
+            .address();
+        let eoa_lower = format!("{:?}", eoa).to_lowercase();
+
+        // An EOA funding its own orders passes, normalized to checksum form
+        let mut config = live(0, &eoa_lower);
+        config.validate().unwrap();
+        assert_eq!(config.credentials.funder_address, eoa.to_checksum(None));
+        assert_ne!(config.credentials.funder_address, eoa_lower);
+
+        // An EOA can't be funded by another wallet...
+        let other = "0x1234567890abcdef1234567890abcdef12345678";
+        let err = live(0, other).validate().unwrap_err();
+        assert!(err.to_string().contains("not the private key's address"));
+        // ...but a proxy or Safe is, so that only warns
+        for signature_type in [1, 2] {
+            live(signature_type, other).validate().unwrap();
+        }
+
+        // Malformed addresses: short, non-hex, and a mixed-case typo
+        for malformed in [
+            "0x1234",
+            "0x1234567890abcdef1234567890abcdef1234567g",
+            "YOUR_FUNDER_ADDRESS_HERE",
+        ] {
+            let err = live(2, malformed).validate().unwrap_err();
+            assert!(err.to_string().contains("not an address"), "{}", err);
+        }
+        let checksummed = eoa.to_checksum(None);
+        let typo = checksummed
+            .char_indices()
+            .find(|(i, c)| *i > 1 && c.is_ascii_alphabetic())
+            .map(|(i, c)| {
+                let flipped = if c.is_ascii_uppercase() {
+                    c.to_ascii_lowercase()
+                } else {
+                    c.to_ascii_uppercase()
+                };
+                format!("{}{}{}", &checksummed[..i], flipped, &checksummed[i + 1..])
+            })
+            .unwrap();
+        let err = live(0, &typo).validate().unwrap_err();
+        assert!(err.to_string().contains("bad checksum"));
+
+        // Unparseable keys are caught before any order is signed
+        let mut config = live(0, &eoa_lower);
+        config.credentials.private_key = "YOUR_PRIVATE_KEY_HERE".to_string();
+        assert!(config.validate().is_err());
+
+        // Simulation never signs, so placeholders are fine there
+        let mut config = create_test_config();
+        config.credentials.funder_address = "YOUR_FUNDER_ADDRESS_HERE".to_string();
+        config.validate().unwrap();
+    }
+
     #[tokio::test]
     async fn test_orderbook_manager() {
         let config = create_test_config();
@@ -56,6 +175,176 @@ mod tests {
         assert!(best_ask.is_some());
     }
 
+    #[test]
+    fn test_with_market_books_borrows_in_place() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+
+        let snapshot = BookSnapshot {
+            market: "borrow_market".to_string(),
+            asset_id: "borrow_asset".to_string(),
+            bids: vec![(dec!(0.48), dec!(100))],
+            asks: vec![(dec!(0.52), dec!(75)), (dec!(0.55), dec!(200))],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager
+            .update_book("borrow_market", "borrow_asset", &snapshot)
+            .unwrap();
+
+        let best_asks = manager
+            .with_market_books("borrow_market", |books| books.best_asks())
+            .unwrap();
+        assert_eq!(
+            best_asks,
+            vec![("borrow_asset".to_string(), dec!(0.52), dec!(75))]
+        );
+        assert_eq!(
+            manager.get_best_asks_for_market("borrow_market"),
+            Some(best_asks)
+        );
+
+        assert!(manager
+            .with_market_books("missing_market", |books| books.books.len())
+            .is_none());
+    }
+
+    #[test]
+    fn test_duplicate_and_out_of_order_snapshots_are_skipped() {
+        use hfptm::orderbook::BookUpdate;
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let snapshot = |ask: Decimal, timestamp: i64, hash: &str| BookSnapshot {
+            market: "order_market".to_string(),
+            asset_id: "order_asset".to_string(),
+            bids: vec![(dec!(0.40), dec!(100))],
+            asks: vec![(ask, dec!(100))],
+            timestamp,
+            hash: hash.to_string(),
+        };
+        let apply = |snapshot: &BookSnapshot| {
+            manager
+                .update_book("order_market", "order_asset", snapshot)
+                .unwrap()
+        };
+        let best_ask = || {
+            manager
+                .get_book("order_market", "order_asset")
+                .and_then(|book| book.best_ask())
+                .map(|(price, _)| price)
+        };
+
+        let first = snapshot(dec!(0.50), 1_000, "a");
+        assert_eq!(apply(&first), BookUpdate::Applied);
+
+        // Redelivery of the same snapshot is a no-op, even over later price changes
+        manager
+            .update_price("order_market", "order_asset", dec!(0.49), dec!(10), "SELL")
+            .unwrap();
+        assert_eq!(apply(&first), BookUpdate::SkippedDuplicate);
+        assert_eq!(best_ask(), Some(dec!(0.49)));
+
+        // Newer snapshot applies; an older one arriving after it doesn't regress the book
+        let newer = snapshot(dec!(0.55), 2_000, "b");
+        assert_eq!(apply(&newer), BookUpdate::Applied);
+        let older = snapshot(dec!(0.45), 1_500, "c");
+        assert_eq!(apply(&older), BookUpdate::SkippedStale);
+        assert_eq!(best_ask(), Some(dec!(0.55)));
+
+        // Same timestamp with a different hash is a new book
+        let same_ms = snapshot(dec!(0.56), 2_000, "d");
+        assert_eq!(apply(&same_ms), BookUpdate::Applied);
+        assert_eq!(best_ask(), Some(dec!(0.56)));
+    }
+
+    #[test]
+    fn test_yes_no_tokens_follow_market_outcome_order() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+
+        let snapshot = |asset: &str, bids: usize, asks: usize, timestamp: i64| BookSnapshot {
+            market: "m".to_string(),
+            asset_id: asset.to_string(),
+            bids: (1..=bids).map(|i| (Decimal::new(i as i64, 2), dec!(10))).collect(),
+            asks: (1..=asks).map(|i| (Decimal::new(50 + i as i64, 2), dec!(10))).collect(),
+            timestamp,
+            hash: format!("{}-{}-{}", asset, bids, asks),
+        };
+        let yes_no = || {
+            manager
+                .with_market_books("m", |books| {
+                    (books.asset_id_yes.clone(), books.asset_id_no.clone())
+                })
+                .unwrap()
+        };
+        let apply = |asset: &str, bids: usize, asks: usize, timestamp: i64| {
+            let book = snapshot(asset, bids, asks, timestamp);
+            manager.update_book("m", asset, &book).unwrap();
+        };
+
+        // Books arriving before registration pick up the mapping once it's known
+        apply("no_token", 5, 1, 1);
+        apply("yes_token", 1, 5, 1);
+        assert_eq!(yes_no(), (None, None));
+
+        manager.register_market(&test_market("m", "Will it?", &["yes_token", "no_token"]));
+        let expected = (Some("yes_token".to_string()), Some("no_token".to_string()));
+        assert_eq!(yes_no(), expected);
+
+        // Flip which side of each book is deeper: the mapping doesn't move
+        for (ts, (bids, asks)) in [(2, (1, 5)), (3, (5, 1)), (4, (3, 3))].into_iter() {
+            apply("yes_token", bids, asks, ts);
+            apply("no_token", asks, bids, ts);
+            assert_eq!(yes_no(), expected);
+        }
+
+        // A market rebuilt after a stale-book sweep keeps it too
+        manager.cleanup_stale_books(0);
+        apply("no_token", 2, 1, 5);
+        assert_eq!(yes_no(), expected);
+    }
+
+    #[test]
+    fn test_market_readiness_as_books_arrive() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let mut engine = ArbEngine::new(&config);
+        let risk_manager = RiskManager::new(&config);
+        let market = test_market("warm", "Who wins?", &["a", "b", "c"]);
+
+        manager.register_market(&market);
+        assert_eq!(manager.market_readiness("warm"), (0, 3));
+        assert_eq!(manager.missing_assets("warm"), vec!["a", "b", "c"]);
+        assert_eq!(manager.warmup_progress(), (0, 1));
+
+        // Two cheap legs look like an arb, but the third outcome hasn't loaded
+        for (loaded, asset_id) in [(1, "a"), (2, "b")] {
+            let snapshot = ask_snapshot("warm", asset_id, vec![(dec!(0.30), dec!(200))]);
+            manager.update_book("warm", asset_id, &snapshot).unwrap();
+            assert_eq!(manager.market_readiness("warm"), (loaded, 3));
+            let arb = engine
+                .detect_arbitrage(&manager, "warm", Some(&market), &risk_manager)
+                .unwrap();
+            assert!(arb.is_none());
+        }
+        assert_eq!(manager.missing_assets("warm"), vec!["c"]);
+
+        let snapshot = ask_snapshot("warm", "c", vec![(dec!(0.30), dec!(200))]);
+        manager.update_book("warm", "c", &snapshot).unwrap();
+        assert_eq!(manager.market_readiness("warm"), (3, 3));
+        assert!(manager.missing_assets("warm").is_empty());
+        assert_eq!(manager.warmup_progress(), (1, 1));
+        assert!(engine
+            .detect_arbitrage(&manager, "warm", Some(&market), &risk_manager)
+            .unwrap()
+            .is_some());
+
+        // Unregistered markets expect whatever has loaded
+        assert_eq!(manager.market_readiness("unknown"), (0, 0));
+    }
+
     #[tokio::test]
     async fn test_arbitrage_detection() {
         let config = create_test_config();
@@ -72,10 +361,12 @@ mod tests {
         let no_price = dec!(0.48);
         let size = dec!(200);
 
+        orderbook_manager.register_market(&test_market(market_id, "Test", &[asset_yes, asset_no]));
+
         let yes_snapshot = BookSnapshot {
             market: market_id.to_string(),
             asset_id: asset_yes.to_string(),
-            bids: vec![(yes_price, size)],
+            bids: vec![(yes_price - dec!(0.01), size)],
             asks: vec![(yes_price, size)],
             timestamp,
             hash: "hash1".to_string(),
@@ -84,7 +375,7 @@ mod tests {
         let no_snapshot = BookSnapshot {
             market: market_id.to_string(),
             asset_id: asset_no.to_string(),
-            bids: vec![(no_price, size)],
+            bids: vec![(no_price - dec!(0.01), size)],
             asks: vec![(no_price, size)],
             timestamp,
             hash: "hash2".to_string(),
@@ -98,7 +389,7 @@ mod tests {
             .unwrap();
 
         // detect_arbitrage is not async
-        let arb_op = arb_engine.detect_arbitrage(&orderbook_manager, market_id, &risk_manager);
+        let arb_op = arb_engine.detect_arbitrage(&orderbook_manager, market_id, None, &risk_manager);
         assert!(arb_op.is_ok());
 
         let opportunity = arb_op.unwrap();
@@ -112,6 +403,268 @@ mod tests {
         assert!(arb.total_edge > Decimal::ZERO);
     }
 
+    #[test]
+    fn test_evaluate_detects_on_caller_owned_books() {
+        use hfptm::monitoring::NearMissRecorder;
+        use hfptm::orderbook::{MarketBooks, OrderBook};
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+        config.monitoring.near_miss_band = dec!(0.1);
+        let near_misses = Arc::new(NearMissRecorder::new(&config.monitoring));
+        let engine = ArbEngine::new(&config).with_near_misses(near_misses.clone());
+        let risk_manager = RiskManager::new(&config);
+
+        // Books built by the caller, no OrderBookManager involved
+        let mut market_books = MarketBooks::new("own_market".to_string());
+        for (asset_id, ask) in [("own_yes", dec!(0.47)), ("own_no", dec!(0.48))] {
+            let mut book = OrderBook::new(
+                "own_market".to_string(),
+                asset_id.to_string(),
+                current_timestamp_ms(),
+                "h".to_string(),
+            );
+            book.asks.insert(ask, dec!(200));
+            market_books.books.push(book);
+        }
+        market_books.asset_id_yes = Some("own_yes".to_string());
+        market_books.asset_id_no = Some("own_no".to_string());
+
+        let arb = engine
+            .evaluate(&market_books, &risk_manager)
+            .expect("binary arb");
+        assert_eq!(arb.market_id, "own_market");
+        assert!(matches!(arb.arb_type, ArbType::Binary));
+        assert_eq!(arb.edges[0].outcome, "YES");
+        assert_eq!(arb.edges[1].price, dec!(0.48));
+
+        // Calling again gives the same answer and leaves the engine's stats alone
+        let again = engine.evaluate(&market_books, &risk_manager).unwrap();
+        assert_eq!(again.position_size, arb.position_size);
+        assert_eq!(engine.get_statistics().0, 0);
+        assert_eq!(engine.get_latency_stats().1, 0);
+
+        // Priced out: sum >= $1
+        market_books.books[1].asks.clear();
+        market_books.books[1].asks.insert(dec!(0.55), dec!(200));
+        assert!(engine.evaluate(&market_books, &risk_manager).is_none());
+
+        // Just under min_edge, and too thin: rejections that detection would
+        // count (and a near miss it would record) leave no trace
+        for (asks, size) in [(dec!(0.478), dec!(200)), (dec!(0.45), dec!(5))] {
+            for book in &mut market_books.books {
+                book.asks.clear();
+                book.asks.insert(asks, size);
+            }
+            assert!(engine.evaluate(&market_books, &risk_manager).is_none());
+        }
+        let counts = engine.rejection_counts("own_market");
+        assert_eq!((counts.edge, counts.liquidity), (0, 0));
+        assert!(near_misses.recent(10).is_empty());
+    }
+
+    #[test]
+    fn test_min_net_profit_floor_ignores_edge_percentage() {
+        let mut config = create_test_config();
+        config.trading.max_arb_size = 1000;
+        config.trading.min_liquidity = 5;
+        config.trading.min_net_profit_usd = dec!(5);
+        let mut arb_engine = ArbEngine::new(&config);
+        let orderbook_manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+
+        let book = |market: &str, asset: &str, ask: Decimal, size: Decimal| {
+            let snapshot = BookSnapshot {
+                market: market.to_string(),
+                asset_id: asset.to_string(),
+                bids: vec![(ask - dec!(0.01), size)],
+                asks: vec![(ask, size)],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            orderbook_manager.update_book(market, asset, &snapshot).unwrap();
+        };
+        orderbook_manager.register_market(&test_market("tiny", "Tiny", &["tiny_yes", "tiny_no"]));
+        orderbook_manager.register_market(&test_market("deep", "Deep", &["deep_yes", "deep_no"]));
+
+        // 18% net edge, but only $10 of depth: $1.80 profit
+        book("tiny", "tiny_yes", dec!(0.40), dec!(10));
+        book("tiny", "tiny_no", dec!(0.40), dec!(10));
+        let tiny = arb_engine
+            .detect_arbitrage(&orderbook_manager, "tiny", None, &risk_manager)
+            .unwrap();
+        assert!(tiny.is_none());
+
+        // 5% net edge on $500: $25 profit
+        book("deep", "deep_yes", dec!(0.45), dec!(500));
+        book("deep", "deep_no", dec!(0.48), dec!(500));
+        let deep = arb_engine
+            .detect_arbitrage(&orderbook_manager, "deep", None, &risk_manager)
+            .unwrap()
+            .expect("large low-edge arb should pass the profit floor");
+        assert_eq!(deep.net_profit, dec!(25));
+        assert_eq!(deep.total_edge, dec!(0.05));
+    }
+
+    #[test]
+    fn test_arb_size_limited_by_available_bankroll() {
+        let mut config = create_test_config();
+        config.trading.bankroll = 90;
+        config.trading.max_arb_size = 1000;
+        config.trading.min_liquidity = 5;
+        config.trading.min_net_profit_usd = dec!(1);
+        let mut arb_engine = ArbEngine::new(&config);
+        let orderbook_manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+
+        for (asset_id, ask) in [("cap_yes", dec!(0.45)), ("cap_no", dec!(0.48))] {
+            let snapshot = BookSnapshot {
+                market: "capped".to_string(),
+                asset_id: asset_id.to_string(),
+                bids: vec![(ask - dec!(0.01), dec!(500))],
+                asks: vec![(ask, dec!(500))],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            orderbook_manager
+                .update_book("capped", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        // $46.50 in the wallet buys 50 sets at $0.93, far below max_arb_size and depth
+        arb_engine.set_available_balance(dec!(46.5));
+        assert_eq!(arb_engine.available_capital(&risk_manager), dec!(46.5));
+        let arb = arb_engine
+            .detect_arbitrage(&orderbook_manager, "capped", None, &risk_manager)
+            .unwrap()
+            .expect("affordable arb should be detected");
+        assert_eq!(arb.position_size, dec!(50));
+
+        // Open positions cost $90: the whole bankroll is deployed
+        let held = binary_arb("held_market", dec!(100), dec!(500));
+        let result = ExecutionResult {
+            success: true,
+            filled: false,
+            partial_fill: false,
+            filled_amount: dec!(100),
+            total_cost: Decimal::ZERO,
+            expected_cost: dec!(90),
+            realized_cost: dec!(90),
+            realized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+        risk_manager
+            .record_arbitrage_execution(&held, &result)
+            .unwrap();
+
+        // A large wallet balance doesn't help once the bankroll is exhausted
+        arb_engine.set_available_balance(dec!(1000));
+        assert_eq!(risk_manager.available_bankroll(), Decimal::ZERO);
+        assert_eq!(arb_engine.available_capital(&risk_manager), Decimal::ZERO);
+        let exhausted = arb_engine
+            .detect_arbitrage(&orderbook_manager, "capped", None, &risk_manager)
+            .unwrap();
+        assert!(exhausted.is_none());
+    }
+
+    #[test]
+    fn test_sell_side_arb_on_held_complete_set() {
+        let mut config = create_test_config();
+        config.trading.min_edge = dec!(0.01);
+        config.trading.max_arb_size = 1000;
+        config.trading.min_liquidity = 5;
+        let mut arb_engine = ArbEngine::new(&config);
+        let orderbook_manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+
+        // Bids sum to 1.04: selling a set beats its $1 redemption value
+        for (asset, bid) in [("yes", dec!(0.53)), ("no", dec!(0.51))] {
+            let snapshot = BookSnapshot {
+                market: "set".to_string(),
+                asset_id: asset.to_string(),
+                bids: vec![(bid, dec!(500))],
+                asks: vec![(bid + dec!(0.02), dec!(500))],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            orderbook_manager
+                .update_book("set", asset, &snapshot)
+                .unwrap();
+        }
+
+        // Nothing held: no set to sell
+        let unheld = arb_engine
+            .detect_arbitrage(&orderbook_manager, "set", None, &risk_manager)
+            .unwrap();
+        assert!(unheld.is_none());
+
+        let held = |asset: &str| ExchangePosition {
+            market_id: "set".to_string(),
+            asset_id: asset.to_string(),
+            outcome: asset.to_string(),
+            size: dec!(100),
+            avg_price: dec!(0.48),
+        };
+        risk_manager
+            .seed_positions(&[held("yes"), held("no")], &[])
+            .unwrap();
+
+        // 100 sets: proceeds $104 - redemption value $100 - fees $2 = $2
+        let arb = arb_engine
+            .detect_arbitrage(&orderbook_manager, "set", None, &risk_manager)
+            .unwrap()
+            .expect("held set with bids above $1 should be sellable");
+        assert!(matches!(arb.arb_type, ArbType::SellCompleteSet));
+        assert_eq!(arb.position_size, dec!(100));
+        assert_eq!(arb.fee_cost, dec!(2));
+        assert_eq!(arb.net_profit, dec!(2));
+        assert_eq!(arb.total_edge, dec!(0.02));
+        assert_eq!(arb.edges[0].expected_cost, dec!(53));
+    }
+
+    #[tokio::test]
+    async fn test_adding_to_position_averages_cost_basis() {
+        let config = create_test_config();
+        let risk_manager = RiskManager::new(&config);
+        let result = ExecutionResult {
+            success: true,
+            filled: true,
+            partial_fill: false,
+            filled_amount: dec!(100),
+            total_cost: dec!(90),
+            expected_cost: dec!(90),
+            realized_cost: dec!(90),
+            realized_pnl: dec!(10),
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+
+        // 100 @ 0.45, then 50 more @ 0.60
+        let first = binary_arb("avg_market", dec!(100), dec!(500));
+        risk_manager
+            .record_arbitrage_execution(&first, &result)
+            .unwrap();
+        let mut second = binary_arb("avg_market", dec!(50), dec!(500));
+        for edge in &mut second.edges {
+            edge.price = dec!(0.60);
+            edge.expected_cost = dec!(30);
+        }
+        risk_manager
+            .record_arbitrage_execution(&second, &result)
+            .unwrap();
+
+        let position = risk_manager.get_position("yes_asset").unwrap();
+        assert_eq!(position.size, dec!(150));
+        assert_eq!(position.total_cost, dec!(75));
+        assert_eq!(position.avg_price, dec!(0.5));
+        assert_eq!(risk_manager.get_market_exposure("avg_market"), dec!(300));
+    }
+
     #[tokio::test]
     async fn test_risk_manager_basic() {
         let config = create_test_config();
@@ -130,25 +683,6872 @@ mod tests {
         assert!(!risk_manager.is_market_blacklisted("test_market"));
     }
 
+    fn binary_arb(market_id: &str, size: Decimal, min_liquidity: Decimal) -> ArbitrageOpportunity {
+        let edge = |asset_id: &str, outcome: &str| ArbEdge {
+            asset_id: asset_id.to_string(),
+            outcome: outcome.to_string(),
+            price: dec!(0.45),
+            size,
+            expected_cost: dec!(0.45) * size,
+        };
+
+        ArbitrageOpportunity {
+            market_id: market_id.to_string(),
+            arb_type: ArbType::Binary,
+            edges: vec![edge("yes_asset", "Yes"), edge("no_asset", "No")],
+            total_edge: dec!(0.1),
+            min_liquidity,
+            position_size: size,
+            expected_profit_usd: dec!(0.1) * size,
+            fee_cost: Decimal::ZERO,
+            net_profit: dec!(0.1) * size,
+            timestamp: current_timestamp_ms(),
+            detection_latency_ms: None,
+        }
+    }
+
+    async fn latest_alert(monitor: &Monitor) -> Alert {
+        monitor.get_recent_alerts(1).await.remove(0)
+    }
+
     #[tokio::test]
-    async fn test_latency_tracker() {
-        let mut tracker = LatencyTracker::new();
+    async fn test_risk_rejections_raise_risk_limit_alerts() {
+        let cases = [
+            (RiskRejectReason::MaxConcurrentArbs, AlertSeverity::Info),
+            (RiskRejectReason::MarketExposure, AlertSeverity::Warning),
+            (RiskRejectReason::EventExposure, AlertSeverity::Warning),
+            (RiskRejectReason::InventoryDrift, AlertSeverity::Error),
+            (RiskRejectReason::InsufficientLiquidity, AlertSeverity::Info),
+            (RiskRejectReason::MarketCooldown, AlertSeverity::Info),
+            (RiskRejectReason::MarketBlacklisted, AlertSeverity::Warning),
+            (RiskRejectReason::MarketPaused, AlertSeverity::Info),
+            (RiskRejectReason::StrategyBudget, AlertSeverity::Warning),
+            (RiskRejectReason::GlobalCapital, AlertSeverity::Warning),
+        ];
 
-        tracker.record(50_000_000);
-        tracker.record(150_000_000);
+        for (expected, severity) in cases {
+            let mut config = create_test_config();
+            config.risk.inventory_drift_threshold = dec!(1000);
+            let mut arb = binary_arb("risk_market", dec!(10), dec!(500));
 
-        assert_eq!(tracker.count(), 2);
-        assert_eq!(tracker.avg_latency_ns(), 100_000_000);
-        assert_eq!(tracker.avg_latency_ms(), 100.0);
+            let failures = match expected {
+                RiskRejectReason::MarketCooldown => 1,
+                RiskRejectReason::MarketBlacklisted => 3,
+                _ => 0,
+            };
+
+            match expected {
+                RiskRejectReason::MaxConcurrentArbs => config.risk.max_concurrent_arbs = 0,
+                RiskRejectReason::MarketExposure => arb.position_size = dec!(250),
+                RiskRejectReason::EventExposure => config.risk.max_exposure_per_event = 5,
+                RiskRejectReason::InventoryDrift => config.risk.inventory_drift_threshold = dec!(1),
+                RiskRejectReason::InsufficientLiquidity => arb.min_liquidity = dec!(10),
+                RiskRejectReason::MarketCooldown | RiskRejectReason::MarketPaused => {}
+                RiskRejectReason::MarketBlacklisted => config.risk.market_cooldown_secs = 0,
+                RiskRejectReason::StrategyBudget => config.strategy_budgets.arbitrage = 5,
+                RiskRejectReason::GlobalCapital => config.strategy_budgets.global_cap = 5,
+                RiskRejectReason::DailyLossLimit | RiskRejectReason::TradeSpacing => {
+                    unreachable!()
+                }
+            }
+
+            let risk_manager = RiskManager::new(&config);
+            for _ in 0..failures {
+                risk_manager.record_market_failure(&arb.market_id);
+            }
+            if expected == RiskRejectReason::MarketPaused {
+                risk_manager.pause_market(&arb.market_id);
+            }
+            let monitor = Monitor::new(&config).await.unwrap();
+
+            let decision = risk_manager.can_execute_arbitrage(&arb).unwrap();
+            assert_eq!(decision, RiskDecision::Reject(expected));
+            assert!(!decision.is_allowed());
+
+            monitor
+                .alert_risk_limit_breached(&arb.market_id, expected)
+                .await;
+            let alert = latest_alert(&monitor).await;
+            assert_eq!(alert.alert_type, AlertType::RiskLimitBreached);
+            assert_eq!(alert.severity, severity);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_global_capital_cap_blocks_strategy_within_its_budget() {
+        use hfptm::utils::StrategyBudgetsConfig;
+        use hfptm::volume_farmer::TrashOpportunity;
+        use hfptm::{Strategy, VolumeFarmer};
+
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        config.strategy_budgets = StrategyBudgetsConfig {
+            arbitrage: 0,
+            market_making: 0,
+            volume_farming: 50,
+            global_cap: 100,
+        };
+        let risk_manager = RiskManager::new(&config);
+        let capital = risk_manager.capital().clone();
+        let mut farmer = VolumeFarmer::new(&config).with_capital(capital.clone());
+
+        // A $90 arb fits under the $100 cap and stays committed while held
+        let arb = binary_arb("cap_market", dec!(100), dec!(500));
+        assert!(risk_manager
+            .can_execute_arbitrage(&arb)
+            .unwrap()
+            .is_allowed());
+        let result = ExecutionResult {
+            success: true,
+            filled: true,
+            partial_fill: false,
+            filled_amount: dec!(100),
+            total_cost: dec!(90),
+            expected_cost: dec!(90),
+            realized_cost: dec!(90),
+            realized_pnl: dec!(10),
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+        risk_manager
+            .record_arbitrage_execution(&arb, &result)
+            .unwrap();
+        assert_eq!(capital.committed(&Strategy::Arbitrage), dec!(90));
+
+        // $15 is well inside volume farming's $50 budget, but the cap is spent
+        let trash = TrashOpportunity {
+            market_id: "trash_market".to_string(),
+            asset_id: "trash_asset".to_string(),
+            outcome_name: "No".to_string(),
+            price: dec!(0.01),
+            available_size: dec!(10000),
+            cost_for_volume: dec!(15),
+            notional_volume: dec!(1500),
+            volume_multiplier: dec!(100),
+        };
+        assert_eq!(
+            capital.check(&Strategy::VolumeFarming, dec!(15)),
+            RiskDecision::Reject(RiskRejectReason::GlobalCapital)
+        );
+        assert!(farmer.simulate_trash_trade(&trash).await.unwrap().is_none());
+        assert_eq!(capital.committed(&Strategy::VolumeFarming), Decimal::ZERO);
+
+        // Its own budget still binds once the arb's capital comes back
+        capital.release(&Strategy::Arbitrage, dec!(90));
+        assert_eq!(
+            capital.check(&Strategy::VolumeFarming, dec!(60)),
+            RiskDecision::Reject(RiskRejectReason::StrategyBudget)
+        );
+        assert!(farmer.simulate_trash_trade(&trash).await.unwrap().is_some());
+        assert_eq!(capital.committed(&Strategy::VolumeFarming), dec!(15));
+        assert_eq!(capital.total_committed(), dec!(15));
+
+        // The position holds its capital until the market is gone
+        let listed = [test_market(
+            "trash_market",
+            "Trash?",
+            &["trash_asset", "other"],
+        )];
+        assert_eq!(farmer.release_resolved(&listed), Decimal::ZERO);
+        assert_eq!(capital.committed(&Strategy::VolumeFarming), dec!(15));
+        assert_eq!(farmer.release_resolved(&[]), dec!(15));
+        assert_eq!(capital.committed(&Strategy::VolumeFarming), Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_capital_goes_to_highest_ev_opportunity_first() {
+        use hfptm::arb_engine::{ShortWindowArbOpportunity, ShortWindowLeg};
+        use hfptm::market_maker::{LadderRung, MMOpportunity};
+        use hfptm::risk::ev::{fund_by_ev, rank_by_ev, EV_HALF_LIFE_SECS};
+        use hfptm::risk::{Candidate, ScoreEv};
+        use hfptm::utils::sim::SimEnv;
+        use hfptm::utils::StrategyBudgetsConfig;
+        use hfptm::volume_farmer::TrashOpportunity;
+        use hfptm::{ShortWindowArbTracker, Strategy, VolumeFarmer};
+
+        let mut config = create_test_config();
+        config.strategy_budgets = StrategyBudgetsConfig {
+            arbitrage: 0,
+            market_making: 0,
+            volume_farming: 0,
+            global_cap: 25,
+        };
+        let risk_manager = RiskManager::new(&config);
+        let capital = risk_manager.capital().clone();
+        let env = SimEnv::deterministic(7, 1_700_000_000_000);
+        let mut tracker = ShortWindowArbTracker::with_sim_env(dec!(1000), env.clone());
+        let mut mm = MarketMaker::new(&config).with_capital(capital.clone());
+        let mut farmer = VolumeFarmer::new(&config).with_capital(capital.clone());
+
+        // $0.50 on $19.40 for 10 minutes
+        let leg = |asset_id: &str, price| ShortWindowLeg {
+            asset_id: asset_id.to_string(),
+            outcome: asset_id.to_uppercase(),
+            price,
+            size: dec!(100),
+            depth: vec![(price, dec!(100))],
+        };
+        let arb = ShortWindowArbOpportunity {
+            market_id: "btc-15m".to_string(),
+            market_question: "Will BTC be up in 15 minutes?".to_string(),
+            minutes_to_expiry: 10,
+            legs: vec![leg("yes", dec!(0.48)), leg("no", dec!(0.49))],
+            sum_prices: dec!(0.97),
+            vwap_sum: dec!(0.97),
+            raw_edge: dec!(0.03),
+            net_edge: dec!(0.01),
+            vwap_net_edge: dec!(0.025),
+            position_size: dec!(20),
+            expected_profit: dec!(0.50),
+            min_liquidity: dec!(100),
+            detected_at: env.clock.now_ms(),
+            annualized_return: Decimal::ZERO,
+            assumed_fill_probability: dec!(0.25),
+            assumed_capital_utilization: dec!(0.5),
+        };
+        // $1 of spread an hour on a $24.50 bid
+        let quote = MMOpportunity {
+            market_id: "mm_market".to_string(),
+            asset_id: "mm_yes".to_string(),
+            midpoint: dec!(0.50),
+            bid_price: dec!(0.49),
+            ask_price: dec!(0.51),
+            size: dec!(50),
+            spread_bps: dec!(400),
+            estimated_reward: dec!(0.01),
+            ladder: vec![LadderRung {
+                level: 0,
+                bid_price: dec!(0.49),
+                ask_price: dec!(0.51),
+                size: dec!(50),
+                estimated_reward: dec!(0.01),
+            }],
+        };
+        // $1.50 of airdrop credit on $15 held for a day
+        let trash = TrashOpportunity {
+            market_id: "trash_market".to_string(),
+            asset_id: "trash_asset".to_string(),
+            outcome_name: "No".to_string(),
+            price: dec!(0.01),
+            available_size: dec!(10000),
+            cost_for_volume: dec!(15),
+            notional_volume: dec!(1500),
+            volume_multiplier: dec!(100),
+        };
+
+        let candidates = vec![
+            Candidate::VolumeFarming(trash),
+            Candidate::MarketMaking(quote),
+            Candidate::ShortWindowArb(arb),
+        ];
+        let now_ms = env.clock.now_ms();
+        let ranked: Vec<Strategy> = rank_by_ev(candidates.clone(), now_ms)
+            .iter()
+            .map(Candidate::strategy)
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                Strategy::Arbitrage,
+                Strategy::MarketMaking,
+                Strategy::VolumeFarming
+            ]
+        );
+        assert!(candidates
+            .iter()
+            .all(|c| c.score_ev(now_ms) > Decimal::ZERO));
+
+        // The arb ages on the simulation clock, halving every EV_HALF_LIFE_SECS
+        let fresh = candidates[2].score_ev(now_ms);
+        let aged = candidates[2].score_ev(now_ms + EV_HALF_LIFE_SECS * 1000);
+        assert!((aged * dec!(2) - fresh).abs() < dec!(0.000000000001) * fresh);
+
+        // The arb takes $19.40 of the $25 cap; neither of the others fits after it
+        let funded = fund_by_ev(
+            candidates,
+            &capital,
+            &env.clock,
+            &mut tracker,
+            &mut mm,
+            &mut farmer,
+        )
+        .await;
+        assert_eq!(funded.len(), 1);
+        assert_eq!(funded[0].market_id(), "btc-15m");
+        assert_eq!(capital.committed(&Strategy::Arbitrage), dec!(19.40));
+        assert_eq!(capital.committed(&Strategy::MarketMaking), Decimal::ZERO);
+        assert_eq!(capital.committed(&Strategy::VolumeFarming), Decimal::ZERO);
+        assert!(tracker.has_open_trade("btc-15m"));
+        assert!(farmer.get_trades().is_empty());
+    }
+
+    #[test]
+    fn test_quality_scoring_is_configurable() {
+        let mut config = create_test_config();
+        let arb = binary_arb("quality_market", dec!(100), dec!(500));
+
+        // Defaults: 0.1 edge => 10 * 0.4, $500 liquidity / 1000 => 0.5 * 0.3,
+        // $100 size / 500 => 0.2 * 0.2, $10 profit / 50 => 0.2 * 0.1
+        let default_score = arb.calculate_quality_score(&config.quality_scoring);
+        assert_eq!(default_score, dec!(4.21));
+
+        config.quality_scoring.liquidity_divisor = dec!(100);
+        let small_bankroll_score = arb.calculate_quality_score(&config.quality_scoring);
+        assert_eq!(small_bankroll_score, dec!(5.56));
+
+        // 4.21 falls below the default 5.0 cutoff; the retuned score clears it
+        let engine = ArbEngine::new(&create_test_config());
+        assert!(!engine.should_execute_opportunity(&arb));
+        let engine = ArbEngine::new(&config);
+        assert!(engine.should_execute_opportunity(&arb));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_same_event_markets_share_the_event_exposure_cap() {
+        use hfptm::arb_engine::OpportunityLeg;
+        use hfptm::executor::SimulationExecutor;
+        use hfptm::parallel_scanner::{CrossArbType, CrossMarketOpportunity};
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        config.risk.max_exposure_per_event = 150;
+        config.trading.min_liquidity = 0;
+        let risk_manager = RiskManager::new(&config);
+        risk_manager.register_market(&event_market("btc_event", "btc_100k", "BTC above 100k?"));
+        risk_manager.register_market(&event_market("btc_event", "btc_110k", "BTC above 110k?"));
+        risk_manager.register_market(&event_market("eth_event", "eth_5k", "ETH above 5k?"));
+
+        let manager = OrderBookManager::new(&config).unwrap();
+        for (market_id, asset_id, ask) in [
+            ("btc_100k", "btc_100k_yes", dec!(0.6)),
+            ("btc_110k", "btc_110k_no", dec!(0.3)),
+            ("eth_5k", "eth_5k_no", dec!(0.3)),
+        ] {
+            let snapshot = ask_snapshot(market_id, asset_id, vec![(ask, dec!(100))]);
+            manager.update_book(market_id, asset_id, &snapshot).unwrap();
+        }
+        let leg = |market_id: &str, asset_id: &str, outcome: &str, price: Decimal| OpportunityLeg {
+            market_id: market_id.to_string(),
+            asset_id: asset_id.to_string(),
+            outcome: outcome.to_string(),
+            side: ArbSide::Buy,
+            price,
+            size: dec!(80),
+        };
+        let pair = |market_b: &str| CrossMarketOpportunity {
+            market_a_id: "btc_100k".to_string(),
+            market_b_id: market_b.to_string(),
+            market_a_question: "YES@0.600".to_string(),
+            market_b_question: "NO@0.300".to_string(),
+            arb_type: CrossArbType::LogicalImplication,
+            edge: dec!(0.1),
+            position_size: dec!(80),
+            expected_profit: dec!(8),
+            confidence: Decimal::ONE,
+            detected_at: chrono::Utc::now().timestamp_millis(),
+            legs: vec![
+                leg("btc_100k", "btc_100k_yes", "Yes", dec!(0.6)),
+                leg(market_b, &format!("{}_no", market_b), "No", dec!(0.3)),
+            ],
+            min_liquidity: dec!(100),
+        };
+
+        let simulator: Arc<dyn Executor> = Arc::new(SimulationExecutor::new(&config));
+        let client = WebSocketClient::new(&config, &[], simulator).await.unwrap();
+        let mut monitor = Monitor::new(&config).await.unwrap();
+
+        // $80 a leg is inside each market's $200 cap, but both legs land in one
+        // event: $160 > $150, so nothing is placed
+        client
+            .execute_arbitrage(&pair("btc_110k"), &manager, &risk_manager, &mut monitor)
+            .await
+            .unwrap();
+        let alert = latest_alert(&monitor).await;
+        assert_eq!(alert.alert_type, AlertType::RiskLimitBreached);
+        assert!(risk_manager.get_position("btc_100k_yes").is_none());
+        assert_eq!(risk_manager.get_event_exposure("btc_event"), Decimal::ZERO);
+
+        // Across two events each only takes $80, and both legs are bought
+        client
+            .execute_arbitrage(&pair("eth_5k"), &manager, &risk_manager, &mut monitor)
+            .await
+            .unwrap();
+        assert_eq!(
+            risk_manager.get_position("eth_5k_no").unwrap().size,
+            dec!(80)
+        );
+        assert_eq!(risk_manager.get_event_exposure("btc_event"), dec!(80));
+        assert_eq!(risk_manager.get_event_exposure("eth_event"), dec!(80));
+        // Start the sibling check below from an empty event
+        risk_manager.release_exposure("btc_100k", dec!(80));
+
+        // A position in one market counts against its sibling's event too
+        let arb = binary_arb("btc_100k", dec!(30), dec!(500));
+        let result = ExecutionResult {
+            success: true,
+            filled: true,
+            partial_fill: false,
+            filled_amount: dec!(30),
+            total_cost: dec!(27),
+            expected_cost: dec!(27),
+            realized_cost: dec!(27),
+            realized_pnl: dec!(3),
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+        risk_manager
+            .record_arbitrage_execution(&arb, &result)
+            .unwrap();
+        assert_eq!(risk_manager.get_event_exposure("btc_event"), dec!(60));
+        assert_eq!(
+            risk_manager
+                .can_execute_arbitrage(&binary_arb("btc_110k", dec!(100), dec!(500)))
+                .unwrap(),
+            RiskDecision::Reject(RiskRejectReason::EventExposure)
+        );
+        risk_manager.release_exposure("btc_100k", dec!(60));
+        assert_eq!(risk_manager.get_event_exposure("btc_event"), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_risk_decision_allows_within_limits() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let risk_manager = RiskManager::new(&config);
+
+        let arb = binary_arb("ok_market", dec!(10), dec!(500));
+        let decision = risk_manager.can_execute_arbitrage(&arb).unwrap();
+        assert_eq!(decision, RiskDecision::Allow);
+        assert!(decision.is_allowed());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shared_risk_manager_under_concurrent_load() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000000);
+        config.risk.max_concurrent_arbs = 1000;
+        // 200 $9 arbs commit more than the $1000 bankroll
+        config.strategy_budgets.global_cap = 10000;
+        let risk_manager = std::sync::Arc::new(RiskManager::new(&config));
+        let orderbook_manager = std::sync::Arc::new(OrderBookManager::new(&config).unwrap());
+
+        let result = ExecutionResult {
+            success: true,
+            filled: true,
+            partial_fill: false,
+            filled_amount: dec!(10),
+            total_cost: dec!(9),
+            expected_cost: dec!(9),
+            realized_cost: dec!(9),
+            realized_pnl: dec!(1),
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+
+        let tasks: Vec<_> = (0..8)
+            .map(|task| {
+                let risk_manager = risk_manager.clone();
+                let orderbook_manager = orderbook_manager.clone();
+                let result = result.clone();
+                tokio::spawn(async move {
+                    for i in 0..25 {
+                        let arb = binary_arb(&format!("m{}_{}", task, i), dec!(10), dec!(500));
+                        assert!(risk_manager.can_execute_arbitrage(&arb).unwrap().is_allowed());
+                        risk_manager.record_arbitrage_execution(&arb, &result).unwrap();
+                        risk_manager.record_market_failure("shared_flaky_market");
+                        risk_manager.mark_to_market(&orderbook_manager);
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // No update was lost across tasks
+        let summary = risk_manager.get_risk_summary();
+        assert_eq!(summary.active_arbitrages, 200);
+        assert_eq!(summary.daily_trades, 200);
+        assert_eq!(summary.market_exposure.len(), 200);
+        assert!(summary.market_exposure.values().all(|e| *e == dec!(20)));
+        assert_eq!(risk_manager.get_daily_pnl().realized_pnl, dec!(200));
+        assert!(risk_manager.is_market_blacklisted("shared_flaky_market"));
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_config_endpoint_redacts_secrets() {
+        let mut config = create_test_config();
+        config.server.polygon_rpc_url =
+            "https://polygon-mainnet.example/v2/rpc-key-123".to_string();
+        config.alerts.telegram_bot_token = "123456:telegram-secret".to_string();
+        config.alerts.webhook_url = "https://hooks.example/webhook-secret".to_string();
+        config.credentials.api_secret = "api-secret-456".to_string();
+        config.monitoring.dashboard_token = "dashboard-token-789".to_string();
+
+        // Grab a free port for the dashboard
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        config.monitoring.dashboard_port = port;
+
+        let monitor = Monitor::new(&config).await.unwrap();
+        monitor.start_dashboard().await;
+
+        let body = reqwest::get(format!("http://127.0.0.1:{}/config", port))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let effective: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        // Trading params and resolved defaults are there
+        assert_eq!(effective["trading"]["min_edge"], "0.025");
+        assert_eq!(effective["trading"]["bankroll"], 1000);
+        assert_eq!(
+            effective["trading"]["short_window_edge_curve_mode"],
+            "linear"
+        );
+        assert_eq!(effective["correlation"]["min_word_len"], 3);
+        assert_eq!(
+            effective["credentials"]["funder_address"],
+            config.credentials.funder_address
+        );
+
+        // Secrets are not
+        for secret in [
+            &config.credentials.private_key,
+            &config.credentials.api_key,
+            &config.credentials.api_secret,
+            &config.credentials.api_passphrase,
+            &config.server.polygon_rpc_url,
+            &config.alerts.telegram_bot_token,
+            &config.alerts.webhook_url,
+            &config.monitoring.dashboard_token,
+        ] {
+            assert!(!body.contains(secret.as_str()), "leaked {}", secret);
+        }
+        assert!(effective["credentials"].get("private_key").is_none());
+        assert_eq!(effective["alerts"]["telegram_bot_token"], "<redacted>");
+        // Unset secrets stay empty so it's clear they weren't configured
+        assert_eq!(effective["alerts"]["discord_webhook_url"], "");
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_pause_requires_token_and_known_market() {
+        let mut config = create_test_config();
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        config.monitoring.dashboard_port = port;
+        config.monitoring.dashboard_token = "dashboard-secret".to_string();
+
+        let monitor = Monitor::new(&config).await.unwrap();
+        monitor.register_market(&test_market("0xknown", "Will it happen?", &["yes", "no"]));
+        monitor.start_dashboard().await;
+        let client = reqwest::Client::new();
+        let post = |path: &str, token: Option<&str>| {
+            let request = client.post(format!("http://127.0.0.1:{}{}", port, path));
+            match token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            }
+            .send()
+        };
+
+        for token in [None, Some("wrong")] {
+            let response = post("/markets/0xknown/pause", token).await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        }
+        assert!(monitor.paused_markets().is_empty());
+
+        let token = Some("dashboard-secret");
+        let response = post("/markets/0xunknown/pause", token).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let response = post("/markets/0xknown/pause", token).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let paused: Vec<String> = response.json().await.unwrap();
+        assert_eq!(paused, ["0xknown"]);
+
+        let response = post("/markets/0xknown/resume", token).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(monitor.paused_markets().is_empty());
+
+        // Without a token, only local callers may change anything
+        config.monitoring.dashboard_token.clear();
+        config.monitoring.dashboard_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let local = Monitor::new(&config).await.unwrap();
+        local.register_market(&test_market("0xknown", "Will it happen?", &["yes", "no"]));
+        local.start_dashboard().await;
+        let response = client
+            .post(format!(
+                "http://127.0.0.1:{}/markets/0xknown/pause",
+                config.monitoring.dashboard_port
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(local.paused_markets().contains("0xknown"));
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_health_reflects_websocket_state() {
+        let mut config = create_test_config();
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        config.monitoring.dashboard_port = port;
+
+        let monitor = Monitor::new(&config).await.unwrap();
+        monitor.start_dashboard().await;
+        let url = format!("http://127.0.0.1:{}/health", port);
+
+        // Not connected yet: unhealthy, and the body says why
+        monitor.set_websocket_connected(false).await;
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let report: serde_json::Value =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        assert_eq!(report["status"], "degraded");
+        let websocket = report["subsystems"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|subsystem| subsystem["name"] == "websocket")
+            .unwrap();
+        assert_eq!(websocket["healthy"], false);
+        assert_eq!(websocket["detail"], "disconnected");
+
+        // Connected, executor reachable and detection running: healthy
+        monitor.set_websocket_connected(true).await;
+        monitor.record_executor_health(true);
+        monitor.record_detection_pass();
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        // A failing executor check degrades it again
+        monitor.record_executor_health(false);
+        let report = monitor.health_report().await;
+        assert!(!report.is_healthy());
+        assert_eq!(
+            report
+                .subsystems
+                .iter()
+                .filter(|subsystem| !subsystem.healthy)
+                .map(|subsystem| subsystem.name)
+                .collect::<Vec<_>>(),
+            vec!["executor"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explain_sizing_names_the_binding_cap() {
+        use hfptm::arb_engine::SizingCap;
+
+        // min_edge 2.5%, max_arb_size $100
+        let config = create_test_config();
+        let engine = ArbEngine::new(&config);
+
+        // 2% raw edge scales the $100 limit down to $80
+        let sizing = engine.explain_sizing(dec!(0.02), dec!(500), dec!(1000));
+        assert_eq!(sizing.by_edge, dec!(80));
+        assert_eq!(sizing.size, dec!(80));
+        assert_eq!(sizing.binding, SizingCap::Edge);
+
+        // 5% would allow $200 by edge, but only $60 rests on the thin leg
+        let sizing = engine.explain_sizing(dec!(0.05), dec!(60), dec!(1000));
+        assert_eq!(sizing.by_edge, dec!(200));
+        assert_eq!(sizing.size, dec!(60));
+        assert_eq!(sizing.binding, SizingCap::Liquidity);
+
+        // $38 buys 40 sets at $0.95
+        let sizing = engine.explain_sizing(dec!(0.05), dec!(500), dec!(38));
+        assert_eq!(sizing.by_bankroll, Some(dec!(40)));
+        assert_eq!(sizing.size, dec!(40));
+        assert_eq!(sizing.binding, SizingCap::Bankroll);
+
+        // Plenty of everything: max_arb_size is what's left
+        let sizing = engine.explain_sizing(dec!(0.05), dec!(500), dec!(1000));
+        assert_eq!(sizing.by_limit, dec!(100));
+        assert_eq!(sizing.size, dec!(100));
+        assert_eq!(sizing.binding, SizingCap::Limit);
+
+        // Detection sizes the same book the same way
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let market = test_market("0xsized", "Will it happen?", &["yes", "no"]);
+        manager.register_market(&market);
+        for asset_id in ["yes", "no"] {
+            let snapshot = ask_snapshot("0xsized", asset_id, vec![(dec!(0.475), dec!(150))]);
+            manager.update_book("0xsized", asset_id, &snapshot).unwrap();
+        }
+        let mut engine = engine;
+        let op = engine
+            .detect_arbitrage(&manager, "0xsized", Some(&market), &risk_manager)
+            .unwrap()
+            .unwrap();
+        let sizing = engine.explain_sizing(
+            dec!(0.05),
+            dec!(150),
+            engine.available_capital(&risk_manager),
+        );
+        assert_eq!(op.position_size, sizing.size);
+        assert_eq!(sizing.binding, SizingCap::Limit);
+    }
+
+    #[tokio::test]
+    async fn test_near_misses_recorded_within_band() {
+        use hfptm::monitoring::NearMissKind;
+
+        let mut config = create_test_config();
+        config.monitoring.near_miss_band = dec!(0.1);
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let monitor = Monitor::new(&config).await.unwrap();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config).with_near_misses(monitor.near_misses());
+        let mut engine = ArbEngine::new(&config).with_near_misses(monitor.near_misses());
+
+        let mut book_pair = |market_id: &str, ask: Decimal| {
+            let market = test_market(market_id, "Will it happen?", &["yes", "no"]);
+            manager.register_market(&market);
+            for asset_id in ["yes", "no"] {
+                let snapshot = ask_snapshot(market_id, asset_id, vec![(ask, dec!(200))]);
+                manager.update_book(market_id, asset_id, &snapshot).unwrap();
+            }
+            engine
+                .detect_arbitrage(&manager, market_id, Some(&market), &risk_manager)
+                .unwrap()
+        };
+
+        // 0.478 + 0.478 + 2% fees = 2.4% net, just under the 2.5% min_edge
+        assert!(book_pair("almost", dec!(0.478)).is_none());
+        // 1% net is nowhere near passing
+        assert!(book_pair("far_off", dec!(0.485)).is_none());
+
+        // $210 against the $200 market limit is close; $300 isn't
+        for (market_id, size) in [("slightly_over", dec!(210)), ("way_over", dec!(300))] {
+            let decision = risk_manager
+                .can_execute_arbitrage(&binary_arb(market_id, size, dec!(500)))
+                .unwrap();
+            assert_eq!(
+                decision,
+                RiskDecision::Reject(RiskRejectReason::MarketExposure)
+            );
+        }
+
+        let near_misses = monitor.near_misses().recent(10);
+        assert_eq!(near_misses.len(), 2);
+
+        assert_eq!(near_misses[0].market_id, "slightly_over");
+        assert_eq!(near_misses[0].kind, NearMissKind::MarketExposure);
+        assert_eq!(near_misses[0].value, dec!(210));
+        assert_eq!(near_misses[0].threshold, dec!(200));
+
+        assert_eq!(near_misses[1].market_id, "almost");
+        assert_eq!(near_misses[1].kind, NearMissKind::Edge);
+        assert_eq!(near_misses[1].value, dec!(0.024));
+        assert_eq!(near_misses[1].threshold, config.trading.min_edge);
+
+        // The same market missing the same threshold again is held back for a
+        // cooldown; another threshold or market still gets through
+        let recorder = monitor.near_misses();
+        recorder.record_below("almost", NearMissKind::Edge, dec!(0.0245), dec!(0.025));
+        recorder.record_below("almost", NearMissKind::Liquidity, dec!(95), dec!(100));
+        recorder.record_below("other", NearMissKind::Edge, dec!(0.0245), dec!(0.025));
+        let near_misses = recorder.recent(10);
+        assert_eq!(near_misses.len(), 4);
+        assert_eq!(near_misses[0].market_id, "other");
+        assert_eq!(near_misses[1].kind, NearMissKind::Liquidity);
+        assert_eq!(near_misses[3].value, dec!(0.024));
+
+        // Off by default
+        let quiet = Monitor::new(&create_test_config()).await.unwrap();
+        quiet
+            .near_misses()
+            .record_below("m", NearMissKind::Edge, dec!(0.024), dec!(0.025));
+        assert!(quiet.near_misses().recent(10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejections_counted_by_reason_per_market() {
+        use hfptm::monitoring::RejectionCounts;
+
+        let config = create_test_config();
+        let monitor = Monitor::new(&config).await.unwrap();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let rejections = monitor.rejections();
+        let mut engine = ArbEngine::new(&config).with_rejections(rejections.clone());
+
+        let market = test_market("0xthin", "Will it happen?", &["yes", "no"]);
+        manager.register_market(&market);
+        let mut feed = |ask: Decimal, size: Decimal| {
+            for asset_id in ["yes", "no"] {
+                let snapshot = ask_snapshot("0xthin", asset_id, vec![(ask, size)]);
+                manager.update_book("0xthin", asset_id, &snapshot).unwrap();
+            }
+            engine
+                .detect_arbitrage(&manager, "0xthin", Some(&market), &risk_manager)
+                .unwrap()
+        };
+
+        // A wide gap on $50 of depth against the $100 min_liquidity
+        assert!(feed(dec!(0.45), dec!(50)).is_none());
+        assert_eq!(
+            rejections.for_market("0xthin"),
+            RejectionCounts {
+                liquidity: 1,
+                ..Default::default()
+            }
+        );
+
+        // Deep enough now, but 0.478 + 0.478 nets 2.4% against the 2.5% min_edge
+        assert!(feed(dec!(0.478), dec!(200)).is_none());
+        assert_eq!(
+            rejections.for_market("0xthin"),
+            RejectionCounts {
+                liquidity: 1,
+                edge: 1,
+                ..Default::default()
+            }
+        );
+
+        let (detections, _, _, totals) = engine.get_statistics();
+        assert_eq!(detections, 0);
+        assert_eq!(totals.total(), 2);
+        assert_eq!(
+            engine.rejection_counts("0xother"),
+            RejectionCounts::default()
+        );
+
+        // The monitor's copy is what the dashboard serves
+        let report = rejections.report();
+        assert_eq!(report.totals, totals);
+        assert_eq!(report.markets["0xthin"].edge, 1);
+    }
+
+    #[tokio::test]
+    async fn test_paused_market_skipped_until_resumed() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let monitor = Monitor::new(&config).await.unwrap();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let paused_markets = monitor.paused_markets();
+        let risk_manager = RiskManager::new(&config).with_paused_markets(paused_markets.clone());
+        let rejections = monitor.rejections();
+        let mut engine = ArbEngine::new(&config).with_rejections(rejections.clone());
+
+        let market = test_market("0xpaused", "Will it happen?", &["yes", "no"]);
+        manager.register_market(&market);
+        for asset_id in ["yes", "no"] {
+            let snapshot = ask_snapshot("0xpaused", asset_id, vec![(dec!(0.45), dec!(200))]);
+            manager
+                .update_book("0xpaused", asset_id, &snapshot)
+                .unwrap();
+        }
+        let mut detect = || {
+            engine
+                .detect_arbitrage(&manager, "0xpaused", Some(&market), &risk_manager)
+                .unwrap()
+        };
+        assert!(detect().is_some());
+
+        // What the dashboard's pause handler does
+        paused_markets.insert("0xpaused".to_string());
+        assert!(detect().is_none());
+        assert_eq!(rejections.for_market("0xpaused").blacklist, 1);
+
+        // Opportunities already in flight are stopped at the risk check too
+        let arb = binary_arb("0xpaused", dec!(10), dec!(500));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&arb).unwrap(),
+            RiskDecision::Reject(RiskRejectReason::MarketPaused)
+        );
+        assert!(!risk_manager.is_market_blacklisted("0xpaused"));
+
+        assert!(risk_manager.resume_market("0xpaused"));
+        assert!(!risk_manager.resume_market("0xpaused"));
+        assert!(paused_markets.is_empty());
+        assert!(detect().is_some());
+        assert!(risk_manager
+            .can_execute_arbitrage(&arb)
+            .unwrap()
+            .is_allowed());
+    }
+
+    #[tokio::test]
+    async fn test_daily_summary_aggregates_recorded_trades() {
+        use hfptm::monitoring::{DailySummary, MarketPnl, TradeRecord};
+
+        let config = create_test_config();
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let reporter = monitor.daily_reporter();
+
+        let result = |filled: bool, realized_pnl: Decimal, fees_paid: Decimal| ExecutionResult {
+            success: true,
+            filled,
+            partial_fill: !filled,
+            filled_amount: dec!(10),
+            total_cost: dec!(9),
+            expected_cost: dec!(9),
+            realized_cost: dec!(9),
+            realized_pnl,
+            fees_paid,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+        let arb_a = binary_arb("market_a", dec!(10), dec!(500));
+        let arb_b = binary_arb("market_b", dec!(10), dec!(500));
+
+        // A: +0.8 and a one-legged -4.5 (no fee); B: +0.7 twice
+        let executions = [
+            (&arb_a, result(true, dec!(0.8), dec!(0.2))),
+            (&arb_a, result(false, dec!(-4.5), Decimal::ZERO)),
+            (&arb_b, result(true, dec!(0.7), dec!(0.3))),
+            (&arb_b, result(true, dec!(0.7), dec!(0.3))),
+        ];
+        for (arb, r) in &executions {
+            monitor
+                .record_arbitrage_executed(*arb, r, std::time::Duration::from_millis(5))
+                .await;
+        }
+
+        // Nothing to report until the day rolls over
+        let today = chrono::Utc::now().date_naive();
+        assert!(risk_manager
+            .roll_daily_pnl_at(&today.format("%Y-%m-%d").to_string())
+            .is_none());
+        let tomorrow = today.succ_opt().unwrap().format("%Y-%m-%d").to_string();
+        let closed = risk_manager.roll_daily_pnl_at(&tomorrow).unwrap();
+        assert_eq!(closed.date, today.format("%Y-%m-%d").to_string());
+        assert!(risk_manager.roll_daily_pnl_at(&tomorrow).is_none());
+
+        let summary = reporter.summarize(&closed, dec!(1200), dec!(3.5));
+        assert_eq!(summary.trades, 4);
+        assert_eq!(summary.wins, 3);
+        assert_eq!(summary.win_rate, 0.75);
+        assert_eq!(summary.realized_pnl, dec!(-2.3));
+        assert_eq!(summary.fees_paid, dec!(0.8));
+        assert_eq!(
+            summary.best_market,
+            Some(MarketPnl {
+                market_id: "market_b".to_string(),
+                pnl: dec!(1.4),
+            })
+        );
+        assert_eq!(
+            summary.worst_market,
+            Some(MarketPnl {
+                market_id: "market_a".to_string(),
+                pnl: dec!(-3.7),
+            })
+        );
+        assert_eq!(summary.volume_farmed, dec!(1200));
+        assert_eq!(summary.mm_rewards_estimate, dec!(3.5));
+
+        // Those trades are now reported; the next day starts empty
+        let next = reporter.summarize(&risk_manager.get_daily_pnl(), Decimal::ZERO, Decimal::ZERO);
+        assert_eq!(next.trades, 0);
+        assert_eq!(next.best_market, None);
+
+        // Trades from other days are left out
+        let record = |timestamp: i64| TradeRecord {
+            timestamp,
+            market_id: "market_c".to_string(),
+            arb_type: "Binary".to_string(),
+            position_size: dec!(10),
+            expected_profit: dec!(1),
+            actual_profit: dec!(1),
+            fees: Decimal::ZERO,
+            execution_time_ms: 1,
+            success: true,
+        };
+        // 2023-11-14 22:13:20 and 2023-11-15 00:00:00 UTC
+        let trades = [record(1_700_000_000), record(1_700_006_400)];
+        assert_eq!(DailySummary::from_trades("2023-11-14", &trades).trades, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fill_rate_and_slippage_metrics() {
+        let config = create_test_config();
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let arb = binary_arb("fill_market", dec!(100), dec!(500));
+
+        let result = |filled: bool, partial: bool, expected: Decimal, realized: Decimal| {
+            ExecutionResult {
+                success: filled,
+                filled,
+                partial_fill: partial,
+                filled_amount: expected,
+                total_cost: Decimal::ZERO,
+                expected_cost: expected,
+                realized_cost: realized,
+                realized_pnl: Decimal::ZERO,
+                fees_paid: Decimal::ZERO,
+                orders: vec![],
+                execution_time_ms: 1,
+                error_message: None,
+            }
+        };
+
+        let results = [
+            result(true, false, dec!(100), dec!(101)),
+            result(true, false, dec!(100), dec!(100)),
+            result(false, true, dec!(50), dec!(49.5)),
+            result(false, false, Decimal::ZERO, Decimal::ZERO),
+        ];
+        for r in &results {
+            monitor
+                .record_arbitrage_executed(&arb, r, std::time::Duration::from_millis(5))
+                .await;
+        }
+
+        let metrics = monitor.get_metrics();
+        assert_eq!(metrics.fills, 2);
+        assert_eq!(metrics.partial_fills, 1);
+        assert_eq!(metrics.arb_missed, 2);
+        assert_eq!(metrics.fill_rate, 0.5);
+        // Paid $250.50 for $250 of quoted cost
+        assert_eq!(metrics.avg_slippage_bps, 20.0);
+
+        let json = serde_json::to_value(&metrics).unwrap();
+        assert_eq!(json["fill_rate"], 0.5);
+        assert!(json.get("filled_expected_cost").is_none());
+    }
+
+    #[test]
+    fn test_repeat_trades_in_a_market_are_spaced_out() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        config.risk.min_seconds_between_trades_per_market = 1;
+        let risk_manager = RiskManager::new(&config);
+        let arb = binary_arb("hot_market", dec!(10), dec!(500));
+        let result = ExecutionResult {
+            success: true,
+            filled: true,
+            partial_fill: false,
+            filled_amount: dec!(10),
+            total_cost: dec!(9),
+            expected_cost: dec!(9),
+            realized_cost: dec!(9),
+            realized_pnl: dec!(1),
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+
+        assert!(risk_manager
+            .can_execute_arbitrage(&arb)
+            .unwrap()
+            .is_allowed());
+        risk_manager
+            .record_arbitrage_execution(&arb, &result)
+            .unwrap();
+
+        // A profitable trade still holds the market off, without a cooldown
+        assert!(risk_manager.is_trade_too_soon("hot_market"));
+        assert!(!risk_manager.is_market_in_cooldown("hot_market"));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&arb).unwrap(),
+            RiskDecision::Reject(RiskRejectReason::TradeSpacing)
+        );
+        let other = binary_arb("quiet_market", dec!(10), dec!(500));
+        assert!(risk_manager
+            .can_execute_arbitrage(&other)
+            .unwrap()
+            .is_allowed());
+
+        std::thread::sleep(std::time::Duration::from_millis(1050));
+        assert!(!risk_manager.is_trade_too_soon("hot_market"));
+        assert!(risk_manager
+            .can_execute_arbitrage(&arb)
+            .unwrap()
+            .is_allowed());
+
+        // Zero spacing never holds a market off
+        config.risk.min_seconds_between_trades_per_market = 0;
+        let risk_manager = RiskManager::new(&config);
+        risk_manager
+            .record_arbitrage_execution(&arb, &result)
+            .unwrap();
+        assert!(!risk_manager.is_trade_too_soon("hot_market"));
+    }
+
+    #[test]
+    fn test_market_cooldown_after_failure() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let risk_manager = RiskManager::new(&config);
+        let arb = binary_arb("flaky_market", dec!(10), dec!(500));
+
+        risk_manager.record_market_failure("flaky_market");
+
+        assert!(risk_manager.is_market_in_cooldown("flaky_market"));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&arb).unwrap(),
+            RiskDecision::Reject(RiskRejectReason::MarketCooldown)
+        );
+
+        // Other markets are unaffected
+        let other = binary_arb("healthy_market", dec!(10), dec!(500));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&other).unwrap(),
+            RiskDecision::Allow
+        );
+
+        // A zero-length cooldown lets the market straight back in
+        config.risk.market_cooldown_secs = 0;
+        let risk_manager = RiskManager::new(&config);
+        risk_manager.record_market_failure("flaky_market");
+        assert!(!risk_manager.is_market_in_cooldown("flaky_market"));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&arb).unwrap(),
+            RiskDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_seeded_positions_count_towards_exposure_cap() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let risk_manager = RiskManager::new(&config);
+        let arb = binary_arb("held_market", dec!(10), dec!(500));
+
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&arb).unwrap(),
+            RiskDecision::Allow
+        );
+
+        let positions = [ExchangePosition {
+            market_id: "held_market".to_string(),
+            asset_id: "yes_asset".to_string(),
+            outcome: "Yes".to_string(),
+            size: dec!(150),
+            avg_price: dec!(0.40),
+        }];
+        let order = |order_id: &str, is_buy: bool| ExchangeOpenOrder {
+            order_id: order_id.to_string(),
+            market_id: "held_market".to_string(),
+            asset_id: "no_asset".to_string(),
+            outcome: "No".to_string(),
+            is_buy,
+            price: dec!(0.50),
+            remaining_size: dec!(45),
+        };
+        // Resting sells don't add exposure
+        let open_orders = [order("buy_order", true), order("sell_order", false)];
+
+        risk_manager.seed_positions(&positions, &open_orders).unwrap();
+
+        assert_eq!(risk_manager.get_market_exposure("held_market"), dec!(195));
+        assert_eq!(risk_manager.get_event_exposure("held_market"), dec!(195));
+        assert_eq!(
+            risk_manager.get_position("yes_asset").unwrap().total_cost,
+            dec!(60)
+        );
+
+        // 195 + 10 breaches the 200 per-market cap
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&arb).unwrap(),
+            RiskDecision::Reject(RiskRejectReason::MarketExposure)
+        );
+
+        let fresh = binary_arb("fresh_market", dec!(10), dec!(500));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&fresh).unwrap(),
+            RiskDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_repeated_failures_blacklist_market() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        config.risk.market_cooldown_secs = 0;
+        let risk_manager = RiskManager::new(&config);
+        let arb = binary_arb("broken_market", dec!(10), dec!(500));
+
+        risk_manager.record_market_failure("broken_market");
+        risk_manager.record_market_failure("broken_market");
+        assert!(!risk_manager.is_market_blacklisted("broken_market"));
+
+        risk_manager.record_market_failure("broken_market");
+        assert!(risk_manager.is_market_blacklisted("broken_market"));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&arb).unwrap(),
+            RiskDecision::Reject(RiskRejectReason::MarketBlacklisted)
+        );
+
+        // Disabled: failures only ever cool down, never blacklist
+        config.risk.blacklist_on_repeated_failure = false;
+        let risk_manager = RiskManager::new(&config);
+        for _ in 0..5 {
+            risk_manager.record_market_failure("broken_market");
+        }
+        assert!(!risk_manager.is_market_blacklisted("broken_market"));
+    }
+
+    #[tokio::test]
+    async fn test_daily_loss_limit_and_pnl_drawdown_alerts() {
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        config.alerts.pnl_drawdown_threshold_usd = 50;
+        let risk_manager = RiskManager::new(&config);
+        let monitor = Monitor::new(&config).await.unwrap();
+        let orderbook_manager = OrderBookManager::new(&config).unwrap();
+
+        // Enter 100 YES + 100 NO at $0.45
+        let arb = binary_arb("loss_market", dec!(100), dec!(500));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&arb).unwrap(),
+            RiskDecision::Allow
+        );
+        let result = ExecutionResult {
+            success: true,
+            filled: false,
+            partial_fill: false,
+            filled_amount: dec!(100),
+            total_cost: Decimal::ZERO,
+            expected_cost: dec!(90),
+            realized_cost: dec!(90),
+            realized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+        risk_manager
+            .record_arbitrage_execution(&arb, &result)
+            .unwrap();
+
+        // Both sides now bid $0.10 => (0.10 - 0.45) * 100 * 2 = -$70
+        for asset_id in ["yes_asset", "no_asset"] {
+            let snapshot = BookSnapshot {
+                market: "loss_market".to_string(),
+                asset_id: asset_id.to_string(),
+                bids: vec![(dec!(0.10), dec!(100))],
+                asks: vec![(dec!(0.12), dec!(100))],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            orderbook_manager
+                .update_book("loss_market", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        let total_pnl = risk_manager.mark_to_market(&orderbook_manager);
+        assert_eq!(total_pnl, dec!(-70));
+
+        monitor.check_pnl_drawdown(total_pnl).await;
+        let alert = latest_alert(&monitor).await;
+        assert_eq!(alert.alert_type, AlertType::PnlDrawdown);
+        assert_eq!(alert.severity, AlertSeverity::Critical);
+
+        // Still in drawdown: no duplicate alert
+        monitor.check_pnl_drawdown(total_pnl).await;
+        assert_eq!(monitor.get_recent_alerts(10).await.len(), 1);
+
+        let next = binary_arb("other_market", dec!(10), dec!(500));
+        let decision = risk_manager.can_execute_arbitrage(&next).unwrap();
+        assert_eq!(
+            decision,
+            RiskDecision::Reject(RiskRejectReason::DailyLossLimit)
+        );
+
+        monitor
+            .alert_risk_limit_breached(&next.market_id, RiskRejectReason::DailyLossLimit)
+            .await;
+        let alert = latest_alert(&monitor).await;
+        assert_eq!(alert.alert_type, AlertType::RiskLimitBreached);
+        assert_eq!(alert.severity, AlertSeverity::Critical);
+    }
+
+    #[test]
+    fn test_ws_batch_detects_once_per_market() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+
+        for market_id in ["batch_market", "other_market"] {
+            let snapshot = BookSnapshot {
+                market: market_id.to_string(),
+                asset_id: format!("{}_yes", market_id),
+                bids: vec![(dec!(0.40), dec!(100))],
+                asks: vec![(dec!(0.45), dec!(100))],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            manager.update_book(market_id, &snapshot.asset_id, &snapshot).unwrap();
+        }
+
+        // 10 level changes to one market, then one to another, then one to an unknown market
+        let mut messages: Vec<WsMessage> = (0..10)
+            .map(|i| price_change_msg("batch_market", &format!("0.4{}", i)))
+            .collect();
+        messages.push(price_change_msg("other_market", "0.41"));
+        messages.push(price_change_msg("unknown_market", "0.41"));
+
+        let touched = WebSocketClient::apply_message_batch(&messages, &manager);
+        assert_eq!(touched, vec!["batch_market", "other_market"]);
+
+        let book = manager.get_book("batch_market", "batch_market_yes").unwrap();
+        assert_eq!(book.best_bid().unwrap().0, dec!(0.49));
+    }
+
+    fn price_change_msg(market_id: &str, price: &str) -> WsMessage {
+        sized_price_change_msg(market_id, price, "10")
+    }
+
+    fn sized_price_change_msg(market_id: &str, price: &str, size: &str) -> WsMessage {
+        serde_json::from_value(serde_json::json!({
+            "event_type": "price_change",
+            "market": market_id,
+            "price_changes": [{
+                "asset_id": format!("{}_yes", market_id),
+                "price": price,
+                "size": size,
+                "side": "BUY",
+                "hash": "h",
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ws_message_variants_parse() {
+        let payload = r#"[
+            {
+                "event_type": "book",
+                "asset_id": "yes_token",
+                "market": "0xbd31dc8a",
+                "bids": [{"price": "0.48", "size": "30"}, {"price": "0.49", "size": "20"}],
+                "asks": [{"price": "0.52", "size": "25"}],
+                "timestamp": "1757908892351",
+                "hash": "0x0abc"
+            },
+            {
+                "event_type": "price_change",
+                "market": "0xbd31dc8a",
+                "price_changes": [{
+                    "asset_id": "yes_token",
+                    "price": "0.5",
+                    "size": "200",
+                    "side": "BUY",
+                    "hash": "56621a121a47ed9333273e21c83b660cff37ae50",
+                    "best_bid": "0.5",
+                    "best_ask": "0.52"
+                }],
+                "timestamp": "1757908892351"
+            },
+            {
+                "event_type": "tick_size_change",
+                "asset_id": "yes_token",
+                "market": "0xbd31dc8a",
+                "old_tick_size": "0.01",
+                "new_tick_size": "0.001",
+                "timestamp": "1757908892351"
+            },
+            {
+                "event_type": "last_trade_price",
+                "asset_id": "yes_token",
+                "market": "0xbd31dc8a",
+                "fee_rate_bps": "0",
+                "price": "0.456",
+                "side": "BUY",
+                "size": "219.217767",
+                "timestamp": "1757908892351"
+            },
+            {
+                "event_type": "new_market",
+                "market": "0xbd31dc8a"
+            }
+        ]"#;
+
+        let messages = WsMessage::parse_batch(payload).unwrap();
+        assert_eq!(messages.len(), 5);
+
+        let WsMessage::Book(book) = &messages[0] else {
+            panic!("expected a book, got {:?}", messages[0]);
+        };
+        assert_eq!((book.bids.len(), book.asks.len()), (2, 1));
+        assert_eq!(book.parse_timestamp(), 1_757_908_892_351);
+
+        let WsMessage::PriceChange(change) = &messages[1] else {
+            panic!("expected a price change, got {:?}", messages[1]);
+        };
+        assert_eq!(change.price_changes[0].best_ask, "0.52");
+
+        let WsMessage::TickSizeChange(tick) = &messages[2] else {
+            panic!("expected a tick size change, got {:?}", messages[2]);
+        };
+        assert_eq!(tick.new_tick_size, dec!(0.001));
+
+        let WsMessage::LastTradePrice(trade) = &messages[3] else {
+            panic!("expected a last trade price, got {:?}", messages[3]);
+        };
+        assert_eq!((trade.price, trade.size), (dec!(0.456), dec!(219.217767)));
+
+        assert!(matches!(messages[4], WsMessage::Unknown));
+        assert_eq!(messages[4].market(), None);
+
+        // Book and level change touch the market once; the tick size is recorded
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let touched = WebSocketClient::apply_message_batch(&messages, &manager);
+        assert_eq!(touched, vec!["0xbd31dc8a"]);
+        assert_eq!(manager.tick_size("0xbd31dc8a"), dec!(0.001));
+        let book = manager.get_book("0xbd31dc8a", "yes_token").unwrap();
+        assert_eq!(book.best_bid(), Some((dec!(0.5), dec!(200))));
+    }
+
+    #[test]
+    fn test_oversized_message_is_reported_with_its_markets() {
+        use hfptm::websocket::OversizedMessage;
+
+        let payload = r#"[
+            {"event_type": "book", "asset_id": "yes_a", "market": "market_a",
+             "bids": [{"price": "0.48", "size": "30"}], "asks": [], "timestamp": "1"},
+            {"event_type": "book", "asset_id": "no_a", "market": "market_a",
+             "bids": [], "asks": [{"price": "0.52", "size": "25"}], "timestamp": "1"},
+            {"event_type": "book", "asset_id": "yes_b", "market":"market_b",
+             "bids": [], "asks": [], "timestamp": "1"}
+        ]"#;
+
+        // The default limit parses it as usual
+        let mut config = create_test_config();
+        let limit = config.server.ws_max_message_bytes;
+        assert_eq!(limit, 5 * 1024 * 1024);
+        assert!(OversizedMessage::check(payload, limit).is_none());
+
+        // A lower configured limit reports it, naming the books it carried
+        config.server.ws_max_message_bytes = 200;
+        let oversized =
+            OversizedMessage::check(payload, config.server.ws_max_message_bytes).unwrap();
+        assert_eq!(oversized.bytes, payload.len());
+        assert_eq!(oversized.limit, 200);
+        assert_eq!(oversized.market_ids, vec!["market_a", "market_b"]);
+        assert_eq!(oversized.asset_ids, vec!["yes_a", "no_a", "yes_b"]);
+
+        // A frame cut short still yields the ids before the cut
+        let truncated = &payload[..payload.find("yes_b").unwrap()];
+        let oversized = OversizedMessage::check(truncated, 100).unwrap();
+        assert_eq!(oversized.market_ids, vec!["market_a"]);
+        assert_eq!(oversized.asset_ids, vec!["yes_a", "no_a"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_oversized_frames_refetch_each_book_once_at_a_time() {
+        use axum::{extract::Query, routing::get, Json, Router};
+        use futures::{SinkExt, StreamExt};
+        use hfptm::executor::SimulationExecutor;
+        use std::collections::HashMap;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // A slow REST book endpoint, so later frames arrive mid-fetch
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let (counter, current, peak) = (fetches.clone(), in_flight.clone(), max_in_flight.clone());
+        let app = Router::new().route(
+            "/book",
+            get(move |Query(params): Query<HashMap<String, String>>| {
+                let (counter, current, peak) = (counter.clone(), current.clone(), peak.clone());
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    peak.fetch_max(current.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                    current.fetch_sub(1, Ordering::SeqCst);
+                    Json(serde_json::json!({
+                        "market": "big_market",
+                        "asset_id": params["token_id"],
+                        "timestamp": current_timestamp_ms().to_string(),
+                        "bids": [{ "price": "0.40", "size": "50" }],
+                        "asks": [{ "price": "0.45", "size": "200" }]
+                    }))
+                }
+            }),
+        );
+        let rest_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let rest_url = format!("http://{}", rest_listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(rest_listener, app).await.unwrap();
+        });
+
+        // A market channel that answers the subscription with oversized frames
+        let ws_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let wss_url = format!("ws://{}", ws_listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = ws_listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _subscription = ws.next().await;
+            let frame = format!(
+                r#"[{{"event_type": "book", "asset_id": "big_yes", "market": "big_market", "bids": [], "asks": [], "timestamp": "1", "pad": "{}"}}]"#,
+                "x".repeat(300)
+            );
+            for _ in 0..5 {
+                ws.send(Message::Text(frame.clone())).await.unwrap();
+            }
+            std::future::pending::<()>().await;
+        });
+
+        let mut config = create_test_config();
+        config.server.wss_url = wss_url;
+        config.server.rest_url = rest_url;
+        config.server.ws_max_message_bytes = 200;
+        let markets = vec![test_market("big_market", "Q?", &["big_yes", "big_no"])];
+        let manager = Arc::new(OrderBookManager::new(&config).unwrap());
+        let simulator: Arc<dyn Executor> = Arc::new(SimulationExecutor::new(&config));
+        let mut client = WebSocketClient::new(&config, &markets, simulator)
+            .await
+            .unwrap();
+        let mut arb_engine = ArbEngine::new(&config);
+        let risk_manager = RiskManager::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+
+        let feed_manager = manager.clone();
+        tokio::spawn(async move {
+            let _ = client
+                .run(&feed_manager, &mut arb_engine, &risk_manager, &mut monitor)
+                .await;
+        });
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while manager.get_book("big_market", "big_yes").is_none()
+            && tokio::time::Instant::now() < deadline
+        {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        // Let a second fetch for frames queued meanwhile finish, and any stray one show up
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        // Five frames: the first fetch, then at most one more for everything
+        // queued while it ran (none if they all landed before it started),
+        // never two at once
+        let fetched = fetches.load(Ordering::SeqCst);
+        assert!((1..=2).contains(&fetched), "{} fetches", fetched);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 1);
+        let book = manager.get_book("big_market", "big_yes").unwrap();
+        assert_eq!(book.best_ask(), Some((dec!(0.45), dec!(200))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_market_refresh_resubscribes_the_market_channel() {
+        use futures::{SinkExt, StreamExt};
+        use hfptm::executor::SimulationExecutor;
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // A market channel that reports each subscription and answers it with
+        // a book for every asset subscribed
+        let ws_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let wss_url = format!("ws://{}", ws_listener.local_addr().unwrap());
+        let (subscribed_tx, mut subscribed_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = ws_listener.accept().await.unwrap();
+                let subscribed_tx = subscribed_tx.clone();
+                tokio::spawn(async move {
+                    let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    let Some(Ok(Message::Text(text))) = ws.next().await else {
+                        return;
+                    };
+                    let subscription: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    let asset_ids: Vec<String> =
+                        serde_json::from_value(subscription["assets_ids"].clone()).unwrap();
+                    for asset_id in &asset_ids {
+                        let market = asset_id.split('_').next().unwrap();
+                        let frame = serde_json::json!([{
+                            "event_type": "book",
+                            "asset_id": asset_id,
+                            "market": market,
+                            "bids": [{ "price": "0.40", "size": "50" }],
+                            "asks": [{ "price": "0.45", "size": "200" }],
+                            "timestamp": current_timestamp_ms().to_string()
+                        }]);
+                        ws.send(Message::Text(frame.to_string())).await.unwrap();
+                    }
+                    subscribed_tx.send(asset_ids).unwrap();
+                    // Held open until the client closes it
+                    while let Some(Ok(message)) = ws.next().await {
+                        if message.is_close() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut config = create_test_config();
+        config.server.wss_url = wss_url;
+        let markets = vec![test_market("old", "Q?", &["old_yes", "old_no"])];
+        let manager = Arc::new(OrderBookManager::new(&config).unwrap());
+        let simulator: Arc<dyn Executor> = Arc::new(SimulationExecutor::new(&config));
+        let (market_updates_tx, market_updates_rx) = tokio::sync::mpsc::channel(4);
+        let mut client = WebSocketClient::new(&config, &markets, simulator)
+            .await
+            .unwrap()
+            .with_market_updates(market_updates_rx);
+        let mut arb_engine = ArbEngine::new(&config);
+        let risk_manager = RiskManager::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+
+        let feed_manager = manager.clone();
+        tokio::spawn(async move {
+            let _ = client
+                .run(&feed_manager, &mut arb_engine, &risk_manager, &mut monitor)
+                .await;
+        });
+        let first = tokio::time::timeout(Duration::from_secs(10), subscribed_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first, ["old_yes", "old_no"]);
+
+        // A refresh that lists a new market reconnects with it subscribed
+        let refreshed = vec![
+            test_market("old", "Q?", &["old_yes", "old_no"]),
+            test_market("new", "Q?", &["new_yes", "new_no"]),
+        ];
+        market_updates_tx.send(refreshed.clone()).await.unwrap();
+        let second = tokio::time::timeout(Duration::from_secs(10), subscribed_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second, ["old_yes", "old_no", "new_yes", "new_no"]);
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while manager.get_book("new", "new_no").is_none() && tokio::time::Instant::now() < deadline
+        {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(manager.get_book("new", "new_yes").is_some());
+        assert!(manager.get_book("new", "new_no").is_some());
+
+        // The same set again leaves the connection alone
+        market_updates_tx.send(refreshed).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(subscribed_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_parse_price_and_size() {
+        use hfptm::utils::parse::{parse_level, parse_price, parse_size};
+
+        assert_eq!(parse_price("0.45").unwrap(), dec!(0.45));
+        assert_eq!(parse_price(" 1 ").unwrap(), dec!(1));
+        assert_eq!(parse_price("0.50").unwrap().scale(), 2);
+        assert_eq!(parse_size("219.217767").unwrap(), dec!(219.217767));
+        assert_eq!(parse_size("0").unwrap(), Decimal::ZERO);
+
+        // Negative
+        assert!(parse_price("-0.45").is_err());
+        assert!(parse_size("-5").is_err());
+
+        // Out of (0, 1]
+        assert!(parse_price("0").is_err());
+        assert!(parse_price("1.01").is_err());
+
+        // Garbage
+        for raw in ["", "abc", "0.4.5", "NaN", "1e400"] {
+            assert!(parse_price(raw).is_err(), "price {:?}", raw);
+            assert!(parse_size(raw).is_err(), "size {:?}", raw);
+        }
+        let err = parse_level("0.45", "ten").unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid size \"ten\""));
+    }
+
+    #[test]
+    fn test_ws_skips_malformed_levels() {
+        let payload = r#"[{
+            "event_type": "book",
+            "asset_id": "yes_token",
+            "market": "bad_levels",
+            "bids": [{"price": "0.40", "size": "abc"}, {"price": "0.39", "size": "50"}],
+            "asks": [{"price": "1.5", "size": "10"}, {"price": "0.45", "size": "-1"},
+                     {"price": "0.47", "size": "100"}],
+            "timestamp": "1757908892351"
+        }]"#;
+        let mut messages = WsMessage::parse_batch(payload).unwrap();
+        let mut change = sized_price_change_msg("bad_levels", "0.41", "oops");
+        if let WsMessage::PriceChange(change) = &mut change {
+            change.price_changes[0].asset_id = "yes_token".to_string();
+        }
+        messages.push(change);
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let touched = WebSocketClient::apply_message_batch(&messages, &manager);
+        assert_eq!(touched, vec!["bad_levels"]);
+
+        // Bad levels are dropped, not kept as empty levels at their price
+        let book = manager.get_book("bad_levels", "yes_token").unwrap();
+        assert_eq!(book.best_bid(), Some((dec!(0.39), dec!(50))));
+        assert_eq!(book.best_ask(), Some((dec!(0.47), dec!(100))));
+    }
+
+    #[tokio::test]
+    async fn test_markets_sharded_across_connections() {
+        use hfptm::executor::SimulationExecutor;
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+        config.server.ws_connections = 2;
+        config.trading.max_order_books = 5;
+        let simulator: Arc<dyn Executor> = Arc::new(SimulationExecutor::new(&config));
+
+        let asset_ids: Vec<[String; 2]> = (0..7)
+            .map(|i| [format!("yes_{}", i), format!("no_{}", i)])
+            .collect();
+        let markets: Vec<Market> = asset_ids
+            .iter()
+            .enumerate()
+            .map(|(i, [yes, no])| test_market(&format!("m{}", i), "Q?", &[yes, no]))
+            .collect();
+
+        let client = WebSocketClient::new(&config, &markets, simulator.clone())
+            .await
+            .unwrap();
+        let shards = client.shard_asset_ids();
+        assert_eq!(shards.len(), 2);
+
+        // Round-robin by market, outcomes kept together: 3 markets and 2 markets
+        assert_eq!(
+            shards[0],
+            ["yes_0", "no_0", "yes_2", "no_2", "yes_4", "no_4"]
+        );
+        assert_eq!(shards[1], ["yes_1", "no_1", "yes_3", "no_3"]);
+
+        // Every tracked asset exactly once, nothing past max_order_books
+        let mut subscribed: Vec<&String> = shards.iter().flatten().collect();
+        subscribed.sort();
+        let mut expected: Vec<&String> = asset_ids[..5].iter().flatten().collect();
+        expected.sort();
+        assert_eq!(subscribed, expected);
+
+        // More connections than markets: no empty subscriptions
+        config.server.ws_connections = 8;
+        let client = WebSocketClient::new(&config, &markets, simulator.clone())
+            .await
+            .unwrap();
+        assert_eq!(client.shard_asset_ids().len(), 5);
+        assert!(client.shard_asset_ids().iter().all(|ids| ids.len() == 2));
+    }
+
+    /// Records what it was asked to execute and fills it in full, unless a
+    /// failure is queued
+    struct MockExecutor {
+        executed: parking_lot::Mutex<Vec<String>>,
+        failure: parking_lot::Mutex<Option<HftpmError>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Executor for MockExecutor {
+        async fn execute_arbitrage(
+            &self,
+            arb_op: &dyn Opportunity,
+            _orderbook_manager: &OrderBookManager,
+        ) -> hfptm::error::Result<ExecutionResult> {
+            self.executed.lock().push(arb_op.market_id().to_string());
+            if let Some(err) = self.failure.lock().take() {
+                return Err(err);
+            }
+            Ok(ExecutionResult {
+                success: true,
+                filled: true,
+                partial_fill: false,
+                filled_amount: arb_op.position_size(),
+                total_cost: Decimal::ZERO,
+                expected_cost: Decimal::ZERO,
+                realized_cost: Decimal::ZERO,
+                realized_pnl: Decimal::ZERO,
+                fees_paid: Decimal::ZERO,
+                orders: vec![],
+                execution_time_ms: 1,
+                error_message: None,
+            })
+        }
+
+        async fn available_balance(&self) -> hfptm::error::Result<Decimal> {
+            Ok(dec!(1000))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_detected_arbs_go_to_the_injected_executor() {
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+        config.quality_scoring.min_quality_score = Decimal::ZERO;
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut arb_engine = ArbEngine::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+
+        for (asset_id, ask) in [("mock_yes", dec!(0.45)), ("mock_no", dec!(0.48))] {
+            let snapshot = ask_snapshot("mock_market", asset_id, vec![(ask, dec!(200))]);
+            manager
+                .update_book("mock_market", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        let mock = Arc::new(MockExecutor {
+            executed: parking_lot::Mutex::new(Vec::new()),
+            failure: parking_lot::Mutex::new(None),
+        });
+        let client = WebSocketClient::new(&config, &[], mock.clone())
+            .await
+            .unwrap();
+
+        client
+            .detect_and_execute(
+                "mock_market",
+                &manager,
+                &mut arb_engine,
+                &risk_manager,
+                &mut monitor,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(*mock.executed.lock(), vec!["mock_market".to_string()]);
+        assert_eq!(monitor.get_metrics().arb_executions, 1);
+
+        // No arb, nothing executed
+        client
+            .detect_and_execute(
+                "missing_market",
+                &manager,
+                &mut arb_engine,
+                &risk_manager,
+                &mut monitor,
+            )
+            .await
+            .unwrap();
+        assert_eq!(mock.executed.lock().len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_held_set_sold_through_the_detection_path() {
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+        config.quality_scoring.min_quality_score = Decimal::ZERO;
+        config.trading.min_edge = dec!(0.01);
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut arb_engine = ArbEngine::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+
+        // Bids sum to 1.04 with asks above them: only the sell side pays
+        for (asset_id, bid) in [("set_yes", dec!(0.53)), ("set_no", dec!(0.51))] {
+            let snapshot = BookSnapshot {
+                market: "set_market".to_string(),
+                asset_id: asset_id.to_string(),
+                bids: vec![(bid, dec!(500))],
+                asks: vec![(bid + dec!(0.02), dec!(500))],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            manager
+                .update_book("set_market", asset_id, &snapshot)
+                .unwrap();
+        }
+        let held = |asset: &str| ExchangePosition {
+            market_id: "set_market".to_string(),
+            asset_id: asset.to_string(),
+            outcome: asset.to_string(),
+            size: dec!(100),
+            avg_price: dec!(0.48),
+        };
+        risk_manager
+            .seed_positions(&[held("set_yes"), held("set_no")], &[])
+            .unwrap();
+
+        let mock = Arc::new(MockExecutor {
+            executed: parking_lot::Mutex::new(Vec::new()),
+            failure: parking_lot::Mutex::new(None),
+        });
+        let client = WebSocketClient::new(&config, &[], mock.clone())
+            .await
+            .unwrap();
+
+        client
+            .detect_and_execute(
+                "set_market",
+                &manager,
+                &mut arb_engine,
+                &risk_manager,
+                &mut monitor,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(*mock.executed.lock(), vec!["set_market".to_string()]);
+        assert_eq!(monitor.get_metrics().arb_executions, 1);
+        // The sold set no longer counts as held
+        assert!(risk_manager
+            .get_position("set_yes")
+            .is_none_or(|p| p.size.is_zero()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stale_opportunity_expires_before_execution() {
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let mock = Arc::new(MockExecutor {
+            executed: parking_lot::Mutex::new(Vec::new()),
+            failure: parking_lot::Mutex::new(None),
+        });
+        let client = WebSocketClient::new(&config, &[], mock.clone())
+            .await
+            .unwrap();
+
+        // Detected 2s ago against a 500ms limit
+        let mut stale = binary_arb("stale_market", dec!(10), dec!(500));
+        stale.timestamp = current_timestamp_ms() - 2000;
+        client
+            .execute_arbitrage(&stale, &manager, &risk_manager, &mut monitor)
+            .await
+            .unwrap();
+
+        assert!(mock.executed.lock().is_empty());
+        let metrics = monitor.get_metrics();
+        assert_eq!(metrics.arb_expired, 1);
+        assert_eq!(metrics.arb_executions, 0);
+
+        // A fresh one goes through
+        let fresh = binary_arb("fresh_market", dec!(10), dec!(500));
+        client
+            .execute_arbitrage(&fresh, &manager, &risk_manager, &mut monitor)
+            .await
+            .unwrap();
+        assert_eq!(*mock.executed.lock(), vec!["fresh_market".to_string()]);
+        assert_eq!(monitor.get_metrics().arb_expired, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_repeated_execution_failures_halt_execution() {
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        config.execution.circuit_breaker_failures = 2;
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let mock = Arc::new(MockExecutor {
+            executed: parking_lot::Mutex::new(Vec::new()),
+            failure: parking_lot::Mutex::new(None),
+        });
+        let client = WebSocketClient::new(&config, &[], mock.clone())
+            .await
+            .unwrap();
+
+        // The exchange rejects two in a row (different markets, so no cooldown applies)
+        for market in ["down_a", "down_b"] {
+            *mock.failure.lock() = Some(HftpmError::Network("503".to_string()));
+            let arb = binary_arb(market, dec!(10), dec!(500));
+            client
+                .execute_arbitrage(&arb, &manager, &risk_manager, &mut monitor)
+                .await
+                .unwrap();
+        }
+
+        let alerts = monitor.get_recent_alerts(10).await;
+        let tripped: Vec<_> = alerts
+            .iter()
+            .filter(|alert| alert.alert_type == AlertType::CircuitBreakerTripped)
+            .collect();
+        assert_eq!(tripped.len(), 1);
+        assert_eq!(tripped[0].severity, AlertSeverity::Critical);
+
+        // Open: a good opportunity isn't even attempted
+        let arb = binary_arb("healthy_market", dec!(10), dec!(500));
+        client
+            .execute_arbitrage(&arb, &manager, &risk_manager, &mut monitor)
+            .await
+            .unwrap();
+        assert_eq!(
+            *mock.executed.lock(),
+            vec!["down_a".to_string(), "down_b".to_string()]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_strategy_schedule_gates_by_utc_hour() {
+        use chrono::Timelike;
+        use hfptm::utils::{ScheduleConfig, Strategy};
+        use std::sync::Arc;
+
+        let schedule: ScheduleConfig =
+            toml::from_str("arbitrage_hours = [[13, 22]]\nvolume_farming_hours = [[22, 6]]")
+                .unwrap();
+
+        assert!(schedule.is_active(&Strategy::Arbitrage, 13));
+        assert!(schedule.is_active(&Strategy::Arbitrage, 21));
+        assert!(!schedule.is_active(&Strategy::Arbitrage, 22));
+        assert!(!schedule.is_active(&Strategy::Arbitrage, 3));
+
+        // Wraps past midnight
+        assert!(schedule.is_active(&Strategy::VolumeFarming, 23));
+        assert!(schedule.is_active(&Strategy::VolumeFarming, 0));
+        assert!(!schedule.is_active(&Strategy::VolumeFarming, 6));
+        assert!(!schedule.is_active(&Strategy::VolumeFarming, 14));
+
+        // No windows means always on
+        assert!((0..24).all(|hour| schedule.is_active(&Strategy::MarketMaking, hour)));
+        assert!((0..24).all(|hour| ScheduleConfig::default().is_active(&Strategy::Arbitrage, hour)));
+
+        // Detection is skipped outside the arbitrage window, with the window
+        // placed half a day from now so the hour can't roll into it
+        let mut config = create_test_config();
+        config.quality_scoring.min_quality_score = Decimal::ZERO;
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let hour = chrono::Utc::now().hour();
+        config.schedule.arbitrage_hours = vec![((hour + 12) % 24, (hour + 13) % 24)];
+        let manager = OrderBookManager::new(&config).unwrap();
+        for (asset_id, ask) in [("mock_yes", dec!(0.45)), ("mock_no", dec!(0.48))] {
+            let snapshot = ask_snapshot("mock_market", asset_id, vec![(ask, dec!(200))]);
+            manager
+                .update_book("mock_market", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        let risk_manager = RiskManager::new(&config);
+        let mut arb_engine = ArbEngine::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let mock = Arc::new(MockExecutor {
+            executed: parking_lot::Mutex::new(Vec::new()),
+            failure: parking_lot::Mutex::new(None),
+        });
+        let client = WebSocketClient::new(&config, &[], mock.clone())
+            .await
+            .unwrap();
+        client
+            .detect_and_execute(
+                "mock_market",
+                &manager,
+                &mut arb_engine,
+                &risk_manager,
+                &mut monitor,
+            )
+            .await
+            .unwrap();
+        assert!(mock.executed.lock().is_empty());
+        assert_eq!(monitor.get_metrics().arb_detections, 0);
+
+        // Inside the window it trades as usual
+        config.schedule.arbitrage_hours = vec![(0, 24)];
+        let client = WebSocketClient::new(&config, &[], mock.clone())
+            .await
+            .unwrap();
+        client
+            .detect_and_execute(
+                "mock_market",
+                &manager,
+                &mut arb_engine,
+                &risk_manager,
+                &mut monitor,
+            )
+            .await
+            .unwrap();
+        assert_eq!(*mock.executed.lock(), vec!["mock_market".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_detections_execute_once_per_market() {
+        use hfptm::websocket::InFlightMarkets;
+        use std::sync::Arc;
+
+        /// Holds each execution open long enough for a second detection to race it
+        struct SlowExecutor {
+            executed: parking_lot::Mutex<Vec<String>>,
+        }
+
+        #[async_trait::async_trait]
+        impl Executor for SlowExecutor {
+            async fn execute_arbitrage(
+                &self,
+                arb_op: &dyn Opportunity,
+                _orderbook_manager: &OrderBookManager,
+            ) -> hfptm::error::Result<ExecutionResult> {
+                self.executed.lock().push(arb_op.market_id().to_string());
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                Ok(ExecutionResult {
+                    success: true,
+                    filled: true,
+                    partial_fill: false,
+                    filled_amount: arb_op.position_size(),
+                    total_cost: Decimal::ZERO,
+                    expected_cost: Decimal::ZERO,
+                    realized_cost: Decimal::ZERO,
+                    realized_pnl: Decimal::ZERO,
+                    fees_paid: Decimal::ZERO,
+                    orders: vec![],
+                    execution_time_ms: 50,
+                    error_message: None,
+                })
+            }
+
+            async fn available_balance(&self) -> hfptm::error::Result<Decimal> {
+                Ok(dec!(1000))
+            }
+        }
+
+        let mut config = create_test_config();
+        config.quality_scoring.min_quality_score = Decimal::ZERO;
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        for (asset_id, ask) in [("mock_yes", dec!(0.45)), ("mock_no", dec!(0.48))] {
+            let snapshot = ask_snapshot("mock_market", asset_id, vec![(ask, dec!(200))]);
+            manager
+                .update_book("mock_market", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        let executor = Arc::new(SlowExecutor {
+            executed: parking_lot::Mutex::new(Vec::new()),
+        });
+        let client = WebSocketClient::new(&config, &[], executor.clone())
+            .await
+            .unwrap();
+
+        // A snapshot and its echoing price_change both detect the same arb
+        let (mut engine_a, mut engine_b) = (ArbEngine::new(&config), ArbEngine::new(&config));
+        let mut monitor_a = Monitor::new(&config).await.unwrap();
+        let mut monitor_b = Monitor::new(&config).await.unwrap();
+        let (a, b) = tokio::join!(
+            client.detect_and_execute(
+                "mock_market",
+                &manager,
+                &mut engine_a,
+                &risk_manager,
+                &mut monitor_a,
+            ),
+            client.detect_and_execute(
+                "mock_market",
+                &manager,
+                &mut engine_b,
+                &risk_manager,
+                &mut monitor_b,
+            ),
+        );
+        a.unwrap();
+        b.unwrap();
+
+        assert_eq!(monitor_a.get_metrics().arb_detections, 1);
+        assert_eq!(monitor_b.get_metrics().arb_detections, 1);
+        assert_eq!(*executor.executed.lock(), vec!["mock_market".to_string()]);
+
+        // The claim is released with the execution
+        let in_flight = InFlightMarkets::new();
+        let claim = in_flight.try_claim("mock_market").unwrap();
+        assert!(in_flight.try_claim("mock_market").is_none());
+        assert!(in_flight.try_claim("other_market").is_some());
+        drop(claim);
+        assert!(!in_flight.is_in_flight("mock_market"));
+        assert!(in_flight.try_claim("mock_market").is_some());
+
+        // A held claim outlives its guard, as for a simulated fill that settles later
+        in_flight.try_claim("mock_market").unwrap().hold();
+        assert!(in_flight.try_claim("mock_market").is_none());
+        in_flight.release("mock_market");
+        assert!(in_flight.try_claim("mock_market").is_some());
+    }
+
+    #[test]
+    fn test_feed_health_message_age_and_rate() {
+        use hfptm::websocket::FeedHealth;
+        use std::time::{Duration, Instant};
+
+        let mut health = FeedHealth::new();
+        let t0 = Instant::now();
+        let at = |ms: u64| t0 + Duration::from_millis(ms);
+        assert_eq!(health.last_message_age_at(t0), None);
+
+        // The stall clock starts at connect
+        health.mark_connected_at(t0);
+        assert_eq!(
+            health.last_message_age_at(at(2_000)),
+            Some(Duration::from_secs(2))
+        );
+
+        // The window opens on the first read; no rate until it spans 5s
+        health.record_messages_at(10, at(1_000));
+        health.record_messages_at(40, at(3_000));
+        assert_eq!(health.messages_per_sec(), 0.0);
+        health.record_messages_at(50, at(6_000));
+        assert_eq!(health.messages_per_sec(), 20.0);
+        assert_eq!(
+            health.last_message_age_at(at(8_500)),
+            Some(Duration::from_millis(2_500))
+        );
+
+        // A silent feed decays to zero on the next health check
+        health.roll_window(at(11_000));
+        assert_eq!(health.messages_per_sec(), 0.0);
+        assert_eq!(
+            health.last_message_age_at(at(11_000)),
+            Some(Duration::from_secs(5))
+        );
+
+        // Across connections the stalest one sets the age
+        let mut other = FeedHealth::new();
+        other.mark_connected_at(at(10_000));
+        other.record_reconnect();
+        let combined = FeedHealth::combined([&health, &other]);
+        assert_eq!(combined.reconnects(), 1);
+        assert_eq!(
+            combined.last_message_age_at(at(11_000)),
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn test_detection_throttled_until_best_ask_moves() {
+        use hfptm::websocket::DetectionThrottle;
+        use std::time::{Duration, Instant};
+
+        let mut throttle = DetectionThrottle::new(Duration::from_millis(5));
+        let t0 = Instant::now();
+        let at = |ms: u64| t0 + Duration::from_millis(ms);
+        let asks = |yes: Decimal, yes_size: Decimal| {
+            vec![
+                ("yes".to_string(), yes, yes_size),
+                ("no".to_string(), dec!(0.48), dec!(100)),
+            ]
+        };
+
+        // A burst of updates that only resize the level: one detection
+        let burst: Vec<bool> = (0..5)
+            .map(|ms| {
+                let resized = asks(dec!(0.47), Decimal::from(100 + ms));
+                throttle.should_detect_at("hot", &resized, at(ms))
+            })
+            .collect();
+        assert_eq!(burst, vec![true, false, false, false, false]);
+        assert_eq!(throttle.skipped(), 4);
+
+        // With no further update, the skipped ones are owed a trailing run once
+        // the interval is up, and only once
+        assert!(throttle.due_at(at(4)).is_empty());
+        assert_eq!(throttle.due_at(at(5)), vec!["hot"]);
+        assert!(throttle.due_at(at(20)).is_empty());
+
+        // Once the interval has passed an update on unchanged asks runs again
+        assert!(throttle.should_detect_at("hot", &asks(dec!(0.47), dec!(90)), at(25)));
+        assert!(throttle.due_at(at(40)).is_empty());
+
+        // A best-ask move runs straight away
+        assert!(throttle.should_detect_at("hot", &asks(dec!(0.46), dec!(90)), at(26)));
+        assert!(!throttle.should_detect_at("hot", &asks(dec!(0.46), dec!(80)), at(27)));
+
+        // Markets are throttled independently
+        assert!(throttle.should_detect_at("other", &asks(dec!(0.47), dec!(100)), at(27)));
+
+        // Zero interval: every update
+        let mut every_update = DetectionThrottle::new(Duration::ZERO);
+        let quiet = asks(dec!(0.47), dec!(100));
+        assert!((0..3).all(|ms| every_update.should_detect_at("hot", &quiet, at(ms))));
+        assert_eq!(every_update.interval(), None);
+        assert!(every_update.due_at(at(100)).is_empty());
+    }
+
+    #[test]
+    fn test_sequence_gap_when_top_of_book_disagrees() {
+        use hfptm::websocket::health::count_sequence_gaps;
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let change = |price: &str, best_bid: &str| -> WsMessage {
+            serde_json::from_value(serde_json::json!({
+                "event_type": "price_change",
+                "market": "gap_market",
+                "price_changes": [{
+                    "asset_id": "gap_yes",
+                    "price": price,
+                    "size": "10",
+                    "side": "BUY",
+                    "hash": "h",
+                    "best_bid": best_bid,
+                    "best_ask": "0.55"
+                }],
+            }))
+            .unwrap()
+        };
+        let snapshot = BookSnapshot {
+            market: "gap_market".to_string(),
+            asset_id: "gap_yes".to_string(),
+            bids: vec![(dec!(0.45), dec!(100))],
+            asks: vec![(dec!(0.55), dec!(100))],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager
+            .update_book("gap_market", "gap_yes", &snapshot)
+            .unwrap();
+
+        // A new best bid at 0.46, as the exchange reports: in sync
+        let batch = vec![change("0.46", "0.46")];
+        WebSocketClient::apply_message_batch(&batch, &manager);
+        assert_eq!(count_sequence_gaps(&batch, &manager), 0);
+
+        // The exchange's best bid is 0.48, but we never saw the 0.47/0.48 updates
+        let batch = vec![change("0.44", "0.48")];
+        WebSocketClient::apply_message_batch(&batch, &manager);
+        assert_eq!(count_sequence_gaps(&batch, &manager), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_feed_stall_alerts_once() {
+        use hfptm::websocket::FeedHealth;
+        use std::time::{Duration, Instant};
+
+        let mut config = create_test_config();
+        config.alerts.ws_stall_threshold_ms = 1_000;
+        let monitor = Monitor::new(&config).await.unwrap();
+        let mut health = FeedHealth::new();
+        let t0 = Instant::now();
+        let at = |ms: u64| t0 + Duration::from_millis(ms);
+        health.mark_connected_at(t0);
+        health.record_reconnect();
+
+        monitor.record_feed_health(&health, at(500)).await;
+        assert!(monitor.get_recent_alerts(10).await.is_empty());
+
+        // Connected, but nothing for 2s
+        monitor.record_feed_health(&health, at(2_000)).await;
+        monitor.record_feed_health(&health, at(3_000)).await;
+        let alerts = monitor.get_recent_alerts(10).await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, AlertType::FeedStalled);
+        assert_eq!(alerts[0].severity, AlertSeverity::Warning);
+
+        let metrics = monitor.get_metrics();
+        assert_eq!(metrics.ws_last_message_age_ms, 3_000);
+        assert_eq!(metrics.ws_reconnects, 1);
+
+        // Data resumes, then stalls again: a fresh alert
+        health.record_messages_at(1, at(4_000));
+        monitor.record_feed_health(&health, at(4_000)).await;
+        monitor.record_feed_health(&health, at(6_000)).await;
+        assert_eq!(monitor.get_recent_alerts(10).await.len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_balance_deviation_alerts_once() {
+        let config = create_test_config();
+        let monitor = Monitor::new(&config).await.unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let expected = risk_manager.available_bankroll();
+
+        // Within the $25 threshold, or holding more than the bankroll: fine
+        monitor
+            .check_balance_deviation(expected - dec!(20), expected)
+            .await;
+        monitor
+            .check_balance_deviation(expected + dec!(5000), expected)
+            .await;
+        assert!(monitor.get_recent_alerts(10).await.is_empty());
+
+        // The bot thinks it has the full bankroll but the wallet holds $3
+        monitor.check_balance_deviation(dec!(3), expected).await;
+        monitor.check_balance_deviation(dec!(3), expected).await;
+        let alerts = monitor.get_recent_alerts(10).await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, AlertType::BalanceDeviation);
+        assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+        assert_eq!(monitor.get_metrics().exchange_balance, Some(dec!(3)));
+
+        // Topped back up, then drained again: a fresh alert
+        monitor.check_balance_deviation(expected, expected).await;
+        monitor.check_balance_deviation(dec!(3), expected).await;
+        assert_eq!(monitor.get_recent_alerts(10).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_history_ages_out_past_retention_window() {
+        use hfptm::monitoring::{evict_history, newest_since, TradeRecord};
+        use std::collections::VecDeque;
+
+        let mut config = create_test_config();
+        config.monitoring.metrics_retention_hours = 1;
+        let monitor = Monitor::new(&config).await.unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let alert = |message: &str, age_secs: i64| Alert {
+            alert_type: AlertType::Error,
+            message: message.to_string(),
+            timestamp: now - age_secs,
+            severity: AlertSeverity::Error,
+        };
+
+        // Two hours old falls outside the one-hour window; half an hour doesn't
+        monitor.push_alert(alert("stale", 7200)).await;
+        monitor.push_alert(alert("recent", 1800)).await;
+        monitor.push_alert(alert("fresh", 0)).await;
+        let messages: Vec<_> = monitor
+            .get_recent_alerts(10)
+            .await
+            .into_iter()
+            .map(|a| a.message)
+            .collect();
+        assert_eq!(messages, ["fresh", "recent"]);
+
+        // The count cap still applies within the window, oldest going first
+        let trade = |market_id: &str, age_secs: i64| TradeRecord {
+            timestamp: now - age_secs,
+            market_id: market_id.to_string(),
+            arb_type: "Binary".to_string(),
+            position_size: dec!(10),
+            expected_profit: dec!(1),
+            actual_profit: dec!(1),
+            fees: Decimal::ZERO,
+            execution_time_ms: 1,
+            success: true,
+        };
+        let mut trades: VecDeque<_> = [
+            trade("expired", 7200),
+            trade("a", 60),
+            trade("b", 30),
+            trade("c", 0),
+        ]
+        .into();
+        // Readers skip what hasn't been evicted yet without touching the history
+        let newest: Vec<_> = newest_since(&trades, now - 3600, 10)
+            .into_iter()
+            .map(|t| t.market_id)
+            .collect();
+        assert_eq!(newest, ["c", "b", "a"]);
+        assert_eq!(newest_since(&trades, now - 3600, 1).len(), 1);
+        assert_eq!(trades.len(), 4);
+
+        evict_history(&mut trades, 100, now - 3600);
+        assert_eq!(trades.len(), 3);
+        evict_history(&mut trades, 2, now - 3600);
+        let markets: Vec<_> = trades.iter().map(|t| t.market_id.as_str()).collect();
+        assert_eq!(markets, ["b", "c"]);
+    }
+
+    #[test]
+    fn test_parse_user_channel_events() {
+        use hfptm::websocket::{TradeStatus, UserEvent, UserOrderUpdate, UserSide};
+
+        // Our resting BUY NO matched by someone else's BUY YES (a mint), then
+        // our BUY YES order placed and cancelled after a partial fill
+        let payload = r#"[
+            {
+                "asset_id": "yes_token",
+                "event_type": "trade",
+                "id": "28c4d2eb-bbea-40e7-a9f0-b2fdb56b2c2e",
+                "last_update": "1672290701",
+                "maker_orders": [
+                    {
+                        "asset_id": "no_token",
+                        "matched_amount": "10",
+                        "order_id": "0xmaker_no",
+                        "outcome": "No",
+                        "owner": "test_key",
+                        "price": "0.43"
+                    },
+                    {
+                        "asset_id": "yes_token",
+                        "matched_amount": "5",
+                        "order_id": "0xother",
+                        "outcome": "Yes",
+                        "owner": "someone_else",
+                        "price": "0.57"
+                    }
+                ],
+                "market": "0xbd31dc8a",
+                "matchtime": "1672290701",
+                "outcome": "Yes",
+                "owner": "someone_else",
+                "price": "0.57",
+                "side": "BUY",
+                "size": "15",
+                "status": "MATCHED",
+                "taker_order_id": "0xtaker",
+                "timestamp": "1672290701",
+                "trade_owner": "someone_else",
+                "type": "TRADE"
+            },
+            {
+                "asset_id": "yes_token",
+                "associate_trades": null,
+                "event_type": "order",
+                "id": "0xour_yes",
+                "market": "0xbd31dc8a",
+                "order_owner": "test_key",
+                "original_size": "20",
+                "outcome": "Yes",
+                "owner": "test_key",
+                "price": "0.55",
+                "side": "BUY",
+                "size_matched": "8",
+                "timestamp": "1672290687",
+                "type": "CANCELLATION"
+            }
+        ]"#;
+
+        let events = UserEvent::parse_batch(payload).unwrap();
+        assert_eq!(events.len(), 2);
+
+        let UserEvent::Trade(trade) = &events[0] else {
+            panic!("expected a trade, got {:?}", events[0]);
+        };
+        assert_eq!(trade.status, TradeStatus::Matched);
+        assert_eq!(trade.size, dec!(15));
+
+        // Only our maker order is a fill of ours; a complementary-token maker buys too
+        let fills = trade.fills_for("test_key");
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order_id, "0xmaker_no");
+        assert_eq!(fills[0].asset_id, "no_token");
+        assert_eq!(fills[0].side, UserSide::Buy);
+        assert_eq!((fills[0].price, fills[0].size), (dec!(0.43), dec!(10)));
+        assert_eq!(trade.fills_for("someone_else").len(), 2);
+
+        let UserEvent::Order(order) = &events[1] else {
+            panic!("expected an order, got {:?}", events[1]);
+        };
+        assert_eq!(order.update, UserOrderUpdate::Cancellation);
+        assert_eq!(order.original_size - order.size_matched, dec!(12));
+
+        // A single (non-array) frame parses too
+        let single = serde_json::to_string(&events[1]).unwrap();
+        assert_eq!(UserEvent::parse_batch(&single).unwrap().len(), 1);
+
+        // Fills feed position tracking
+        let config = create_test_config();
+        let risk_manager = RiskManager::new(&config);
+        risk_manager.record_fill(&fills[0]);
+        let position = risk_manager.get_position("no_token").unwrap();
+        assert_eq!(position.size, dec!(10));
+        assert_eq!(position.avg_price, dec!(0.43));
+    }
+
+    #[test]
+    fn test_resting_arb_frees_its_slot_once_every_leg_closes() {
+        use hfptm::executor::OrderResult;
+        use hfptm::websocket::{UserFill, UserSide};
+
+        let mut config = create_test_config();
+        config.risk.max_concurrent_arbs = 1;
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let risk_manager = RiskManager::new(&config);
+
+        let arb = binary_arb("resting_market", dec!(10), dec!(500));
+        let order = |asset_id: &str, order_id: &str| OrderResult {
+            asset_id: asset_id.to_string(),
+            success: true,
+            order_id: Some(order_id.to_string()),
+            error: None,
+            matched_price: None,
+        };
+        let result = ExecutionResult {
+            success: true,
+            filled: false,
+            partial_fill: false,
+            filled_amount: Decimal::ZERO,
+            total_cost: Decimal::ZERO,
+            expected_cost: Decimal::ZERO,
+            realized_cost: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            orders: vec![order("yes_asset", "0xyes"), order("no_asset", "0xno")],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+        risk_manager.record_arbitrage_submission(&arb, &result);
+        assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 1);
+
+        let other = binary_arb("other_market", dec!(10), dec!(500));
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&other).unwrap(),
+            RiskDecision::Reject(RiskRejectReason::MaxConcurrentArbs)
+        );
+
+        // The YES leg fills in two pieces; the arb still holds its slot
+        let fill = |size: Decimal| UserFill {
+            trade_id: "t".to_string(),
+            order_id: "0xyes".to_string(),
+            market_id: "resting_market".to_string(),
+            asset_id: "yes_asset".to_string(),
+            outcome: "Yes".to_string(),
+            side: UserSide::Buy,
+            price: dec!(0.45),
+            size,
+            fee: Decimal::ZERO,
+        };
+        risk_manager.record_fill(&fill(dec!(4)));
+        risk_manager.record_fill(&fill(dec!(6)));
+        assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 1);
+
+        // Cancelling the NO leg closes the last one
+        risk_manager.record_order_closed("0xno");
+        assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 0);
+        risk_manager.record_order_closed("0xno");
+        assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 0);
+        assert_eq!(
+            risk_manager.can_execute_arbitrage(&other).unwrap(),
+            RiskDecision::Allow
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_arb_slot_reservations_respect_limit() {
+        let mut config = create_test_config();
+        config.risk.max_concurrent_arbs = 5;
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let risk_manager = std::sync::Arc::new(RiskManager::new(&config));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let risk_manager = risk_manager.clone();
+                tokio::spawn(async move {
+                    let mut reserved = 0;
+                    for _ in 0..25 {
+                        if risk_manager.try_reserve_arb_slot() {
+                            reserved += 1;
+                        }
+                        tokio::task::yield_now().await;
+                    }
+                    reserved
+                })
+            })
+            .collect();
+        let mut reserved = 0;
+        for task in tasks {
+            reserved += task.await.unwrap();
+        }
+        assert_eq!(reserved, 5);
+        assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 5);
+
+        // A recorded execution keeps its reserved slot rather than taking another
+        let arb = binary_arb("reserved_market", dec!(10), dec!(500));
+        let mut result = ExecutionResult {
+            success: true,
+            filled: true,
+            partial_fill: false,
+            filled_amount: dec!(10),
+            total_cost: dec!(9),
+            expected_cost: dec!(9),
+            realized_cost: dec!(9),
+            realized_pnl: dec!(1),
+            fees_paid: Decimal::ZERO,
+            orders: vec![],
+            execution_time_ms: 1,
+            error_message: None,
+        };
+        risk_manager
+            .record_arbitrage_execution(&arb, &result)
+            .unwrap();
+        assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 5);
+
+        // One that filled nothing, or was never sent, gives its slot back
+        result.success = false;
+        result.filled = false;
+        risk_manager
+            .record_arbitrage_execution(&arb, &result)
+            .unwrap();
+        risk_manager.release_arb_slot();
+        assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 3);
+        assert!(risk_manager.try_reserve_arb_slot());
+    }
+
+    #[test]
+    fn test_rate_limiter_bounds_processing_under_flood() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let mut limiter = UpdateRateLimiter::new(config.latency.max_orderbook_updates_per_sec);
+
+        let snapshot = BookSnapshot {
+            market: "flood_market".to_string(),
+            asset_id: "flood_market_yes".to_string(),
+            bids: vec![],
+            asks: vec![],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager
+            .update_book("flood_market", "flood_market_yes", &snapshot)
+            .unwrap();
+
+        // 20k level changes within the same instant, cycling over 50 bid levels,
+        // each carrying its sequence number as size so we can check freshness
+        let now = std::time::Instant::now();
+        let total = 20_000;
+        for batch in 0..20 {
+            let messages: Vec<WsMessage> = (0..1000)
+                .map(|j| {
+                    let i = batch * 1000 + j;
+                    let price = format!("0.{:02}", i % 50 + 1);
+                    sized_price_change_msg("flood_market", &price, &i.to_string())
+                })
+                .collect();
+
+            let messages = limiter.throttle_at(messages, now);
+            WebSocketClient::apply_message_batch(&messages, &manager);
+        }
+
+        // First 10k pass through; the market was just processed, so the rest
+        // are coalesced across batches and held back rather than processed
+        assert_eq!(limiter.processed_updates(), 10_000);
+        let release = limiter.next_release().unwrap();
+        assert_eq!(release, now + MARKET_PROCESS_INTERVAL);
+
+        // A market not processed recently still goes straight through
+        let quiet = limiter.throttle_at(
+            vec![sized_price_change_msg("quiet_market", "0.40", "5")],
+            now,
+        );
+        assert_eq!(quiet.len(), 1);
+        assert_eq!(limiter.processed_updates(), 10_001);
+
+        // Once the interval passes the held changes go out as one pass of 50 levels
+        let messages = limiter.throttle_at(Vec::new(), release);
+        WebSocketClient::apply_message_batch(&messages, &manager);
+        assert_eq!(limiter.processed_updates(), 10_001 + 50);
+        assert!(limiter.next_release().is_none());
+        assert_eq!(
+            limiter.processed_updates() + limiter.dropped_updates(),
+            total + 1
+        );
+
+        // Best bid reflects the very last update to that level
+        let book = manager
+            .get_book("flood_market", "flood_market_yes")
+            .unwrap();
+        assert_eq!(book.best_bid(), Some((dec!(0.50), dec!(19999))));
+        assert_eq!(book.bids.len(), 50);
+    }
+
+    #[test]
+    fn test_trade_records_export_to_csv() {
+        use hfptm::monitoring::TradeRecord;
+        use hfptm::utils::export::{export_csv_arg, suffixed_path, write_csv};
+
+        let trades = vec![
+            TradeRecord {
+                timestamp: 1_700_000_000,
+                market_id: "mkt_a".to_string(),
+                arb_type: "Binary".to_string(),
+                position_size: dec!(50),
+                expected_profit: dec!(1.5),
+                actual_profit: dec!(1.25),
+                fees: dec!(1),
+                execution_time_ms: 42,
+                success: true,
+            },
+            TradeRecord {
+                timestamp: 1_700_000_060,
+                market_id: "mkt, b".to_string(),
+                arb_type: "MultiOutcome".to_string(),
+                position_size: dec!(12.345678),
+                expected_profit: dec!(0.1),
+                actual_profit: Decimal::ZERO,
+                fees: Decimal::ZERO,
+                execution_time_ms: 7,
+                success: false,
+            },
+        ];
+
+        let mut out = Vec::new();
+        write_csv(&mut out, &trades).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "timestamp,market_id,arb_type,position_size,expected_profit,actual_profit,execution_time_ms,success\n\
+             1700000000,mkt_a,Binary,50.0000,1.5000,1.2500,42,true\n\
+             1700000060,\"mkt, b\",MultiOutcome,12.3457,0.1000,0.0000,7,false\n"
+        );
+
+        let args = ["--export-csv", "logs/trades.csv"].map(String::from);
+        let path = export_csv_arg(args).unwrap();
+        assert_eq!(
+            suffixed_path(&path, "arb"),
+            std::path::PathBuf::from("logs/trades_arb.csv")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_stale_books() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let max_age_ms = config.execution.stale_book_max_age_ms;
+
+        let stale_snapshot = BookSnapshot {
+            market: "stale_market".to_string(),
+            asset_id: "stale_asset".to_string(),
+            bids: vec![(dec!(0.48), dec!(100))],
+            asks: vec![(dec!(0.52), dec!(100))],
+            timestamp: current_timestamp_ms() - max_age_ms as i64 - 1_000,
+            hash: "stale_hash".to_string(),
+        };
+        let fresh_snapshot = BookSnapshot {
+            market: "fresh_market".to_string(),
+            asset_id: "fresh_asset".to_string(),
+            bids: vec![(dec!(0.48), dec!(100))],
+            asks: vec![(dec!(0.52), dec!(100))],
+            timestamp: current_timestamp_ms(),
+            hash: "fresh_hash".to_string(),
+        };
+
+        manager
+            .update_book("stale_market", "stale_asset", &stale_snapshot)
+            .unwrap();
+        manager
+            .update_book("fresh_market", "fresh_asset", &fresh_snapshot)
+            .unwrap();
+
+        let swept = manager.cleanup_stale_books(max_age_ms);
+
+        assert_eq!(swept, vec!["stale_market".to_string()]);
+        assert!(manager.get_market_books("stale_market").is_none());
+        assert!(manager.get_market_books("fresh_market").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_stale_markets_lists_only_stale_books_with_ages() {
+        use hfptm::monitoring::StaleMarket;
+
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut engine = ArbEngine::new(&config);
+        let feed = |market_id: &str, asset_id: &str, age_ms: i64| {
+            let mut snapshot = ask_snapshot(market_id, asset_id, vec![(dec!(0.45), dec!(200))]);
+            snapshot.timestamp = current_timestamp_ms() - age_ms;
+            manager.update_book(market_id, asset_id, &snapshot).unwrap();
+        };
+
+        feed("fresh", "fresh_yes", 0);
+        feed("quiet", "quiet_yes", 120_000);
+        feed("dead", "dead_yes", 600_000);
+        // One live leg doesn't make up for a dead one
+        let market = test_market("half", "Will it happen?", &["yes", "no"]);
+        manager.register_market(&market);
+        feed("half", "yes", 0);
+        feed("half", "no", 300_000);
+
+        let stale = manager.stale_markets(60_000);
+        let ids: Vec<&str> = stale.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["dead", "half", "quiet"]);
+        for ((_, age_ms), expected) in stale.iter().zip([600_000, 300_000, 120_000]) {
+            assert!((expected..expected + 5_000).contains(age_ms), "{}", age_ms);
+        }
+        assert_eq!(manager.stale_markets(400_000).len(), 1);
+        assert!(manager.market_age_ms("fresh").unwrap() < 5_000);
+        assert_eq!(manager.market_age_ms("missing"), None);
+
+        // Read-only: nothing was swept
+        assert_eq!(manager.get_all_market_ids().len(), 4);
+
+        // Past stale_book_max_age_ms (60s here), detection waits for the sweeper
+        // rather than pricing off the dead leg
+        let mut detect = || {
+            engine
+                .detect_arbitrage(&manager, "half", Some(&market), &risk_manager)
+                .unwrap()
+        };
+        assert!(detect().is_none());
+        feed("half", "no", 0);
+        assert!(detect().is_some());
+
+        // The monitor keeps the last list for /stale_books
+        let monitor = Monitor::new(&config).await.unwrap();
+        monitor.record_stale_markets(manager.stale_markets(60_000));
+        let listed = monitor.stale_markets();
+        assert_eq!(
+            listed
+                .iter()
+                .map(|m| m.market_id.as_str())
+                .collect::<Vec<_>>(),
+            ["dead", "quiet"]
+        );
+        assert!(matches!(
+            listed[0],
+            StaleMarket { age_ms, .. } if age_ms >= 600_000
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_book_rest_populates_manager() {
+        use axum::{routing::get, Json, Router};
+
+        let book_json = serde_json::json!({
+            "market": "rest_market",
+            "asset_id": "rest_asset",
+            "timestamp": current_timestamp_ms().to_string(),
+            "hash": "rest_hash",
+            "bids": [
+                { "price": "0.40", "size": "50" },
+                { "price": "0.45", "size": "120" }
+            ],
+            "asks": [
+                { "price": "0.60", "size": "80" },
+                { "price": "0.55", "size": "30" }
+            ]
+        });
+
+        let app = Router::new().route(
+            "/book",
+            get(move || {
+                let body = book_json.clone();
+                async move { Json(body) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let client = reqwest::Client::new();
+
+        let book = manager
+            .fetch_book_rest(&client, &base_url, "rest_asset")
+            .await
+            .unwrap()
+            .expect("book should be returned");
+
+        assert_eq!(book.bids.len(), 2);
+        assert_eq!(book.asks.len(), 2);
+        assert_eq!(book.best_ask(), Some((dec!(0.55), dec!(30))));
+        assert_eq!(book.best_bid(), Some((dec!(0.45), dec!(120))));
+        assert!(manager.get_book("rest_market", "rest_asset").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rest_fallback_polls_while_websocket_is_down() {
+        use axum::{extract::Query, routing::get, Json, Router};
+        use hfptm::websocket::{poll_books, FallbackTransition, RestFallback};
+        use std::collections::HashMap;
+        use std::time::{Duration, Instant};
+
+        let app = Router::new().route(
+            "/book",
+            get(|Query(params): Query<HashMap<String, String>>| async move {
+                Json(serde_json::json!({
+                    "market": "fallback_market",
+                    "asset_id": params["token_id"],
+                    "timestamp": current_timestamp_ms().to_string(),
+                    "bids": [{ "price": "0.40", "size": "50" }],
+                    "asks": [{ "price": "0.45", "size": "200" }]
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let client = reqwest::Client::new();
+        let asset_ids = vec!["yes_asset".to_string(), "no_asset".to_string()];
+        let mut fallback = RestFallback::new(Duration::from_secs(30));
+        let t0 = Instant::now();
+
+        // A short outage is left to the reconnect loop
+        assert_eq!(fallback.update(false, t0), None);
+        assert_eq!(fallback.update(false, t0 + Duration::from_secs(29)), None);
+        assert!(!fallback.is_active());
+
+        // Down past the threshold: REST polling takes over and feeds the books
+        assert_eq!(
+            fallback.update(false, t0 + Duration::from_secs(30)),
+            Some(FallbackTransition::Activated)
+        );
+        assert!(fallback.is_active());
+        let touched = poll_books(&manager, &client, &base_url, &asset_ids).await;
+        assert_eq!(touched, vec!["fallback_market".to_string()]);
+        let best_asks = manager.get_best_asks_for_market("fallback_market").unwrap();
+        assert_eq!(best_asks.len(), 2);
+        assert!(best_asks.iter().all(|(_, price, _)| *price == dec!(0.45)));
+
+        // Stays on without repeating the transition, then yields to the WebSocket
+        assert_eq!(fallback.update(false, t0 + Duration::from_secs(60)), None);
+        assert_eq!(
+            fallback.update(true, t0 + Duration::from_secs(61)),
+            Some(FallbackTransition::Deactivated)
+        );
+        assert!(!fallback.is_active());
+
+        // A new outage restarts the clock
+        assert_eq!(fallback.update(false, t0 + Duration::from_secs(70)), None);
+        assert_eq!(fallback.update(false, t0 + Duration::from_secs(99)), None);
+        assert_eq!(
+            fallback.update(false, t0 + Duration::from_secs(100)),
+            Some(FallbackTransition::Activated)
+        );
+    }
+
+    #[test]
+    fn test_estimate_annualized_return() {
+        use hfptm::arb_engine::estimate_annualized_return;
+
+        // 1% edge, 525,600 min/year / 60 min = 8,760 cycles => 87.6x naive
+        // Haircut by 10% fill and 50% utilization => 4.38x
+        let realistic =
+            estimate_annualized_return(dec!(0.01), 60, dec!(0.1), dec!(0.5), dec!(100));
+        assert_eq!(realistic, dec!(4.38));
+
+        // Same inputs at 100% fill/utilization would be 87.6x; the cap clamps it
+        let capped = estimate_annualized_return(dec!(0.01), 60, dec!(1), dec!(1), dec!(10));
+        assert_eq!(capped, dec!(10));
+    }
+
+    fn test_market(condition_id: &str, question: &str, asset_ids: &[&str]) -> Market {
+        Market {
+            id: "1".to_string(),
+            question: question.to_string(),
+            slug: question.to_lowercase().replace(' ', "-"),
+            market: condition_id.to_string(),
+            description: None,
+            outcomes: vec![],
+            assets_ids: asset_ids.iter().map(|a| a.to_string()).collect(),
+            ticker_tag: None,
+            end_date: Some((chrono::Utc::now() + chrono::Duration::minutes(10)).to_rfc3339()),
+            volume_24h: Some(5000.0),
+            active: true,
+            closed: false,
+            enable_order_book: true,
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn test_microprice_vs_midpoint_on_imbalanced_book() {
+        let mut config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+
+        // Heavy bid (900) vs thin ask (100): pressure is upward
+        let snapshot = BookSnapshot {
+            market: "mm_market".to_string(),
+            asset_id: "mm_asset".to_string(),
+            bids: vec![(dec!(0.40), dec!(900))],
+            asks: vec![(dec!(0.50), dec!(100))],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager.update_book("mm_market", "mm_asset", &snapshot).unwrap();
+
+        let book = manager.get_book("mm_market", "mm_asset").unwrap();
+        assert_eq!(book.midpoint(), Some(dec!(0.45)));
+        // (0.40 * 100 + 0.50 * 900) / 1000
+        assert_eq!(book.microprice(), Some(dec!(0.49)));
+
+        let markets = vec![test_market("mm_market", "Will it rain?", &["mm_asset"])];
+
+        let arithmetic = MarketMaker::new(&config).find_opportunities(&manager, &markets);
+        assert_eq!(arithmetic[0].midpoint, dec!(0.45));
+
+        config.trading.mm_midpoint_mode = MidpointMode::Microprice;
+        let microprice = MarketMaker::new(&config).find_opportunities(&manager, &markets);
+        assert_eq!(microprice[0].midpoint, dec!(0.49));
+        assert!(microprice[0].bid_price > arithmetic[0].bid_price);
+    }
+
+    #[test]
+    fn test_mm_min_spread_counts_market_ticks() {
+        let mut config = create_test_config();
+        config.trading.mm_min_spread_ticks = 2;
+        let manager = OrderBookManager::new(&config).unwrap();
+        let mm = MarketMaker::new(&config);
+
+        let book = |market_id: &str, bid: Decimal, ask: Decimal| {
+            let snapshot = BookSnapshot {
+                market: market_id.to_string(),
+                asset_id: format!("{}_asset", market_id),
+                bids: vec![(bid, dec!(100))],
+                asks: vec![(ask, dec!(100))],
+                timestamp: current_timestamp_ms(),
+                hash: format!("{}-{}", bid, ask),
+            };
+            manager
+                .update_book(market_id, &snapshot.asset_id, &snapshot)
+                .unwrap();
+            vec![test_market(market_id, market_id, &[&snapshot.asset_id])]
+        };
+
+        // 0.49 / 0.50 is a single 0.01 tick: nothing to quote inside
+        let coarse = book("coarse", dec!(0.49), dec!(0.50));
+        assert!(mm.find_opportunities(&manager, &coarse).is_empty());
+
+        // The same prices are 10 ticks wide on a 0.001-tick market
+        manager.set_tick_size("fine", dec!(0.001));
+        let fine = book("fine", dec!(0.49), dec!(0.50));
+        assert_eq!(mm.find_opportunities(&manager, &fine).len(), 1);
+
+        // ...where a single 0.001 tick is too tight again
+        let fine = book("fine", dec!(0.499), dec!(0.500));
+        assert!(mm.find_opportunities(&manager, &fine).is_empty());
+
+        // A 3-tick minimum needs 0.03 at a 0.01 tick
+        config.trading.mm_min_spread_ticks = 3;
+        let mm = MarketMaker::new(&config);
+        let coarse = book("coarse", dec!(0.48), dec!(0.50));
+        assert!(mm.find_opportunities(&manager, &coarse).is_empty());
+        let coarse = book("coarse", dec!(0.47), dec!(0.50));
+        assert_eq!(mm.find_opportunities(&manager, &coarse).len(), 1);
+    }
+
+    #[test]
+    fn test_mm_price_improvement_pegs_inside_best_quotes() {
+        let mut config = create_test_config();
+        config.trading.mm_spread_bps = 1000;
+        config.trading.mm_ladder_levels = 1;
+        config.trading.mm_min_spread_ticks = 2;
+        config.trading.mm_price_improve_ticks = 1;
+        let manager = OrderBookManager::new(&config).unwrap();
+        let markets = vec![test_market(
+            "improve_market",
+            "Improve?",
+            &["improve_asset"],
+        )];
+
+        let quote = |mm: &MarketMaker, bid: Decimal, ask: Decimal| {
+            let snapshot = BookSnapshot {
+                market: "improve_market".to_string(),
+                asset_id: "improve_asset".to_string(),
+                bids: vec![(bid, dec!(300))],
+                asks: vec![(ask, dec!(300))],
+                timestamp: current_timestamp_ms(),
+                hash: format!("{}-{}", bid, ask),
+            };
+            manager
+                .update_book("improve_market", "improve_asset", &snapshot)
+                .unwrap();
+            let opportunities = mm.find_opportunities(&manager, &markets);
+            (opportunities[0].bid_price, opportunities[0].ask_price)
+        };
+        // Midpoint +/- 5%, on the tick
+        let spread_based = |midpoint: Decimal| {
+            let half_spread = midpoint * dec!(1000) / dec!(20000);
+            (
+                manager.round_to_tick("improve_market", midpoint - half_spread, ArbSide::Buy),
+                manager.round_to_tick("improve_market", midpoint + half_spread, ArbSide::Sell),
+            )
+        };
+
+        // Competitors at 0.48 / 0.54 sit inside our 0.48 / 0.54 spread-based
+        // quote: we go one tick ahead of each
+        let mm = MarketMaker::new(&config);
+        assert_eq!(spread_based(dec!(0.51)), (dec!(0.48), dec!(0.54)));
+        assert_eq!(quote(&mm, dec!(0.48), dec!(0.54)), (dec!(0.49), dec!(0.53)));
+
+        // Competitors at 0.40 / 0.60 are already behind the spread-based quote,
+        // which is never widened to meet them
+        assert_eq!(quote(&mm, dec!(0.40), dec!(0.60)), spread_based(dec!(0.50)));
+
+        // At 0.48 / 0.51, 0.49 / 0.50 would be one tick wide: keep the
+        // spread-based quote instead
+        assert_eq!(
+            quote(&mm, dec!(0.48), dec!(0.51)),
+            spread_based(dec!(0.495))
+        );
+
+        // Off, the spread-based quote stands even in a wide book
+        config.trading.mm_price_improve_ticks = 0;
+        let mm = MarketMaker::new(&config);
+        assert_eq!(quote(&mm, dec!(0.40), dec!(0.60)), spread_based(dec!(0.50)));
+    }
+
+    #[tokio::test]
+    async fn test_mm_ladder_geometry() {
+        let mut config = create_test_config();
+        config.trading.mm_spread_bps = 200;
+        config.trading.mm_order_size = 40;
+        config.trading.mm_ladder_levels = 4;
+        config.trading.mm_ladder_step_bps = 100;
+        config.trading.mm_ladder_size_decay = dec!(0.5);
+        config.trading.mm_max_orders_per_market = 6;
+        let manager = OrderBookManager::new(&config).unwrap();
+        manager.set_tick_size("mm_market", dec!(0.001));
+
+        let snapshot = BookSnapshot {
+            market: "mm_market".to_string(),
+            asset_id: "mm_asset".to_string(),
+            bids: vec![(dec!(0.45), dec!(100))],
+            asks: vec![(dec!(0.55), dec!(100))],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager.update_book("mm_market", "mm_asset", &snapshot).unwrap();
+        let markets = vec![test_market("mm_market", "Will it rain?", &["mm_asset"])];
+
+        let mut mm = MarketMaker::new(&config);
+        let opportunities = mm.find_opportunities(&manager, &markets);
+        let ladder = &opportunities[0].ladder;
+
+        // Midpoint 0.50: half-spread 1% (0.005), then +1% of midpoint per rung.
+        // 4 levels requested, but 6 max orders only leaves room for 3 bid/ask pairs.
+        let geometry: Vec<_> = ladder
+            .iter()
+            .map(|r| (r.level, r.bid_price, r.ask_price, r.size))
+            .collect();
+        assert_eq!(
+            geometry,
+            vec![
+                (0, dec!(0.495), dec!(0.505), dec!(40)),
+                (1, dec!(0.490), dec!(0.510), dec!(20)),
+                (2, dec!(0.485), dec!(0.515), dec!(10)),
+            ]
+        );
+        assert_eq!(opportunities[0].bid_price, dec!(0.495));
+        assert_eq!(opportunities[0].size, dec!(40));
+
+        // Every rung becomes its own pair of open orders
+        let placed = mm.simulate_mm_orders(&opportunities).await.unwrap();
+        assert_eq!(placed.len(), 3);
+        for (result, rung) in placed.iter().zip(ladder) {
+            assert_eq!(result.level, rung.level);
+            let bid = mm.get_order(&result.bid_order_id).unwrap();
+            let ask = mm.get_order(&result.ask_order_id).unwrap();
+            assert_eq!((bid.price, bid.size), (rung.bid_price, rung.size));
+            assert_eq!((ask.price, ask.size), (rung.ask_price, rung.size));
+        }
+        assert_eq!(mm.get_stats().open_orders, 6);
+
+        // The market is full: a second pass places nothing
+        assert!(mm.simulate_mm_orders(&opportunities).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mm_rewards_favour_orders_near_midpoint() {
+        let mut config = create_test_config();
+        config.trading.mm_spread_bps = 200;
+        config.trading.mm_order_size = 40;
+        config.trading.mm_ladder_levels = 6;
+        config.trading.mm_ladder_step_bps = 100;
+        config.trading.mm_max_orders_per_market = 12;
+        config.trading.mm_reward_pool_usd = dec!(50);
+        config.trading.mm_reward_max_spread = dec!(0.03);
+        let manager = OrderBookManager::new(&config).unwrap();
+        manager.set_tick_size("mm_market", dec!(0.001));
+
+        // Competing liquidity 2¢ either side of a 0.50 midpoint
+        let snapshot = BookSnapshot {
+            market: "mm_market".to_string(),
+            asset_id: "mm_asset".to_string(),
+            bids: vec![(dec!(0.48), dec!(100))],
+            asks: vec![(dec!(0.52), dec!(100))],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager.update_book("mm_market", "mm_asset", &snapshot).unwrap();
+        let markets = vec![test_market("mm_market", "Will it rain?", &["mm_asset"])];
+
+        let opportunities = MarketMaker::new(&config).find_opportunities(&manager, &markets);
+        let rewards: Vec<Decimal> = opportunities[0]
+            .ladder
+            .iter()
+            .map(|r| r.estimated_reward)
+            .collect();
+
+        // Same size on every rung, 0.5¢ further out each time: rewards strictly fall
+        assert_eq!(rewards.len(), 6);
+        assert!(rewards.windows(2).all(|w| w[0] > w[1]));
+        // The outermost rung sits 3¢ away, at the edge of the reward spread
+        assert_eq!(rewards[5], Decimal::ZERO);
+        // The book's resting liquidity takes its share of the pool
+        let total: Decimal = rewards.iter().sum();
+        assert!(total > Decimal::ZERO && total < dec!(50));
+        assert_eq!(opportunities[0].estimated_reward, total);
+    }
+
+    #[test]
+    fn test_mm_market_score_follows_polymarket_q_min() {
+        use hfptm::market_maker::two_sided_score;
+
+        // Q_min = max(min(Q_one, Q_two), max(Q_one / c, Q_two / c)) with c = 3
+        // for a midpoint in [0.10, 0.90]; min(Q_one, Q_two) outside it
+        assert_eq!(two_sided_score(dec!(0.50), dec!(90), dec!(60)), dec!(60));
+        assert_eq!(two_sided_score(dec!(0.50), dec!(90), dec!(15)), dec!(30));
+        assert_eq!(
+            two_sided_score(dec!(0.50), Decimal::ZERO, dec!(90)),
+            dec!(30)
+        );
+        assert_eq!(
+            two_sided_score(dec!(0.10), dec!(90), Decimal::ZERO),
+            dec!(30)
+        );
+
+        // Near the extremes one-sided liquidity earns nothing
+        assert_eq!(two_sided_score(dec!(0.95), dec!(90), dec!(15)), dec!(15));
+        assert_eq!(
+            two_sided_score(dec!(0.05), dec!(90), Decimal::ZERO),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_book_microstructure_queries() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let snapshot = BookSnapshot {
+            market: "micro".to_string(),
+            asset_id: "yes".to_string(),
+            bids: vec![
+                (dec!(0.48), dec!(100)),
+                (dec!(0.47), dec!(200)),
+                (dec!(0.45), dec!(300)),
+            ],
+            asks: vec![
+                (dec!(0.52), dec!(50)),
+                (dec!(0.53), dec!(150)),
+                (dec!(0.60), dec!(400)),
+            ],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager.update_book("micro", "yes", &snapshot).unwrap();
+
+        assert_eq!(manager.midpoint("micro", "yes"), Some(dec!(0.50)));
+        // 0.04 spread on a 0.50 midpoint
+        assert_eq!(manager.spread_bps("micro", "yes"), Some(dec!(800)));
+
+        // Top 2 levels: 300 bid vs 200 ask
+        assert_eq!(manager.book_imbalance("micro", "yes", 2), Some(dec!(0.6)));
+        // Whole book: 600 vs 600
+        assert_eq!(manager.book_imbalance("micro", "yes", 10), Some(dec!(0.5)));
+
+        // 600 bps of 0.50 = 0.47..=0.53
+        assert_eq!(
+            manager.depth_within_bps("micro", "yes", 600),
+            Some((dec!(300), dec!(200)))
+        );
+        // 0.45..=0.55
+        assert_eq!(
+            manager.depth_within_bps("micro", "yes", 1000),
+            Some((dec!(600), dec!(200)))
+        );
+
+        assert_eq!(manager.midpoint("micro", "no"), None);
+        assert_eq!(manager.spread_bps("unknown", "yes"), None);
+
+        // One-sided book: no midpoint, all of the depth is bid
+        let bids_only = BookSnapshot {
+            asset_id: "no".to_string(),
+            asks: vec![],
+            ..snapshot
+        };
+        manager.update_book("micro", "no", &bids_only).unwrap();
+        assert_eq!(manager.midpoint("micro", "no"), None);
+        assert_eq!(manager.depth_within_bps("micro", "no", 600), None);
+        assert_eq!(manager.book_imbalance("micro", "no", 3), Some(Decimal::ONE));
+    }
+
+    #[test]
+    fn test_round_to_tick_follows_market_tick_size() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+
+        let buy = |market: &str, price: Decimal| manager.round_to_tick(market, price, ArbSide::Buy);
+        let sell =
+            |market: &str, price: Decimal| manager.round_to_tick(market, price, ArbSide::Sell);
+
+        // Config default: 0.01. Buys round down and sells up, never against us
+        assert_eq!(manager.tick_size("tick_market"), dec!(0.01));
+        assert_eq!(buy("tick_market", dec!(0.456)), dec!(0.45));
+        assert_eq!(sell("tick_market", dec!(0.456)), dec!(0.46));
+        assert_eq!(buy("tick_market", dec!(0.454)), dec!(0.45));
+        assert_eq!(sell("tick_market", dec!(0.454)), dec!(0.46));
+        // On-tick prices are left alone
+        assert_eq!(buy("tick_market", dec!(0.45)), dec!(0.45));
+        assert_eq!(sell("tick_market", dec!(0.45)), dec!(0.45));
+        // Never rounds to 0 or 1
+        assert_eq!(buy("tick_market", dec!(0.001)), dec!(0.01));
+        assert_eq!(sell("tick_market", dec!(0.999)), dec!(0.99));
+
+        // A tick_size_change for one market leaves the others on the default
+        manager.set_tick_size("tick_market", dec!(0.001));
+        assert_eq!(buy("tick_market", dec!(0.4564)), dec!(0.456));
+        assert_eq!(buy("other_market", dec!(0.4564)), dec!(0.45));
+        assert_eq!(sell("other_market", dec!(0.4564)), dec!(0.46));
+
+        // Market maker quotes land on the market's tick
+        let mut config = create_test_config();
+        config.trading.mm_spread_bps = 300;
+        let manager = OrderBookManager::new(&config).unwrap();
+        let snapshot = BookSnapshot {
+            market: "mm_market".to_string(),
+            asset_id: "mm_asset".to_string(),
+            bids: vec![(dec!(0.45), dec!(100))],
+            asks: vec![(dec!(0.55), dec!(100))],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager
+            .update_book("mm_market", "mm_asset", &snapshot)
+            .unwrap();
+        let markets = vec![test_market("mm_market", "Will it rain?", &["mm_asset"])];
+
+        // Midpoint 0.50 +/- 0.0075 rounds out to 0.49 / 0.51
+        let opportunities = MarketMaker::new(&config).find_opportunities(&manager, &markets);
+        assert_eq!(
+            (opportunities[0].bid_price, opportunities[0].ask_price),
+            (dec!(0.49), dec!(0.51))
+        );
+    }
+
+    #[test]
+    fn test_short_window_multi_outcome_market() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut engine = ArbEngine::new(&config);
+
+        let asset_ids = ["bucket_low", "bucket_mid", "bucket_high"];
+        let market = test_market("btc_buckets", "BTC price at 12:15 - which bucket?", &asset_ids);
+
+        // 0.30 + 0.31 + 0.32 = 0.93 => 7% raw, 5% net of fees
+        let asks = [(dec!(0.30), dec!(150)), (dec!(0.31), dec!(120)), (dec!(0.32), dec!(200))];
+        for (asset_id, ask) in asset_ids.iter().zip(asks) {
+            let snapshot = BookSnapshot {
+                market: "btc_buckets".to_string(),
+                asset_id: asset_id.to_string(),
+                bids: vec![],
+                asks: vec![ask],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            manager
+                .update_book("btc_buckets", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        let opp = engine
+            .detect_short_window_arbitrage(&manager, &market, &config.markets, &risk_manager)
+            .unwrap()
+            .expect("3-outcome short-window arb");
+
+        assert_eq!(opp.legs.len(), 3);
+        assert_eq!(opp.sum_prices, dec!(0.93));
+        assert_eq!(opp.net_edge, dec!(0.05));
+        assert_eq!(opp.min_liquidity, dec!(120));
+        assert_eq!(opp.position_size, dec!(50));
+        assert_eq!(opp.expected_profit, dec!(2.50));
+    }
+
+    #[test]
+    fn test_short_window_sizes_to_vwap_edge() {
+        let mut config = create_test_config();
+        config.trading.min_net_profit_usd = dec!(0.1);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut engine = ArbEngine::new(&config);
+
+        let market = test_market("btc_15m", "BTC up or down at 12:15?", &["up", "down"]);
+
+        // Only 10 at the top of UP; the rest of the ladder is progressively worse
+        let up_asks = vec![
+            (dec!(0.45), dec!(10)),
+            (dec!(0.48), dec!(20)),
+            (dec!(0.55), dec!(100)),
+        ];
+        let down_asks = vec![(dec!(0.48), dec!(200))];
+        manager
+            .update_book("btc_15m", "up", &ask_snapshot("btc_15m", "up", up_asks))
+            .unwrap();
+        manager
+            .update_book(
+                "btc_15m",
+                "down",
+                &ask_snapshot("btc_15m", "down", down_asks),
+            )
+            .unwrap();
+
+        let opp = engine
+            .detect_short_window_arbitrage(&manager, &market, &config.markets, &risk_manager)
+            .unwrap()
+            .expect("short-window arb");
+
+        // Top of book: 0.45 + 0.48 = 0.93, 5% net
+        assert_eq!(opp.sum_prices, dec!(0.93));
+        assert_eq!(opp.net_edge, dec!(0.05));
+        assert_eq!(opp.legs[0].depth.len(), 3);
+        assert_eq!(opp.min_liquidity, dec!(130));
+
+        // Walking the ladder costs more per set, so the VWAP edge is thinner, and
+        // sizing stops (short of the $50 cap) where it would drop below min_edge
+        assert!(opp.vwap_sum > opp.sum_prices);
+        assert!(opp.vwap_net_edge < opp.net_edge);
+        assert!(opp.vwap_net_edge >= config.trading.short_window_min_edge);
+        assert_eq!(opp.position_size, dec!(41.37));
+        assert_eq!(opp.expected_profit, opp.position_size * opp.vwap_net_edge);
+
+        // UP's 41.37 reaches its 0.55 level, so that is the limit it is sent at
+        let limits: Vec<_> = opp.legs().iter().map(|leg| leg.price).collect();
+        assert_eq!(limits, [dec!(0.55), dec!(0.48)]);
+
+        // One more cent of size would take the VWAP edge under min_edge
+        let worse = (opp.vwap_sum * opp.position_size + dec!(1.03) * dec!(0.01))
+            / (opp.position_size + dec!(0.01));
+        assert!(Decimal::ONE - worse - dec!(0.02) < config.trading.short_window_min_edge);
+    }
+
+    #[test]
+    fn test_short_window_min_edge_rises_with_time_to_expiry() {
+        use hfptm::utils::{EdgeCurveMode, EdgeCurvePoint};
+
+        let mut config = create_test_config();
+        config.trading.short_window_edge_curve = vec![
+            EdgeCurvePoint {
+                minutes: 5,
+                min_edge: dec!(0.006),
+            },
+            EdgeCurvePoint {
+                minutes: 30,
+                min_edge: dec!(0.016),
+            },
+        ];
+        assert_eq!(config.trading.short_window_min_edge_for(2), dec!(0.006));
+        assert_eq!(config.trading.short_window_min_edge_for(25), dec!(0.014));
+        assert_eq!(config.trading.short_window_min_edge_for(60), dec!(0.016));
+
+        config.trading.short_window_edge_curve_mode = EdgeCurveMode::Step;
+        assert_eq!(config.trading.short_window_min_edge_for(25), dec!(0.006));
+        assert_eq!(config.trading.short_window_min_edge_for(30), dec!(0.016));
+
+        config.trading.short_window_edge_curve_mode = EdgeCurveMode::Linear;
+        config.trading.min_net_profit_usd = dec!(0.1);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut engine = ArbEngine::new(&config);
+
+        // Same 1.2% net edge (0.484 + 0.484 + 2% fees) on a 5- and a 25-minute market
+        let detect = |engine: &mut ArbEngine, id: &str, minutes: i64| {
+            let mut market = test_market(id, "BTC up or down?", &["up", "down"]);
+            market.end_date = Some(
+                (chrono::Utc::now() + chrono::Duration::seconds(minutes * 60 + 30)).to_rfc3339(),
+            );
+            for asset_id in ["up", "down"] {
+                let snapshot = ask_snapshot(id, asset_id, vec![(dec!(0.484), dec!(200))]);
+                manager.update_book(id, asset_id, &snapshot).unwrap();
+            }
+            engine
+                .detect_short_window_arbitrage(&manager, &market, &config.markets, &risk_manager)
+                .unwrap()
+        };
+
+        let near = detect(&mut engine, "btc_5m", 5).expect("5-minute market clears 0.6%");
+        assert_eq!(near.minutes_to_expiry, 5);
+        assert_eq!(near.net_edge, dec!(0.012));
+        assert!(detect(&mut engine, "btc_25m", 25).is_none());
+    }
+
+    #[test]
+    fn test_multi_outcome_edges_carry_outcome_names() {
+        use hfptm::gamma_api::Outcome;
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut engine = ArbEngine::new(&config);
+
+        let asset_ids = ["token_low", "token_mid", "token_high"];
+        let mut market = test_market("election", "Who wins?", &asset_ids);
+        // Listed out of asset order so names must be joined by token id
+        market.outcomes = [
+            ("2", "Carol", "token_high"),
+            ("0", "Alice", "token_low"),
+            ("1", "Bob", "token_mid"),
+        ]
+        .iter()
+        .map(|(id, name, token_id)| Outcome {
+            id: id.to_string(),
+            name: name.to_string(),
+            token_id: token_id.to_string(),
+        })
+        .collect();
+
+        for (asset_id, price) in asset_ids.iter().zip([dec!(0.30), dec!(0.31), dec!(0.32)]) {
+            let snapshot = BookSnapshot {
+                market: "election".to_string(),
+                asset_id: asset_id.to_string(),
+                bids: vec![],
+                asks: vec![(price, dec!(150))],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            manager.update_book("election", asset_id, &snapshot).unwrap();
+        }
+
+        let arb = engine
+            .detect_arbitrage(&manager, "election", Some(&market), &risk_manager)
+            .unwrap()
+            .expect("3-outcome arb");
+
+        assert!(matches!(arb.arb_type, ArbType::MultiOutcome));
+        for edge in &arb.edges {
+            let expected = match edge.asset_id.as_str() {
+                "token_low" => "Alice",
+                "token_mid" => "Bob",
+                _ => "Carol",
+            };
+            assert_eq!(edge.outcome, expected);
+        }
+
+        // Without token ids on the outcomes, position in assets_ids decides
+        for outcome in &mut market.outcomes {
+            outcome.token_id.clear();
+        }
+        assert_eq!(market.outcome_for_token("token_low").unwrap().name, "Carol");
+        assert!(market.outcome_for_token("unknown").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_multi_outcome_arb_skips_markets_over_outcome_limit() {
+        use hfptm::gamma_api::Outcome;
+        use hfptm::ParallelScanner;
+
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+
+        // 25 candidates at 3¢ sum to $0.75: a wide edge, but 25 legs to fill
+        let asset_ids: Vec<String> = (0..25).map(|i| format!("candidate_{}", i)).collect();
+        let asset_refs: Vec<&str> = asset_ids.iter().map(String::as_str).collect();
+        let mut market = test_market("election_25", "Who wins the election?", &asset_refs);
+        market.outcomes = asset_ids
+            .iter()
+            .enumerate()
+            .map(|(i, asset_id)| Outcome {
+                id: i.to_string(),
+                name: format!("Candidate {}", i),
+                token_id: asset_id.clone(),
+            })
+            .collect();
+        for asset_id in &asset_ids {
+            let snapshot = ask_snapshot("election_25", asset_id, vec![(dec!(0.03), dec!(200))]);
+            manager
+                .update_book("election_25", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        for (max_outcomes, expected) in [(0, 1), (25, 1), (10, 0)] {
+            config.trading.max_outcomes_for_arb = max_outcomes;
+
+            let scanner = ParallelScanner::new(&config, vec![market.clone()]);
+            let opportunities = scanner.scan_multi_outcome_parallel(&manager).await;
+            assert_eq!(opportunities.len(), expected, "limit {}", max_outcomes);
+
+            let detected = ArbEngine::new(&config)
+                .detect_arbitrage(&manager, "election_25", Some(&market), &risk_manager)
+                .unwrap();
+            assert_eq!(detected.is_some(), expected == 1, "limit {}", max_outcomes);
+        }
+    }
+
+    #[test]
+    fn test_multi_outcome_legs_must_fill_at_limit() {
+        use hfptm::arb_engine::legs_fill_at_limit;
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let edge = |asset_id: &str| ArbEdge {
+            asset_id: asset_id.to_string(),
+            outcome: asset_id.to_string(),
+            price: dec!(0.30),
+            size: dec!(100),
+            expected_cost: dec!(30),
+        };
+        let arb = ArbitrageOpportunity {
+            market_id: "three_way".to_string(),
+            arb_type: ArbType::MultiOutcome,
+            edges: vec![edge("a"), edge("b"), edge("c")],
+            total_edge: dec!(0.08),
+            min_liquidity: dec!(60),
+            position_size: dec!(100),
+            expected_profit_usd: dec!(10),
+            fee_cost: dec!(2),
+            net_profit: dec!(8),
+            timestamp: current_timestamp_ms(),
+            detection_latency_ms: None,
+        };
+
+        // "c" holds 60 at the quote and the rest 2¢ higher, which a 0.30
+        // limit can't reach
+        for (asset_id, asks) in [
+            ("a", vec![(dec!(0.30), dec!(100))]),
+            ("b", vec![(dec!(0.28), dec!(40)), (dec!(0.30), dec!(60))]),
+            ("c", vec![(dec!(0.30), dec!(60)), (dec!(0.32), dec!(40))]),
+        ] {
+            let snapshot = ask_snapshot("three_way", asset_id, asks);
+            manager
+                .update_book("three_way", asset_id, &snapshot)
+                .unwrap();
+        }
+        assert!(!legs_fill_at_limit(&arb, &manager));
+
+        // Depth at or better than the limit covers the leg
+        let snapshot = ask_snapshot(
+            "three_way",
+            "c",
+            vec![(dec!(0.29), dec!(50)), (dec!(0.30), dec!(50))],
+        );
+        manager.update_book("three_way", "c", &snapshot).unwrap();
+        assert!(legs_fill_at_limit(&arb, &manager));
+
+        // A leg without a book can't be filled at all
+        let mut orphaned = arb.clone();
+        orphaned.edges.push(edge("d"));
+        assert!(!legs_fill_at_limit(&orphaned, &manager));
+    }
+
+    #[test]
+    fn test_near_resolved_books_yield_no_opportunity() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut engine = ArbEngine::new(&config);
+
+        let seed = |market_id: &str, legs: &[(&str, Decimal)]| {
+            for (asset_id, price) in legs {
+                let snapshot = BookSnapshot {
+                    market: market_id.to_string(),
+                    asset_id: asset_id.to_string(),
+                    bids: vec![],
+                    asks: vec![(*price, dec!(500))],
+                    timestamp: current_timestamp_ms(),
+                    hash: "h".to_string(),
+                };
+                manager.update_book(market_id, asset_id, &snapshot).unwrap();
+            }
+        };
+
+        // Both sides of a resolved binary book collapse to 0.1¢
+        seed("resolved_binary", &[("rb_yes", dec!(0.001)), ("rb_no", dec!(0.001))]);
+        assert!(engine
+            .detect_arbitrage(&manager, "resolved_binary", None, &risk_manager)
+            .unwrap()
+            .is_none());
+
+        // One bucket has snapped to 99.5¢, the rest are dust
+        seed(
+            "resolved_multi",
+            &[
+                ("rm_a", dec!(0.995)),
+                ("rm_b", dec!(0.002)),
+                ("rm_c", dec!(0.002)),
+            ],
+        );
+        assert!(engine
+            .detect_arbitrage(&manager, "resolved_multi", None, &risk_manager)
+            .unwrap()
+            .is_none());
+
+        let asset_ids = ["sw_up", "sw_down"];
+        let market = test_market("resolved_sw", "BTC up or down at 12:15?", &asset_ids);
+        seed("resolved_sw", &[("sw_up", dec!(0.005)), ("sw_down", dec!(0.90))]);
+        assert!(engine
+            .detect_short_window_arbitrage(&manager, &market, &config.markets, &risk_manager)
+            .unwrap()
+            .is_none());
+    }
+
+    fn ask_snapshot(market_id: &str, asset_id: &str, asks: Vec<(Decimal, Decimal)>) -> BookSnapshot {
+        BookSnapshot {
+            market: market_id.to_string(),
+            asset_id: asset_id.to_string(),
+            bids: vec![],
+            hash: format!("{:?}", asks),
+            asks,
+            timestamp: current_timestamp_ms(),
+        }
+    }
+
+    #[test]
+    fn test_mean_reversion_signal_when_sum_breaches_band() {
+        use hfptm::arb_engine::ReversionDirection;
+
+        let mut config = create_test_config();
+        config.trading.mean_reversion_window = 5;
+        config.trading.mean_reversion_band = dec!(0.02);
+        let manager = OrderBookManager::new(&config).unwrap();
+        let market = test_market("updown", "BTC up or down at 12:15?", &["up", "down"]);
+        manager.register_market(&market);
+        let feed = |up: Decimal, down: Decimal| {
+            for (asset_id, ask) in [("up", up), ("down", down)] {
+                let snapshot = ask_snapshot("updown", asset_id, vec![(ask, dec!(200))]);
+                manager.update_book("updown", asset_id, &snapshot).unwrap();
+            }
+        };
+
+        // Off by default: even a wild sum is never flagged
+        let mut engine = ArbEngine::new(&config);
+        feed(dec!(0.70), dec!(0.70));
+        for _ in 0..10 {
+            assert!(engine.detect_mean_reversion(&manager, "updown").is_none());
+        }
+
+        config.trading.enable_mean_reversion_signals = true;
+        let mut engine = ArbEngine::new(&config);
+
+        // Five sums of $1.00 fill the window; nothing to compare against until then
+        feed(dec!(0.50), dec!(0.50));
+        for _ in 0..5 {
+            assert!(engine.detect_mean_reversion(&manager, "updown").is_none());
+        }
+
+        // Within the band
+        feed(dec!(0.50), dec!(0.51));
+        assert!(engine.detect_mean_reversion(&manager, "updown").is_none());
+
+        // Spike to $1.04 against a mean of $1.00: expected to fall back
+        feed(dec!(0.52), dec!(0.52));
+        let spike = engine
+            .detect_mean_reversion(&manager, "updown")
+            .expect("sum above the band");
+        assert_eq!(spike.market_id, "updown");
+        assert_eq!(spike.sum, dec!(1.04));
+        assert_eq!(spike.rolling_mean, dec!(1.002));
+        assert_eq!(spike.deviation, dec!(0.038));
+        assert_eq!(spike.direction, ReversionDirection::Down);
+        // Not an arb, so never anywhere near certain
+        assert!(spike.confidence > Decimal::ZERO && spike.confidence < dec!(0.5));
+
+        // Dip to $0.95 against (1.00 * 3 + 1.01 + 1.04) / 5: expected to rise
+        feed(dec!(0.47), dec!(0.48));
+        let dip = engine.detect_mean_reversion(&manager, "updown").unwrap();
+        assert_eq!(dip.rolling_mean, dec!(1.01));
+        assert_eq!(dip.deviation, dec!(-0.06));
+        assert_eq!(dip.direction, ReversionDirection::Up);
+        // Three times the band out: 0.5 * (1 - 0.02 / 0.06)
+        assert_eq!(dip.confidence.round_dp(4), dec!(0.3333));
+        assert!(dip.confidence > spike.confidence);
+
+        // Markets that aren't binary have no YES+NO sum
+        let multi = test_market("buckets", "BTC price bucket?", &["a", "b", "c"]);
+        manager.register_market(&multi);
+        for asset_id in ["a", "b", "c"] {
+            let snapshot = ask_snapshot("buckets", asset_id, vec![(dec!(0.30), dec!(200))]);
+            manager.update_book("buckets", asset_id, &snapshot).unwrap();
+        }
+        assert!(engine.detect_mean_reversion(&manager, "buckets").is_none());
+
+        // The dip is the one signal still standing, until a sum back in the band clears it
+        assert_eq!(engine.mean_reversion_signals(), vec![dip]);
+        feed(dec!(0.50), dec!(0.51));
+        assert!(engine.detect_mean_reversion(&manager, "updown").is_none());
+        assert!(engine.mean_reversion_signals().is_empty());
+
+        // A market left unsampled past the TTL starts its window over
+        feed(dec!(0.47), dec!(0.48));
+        engine.prune_idle_markets(
+            std::time::Instant::now() + hfptm::arb_engine::IDLE_MARKET_STATE_TTL,
+        );
+        assert!(engine.detect_mean_reversion(&manager, "updown").is_none());
+        assert!(engine.mean_reversion_signals().is_empty());
+    }
+
+    #[test]
+    fn test_crossed_book_is_skipped_by_detection() {
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut engine = ArbEngine::new(&config);
+
+        let book = |asset_id: &str, bid: Decimal, ask: Decimal| BookSnapshot {
+            market: "crossed_market".to_string(),
+            asset_id: asset_id.to_string(),
+            bids: vec![(bid, dec!(200))],
+            asks: vec![(ask, dec!(200))],
+            timestamp: current_timestamp_ms(),
+            hash: format!("{}/{}", bid, ask),
+        };
+
+        // 0.45 + 0.45 would be a fat arb, but YES is bid at 0.50 over its 0.45 ask
+        let snapshot = book("yes_asset", dec!(0.50), dec!(0.45));
+        manager
+            .update_book("crossed_market", "yes_asset", &snapshot)
+            .unwrap();
+        let snapshot = book("no_asset", dec!(0.44), dec!(0.45));
+        manager
+            .update_book("crossed_market", "no_asset", &snapshot)
+            .unwrap();
+
+        let yes_book = manager.get_book("crossed_market", "yes_asset").unwrap();
+        assert!(yes_book.is_crossed());
+        assert!(!manager.get_book("crossed_market", "no_asset").unwrap().is_crossed());
+        assert_eq!(
+            manager.crossed_asset_id("crossed_market").as_deref(),
+            Some("yes_asset")
+        );
+
+        assert!(engine
+            .detect_arbitrage(&manager, "crossed_market", None, &risk_manager)
+            .unwrap()
+            .is_none());
+
+        let markets = vec![test_market("crossed_market", "Will it rain?", &["yes_asset"])];
+        assert!(MarketMaker::new(&config)
+            .find_opportunities(&manager, &markets)
+            .is_empty());
+
+        // A locked book (bid == ask) is skipped too; once it uncrosses the arb shows up
+        let snapshot = book("yes_asset", dec!(0.45), dec!(0.45));
+        manager
+            .update_book("crossed_market", "yes_asset", &snapshot)
+            .unwrap();
+        assert!(manager.get_book("crossed_market", "yes_asset").unwrap().is_crossed());
+
+        let snapshot = book("yes_asset", dec!(0.44), dec!(0.45));
+        manager
+            .update_book("crossed_market", "yes_asset", &snapshot)
+            .unwrap();
+        assert!(manager.crossed_asset_id("crossed_market").is_none());
+        assert!(engine
+            .detect_arbitrage(&manager, "crossed_market", None, &risk_manager)
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_mm_partial_fill_then_completion() {
+        use hfptm::market_maker::{LadderRung, MMOpportunity, OrderStatus};
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let mut mm = MarketMaker::new(&config);
+
+        let placed = mm
+            .simulate_mm_orders(&[MMOpportunity {
+                market_id: "mm_market".to_string(),
+                asset_id: "mm_asset".to_string(),
+                midpoint: dec!(0.50),
+                bid_price: dec!(0.45),
+                ask_price: dec!(0.55),
+                size: dec!(50),
+                spread_bps: dec!(200),
+                estimated_reward: dec!(0.1),
+                ladder: vec![LadderRung {
+                    level: 0,
+                    bid_price: dec!(0.45),
+                    ask_price: dec!(0.55),
+                    size: dec!(50),
+                    estimated_reward: dec!(0.1),
+                }],
+            }])
+            .await
+            .unwrap();
+        let bid_id = &placed[0].bid_order_id;
+
+        // Only 20 offered at our bid (the 0.46 level doesn't cross)
+        let thin = ask_snapshot(
+            "mm_market",
+            "mm_asset",
+            vec![(dec!(0.45), dec!(20)), (dec!(0.46), dec!(500))],
+        );
+        manager.update_book("mm_market", "mm_asset", &thin).unwrap();
+
+        let fills = mm.simulate_fills(&manager).await;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].size, dec!(20));
+        assert_eq!(fills[0].remaining_size, dec!(30));
+
+        let order = mm.get_order(bid_id).unwrap();
+        assert_eq!(order.status, OrderStatus::PartialFill);
+        assert_eq!(order.remaining_size, dec!(30));
+        assert_eq!(mm.get_stats().simulated_balance, dec!(991));
+
+        // More size arrives below our bid: the remainder fills, never more
+        let deep = ask_snapshot("mm_market", "mm_asset", vec![(dec!(0.44), dec!(100))]);
+        manager.update_book("mm_market", "mm_asset", &deep).unwrap();
+
+        let fills = mm.simulate_fills(&manager).await;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].size, dec!(30));
+        assert_eq!(mm.get_order(bid_id).unwrap().status, OrderStatus::Filled);
+
+        let stats = mm.get_stats();
+        assert_eq!(stats.filled_orders, 1);
+        assert_eq!(stats.partially_filled_orders, 0);
+        // 50 bought at 0.45 in total
+        assert_eq!(stats.simulated_balance, dec!(977.5));
+
+        assert!(mm.simulate_fills(&manager).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mm_quotes_share_book_depth_within_a_pass() {
+        use hfptm::market_maker::{LadderRung, MMOpportunity, OrderStatus};
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let mut mm = MarketMaker::new(&config);
+
+        let rung = |level: usize, bid_price: Decimal| LadderRung {
+            level,
+            bid_price,
+            ask_price: dec!(0.55),
+            size: dec!(50),
+            estimated_reward: dec!(0.1),
+        };
+        let placed = mm
+            .simulate_mm_orders(&[MMOpportunity {
+                market_id: "mm_market".to_string(),
+                asset_id: "mm_asset".to_string(),
+                midpoint: dec!(0.50),
+                bid_price: dec!(0.45),
+                ask_price: dec!(0.55),
+                size: dec!(50),
+                spread_bps: dec!(200),
+                estimated_reward: dec!(0.1),
+                ladder: vec![rung(0, dec!(0.45)), rung(1, dec!(0.44))],
+            }])
+            .await
+            .unwrap();
+        assert_eq!(placed.len(), 2);
+
+        // 70 offered in total; both bids cross the 0.44 level
+        let book = ask_snapshot(
+            "mm_market",
+            "mm_asset",
+            vec![(dec!(0.44), dec!(60)), (dec!(0.45), dec!(10))],
+        );
+        manager.update_book("mm_market", "mm_asset", &book).unwrap();
+
+        let fills = mm.simulate_fills(&manager).await;
+        let filled: Decimal = fills.iter().map(|fill| fill.size).sum();
+        assert_eq!(filled, dec!(60));
+
+        // The higher bid takes 50 of the 0.44 level, leaving 10 for the lower one
+        let top = mm.get_order(&placed[0].bid_order_id).unwrap();
+        assert_eq!(top.status, OrderStatus::Filled);
+        let lower = mm.get_order(&placed[1].bid_order_id).unwrap();
+        assert_eq!(lower.status, OrderStatus::PartialFill);
+        assert_eq!(lower.remaining_size, dec!(40));
+    }
+
+    #[tokio::test]
+    async fn test_mm_orders_reserve_balance_until_filled() {
+        use hfptm::market_maker::{LadderRung, MMOpportunity};
+
+        let mut config = create_test_config();
+        config.trading.bankroll = 30;
+        let manager = OrderBookManager::new(&config).unwrap();
+        let mut mm = MarketMaker::new(&config);
+
+        let opportunity = |asset_id: &str| MMOpportunity {
+            market_id: "mm_market".to_string(),
+            asset_id: asset_id.to_string(),
+            midpoint: dec!(0.50),
+            bid_price: dec!(0.45),
+            ask_price: dec!(0.55),
+            size: dec!(50),
+            spread_bps: dec!(200),
+            estimated_reward: dec!(0.1),
+            ladder: vec![LadderRung {
+                level: 0,
+                bid_price: dec!(0.45),
+                ask_price: dec!(0.55),
+                size: dec!(50),
+                estimated_reward: dec!(0.1),
+            }],
+        };
+
+        // The first bid locks $22.50 of $30; the second would need another $22.50
+        let placed = mm
+            .simulate_mm_orders(&[opportunity("asset_a"), opportunity("asset_b")])
+            .await
+            .unwrap();
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].asset_id, "asset_a");
+        assert_eq!(mm.reserved_balance(), dec!(22.5));
+        assert_eq!(mm.available_balance(), dec!(7.5));
+        assert_eq!(mm.get_stats().simulated_balance, dec!(30));
+
+        // Filling 20 of the bid turns $9 of the reservation into a debit
+        let thin = ask_snapshot("mm_market", "asset_a", vec![(dec!(0.45), dec!(20))]);
+        manager.update_book("mm_market", "asset_a", &thin).unwrap();
+        mm.simulate_fills(&manager).await;
+        assert_eq!(mm.reserved_balance(), dec!(13.5));
+        assert_eq!(mm.get_stats().simulated_balance, dec!(21));
+        assert_eq!(mm.available_balance(), dec!(7.5));
+
+        // Still not enough to place the second market
+        let placed = mm
+            .simulate_mm_orders(&[opportunity("asset_b")])
+            .await
+            .unwrap();
+        assert!(placed.is_empty());
+
+        // Refreshing cancels the resting quotes and frees the rest of the bid
+        assert_eq!(mm.refresh_orders().await, 2);
+        assert_eq!(mm.reserved_balance(), Decimal::ZERO);
+        assert_eq!(mm.available_balance(), dec!(21));
+
+        // ...which the next round can quote with
+        let placed = mm
+            .simulate_mm_orders(&[MMOpportunity {
+                size: dec!(40),
+                ladder: vec![LadderRung {
+                    size: dec!(40),
+                    ..opportunity("asset_b").ladder[0].clone()
+                }],
+                ..opportunity("asset_b")
+            }])
+            .await
+            .unwrap();
+        assert_eq!(placed.len(), 1);
+        assert_eq!(mm.reserved_balance(), dec!(18));
+    }
+
+    #[tokio::test]
+    async fn test_mm_ask_fill_releases_the_bid_cost_basis() {
+        use hfptm::market_maker::{LadderRung, MMOpportunity};
+        use hfptm::Strategy;
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let capital = risk_manager.capital().clone();
+        let mut mm = MarketMaker::new(&config).with_capital(capital.clone());
+
+        mm.simulate_mm_orders(&[MMOpportunity {
+            market_id: "mm_market".to_string(),
+            asset_id: "mm_asset".to_string(),
+            midpoint: dec!(0.50),
+            bid_price: dec!(0.45),
+            ask_price: dec!(0.55),
+            size: dec!(50),
+            spread_bps: dec!(200),
+            estimated_reward: dec!(0.1),
+            ladder: vec![LadderRung {
+                level: 0,
+                bid_price: dec!(0.45),
+                ask_price: dec!(0.55),
+                size: dec!(50),
+                estimated_reward: dec!(0.1),
+            }],
+        }])
+        .await
+        .unwrap();
+        assert_eq!(capital.committed(&Strategy::MarketMaking), dec!(22.5));
+
+        // The bid fills in full: its reservation now backs the inventory
+        let asks = ask_snapshot("mm_market", "mm_asset", vec![(dec!(0.45), dec!(50))]);
+        manager.update_book("mm_market", "mm_asset", &asks).unwrap();
+        mm.simulate_fills(&manager).await;
+        assert_eq!(capital.committed(&Strategy::MarketMaking), dec!(22.5));
+
+        // Selling 20 at 0.55 frees their $9 cost, not the $11 proceeds
+        let bids = BookSnapshot {
+            market: "mm_market".to_string(),
+            asset_id: "mm_asset".to_string(),
+            bids: vec![(dec!(0.55), dec!(20))],
+            asks: vec![(dec!(0.60), dec!(100))],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager.update_book("mm_market", "mm_asset", &bids).unwrap();
+        mm.simulate_fills(&manager).await;
+        assert_eq!(mm.inventory("mm_asset").unwrap().size, dec!(30));
+        assert_eq!(capital.committed(&Strategy::MarketMaking), dec!(13.5));
+    }
+
+    #[tokio::test]
+    async fn test_mm_yes_fill_is_hedged_with_no_bid() {
+        use hfptm::market_maker::{LadderRung, MMOpportunity, OrderSide, OrderStatus};
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let mut mm = MarketMaker::new(&config);
+
+        let no_book = ask_snapshot("mm_market", "no_token", vec![(dec!(0.60), dec!(100))]);
+        manager
+            .update_book("mm_market", "no_token", &no_book)
+            .unwrap();
+        let yes_book = ask_snapshot("mm_market", "yes_token", vec![(dec!(0.50), dec!(100))]);
+        manager
+            .update_book("mm_market", "yes_token", &yes_book)
+            .unwrap();
+        manager.register_market(&test_market(
+            "mm_market",
+            "Hedge",
+            &["yes_token", "no_token"],
+        ));
+
+        let opportunity = MMOpportunity {
+            market_id: "mm_market".to_string(),
+            asset_id: "yes_token".to_string(),
+            midpoint: dec!(0.50),
+            bid_price: dec!(0.45),
+            ask_price: dec!(0.55),
+            size: dec!(50),
+            spread_bps: dec!(200),
+            estimated_reward: dec!(0.1),
+            ladder: vec![LadderRung {
+                level: 0,
+                bid_price: dec!(0.45),
+                ask_price: dec!(0.55),
+                size: dec!(50),
+                estimated_reward: dec!(0.1),
+            }],
+        };
+        mm.simulate_mm_orders(std::slice::from_ref(&opportunity)).await.unwrap();
+
+        // Nothing filled, nothing to hedge
+        assert!(mm.hedge_inventory(&manager).await.is_empty());
+
+        // Our YES bid fills in full
+        let yes_book = ask_snapshot("mm_market", "yes_token", vec![(dec!(0.45), dec!(50))]);
+        manager
+            .update_book("mm_market", "yes_token", &yes_book)
+            .unwrap();
+        mm.simulate_fills(&manager).await;
+        let position = mm.inventory("yes_token").unwrap();
+        assert_eq!((position.size, position.avg_price), (dec!(50), dec!(0.45)));
+
+        // Hedged with a NO bid of the same size, a tick under NO's best ask
+        let hedges = mm.hedge_inventory(&manager).await;
+        assert_eq!(hedges.len(), 1);
+        assert_eq!(hedges[0].asset_id, "no_token");
+        assert_eq!(hedges[0].side, OrderSide::Bid);
+        assert_eq!(hedges[0].size, dec!(50));
+        assert_eq!(hedges[0].price, dec!(0.59));
+
+        // One hedge at a time
+        assert!(mm.hedge_inventory(&manager).await.is_empty());
+
+        // The hedge fills: the position is covered and NO isn't new inventory
+        let no_book = ask_snapshot("mm_market", "no_token", vec![(dec!(0.55), dec!(50))]);
+        manager
+            .update_book("mm_market", "no_token", &no_book)
+            .unwrap();
+        mm.simulate_fills(&manager).await;
+        assert_eq!(
+            mm.get_order(&hedges[0].order_id).unwrap().status,
+            OrderStatus::Filled
+        );
+        assert_eq!(mm.inventory("yes_token").unwrap().hedged_size, dec!(50));
+        assert!(mm.inventory("no_token").is_none());
+        assert!(mm.hedge_inventory(&manager).await.is_empty());
+
+        // Selling YES leaves fewer shares for the hedge to cover
+        mm.simulate_mm_orders(&[MMOpportunity {
+            bid_price: dec!(0.10),
+            ask_price: dec!(0.60),
+            size: dec!(20),
+            ladder: vec![LadderRung {
+                level: 0,
+                bid_price: dec!(0.10),
+                ask_price: dec!(0.60),
+                size: dec!(20),
+                estimated_reward: dec!(0.1),
+            }],
+            ..opportunity
+        }])
+        .await
+        .unwrap();
+        let yes_book = BookSnapshot {
+            market: "mm_market".to_string(),
+            asset_id: "yes_token".to_string(),
+            bids: vec![(dec!(0.60), dec!(20))],
+            asks: vec![],
+            timestamp: current_timestamp_ms(),
+            hash: "h".to_string(),
+        };
+        manager
+            .update_book("mm_market", "yes_token", &yes_book)
+            .unwrap();
+        mm.simulate_fills(&manager).await;
+        let position = mm.inventory("yes_token").unwrap();
+        assert_eq!((position.size, position.hedged_size), (dec!(30), dec!(30)));
+    }
+
+    #[tokio::test]
+    async fn test_simulated_arb_fills_only_available_depth() {
+        use hfptm::executor::SimulationExecutor;
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let simulator = SimulationExecutor::new(&config);
+
+        let yes = ask_snapshot("sim_market", "yes_asset", vec![(dec!(0.45), dec!(100))]);
+        let no = ask_snapshot("sim_market", "no_asset", vec![(dec!(0.45), dec!(40))]);
+        manager.update_book("sim_market", "yes_asset", &yes).unwrap();
+        manager.update_book("sim_market", "no_asset", &no).unwrap();
+
+        let arb = binary_arb("sim_market", dec!(100), dec!(100));
+        let result = simulator.simulate_arbitrage(&arb, &manager).await.unwrap();
+
+        assert!(result.partial_fill);
+        assert!(!result.filled);
+        // 40 complete sets pay out; the unmatched 60 YES is paid for but not redeemed
+        assert_eq!(result.filled_amount, dec!(40));
+
+        let trade = simulator.get_trades().await.remove(0);
+        assert_eq!(trade.edges[0].size, dec!(100));
+        assert_eq!(trade.edges[1].size, dec!(40));
+        assert_eq!(trade.total_cost, dec!(63));
+        // Less the 2% fee on the $40 payout
+        assert_eq!(simulator.get_simulated_balance().await, dec!(976.2));
+        assert_eq!(result.total_cost, dec!(63));
+        assert_eq!(result.realized_pnl, dec!(-23.8));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fees_paid_accumulate_across_executions_and_fills() {
+        use hfptm::executor::SimulationExecutor;
+        use hfptm::websocket::UserEvent;
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let simulator = SimulationExecutor::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let reporter = monitor.daily_reporter();
+
+        // 100 and 60 complete sets, then only 40 of 100 against a thin NO book
+        let mut gross = Decimal::ZERO;
+        let mut net = Decimal::ZERO;
+        for (market_id, size, no_depth) in [
+            ("fee_a", dec!(100), dec!(500)),
+            ("fee_b", dec!(60), dec!(500)),
+            ("fee_c", dec!(100), dec!(40)),
+        ] {
+            let yes = ask_snapshot(market_id, "yes_asset", vec![(dec!(0.45), dec!(500))]);
+            let no = ask_snapshot(market_id, "no_asset", vec![(dec!(0.45), no_depth)]);
+            manager.update_book(market_id, "yes_asset", &yes).unwrap();
+            manager.update_book(market_id, "no_asset", &no).unwrap();
+
+            let arb = binary_arb(market_id, size, dec!(500));
+            let result = simulator.simulate_arbitrage(&arb, &manager).await.unwrap();
+            // 2% of the payout, taken out of the P&L and nowhere else
+            assert_eq!(result.fees_paid, result.filled_amount * dec!(0.02));
+            assert_eq!(
+                result.realized_pnl + result.fees_paid,
+                result.filled_amount - result.total_cost
+            );
+            gross += result.filled_amount - result.total_cost;
+            net += result.realized_pnl;
+
+            monitor
+                .record_arbitrage_executed(&arb, &result, std::time::Duration::from_millis(1))
+                .await;
+        }
+
+        let trades = monitor.get_recent_trades().await;
+        let trade_fees: Decimal = trades.iter().map(|t| t.fees).sum();
+        assert_eq!(trade_fees, dec!(4));
+        assert_eq!(gross - net, dec!(4));
+        assert_eq!(monitor.get_metrics().total_fees_paid, dec!(4));
+
+        // A live fill charges the rate the exchange reports: 1% of min(0.4, 0.6) * 50
+        let payload = r#"{
+            "event_type": "trade",
+            "id": "fee-trade",
+            "market": "fee_live",
+            "asset_id": "live_yes",
+            "outcome": "Yes",
+            "side": "BUY",
+            "price": "0.4",
+            "size": "50",
+            "status": "MATCHED",
+            "taker_order_id": "0xours",
+            "trade_owner": "test_key",
+            "fee_rate_bps": "100"
+        }"#;
+        let UserEvent::Trade(trade) = UserEvent::parse_batch(payload).unwrap().remove(0) else {
+            panic!("expected a trade");
+        };
+        let fill = trade.fills_for("test_key").remove(0);
+        assert_eq!(fill.fee, dec!(0.2));
+
+        monitor.record_user_fill(&fill).await;
+        risk_manager.record_fill(&fill);
+        assert_eq!(monitor.get_metrics().total_fees_paid, dec!(4.2));
+        assert_eq!(risk_manager.get_daily_pnl().fill_fees, dec!(0.2));
+        assert_eq!(risk_manager.get_daily_pnl().realized_pnl, dec!(-0.2));
+
+        // The day's summary covers both
+        let tomorrow = chrono::Utc::now()
+            .date_naive()
+            .succ_opt()
+            .unwrap()
+            .format("%Y-%m-%d")
+            .to_string();
+        let closed = risk_manager.roll_daily_pnl_at(&tomorrow).unwrap();
+        let summary = reporter.summarize(&closed, Decimal::ZERO, Decimal::ZERO);
+        assert_eq!(summary.fees_paid, dec!(4.2));
+        assert_eq!(summary.realized_pnl, net);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_realized_pnl_is_payout_minus_cost_minus_fees() {
+        use hfptm::executor::SimulationExecutor;
+
+        let config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        for asset_id in ["yes_asset", "no_asset"] {
+            let snapshot = ask_snapshot("pnl_market", asset_id, vec![(dec!(0.45), dec!(100))]);
+            manager.update_book("pnl_market", asset_id, &snapshot).unwrap();
+        }
+
+        // Simulation: $100 payout - $90 cost - 2% fee
+        let mut arb = binary_arb("pnl_market", dec!(100), dec!(100));
+        let simulated = SimulationExecutor::new(&config)
+            .simulate_arbitrage(&arb, &manager)
+            .await
+            .unwrap();
+        assert!(simulated.filled);
+        assert_eq!(simulated.total_cost, dec!(90));
+        assert_eq!(simulated.realized_pnl, dec!(8));
+
+        // Live: same definition from the arb's fee estimate; a lone leg is all cost
+        arb.fee_cost = dec!(2);
+        assert_eq!(arb.realized_pnl(dec!(90), true), dec!(8));
+        assert_eq!(arb.realized_pnl(dec!(95), true), dec!(3));
+        assert_eq!(arb.realized_pnl(dec!(45), false), dec!(-45));
+
+        // Both books accumulate realized P&L, never the cost spent
+        let live = ExecutionResult {
+            realized_pnl: arb.realized_pnl(dec!(90), true),
+            fees_paid: arb.fee_cost,
+            ..simulated.clone()
+        };
+        for result in [&simulated, &live] {
+            monitor
+                .record_arbitrage_executed(&arb, result, std::time::Duration::from_millis(1))
+                .await;
+            risk_manager.record_arbitrage_execution(&arb, result).unwrap();
+        }
+
+        assert_eq!(monitor.get_metrics().total_pnl, dec!(16));
+        assert_eq!(risk_manager.get_daily_pnl().realized_pnl, dec!(16));
+        let trades = monitor.get_recent_trades().await;
+        assert!(trades.iter().all(|t| t.actual_profit == dec!(8)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_every_opportunity_type_routes_through_one_pipeline() {
+        use hfptm::arb_engine::{OpportunityLeg, ShortWindowArbOpportunity, ShortWindowLeg};
+        use hfptm::executor::SimulationExecutor;
+        use hfptm::parallel_scanner::{
+            CrossArbType, CrossMarketOpportunity, MultiOutcomeOpportunity, OutcomePrice,
+        };
+
+        let mut config = create_test_config();
+        config.risk.inventory_drift_threshold = dec!(1000000);
+        config.risk.max_exposure_per_market = 150;
+        config.risk.max_exposure_per_event = 150;
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let simulator = SimulationExecutor::new(&config);
+
+        for (market_id, asset_id, ask) in [
+            ("route_binary", "yes_asset", dec!(0.45)),
+            ("route_binary", "no_asset", dec!(0.45)),
+            ("route_sw", "up", dec!(0.45)),
+            ("route_sw", "down", dec!(0.45)),
+            ("route_a", "a_yes", dec!(0.4)),
+            ("route_b", "b_yes", dec!(0.5)),
+        ] {
+            let snapshot = ask_snapshot(market_id, asset_id, vec![(ask, dec!(100))]);
+            manager.update_book(market_id, asset_id, &snapshot).unwrap();
+        }
+        for asset_id in ["a", "b", "c"] {
+            let snapshot = ask_snapshot("route_multi", asset_id, vec![(dec!(0.3), dec!(100))]);
+            manager
+                .update_book("route_multi", asset_id, &snapshot)
+                .unwrap();
+        }
+        for asset_id in ["sell_yes", "sell_no"] {
+            let snapshot = BookSnapshot {
+                market: "route_sell".to_string(),
+                asset_id: asset_id.to_string(),
+                bids: vec![(dec!(0.52), dec!(100))],
+                asks: vec![(dec!(0.54), dec!(100))],
+                timestamp: current_timestamp_ms(),
+                hash: "h".to_string(),
+            };
+            manager
+                .update_book("route_sell", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        let binary = binary_arb("route_binary", dec!(50), dec!(100));
+        let short_window = ShortWindowArbOpportunity {
+            market_id: "route_sw".to_string(),
+            market_question: "BTC up or down?".to_string(),
+            minutes_to_expiry: 10,
+            legs: [("up", "Up"), ("down", "Down")]
+                .iter()
+                .map(|(asset_id, outcome)| ShortWindowLeg {
+                    asset_id: asset_id.to_string(),
+                    outcome: outcome.to_string(),
+                    price: dec!(0.45),
+                    size: dec!(100),
+                    depth: vec![(dec!(0.45), dec!(100))],
+                })
+                .collect(),
+            sum_prices: dec!(0.9),
+            vwap_sum: dec!(0.9),
+            raw_edge: dec!(0.1),
+            net_edge: dec!(0.08),
+            vwap_net_edge: dec!(0.08),
+            position_size: dec!(20),
+            expected_profit: dec!(1.6),
+            min_liquidity: dec!(100),
+            detected_at: 0,
+            annualized_return: Decimal::ZERO,
+            assumed_fill_probability: Decimal::ONE,
+            assumed_capital_utilization: Decimal::ONE,
+        };
+        let multi_outcome = MultiOutcomeOpportunity {
+            market_id: "route_multi".to_string(),
+            market_question: "Who wins?".to_string(),
+            num_outcomes: 3,
+            total_price: dec!(0.9),
+            edge: dec!(0.08),
+            min_liquidity: dec!(100),
+            position_size: dec!(10),
+            expected_profit: dec!(0.8),
+            outcomes: ["a", "b", "c"]
+                .iter()
+                .map(|asset_id| OutcomePrice {
+                    asset_id: asset_id.to_string(),
+                    name: asset_id.to_uppercase(),
+                    ask_price: dec!(0.3),
+                    ask_size: dec!(100),
+                })
+                .collect(),
+        };
+        let cross_market = CrossMarketOpportunity {
+            market_a_id: "route_a".to_string(),
+            market_b_id: "route_b".to_string(),
+            market_a_question: "YES@0.400".to_string(),
+            market_b_question: "YES@0.500".to_string(),
+            arb_type: CrossArbType::MutualExclusion,
+            edge: dec!(0.1),
+            position_size: dec!(50),
+            expected_profit: dec!(4),
+            confidence: Decimal::ONE,
+            detected_at: 0,
+            legs: [
+                ("route_a", "a_yes", dec!(0.4)),
+                ("route_b", "b_yes", dec!(0.5)),
+            ]
+            .iter()
+            .map(|(market_id, asset_id, price)| OpportunityLeg {
+                market_id: market_id.to_string(),
+                asset_id: asset_id.to_string(),
+                outcome: "Yes".to_string(),
+                side: ArbSide::Buy,
+                price: *price,
+                size: dec!(50),
+            })
+            .collect(),
+            min_liquidity: dec!(100),
+        };
+        let held = |asset: &str| ExchangePosition {
+            market_id: "route_sell".to_string(),
+            asset_id: asset.to_string(),
+            outcome: asset.to_string(),
+            size: dec!(100),
+            avg_price: dec!(0.48),
+        };
+        risk_manager
+            .seed_positions(&[held("sell_yes"), held("sell_no")], &[])
+            .unwrap();
+        let sell_edge = |asset_id: &str| ArbEdge {
+            asset_id: asset_id.to_string(),
+            outcome: asset_id.to_string(),
+            price: dec!(0.52),
+            size: dec!(100),
+            expected_cost: dec!(52),
+        };
+        let sell = ArbitrageOpportunity {
+            market_id: "route_sell".to_string(),
+            arb_type: ArbType::SellCompleteSet,
+            edges: vec![sell_edge("sell_yes"), sell_edge("sell_no")],
+            total_edge: dec!(0.02),
+            min_liquidity: dec!(100),
+            position_size: dec!(100),
+            expected_profit_usd: dec!(4),
+            fee_cost: dec!(2),
+            net_profit: dec!(2),
+            timestamp: current_timestamp_ms(),
+            detection_latency_ms: None,
+        };
+
+        // Selling frees exposure, so it passes even with the market over its limit
+        assert_eq!(risk_manager.get_market_exposure("route_sell"), dec!(200));
+        assert!(risk_manager
+            .can_execute_arbitrage(&sell)
+            .unwrap()
+            .is_allowed());
+        assert_eq!(sell.side(), ArbSide::Sell);
+        assert!(sell.legs().iter().all(|leg| leg.side == ArbSide::Sell));
+
+        let opportunities: [&dyn Opportunity; 5] =
+            [&binary, &short_window, &multi_outcome, &cross_market, &sell];
+        let mut results = Vec::new();
+        for opportunity in opportunities {
+            monitor.record_arbitrage_detected(opportunity).await;
+            let result = simulator
+                .execute_arbitrage(opportunity, &manager)
+                .await
+                .unwrap();
+            risk_manager
+                .record_arbitrage_execution(opportunity, &result)
+                .unwrap();
+            monitor
+                .record_arbitrage_executed(
+                    opportunity,
+                    &result,
+                    std::time::Duration::from_millis(1),
+                )
+                .await;
+            results.push(result);
+        }
+
+        // Payout $50 - cost $45 - 2% fee
+        assert!(results[0].filled);
+        assert_eq!(results[0].realized_pnl, dec!(4));
+        let outcomes: Vec<_> = short_window
+            .legs()
+            .into_iter()
+            .map(|leg| leg.outcome)
+            .collect();
+        assert_eq!(outcomes, ["Up", "Down"]);
+        assert!(results[1].filled);
+        assert_eq!(results[1].realized_pnl, dec!(1.6));
+        assert_eq!(multi_outcome.legs().len(), 3);
+        assert!(results[2].filled);
+        assert_eq!(results[2].realized_pnl, dec!(0.8));
+
+        // Cross-market buys YES in both markets: payout $50 - cost $45 - 2% fee
+        assert_eq!(cross_market.market_ids(), vec!["route_a", "route_b"]);
+        assert!(results[3].filled);
+        assert_eq!(results[3].realized_pnl, dec!(4));
+        assert_eq!(risk_manager.get_market_exposure("route_b"), dec!(50));
+
+        // Proceeds $104 - redemption value $100 - fees $2; the held set is gone
+        assert!(results[4].filled);
+        assert_eq!(results[4].total_cost, dec!(-104));
+        assert_eq!(results[4].realized_pnl, dec!(2));
+        assert!(risk_manager.get_position("sell_yes").is_none());
+        assert_eq!(
+            risk_manager.get_market_exposure("route_sell"),
+            Decimal::ZERO
+        );
+        assert_eq!(simulator.get_simulated_balance().await, dec!(1112.4));
+
+        let trades = monitor.get_recent_trades().await;
+        let arb_types: Vec<_> = trades.iter().map(|t| t.arb_type.as_str()).collect();
+        assert_eq!(
+            arb_types,
+            [
+                "Binary",
+                "ShortWindow",
+                "MultiOutcome",
+                "CrossMarket",
+                "SellCompleteSet"
+            ]
+        );
+        assert_eq!(monitor.get_metrics().arb_detections, 5);
+        assert_eq!(monitor.get_metrics().total_pnl, dec!(12.4));
+    }
+
+    #[tokio::test]
+    async fn test_simulated_fill_latency_and_probability() {
+        use hfptm::executor::SimulationExecutor;
+        use hfptm::utils::sim::SimEnv;
+
+        let mut config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        for asset_id in ["yes_asset", "no_asset"] {
+            let snapshot = ask_snapshot("sim_market", asset_id, vec![(dec!(0.45), dec!(100))]);
+            manager
+                .update_book("sim_market", asset_id, &snapshot)
+                .unwrap();
+        }
+        let arb = binary_arb("sim_market", dec!(100), dec!(100));
+
+        // Never fills: nothing bought, balance untouched
+        config.simulation.fill_probability = 0.0;
+        let simulator = SimulationExecutor::with_sim_env(&config, SimEnv::deterministic(1, 0));
+        for _ in 0..10 {
+            let result = simulator.simulate_arbitrage(&arb, &manager).await.unwrap();
+            assert!(!result.success && !result.filled && !result.partial_fill);
+        }
+        assert!(simulator.get_trades().await.is_empty());
+        assert_eq!(simulator.get_simulated_balance().await, dec!(1000));
+
+        // Latency isn't waited out inline: the orders are placed, then fill
+        // against the books once it has passed, within the jitter range
+        config.simulation.fill_probability = 1.0;
+        config.simulation.fill_latency_ms = 200;
+        config.simulation.fill_latency_jitter_ms = 50;
+        let sim_env = SimEnv::deterministic(1, 0);
+        let simulator = SimulationExecutor::with_sim_env(&config, sim_env.clone());
+        for _ in 0..10 {
+            let result = simulator.simulate_arbitrage(&arb, &manager).await.unwrap();
+            assert!(result.success && !result.filled && !result.partial_fill);
+            assert_eq!(result.execution_time_ms, 0);
+        }
+        assert_eq!(sim_env.clock.now_ms(), 0);
+
+        sim_env.clock.advance_ms(199);
+        assert!(simulator.settle_fills(&manager).await.is_empty());
+        assert!(simulator.get_trades().await.is_empty());
+
+        // The books have moved by the time the latency passes: half the depth
+        // left, which the first fill takes and the rest find gone
+        for asset_id in ["yes_asset", "no_asset"] {
+            let snapshot = ask_snapshot("sim_market", asset_id, vec![(dec!(0.45), dec!(50))]);
+            manager
+                .update_book("sim_market", asset_id, &snapshot)
+                .unwrap();
+        }
+        sim_env.clock.advance_ms(51);
+        let settled = simulator.settle_fills(&manager).await;
+        assert_eq!(settled.len(), 10);
+        assert!(settled[0].result.partial_fill);
+        assert_eq!(settled[0].filled_sizes, vec![dec!(50), dec!(50)]);
+        assert!(settled[1..]
+            .iter()
+            .all(|fill| !fill.result.success && fill.filled_sizes == vec![Decimal::ZERO; 2]));
+        let trades = simulator.get_trades().await;
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].edges[0].size, dec!(50));
+        assert_eq!(trades[0].execution_time_ms, 250);
+        for asset_id in ["yes_asset", "no_asset"] {
+            let snapshot = ask_snapshot("sim_market", asset_id, vec![(dec!(0.45), dec!(100))]);
+            manager
+                .update_book("sim_market", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        // Fees follow fee_rate on the payout: 100 sets at 90¢, 1% of $100
+        config.simulation.fill_latency_ms = 0;
+        config.simulation.fill_latency_jitter_ms = 0;
+        config.simulation.fee_rate = dec!(0.01);
+        let simulator = SimulationExecutor::new(&config);
+        simulator.simulate_arbitrage(&arb, &manager).await.unwrap();
+        assert_eq!(simulator.get_simulated_balance().await, dec!(1009));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_deferred_sim_fills_recorded_when_they_settle() {
+        use hfptm::executor::SimulationExecutor;
+        use hfptm::utils::sim::SimEnv;
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+        config.simulation.fill_latency_ms = 100;
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        for asset_id in ["yes_asset", "no_asset"] {
+            let snapshot = ask_snapshot("sim_market", asset_id, vec![(dec!(0.45), dec!(100))]);
+            manager
+                .update_book("sim_market", asset_id, &snapshot)
+                .unwrap();
+        }
+        let arb = binary_arb("sim_market", dec!(100), dec!(100));
+
+        for (fill_probability, filled) in [(1.0, true), (0.0, false)] {
+            config.simulation.fill_probability = fill_probability;
+            let sim_env = SimEnv::deterministic(1, 0);
+            let simulator: Arc<dyn Executor> =
+                Arc::new(SimulationExecutor::with_sim_env(&config, sim_env.clone()));
+            let client = WebSocketClient::new(&config, &[], simulator).await.unwrap();
+            let risk_manager = RiskManager::new(&config);
+            let mut monitor = Monitor::new(&config).await.unwrap();
+
+            // Placing reserves exposure and a slot, but nothing is bought or counted yet
+            client
+                .execute_arbitrage(&arb, &manager, &risk_manager, &mut monitor)
+                .await
+                .unwrap();
+            assert_eq!(risk_manager.get_market_exposure("sim_market"), dec!(200));
+            assert!(risk_manager.get_position("yes_asset").is_none());
+            assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 1);
+            let metrics = monitor.get_metrics();
+            assert_eq!((metrics.arb_executions, metrics.arb_missed), (0, 0));
+
+            // Not due yet
+            sim_env.clock.advance_ms(99);
+            client
+                .settle_fills(&manager, &risk_manager, &mut monitor)
+                .await;
+            assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 1);
+
+            sim_env.clock.advance_ms(1);
+            client
+                .settle_fills(&manager, &risk_manager, &mut monitor)
+                .await;
+            assert_eq!(risk_manager.get_risk_summary().active_arbitrages, 0);
+            let metrics = monitor.get_metrics();
+            if filled {
+                // 100 sets at 90¢ each: positions open and the profit, less
+                // the 2% fee, is realized
+                assert_eq!(
+                    risk_manager.get_position("yes_asset").unwrap().size,
+                    dec!(100)
+                );
+                assert_eq!(risk_manager.get_market_exposure("sim_market"), dec!(200));
+                assert_eq!(risk_manager.get_daily_pnl().realized_pnl, dec!(8));
+                assert_eq!((metrics.arb_executions, metrics.fills), (1, 1));
+                assert_eq!(metrics.total_pnl, dec!(8));
+            } else {
+                // A miss gives the reservation back
+                assert!(risk_manager.get_position("yes_asset").is_none());
+                assert_eq!(
+                    risk_manager.get_market_exposure("sim_market"),
+                    Decimal::ZERO
+                );
+                assert_eq!(risk_manager.get_daily_pnl().realized_pnl, Decimal::ZERO);
+                assert_eq!((metrics.arb_executions, metrics.arb_missed), (0, 1));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulated_redemption_credited_at_resolution() {
+        use hfptm::executor::{Executor, SimulationExecutor};
+        use hfptm::utils::sim::SimEnv;
+
+        let mut config = create_test_config();
+        config.simulation.redeem_at_resolution = true;
+        config.simulation.default_resolution_delay_secs = 60;
+        let manager = OrderBookManager::new(&config).unwrap();
+        for market_id in ["sw_market", "unknown_end"] {
+            for asset_id in ["yes_asset", "no_asset"] {
+                let snapshot = ask_snapshot(market_id, asset_id, vec![(dec!(0.45), dec!(100))]);
+                manager.update_book(market_id, asset_id, &snapshot).unwrap();
+            }
+        }
+
+        let start_ms = 1_700_000_000_000;
+        let sim_env = SimEnv::deterministic(1, start_ms);
+        let simulator = SimulationExecutor::with_sim_env(&config, sim_env.clone());
+        let mut market = test_market("sw_market", "BTC up?", &["yes_asset", "no_asset"]);
+        market.end_date = Some(
+            chrono::DateTime::from_timestamp_millis(start_ms + 15 * 60_000)
+                .unwrap()
+                .to_rfc3339(),
+        );
+        simulator.register_market(&market);
+
+        // Entry pays $90 for 100 sets; the $98 net payout waits for resolution
+        let arb = binary_arb("sw_market", dec!(100), dec!(100));
+        let result = simulator.simulate_arbitrage(&arb, &manager).await.unwrap();
+        assert!(result.filled);
+        assert_eq!(result.realized_pnl, Decimal::ZERO);
+        assert_eq!(simulator.get_simulated_balance().await, dec!(910));
+        let trades = simulator.get_trades().await;
+        assert!(trades[0].pending_redemption);
+        assert_eq!(trades[0].net_profit, Decimal::ZERO);
+
+        let pending = simulator.pending_redemptions().await;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].sets, dec!(100));
+        assert_eq!(pending[0].net_payout, dec!(98));
+        assert_eq!(pending[0].resolves_at_ms, start_ms + 15 * 60_000);
+
+        // A market without an end date resolves after the default delay
+        let other = binary_arb("unknown_end", dec!(100), dec!(100));
+        simulator
+            .simulate_arbitrage(&other, &manager)
+            .await
+            .unwrap();
+        assert_eq!(simulator.get_simulated_balance().await, dec!(820));
+
+        sim_env.clock.advance_ms(59_999);
+        assert_eq!(simulator.settle_redemptions().await, Decimal::ZERO);
+        sim_env.clock.advance_ms(1);
+        assert_eq!(simulator.available_balance().await.unwrap(), dec!(918));
+        let trades = simulator.get_trades().await;
+        assert!(trades[0].pending_redemption);
+        assert!(!trades[1].pending_redemption);
+        assert_eq!(trades[1].net_profit, dec!(8));
+
+        sim_env.clock.advance_ms(14 * 60_000);
+        assert_eq!(simulator.settle_redemptions().await, dec!(98));
+        assert!(simulator.pending_redemptions().await.is_empty());
+        assert_eq!(simulator.get_simulated_pnl().await, dec!(16));
+        let trades = simulator.get_trades().await;
+        assert!(trades.iter().all(|trade| !trade.pending_redemption));
+        assert_eq!(trades[0].net_profit, dec!(8));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_simulated_pnl_surfaces_in_metrics() {
+        use hfptm::executor::SimulationExecutor;
+        use hfptm::utils::TradingMode;
+
+        let mut config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        for asset_id in ["yes_asset", "no_asset"] {
+            let snapshot = ask_snapshot("sim_market", asset_id, vec![(dec!(0.45), dec!(100))]);
+            manager
+                .update_book("sim_market", asset_id, &snapshot)
+                .unwrap();
+        }
+
+        let simulator = SimulationExecutor::new(&config);
+        let monitor = Monitor::new(&config).await.unwrap();
+        let json = serde_json::to_value(monitor.get_metrics()).unwrap();
+        assert!(json["sim_pnl"].is_null());
+
+        // $90 for 100 sets paying out $98 after 2% fees
+        let arb = binary_arb("sim_market", dec!(100), dec!(100));
+        simulator.simulate_arbitrage(&arb, &manager).await.unwrap();
+        monitor.refresh_simulation_metrics(&simulator).await;
+
+        let json = serde_json::to_value(monitor.get_metrics()).unwrap();
+        let decimal = |value: &serde_json::Value| -> Decimal {
+            serde_json::from_value(value.clone()).unwrap()
+        };
+        assert_eq!(decimal(&json["sim_balance"]), dec!(1008));
+        assert_eq!(decimal(&json["sim_pnl"]), dec!(8));
+
+        // Live mode leaves them out, as does an executor that isn't a simulator
+        config.trading.trading_mode = TradingMode::Live;
+        let monitor = Monitor::new(&config).await.unwrap();
+        monitor.refresh_simulation_metrics(&simulator).await;
+        assert_eq!(monitor.get_metrics().sim_pnl, None);
+
+        let mock = MockExecutor {
+            executed: parking_lot::Mutex::new(Vec::new()),
+            failure: parking_lot::Mutex::new(None),
+        };
+        config.trading.trading_mode = TradingMode::Simulation;
+        let monitor = Monitor::new(&config).await.unwrap();
+        monitor.refresh_simulation_metrics(&mock).await;
+        assert_eq!(monitor.get_metrics().sim_balance, None);
+    }
+
+    #[test]
+    fn test_short_window_tracker_dedups_open_trades() {
+        use hfptm::arb_engine::{ShortWindowArbOpportunity, ShortWindowLeg};
+        use hfptm::ShortWindowArbTracker;
+
+        let mut tracker = ShortWindowArbTracker::new(dec!(1000));
+        let opp = ShortWindowArbOpportunity {
+            market_id: "btc-15m".to_string(),
+            market_question: "Will BTC be up in 15 minutes?".to_string(),
+            minutes_to_expiry: 10,
+            legs: vec![
+                ShortWindowLeg {
+                    asset_id: "yes".to_string(),
+                    outcome: "YES".to_string(),
+                    price: dec!(0.48),
+                    size: dec!(100),
+                    depth: vec![(dec!(0.48), dec!(100))],
+                },
+                ShortWindowLeg {
+                    asset_id: "no".to_string(),
+                    outcome: "NO".to_string(),
+                    price: dec!(0.49),
+                    size: dec!(100),
+                    depth: vec![(dec!(0.49), dec!(100))],
+                },
+            ],
+            sum_prices: dec!(0.97),
+            vwap_sum: dec!(0.97),
+            raw_edge: dec!(0.03),
+            net_edge: dec!(0.01),
+            vwap_net_edge: dec!(0.01),
+            position_size: dec!(50),
+            expected_profit: dec!(0.50),
+            min_liquidity: dec!(100),
+            detected_at: 0,
+            annualized_return: Decimal::ZERO,
+            assumed_fill_probability: dec!(0.25),
+            assumed_capital_utilization: dec!(0.5),
+        };
+
+        assert!(!tracker.has_open_trade("btc-15m"));
+
+        // Same mispricing seen on 5 consecutive scan ticks
+        let entered = (0..5)
+            .filter(|_| tracker.simulate_entry(&opp).is_some())
+            .count();
+
+        assert_eq!(entered, 1);
+        assert!(tracker.has_open_trade("btc-15m"));
+        assert_eq!(tracker.get_open_trades().len(), 1);
+        assert_eq!(tracker.get_stats().trades_entered, 1);
+    }
+
+    #[test]
+    fn test_short_window_tracker_state_survives_restart() {
+        use hfptm::arb_engine::{ShortWindowArbOpportunity, ShortWindowLeg, SimulatedTradeStatus};
+        use hfptm::utils::sim::SimEnv;
+        use hfptm::ShortWindowArbTracker;
+
+        let env = SimEnv::deterministic(7, 1_700_000_000_000);
+        let opp = |market_id: &str, minutes_to_expiry: i64| ShortWindowArbOpportunity {
+            market_id: market_id.to_string(),
+            market_question: format!("Will {} be up?", market_id),
+            minutes_to_expiry,
+            legs: ["up", "down"]
+                .iter()
+                .map(|asset_id| ShortWindowLeg {
+                    asset_id: asset_id.to_string(),
+                    outcome: asset_id.to_string(),
+                    price: dec!(0.48),
+                    size: dec!(100),
+                    depth: vec![(dec!(0.48), dec!(100))],
+                })
+                .collect(),
+            sum_prices: dec!(0.96),
+            vwap_sum: dec!(0.96),
+            raw_edge: dec!(0.04),
+            net_edge: dec!(0.02),
+            vwap_net_edge: dec!(0.02),
+            position_size: dec!(50),
+            expected_profit: dec!(1),
+            min_liquidity: dec!(100),
+            detected_at: 0,
+            annualized_return: Decimal::ZERO,
+            assumed_fill_probability: dec!(0.25),
+            assumed_capital_utilization: dec!(0.5),
+        };
+
+        // One trade resolved, one still open with 60 minutes to go
+        let mut tracker = ShortWindowArbTracker::with_sim_env(dec!(1000), env.clone());
+        let resolved = tracker.simulate_entry(&opp("btc-15m", 15)).unwrap();
+        let open = tracker.simulate_entry(&opp("eth-1h", 60)).unwrap();
+        tracker.simulate_resolution(&resolved.id, true);
+
+        let path =
+            std::env::temp_dir().join(format!("hfptm_sw_state_{}/state.json", std::process::id()));
+        tracker.save_state(&path).unwrap();
+
+        // Restarted 10 minutes later
+        env.clock.advance_ms(10 * 60 * 1000);
+        let mut restored = ShortWindowArbTracker::with_sim_env(dec!(1000), env.clone());
+        restored.load_state(&path, 3600).unwrap();
+
+        let (before, after) = (tracker.get_stats(), restored.get_stats());
+        assert_eq!(after.trades_entered, 2);
+        assert_eq!(after.trades_won, before.trades_won);
+        assert_eq!(after.total_pnl, before.total_pnl);
+        assert_eq!(after.win_rate, before.win_rate);
+        assert_eq!(after.simulated_balance, before.simulated_balance);
+        assert_eq!(after.total_capital_deployed, before.total_capital_deployed);
+        assert_eq!(after.roi, before.roi);
+        assert!(restored.has_open_trade("eth-1h"));
+        assert!(!restored.has_open_trade("btc-15m"));
+
+        // The open trade still resolves at its original time
+        assert!(restored.auto_resolve_expired().is_empty());
+        env.clock.advance_ms(51 * 60 * 1000);
+        let expired = restored.auto_resolve_expired();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, open.id);
+        assert_eq!(expired[0].status, SimulatedTradeStatus::ResolvedProfit);
+        assert_eq!(restored.get_stats().trades_won, 2);
+
+        // An hour-old save belongs to an earlier session
+        let mut fresh = ShortWindowArbTracker::with_sim_env(dec!(1000), env.clone());
+        let err = fresh.load_state(&path, 3600).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<HftpmError>(),
+            Some(HftpmError::Stale(_))
+        ));
+        assert_eq!(fresh.get_stats().trades_entered, 0);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_adversarial_fills_resolve_expired_trades_as_losses() {
+        use hfptm::arb_engine::{ShortWindowArbOpportunity, ShortWindowLeg, SimulatedTradeStatus};
+        use hfptm::utils::sim::SimEnv;
+        use hfptm::ShortWindowArbTracker;
+
+        // 0.52 + 0.44 = 0.96 on 50 sets: $48 in, $2 expected
+        let opp = |market_id: &str| ShortWindowArbOpportunity {
+            market_id: market_id.to_string(),
+            market_question: format!("Will {} be up?", market_id),
+            minutes_to_expiry: 15,
+            legs: [("up", dec!(0.52)), ("down", dec!(0.44))]
+                .iter()
+                .map(|(asset_id, price)| ShortWindowLeg {
+                    asset_id: asset_id.to_string(),
+                    outcome: asset_id.to_string(),
+                    price: *price,
+                    size: dec!(100),
+                    depth: vec![(*price, dec!(100))],
+                })
+                .collect(),
+            sum_prices: dec!(0.96),
+            vwap_sum: dec!(0.96),
+            raw_edge: dec!(0.04),
+            net_edge: dec!(0.02),
+            vwap_net_edge: dec!(0.02),
+            position_size: dec!(50),
+            expected_profit: dec!(2),
+            min_liquidity: dec!(100),
+            detected_at: 0,
+            annualized_return: Decimal::ZERO,
+            assumed_fill_probability: dec!(0.25),
+            assumed_capital_utilization: dec!(0.5),
+        };
+        let run = |probability: f64| {
+            let env = SimEnv::deterministic(11, 1_700_000_000_000);
+            let mut tracker = ShortWindowArbTracker::with_sim_env(dec!(1000), env.clone())
+                .with_adversarial_fills(probability);
+            tracker.simulate_entry(&opp("btc-15m")).unwrap();
+            tracker.simulate_entry(&opp("eth-15m")).unwrap();
+            env.clock.advance_ms(16 * 60 * 1000);
+            let resolved = tracker.auto_resolve_expired();
+            (resolved, tracker.get_stats())
+        };
+
+        // The default assumes one side always redeems
+        let (resolved, stats) = run(0.0);
+        assert!(resolved
+            .iter()
+            .all(|t| t.status == SimulatedTradeStatus::ResolvedProfit));
+        assert_eq!(stats.total_pnl, dec!(4));
+
+        // Always picked off: only the 0.52 leg filled and it lost, so $26 of
+        // each $48 is gone and the unfilled $22 comes back
+        let (resolved, stats) = run(1.0);
+        assert_eq!(resolved.len(), 2);
+        for trade in &resolved {
+            assert_eq!(trade.status, SimulatedTradeStatus::ResolvedLoss);
+            assert_eq!(trade.actual_profit, Some(dec!(-26)));
+            assert!(trade.resolution_time.is_some());
+        }
+        assert_eq!(stats.trades_won, 0);
+        assert_eq!(stats.trades_lost, 2);
+        assert_eq!(stats.win_rate, Decimal::ZERO);
+        assert_eq!(stats.total_pnl, dec!(-52));
+        assert_eq!(stats.simulated_balance, dec!(948));
+        assert_eq!(stats.trades_open, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_capital_turnover_from_deploy_and_recycle() {
+        use hfptm::arb_engine::{ShortWindowArbOpportunity, ShortWindowLeg};
+        use hfptm::monitoring::{CapitalEfficiency, CapitalUse};
+        use hfptm::utils::sim::SimEnv;
+        use hfptm::ShortWindowArbTracker;
+
+        // $100 recycled every 6 hours over a day: three round trips earning $2
+        // each, and a fourth still at risk when the day ends
+        let hour = 3600;
+        let uses: Vec<CapitalUse> = (0..4)
+            .map(|i| CapitalUse {
+                amount: dec!(100),
+                entered_at: i * 6 * hour,
+                exited_at: (i < 3).then_some((i + 1) * 6 * hour),
+                profit: dec!(2),
+            })
+            .collect();
+        let day = CapitalEfficiency::measure(&uses, 0, 24 * hour);
+        assert_eq!(day.capital_deployed, dec!(400));
+        assert_eq!(day.avg_capital_at_risk, dec!(100));
+        assert_eq!(day.turnover, 4.0);
+        assert_eq!(day.profit_per_dollar_day, 0.06);
+
+        // Half the day idle halves the capital at risk, doubling both ratios
+        let idle = CapitalEfficiency::measure(&uses[..2], 0, 24 * hour);
+        assert_eq!(idle.avg_capital_at_risk, dec!(50));
+        assert_eq!(idle.turnover, 4.0);
+        assert_eq!(idle.profit_per_dollar_day, 0.08);
+
+        // Capital deployed before the session only counts its time at risk within it
+        let late = CapitalEfficiency::measure(&uses, 12 * hour, 24 * hour);
+        assert_eq!(late.capital_deployed, dec!(200));
+        assert_eq!(late.avg_capital_at_risk, dec!(100));
+        assert_eq!(late.turnover, 2.0);
+        assert_eq!(
+            CapitalEfficiency::measure(&uses, 0, 0),
+            CapitalEfficiency::default()
+        );
+
+        // Fed from the tracker: $48 out for 20 minutes, then recycled into a
+        // second trade that's still open 20 minutes later
+        let env = SimEnv::deterministic(3, 1_700_000_000_000);
+        let start = env.clock.now_secs();
+        let opp = |market_id: &str| ShortWindowArbOpportunity {
+            market_id: market_id.to_string(),
+            market_question: format!("Will {} be up?", market_id),
+            minutes_to_expiry: 15,
+            legs: ["up", "down"]
+                .iter()
+                .map(|asset_id| ShortWindowLeg {
+                    asset_id: asset_id.to_string(),
+                    outcome: asset_id.to_string(),
+                    price: dec!(0.48),
+                    size: dec!(100),
+                    depth: vec![(dec!(0.48), dec!(100))],
+                })
+                .collect(),
+            sum_prices: dec!(0.96),
+            vwap_sum: dec!(0.96),
+            raw_edge: dec!(0.04),
+            net_edge: dec!(0.02),
+            vwap_net_edge: dec!(0.02),
+            position_size: dec!(50),
+            expected_profit: dec!(2),
+            min_liquidity: dec!(100),
+            detected_at: 0,
+            annualized_return: Decimal::ZERO,
+            assumed_fill_probability: dec!(0.25),
+            assumed_capital_utilization: dec!(0.5),
+        };
+        let mut tracker = ShortWindowArbTracker::with_sim_env(dec!(1000), env.clone());
+        tracker.simulate_entry(&opp("btc-15m")).unwrap();
+        env.clock.advance_ms(20 * 60 * 1000);
+        assert_eq!(tracker.auto_resolve_expired().len(), 1);
+        tracker.simulate_entry(&opp("eth-15m")).unwrap();
+        env.clock.advance_ms(20 * 60 * 1000);
+
+        let session =
+            CapitalEfficiency::measure(&tracker.capital_uses(), start, env.clock.now_secs());
+        assert_eq!(session.capital_deployed, dec!(96));
+        assert_eq!(session.avg_capital_at_risk, dec!(48));
+        assert_eq!(session.turnover, 2.0);
+        // $2 on $48 over 40 minutes
+        assert_eq!(session.profit_per_dollar_day, 1.5);
+
+        // The feed publishes into the dashboard metrics, measured from when the
+        // monitor started, which all of the uses above predate
+        let monitor = Monitor::new(&create_test_config()).await.unwrap();
+        let published = monitor.capital_efficiency_feed().record(&uses).await;
+        assert_eq!(monitor.get_metrics().capital_efficiency, published);
+        assert_eq!(published.capital_deployed, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_clob_signing_config_from_credentials() {
+        use hfptm::executor::ClobSigningConfig;
+        use polymarket_client_sdk::clob::types::SignatureType;
+
+        let mut credentials = create_test_config().credentials;
+        let funder = credentials.funder_address.parse().unwrap();
+
+        let mapping = ClobSigningConfig::from_credentials;
+
+        // EOA ignores any configured funder
+        credentials.signature_type = 0;
+        assert_eq!(
+            mapping(&credentials).unwrap(),
+            ClobSigningConfig {
+                signature_type: SignatureType::Eoa,
+                funder: None,
+            }
+        );
+
+        for (raw, expected) in [(1, SignatureType::Proxy), (2, SignatureType::GnosisSafe)] {
+            credentials.signature_type = raw;
+            assert_eq!(
+                mapping(&credentials).unwrap(),
+                ClobSigningConfig {
+                    signature_type: expected,
+                    funder: Some(funder),
+                }
+            );
+        }
+
+        credentials.signature_type = 3;
+        let err = mapping(&credentials).unwrap_err();
+        assert!(matches!(err, HftpmError::Config(_)));
+        assert!(
+            err.to_string().contains("Invalid signature_type 3"),
+            "{}",
+            err
+        );
+
+        // Proxy/Safe without a usable funder address
+        credentials.signature_type = 2;
+        let zero = "0x0000000000000000000000000000000000000000";
+        for bad in ["", "YOUR_FUNDER_ADDRESS_HERE", zero] {
+            credentials.funder_address = bad.to_string();
+            assert!(
+                matches!(mapping(&credentials), Err(HftpmError::Config(_))),
+                "accepted funder {:?}",
+                bad
+            );
+        }
+    }
+
+    /// Drives every simulator through a fixed script and returns their trade logs as JSON
+    async fn deterministic_sim_run(seed: u64) -> String {
+        use hfptm::arb_engine::{ShortWindowArbOpportunity, ShortWindowLeg};
+        use hfptm::executor::SimulationExecutor;
+        use hfptm::market_maker::{LadderRung, MMOpportunity};
+        use hfptm::utils::sim::SimEnv;
+        use hfptm::volume_farmer::TrashOpportunity;
+        use hfptm::{ShortWindowArbTracker, VolumeFarmer};
+
+        let config = create_test_config();
+        let env = SimEnv::deterministic(seed, 1_700_000_000_000);
+        let manager = OrderBookManager::new(&config).unwrap();
+        for (asset_id, asks) in [
+            ("yes_asset", vec![(dec!(0.45), dec!(100))]),
+            ("no_asset", vec![(dec!(0.45), dec!(60))]),
+        ] {
+            let snapshot = ask_snapshot("det_market", asset_id, asks);
+            manager.update_book("det_market", asset_id, &snapshot).unwrap();
+        }
+
+        let simulator = SimulationExecutor::with_sim_env(&config, env.clone());
+        simulator
+            .simulate_arbitrage(&binary_arb("det_market", dec!(100), dec!(100)), &manager)
+            .await
+            .unwrap();
+
+        env.clock.advance_ms(1_500);
+
+        let mut mm = MarketMaker::with_sim_env(&config, env.clone());
+        let opportunities: Vec<MMOpportunity> = ["yes_asset", "no_asset"]
+            .iter()
+            .map(|asset_id| MMOpportunity {
+                market_id: "det_market".to_string(),
+                asset_id: asset_id.to_string(),
+                midpoint: dec!(0.50),
+                bid_price: dec!(0.45),
+                ask_price: dec!(0.55),
+                size: dec!(80),
+                spread_bps: dec!(200),
+                estimated_reward: dec!(0.1),
+                ladder: vec![LadderRung {
+                    level: 0,
+                    bid_price: dec!(0.45),
+                    ask_price: dec!(0.55),
+                    size: dec!(80),
+                    estimated_reward: dec!(0.1),
+                }],
+            })
+            .collect();
+        let placed = mm.simulate_mm_orders(&opportunities).await.unwrap();
+        mm.simulate_fills(&manager).await;
+        let mm_orders: Vec<_> = placed
+            .iter()
+            .flat_map(|p| [&p.bid_order_id, &p.ask_order_id])
+            .map(|id| mm.get_order(id).unwrap().clone())
+            .collect();
+
+        let mut farmer = VolumeFarmer::with_sim_env(&config, env.clone());
+        farmer
+            .simulate_trash_trade(&TrashOpportunity {
+                market_id: "det_market".to_string(),
+                asset_id: "trash_asset".to_string(),
+                outcome_name: "No".to_string(),
+                price: dec!(0.02),
+                available_size: dec!(1000),
+                cost_for_volume: dec!(2),
+                notional_volume: dec!(100),
+                volume_multiplier: dec!(50),
+            })
+            .await
+            .unwrap();
+
+        let mut tracker = ShortWindowArbTracker::with_sim_env(dec!(1000), env.clone());
+        tracker.simulate_entry(&ShortWindowArbOpportunity {
+            market_id: "btc-15m".to_string(),
+            market_question: "Will BTC be up in 15 minutes?".to_string(),
+            minutes_to_expiry: 10,
+            legs: vec![
+                ShortWindowLeg {
+                    asset_id: "up".to_string(),
+                    outcome: "Up".to_string(),
+                    price: dec!(0.48),
+                    size: dec!(100),
+                    depth: vec![(dec!(0.48), dec!(100))],
+                },
+                ShortWindowLeg {
+                    asset_id: "down".to_string(),
+                    outcome: "Down".to_string(),
+                    price: dec!(0.49),
+                    size: dec!(100),
+                    depth: vec![(dec!(0.49), dec!(100))],
+                },
+            ],
+            sum_prices: dec!(0.97),
+            vwap_sum: dec!(0.97),
+            raw_edge: dec!(0.03),
+            net_edge: dec!(0.01),
+            vwap_net_edge: dec!(0.01),
+            position_size: dec!(50),
+            expected_profit: dec!(0.50),
+            min_liquidity: dec!(100),
+            detected_at: 0,
+            annualized_return: Decimal::ZERO,
+            assumed_fill_probability: dec!(0.25),
+            assumed_capital_utilization: dec!(0.5),
+        });
+        env.clock.advance_ms(11 * 60 * 1000);
+        tracker.auto_resolve_expired();
+
+        serde_json::to_string(&serde_json::json!({
+            "arb": simulator.get_trades().await,
+            "mm": mm_orders,
+            "vf": farmer.get_trades(),
+            "sw": tracker.get_trades(),
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_seeded_simulation_is_reproducible() {
+        let first = deterministic_sim_run(42).await;
+        let second = deterministic_sim_run(42).await;
+        assert_eq!(first, second);
+
+        // The seed drives the IDs, so a different seed gives a different log
+        assert_ne!(first, deterministic_sim_run(7).await);
+        assert!(first.contains("SIM_MM_") && first.contains("SIM_SW_"));
+    }
+
+    fn event_market(event_id: &str, condition_id: &str, question: &str) -> Market {
+        let mut market = test_market(condition_id, question, &[]);
+        market.events = vec![hfptm::gamma_api::EventInfo {
+            id: event_id.to_string(),
+            title: None,
+        }];
+        market
+    }
+
+    #[tokio::test]
+    async fn test_parallel_correlation_graph_matches_serial() {
+        use hfptm::ParallelScanner;
+
+        // 10 events x 12 "reach $X" thresholds: every pair within an event correlates
+        let markets: Vec<Market> = (0..10)
+            .flat_map(|e| {
+                (0..12).map(move |m| {
+                    event_market(
+                        &format!("event-{}", e),
+                        &format!("0xe{}m{}", e, m),
+                        &format!("Will Bitcoin reach ${}k by Friday?", 50 + m * 10),
+                    )
+                })
+            })
+            .collect();
+
+        let mut config = create_test_config();
+        config.latency.scanner_workers = 1;
+        let serial = ParallelScanner::new(&config, markets.clone());
+        serial.build_correlation_graph().await;
+
+        config.latency.scanner_workers = 4;
+        let parallel = ParallelScanner::new(&config, markets);
+        parallel.build_correlation_graph().await;
+
+        assert_eq!(serial.num_correlations().await, 10 * 12 * 11 / 2);
+        assert_eq!(parallel.num_correlations().await, serial.num_correlations().await);
+
+        assert_eq!(serial.get_stats().await.correlation_threads, 1);
+        let stats = parallel.get_stats().await;
+        assert_eq!(stats.workers, 4);
+        assert_eq!(stats.correlation_threads, 4);
+    }
+
+    #[tokio::test]
+    async fn test_update_markets_patches_correlation_graph() {
+        use hfptm::ParallelScanner;
+
+        let btc = |id: &str, target: u32| {
+            event_market(
+                "btc-friday",
+                id,
+                &format!("Will Bitcoin reach ${}k by Friday?", target),
+            )
+        };
+        let markets = vec![btc("0xa", 100), btc("0xb", 110)];
+
+        let scanner = ParallelScanner::new(&create_test_config(), markets.clone());
+        scanner.build_correlation_graph().await;
+        assert_eq!(scanner.num_correlations().await, 1);
+
+        // A new threshold in the same event correlates with both existing markets
+        let mut refreshed = markets.clone();
+        refreshed.push(btc("0xc", 120));
+        scanner.update_markets(refreshed.clone()).await;
+        assert_eq!(scanner.num_correlations().await, 3);
+        let mut related = scanner.related_markets("0xc").await;
+        related.sort();
+        assert_eq!(related, vec!["0xa", "0xb"]);
+
+        // Dropping a resolved market removes its pairs and cache entries
+        refreshed.retain(|m| m.market != "0xa");
+        scanner.update_markets(refreshed.clone()).await;
+        assert_eq!(scanner.num_correlations().await, 1);
+        assert!(scanner.related_markets("0xa").await.is_empty());
+        assert_eq!(scanner.related_markets("0xb").await, vec!["0xc"]);
+
+        // The patched graph matches a full rebuild over the same markets
+        let rebuilt = ParallelScanner::new(&create_test_config(), refreshed);
+        rebuilt.build_correlation_graph().await;
+        assert_eq!(rebuilt.num_correlations().await, scanner.num_correlations().await);
+    }
+
+    #[tokio::test]
+    async fn test_correlation_keywords_come_from_config() {
+        use hfptm::ParallelScanner;
+
+        // Two words in common: not siblings, and no default keyword marks a parent
+        let markets = vec![
+            event_market("race", "0xnom", "Will Smith take the nomination?"),
+            event_market("race", "0xiowa", "Will Smith take the Iowa primary?"),
+        ];
+
+        let scanner = ParallelScanner::new(&create_test_config(), markets.clone());
+        scanner.build_correlation_graph().await;
+        assert_eq!(scanner.num_correlations().await, 0);
+
+        let mut config = create_test_config();
+        config.correlation.parent_keywords = vec!["nomination".to_string()];
+        config.correlation.child_keywords = vec!["primary".to_string()];
+        let scanner = ParallelScanner::new(&config, markets.clone());
+        scanner.build_correlation_graph().await;
+        assert_eq!(scanner.num_correlations().await, 1);
+        assert_eq!(scanner.related_markets("0xnom").await, vec!["0xiowa"]);
+
+        // Thresholds are configurable too
+        let mut config = create_test_config();
+        config.correlation.min_sibling_common_words = 2;
+        let scanner = ParallelScanner::new(&config, markets.clone());
+        scanner.build_correlation_graph().await;
+        assert_eq!(scanner.num_correlations().await, 1);
+
+        // A stop word no longer counts toward the shared words
+        config.correlation.stop_words.push("take".to_string());
+        let scanner = ParallelScanner::new(&config, markets);
+        scanner.build_correlation_graph().await;
+        assert_eq!(scanner.num_correlations().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_correlations_are_not_actionable() {
+        use hfptm::parallel_scanner::{CorrelationType, MarketCorrelation};
+        use hfptm::ParallelScanner;
+
+        let sibling = MarketCorrelation {
+            market_a: "0xa".to_string(),
+            market_b: "0xb".to_string(),
+            correlation_type: CorrelationType::Sibling,
+            strength: 0.6,
+            parse_quality: 1.0,
+        };
+        assert_eq!(sibling.confidence(), dec!(0.6));
+
+        // "Lakers vs Celtics" siblings share 4 entities; the price-target pair parses cleanly
+        let markets = vec![
+            event_market("nba", "0xa", "Lakers vs Celtics Game 7: Lakers cover spread?"),
+            event_market("nba", "0xb", "Lakers vs Celtics Game 7: Celtics cover spread?"),
+            event_market("btc", "0xc", "Will Bitcoin reach $100k by Friday?"),
+            event_market("btc", "0xd", "Will Bitcoin reach $110k by Friday?"),
+        ];
+
+        let mut config = create_test_config();
+        config.trading.min_cross_market_confidence = dec!(0.8);
+        let scanner = ParallelScanner::new(&config, markets);
+        scanner.build_correlation_graph().await;
+        assert_eq!(scanner.num_correlations().await, 2);
+
+        let actionable = scanner.actionable_correlations().await;
+        assert_eq!(actionable.len(), 1);
+        assert_eq!(actionable[0].correlation_type, CorrelationType::Parent);
+        assert_eq!(actionable[0].confidence(), dec!(0.95));
+    }
+
+    #[test]
+    fn test_parse_end_date_formats() {
+        use hfptm::gamma_api::parse_end_date;
+
+        // 2025-01-15 14:30:00 UTC
+        let expected = 1_736_951_400;
+        let cases = [
+            "2025-01-15T14:30:00Z",
+            "2025-01-15T14:30:00.000Z",
+            "2025-01-15T14:30:00.123456Z",
+            "2025-01-15T14:30:00+00:00",
+            "2025-01-15T14:30:00.000+00:00",
+            "2025-01-15T16:30:00+02:00",
+            "2025-01-15T09:30:00-05:00",
+            "2025-01-15T14:30:00+0000",
+            "2025-01-15 14:30:00+00",
+            "2025-01-15 14:30:00",
+            "2025-01-15T14:30:00",
+            "2025-01-15T14:30Z",
+            " 2025-01-15T14:30:00Z ",
+            "1736951400",
+            "1736951400000",
+        ];
+
+        for case in cases {
+            let parsed = parse_end_date(case).unwrap_or_else(|| panic!("failed to parse {:?}", case));
+            assert_eq!(parsed.timestamp(), expected, "{:?}", case);
+        }
+
+        // Bare dates resolve at midnight UTC
+        assert_eq!(parse_end_date("2025-01-15").unwrap().timestamp(), 1_736_899_200);
+        assert!(parse_end_date("next tuesday").is_none());
+        assert!(parse_end_date("").is_none());
+    }
+
+    #[test]
+    fn test_short_window_event_slugs_cover_horizon() {
+        use hfptm::gamma_api::short_window_event_slugs;
+
+        // 14:37 UTC with a 30 min horizon: 15m windows at 14:30, 14:45, 15:00; 1h at 14:00, 15:00
+        let now = chrono::DateTime::parse_from_rfc3339("2025-01-15T14:37:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let slugs = short_window_event_slugs(now, 30);
+
+        assert_eq!(slugs.len(), 3 * 15 + 2 * 8);
+        for slug in [
+            "btc-updown-15m-1736951400",
+            "hype-updown-15m-1736952300",
+            "btc-updown-15m-1736953200",
+            "eth-updown-1h-1736949600",
+            "eth-updown-1h-1736953200",
+        ] {
+            assert!(slugs.contains(&slug.to_string()), "missing {}", slug);
+        }
+        assert!(!slugs.contains(&"btc-updown-15m-1736954100".to_string()));
+
+        // A horizon shorter than the window still probes the live one
+        assert_eq!(short_window_event_slugs(now, 0).len(), 15 + 8);
+    }
+
+    #[test]
+    fn test_far_future_and_past_end_dates_do_not_panic() {
+        use hfptm::arb_engine::{
+            estimate_annualized_return, ShortWindowArbOpportunity, ShortWindowLeg,
+        };
+        use hfptm::gamma_api::{parse_end_date, short_window_event_slugs};
+        use hfptm::utils::sim::SimEnv;
+        use hfptm::ShortWindowArbTracker;
+
+        let mut config = create_test_config();
+        config.markets.enable_short_window_markets = true;
+        let manager = OrderBookManager::new(&config).unwrap();
+        let risk_manager = RiskManager::new(&config);
+        let mut engine = ArbEngine::new(&config);
+        for asset_id in ["up", "down"] {
+            let snapshot = ask_snapshot("btc_updown", asset_id, vec![(dec!(0.45), dec!(200))]);
+            manager
+                .update_book("btc_updown", asset_id, &snapshot)
+                .unwrap();
+        }
+        let mut market = test_market("btc_updown", "BTC up or down at 12:15?", &["up", "down"]);
+        assert_eq!(
+            market
+                .analyze_short_window(&config.markets)
+                .minutes_to_expiry,
+            Some(9)
+        );
+
+        // Year 3000, an hour ago, and a date chrono can't represent
+        let past = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        assert!(parse_end_date("3000-01-01T00:00:00Z").is_some());
+        assert!(parse_end_date("99999999999999999999").is_none());
+        for end_date in ["3000-01-01T00:00:00Z", past.as_str(), "9999999999999999"] {
+            market.end_date = Some(end_date.to_string());
+            let info = market.analyze_short_window(&config.markets);
+            assert_eq!(info.minutes_to_expiry, None, "{}", end_date);
+            assert!(!info.is_short_window);
+            assert!(engine
+                .detect_short_window_arbitrage(&manager, &market, &config.markets, &risk_manager)
+                .unwrap()
+                .is_none());
+        }
+
+        // An absurd minimum no longer wraps negative and lets everything through
+        market.end_date = Some((chrono::Utc::now() + chrono::Duration::minutes(10)).to_rfc3339());
+        config.markets.min_minutes_to_expiry = u64::MAX;
+        assert!(!market.analyze_short_window(&config.markets).is_short_window);
+
+        // An expired window earns nothing rather than dividing by a clamped minute
+        for minutes in [0, -5, i64::MIN] {
+            assert_eq!(
+                estimate_annualized_return(dec!(0.01), minutes, dec!(1), dec!(1), dec!(10)),
+                Decimal::ZERO
+            );
+        }
+
+        // A horizon of u64::MAX minutes is capped at a day of windows
+        let now = chrono::Utc::now();
+        assert_eq!(
+            short_window_event_slugs(now, u64::MAX).len(),
+            short_window_event_slugs(now, 24 * 60).len()
+        );
+
+        // Resolution times saturate instead of overflowing
+        let env = SimEnv::deterministic(5, 1_700_000_000_000);
+        let mut tracker = ShortWindowArbTracker::with_sim_env(dec!(1000), env.clone());
+        let opp = |market_id: &str, minutes_to_expiry: i64| ShortWindowArbOpportunity {
+            market_id: market_id.to_string(),
+            market_question: format!("Will {} be up?", market_id),
+            minutes_to_expiry,
+            legs: ["up", "down"]
+                .iter()
+                .map(|asset_id| ShortWindowLeg {
+                    asset_id: asset_id.to_string(),
+                    outcome: asset_id.to_string(),
+                    price: dec!(0.48),
+                    size: dec!(100),
+                    depth: vec![(dec!(0.48), dec!(100))],
+                })
+                .collect(),
+            sum_prices: dec!(0.96),
+            vwap_sum: dec!(0.96),
+            raw_edge: dec!(0.04),
+            net_edge: dec!(0.02),
+            vwap_net_edge: dec!(0.02),
+            position_size: dec!(50),
+            expected_profit: dec!(2),
+            min_liquidity: dec!(100),
+            detected_at: 0,
+            annualized_return: Decimal::ZERO,
+            assumed_fill_probability: dec!(0.25),
+            assumed_capital_utilization: dec!(0.5),
+        };
+        let forever = tracker.simulate_entry(&opp("forever", i64::MAX)).unwrap();
+        assert_eq!(forever.expected_resolution_time, i64::MAX);
+        let expired = tracker.simulate_entry(&opp("expired", -30)).unwrap();
+        assert_eq!(expired.expected_resolution_time, env.clock.now_secs());
+
+        env.clock.advance_ms(1000);
+        let resolved = tracker.auto_resolve_expired();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].id, expired.id);
+        assert!(tracker.has_open_trade("forever"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_short_window_markets_bounded_and_deduped() {
+        use axum::{extract::Query, routing::get, Json, Router};
+        use hfptm::gamma_api::{short_window_event_slugs, GammaClient};
+        use std::collections::HashMap;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let (in_flight_h, peak_h, requests_h) = (in_flight.clone(), peak.clone(), requests.clone());
+        let app = Router::new().route(
+            "/events",
+            get(move |Query(params): Query<HashMap<String, String>>| {
+                let (in_flight, peak, requests) =
+                    (in_flight_h.clone(), peak_h.clone(), requests_h.clone());
+                async move {
+                    requests.fetch_add(1, Ordering::SeqCst);
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    // Every window of a ticker/interval returns the same market
+                    let slug = params.get("slug").cloned().unwrap_or_default();
+                    let series = slug.rsplit_once('-').map(|(s, _)| s.to_string()).unwrap_or_default();
+                    let end_date = (chrono::Utc::now() + chrono::Duration::minutes(10)).to_rfc3339();
+                    Json(serde_json::json!([{
+                        "id": format!("event-{}", series),
+                        "slug": slug,
+                        "title": "Crypto Up or Down",
+                        "active": true,
+                        "markets": [{
+                            "id": "1",
+                            "question": format!("{} Up or Down?", series),
+                            "slug": slug,
+                            "conditionId": format!("0x{}", series),
+                            "outcomes": "[\"Up\", \"Down\"]",
+                            "clobTokenIds": "[\"up_token\", \"down_token\"]",
+                            "endDate": end_date,
+                            "active": true,
+                            "enableOrderBook": true
+                        }]
+                    }]))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let mut config = create_test_config();
+        config.markets.max_concurrent_event_fetches = 4;
+        let expected_probes =
+            short_window_event_slugs(chrono::Utc::now(), config.markets.short_window_minutes).len();
+
+        let client = GammaClient::new(&base_url, &config.execution).unwrap();
+        let markets = client.fetch_short_window_markets(&config.markets).await.unwrap();
+
+        // One market per ticker and interval, however many windows it showed up under
+        assert_eq!(markets.len(), 15 + 8);
+        assert!(requests.load(Ordering::SeqCst) >= expected_probes);
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(peak > 1 && peak <= 4, "peak concurrency was {}", peak);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_operations_return_typed_errors() {
+        use hfptm::gamma_api::GammaClient;
+        use reqwest::StatusCode;
+        use std::sync::Arc;
+
+        let mut config = create_test_config();
+
+        // OrderBookManager: bad config, unknown market
+        config.trading.tick_size = "abc".to_string();
+        assert!(matches!(
+            OrderBookManager::new(&config),
+            Err(HftpmError::Config(_))
+        ));
+        config.trading.tick_size = "1.5".to_string();
+        assert!(matches!(
+            OrderBookManager::new(&config),
+            Err(HftpmError::Config(_))
+        ));
+        config = create_test_config();
+        let manager = OrderBookManager::new(&config).unwrap();
+        let err = manager
+            .update_price("nope", "asset", dec!(0.5), dec!(10), "BUY")
+            .unwrap_err();
+        assert!(matches!(err, HftpmError::NotFound(_)), "{:?}", err);
+
+        // GammaClient: nothing listening, missing and corrupt caches
+        let err = GammaClient::new("http://127.0.0.1:1", &config.execution)
+            .unwrap()
+            .fetch_markets(&config.markets)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HftpmError::Network(_)), "{:?}", err);
+        let cache_path =
+            std::env::temp_dir().join(format!("hfptm_typed_errors_{}.json", std::process::id()));
+        let client = GammaClient::new("http://127.0.0.1:1", &config.execution).unwrap();
+        let err = client
+            .load_cached_markets(&cache_path, 60)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HftpmError::NotFound(_)), "{:?}", err);
+        std::fs::write(&cache_path, b"not json").unwrap();
+        let err = client
+            .load_cached_markets(&cache_path, 60)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HftpmError::Parse(_)), "{:?}", err);
+        std::fs::remove_file(&cache_path).unwrap();
+
+        // HTTP statuses map to the kind callers act on
+        let kind = |status| HftpmError::from_status(status, "test");
+        assert!(kind(StatusCode::UNAUTHORIZED).is_fatal());
+        assert!(kind(StatusCode::FORBIDDEN).is_fatal());
+        assert!(matches!(
+            kind(StatusCode::TOO_MANY_REQUESTS),
+            HftpmError::RateLimited(_)
+        ));
+        assert!(kind(StatusCode::BAD_GATEWAY).is_retryable());
+
+        // The detection loop keeps going on a network error but stops on auth
+        config.quality_scoring.min_quality_score = Decimal::ZERO;
+        config.risk.inventory_drift_threshold = dec!(1000);
+        let risk_manager = RiskManager::new(&config);
+        let mut arb_engine = ArbEngine::new(&config);
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let mock = Arc::new(MockExecutor {
+            executed: parking_lot::Mutex::new(Vec::new()),
+            failure: parking_lot::Mutex::new(None),
+        });
+        let client = WebSocketClient::new(&config, &[], mock.clone())
+            .await
+            .unwrap();
+
+        for (i, failure) in [
+            HftpmError::Network("connection reset".to_string()),
+            HftpmError::Auth("invalid api key".to_string()),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let market_id = format!("err_market_{}", i);
+            for asset_id in ["yes", "no"] {
+                let snapshot = ask_snapshot(&market_id, asset_id, vec![(dec!(0.45), dec!(200))]);
+                manager
+                    .update_book(&market_id, asset_id, &snapshot)
+                    .unwrap();
+            }
+            let fatal = failure.is_fatal();
+            *mock.failure.lock() = Some(failure);
+
+            let result = client
+                .detect_and_execute(
+                    &market_id,
+                    &manager,
+                    &mut arb_engine,
+                    &risk_manager,
+                    &mut monitor,
+                )
+                .await;
+            assert_eq!(mock.executed.lock().len(), i + 1);
+            match result {
+                Ok(()) => assert!(!fatal),
+                Err(e) => {
+                    assert!(fatal);
+                    assert!(matches!(
+                        e.downcast_ref::<HftpmError>(),
+                        Some(HftpmError::Auth(_))
+                    ));
+                }
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_http_client_applies_configured_timeout() {
+        use axum::{routing::get, Router};
+        use hfptm::gamma_api::GammaClient;
+        use hfptm::utils::build_http_client;
+        use std::time::{Duration, Instant};
+
+        // Answers long after any sane timeout
+        let app = Router::new().fallback(get(|| async {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            "[]"
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let mut config = create_test_config();
+        config.execution.http_timeout_secs = 1;
+
+        let client = build_http_client(&config.execution).unwrap();
+        let started = Instant::now();
+        let err = client
+            .get(format!("{}/book", base_url))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout(), "{:?}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Gamma goes through the same client rather than its own 30s one
+        let started = Instant::now();
+        let err = GammaClient::new(&base_url, &config.execution)
+            .unwrap()
+            .fetch_markets(&config.markets)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HftpmError::Network(_)), "{:?}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_gamma_lists_parse_from_strings_or_arrays() {
+        use hfptm::gamma_api::{EventMarket, Market};
+
+        let market = |outcomes: serde_json::Value, token_ids: serde_json::Value| {
+            serde_json::json!({
+                "id": "1",
+                "question": "Will it rain?",
+                "slug": "rain",
+                "conditionId": "0xrain",
+                "outcomes": outcomes,
+                "clobTokenIds": token_ids,
+                "active": true
+            })
+        };
+        let stringified = market(
+            serde_json::json!("[\"Yes\", \"No\"]"),
+            serde_json::json!("[\"yes_token\", \"no_token\"]"),
+        );
+        let native = market(
+            serde_json::json!(["Yes", "No"]),
+            serde_json::json!(["yes_token", "no_token"]),
+        );
+
+        let outcomes = |market: &Market| -> Vec<(String, String)> {
+            market
+                .outcomes
+                .iter()
+                .map(|o| (o.id.clone(), o.name.clone()))
+                .collect()
+        };
+        let from_string: Market = serde_json::from_value(stringified.clone()).unwrap();
+        let from_array: Market = serde_json::from_value(native.clone()).unwrap();
+        assert_eq!(outcomes(&from_string), outcomes(&from_array));
+        assert_eq!(
+            outcomes(&from_array),
+            vec![
+                ("0".to_string(), "Yes".to_string()),
+                ("1".to_string(), "No".to_string())
+            ]
+        );
+        assert_eq!(from_string.assets_ids, from_array.assets_ids);
+        assert_eq!(from_array.assets_ids, vec!["yes_token", "no_token"]);
+
+        // Event markets take either encoding too
+        for value in [stringified, native] {
+            let event_market: EventMarket = serde_json::from_value(value).unwrap();
+            let converted = event_market.to_market("event", None).unwrap();
+            assert_eq!(outcomes(&converted), outcomes(&from_array));
+            assert_eq!(converted.outcomes[1].token_id, "no_token");
+        }
+
+        // A garbled list drops that market, not the event it came in
+        let garbled = market(serde_json::json!("[\"Yes\", "), serde_json::json!(42));
+        let event_market: EventMarket = serde_json::from_value(garbled.clone()).unwrap();
+        assert!(event_market.to_market("event", None).is_none());
+        assert!(serde_json::from_value::<Market>(garbled).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_markets_with_empty_or_duplicate_token_ids_are_excluded() {
+        use axum::{routing::get, Json, Router};
+        use hfptm::gamma_api::{EventMarket, GammaClient};
+
+        let market = |condition_id: &str, token_ids: &str| {
+            serde_json::json!({
+                "id": condition_id,
+                "question": format!("Will {} happen?", condition_id),
+                "slug": condition_id,
+                "conditionId": condition_id,
+                "outcomes": "[\"Yes\", \"No\"]",
+                "clobTokenIds": token_ids,
+                "category": "Sports",
+                "endDate": (chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339(),
+                "volume24hr": 25000.0,
+                "active": true,
+                "enableOrderBook": true
+            })
+        };
+        let markets = serde_json::json!([
+            market("0xvalid", "[\"yes_token\", \"no_token\"]"),
+            market("0xempty", "[\"yes_token\", \"\"]"),
+            market("0xduplicate", "[\"same_token\", \"same_token\"]"),
+        ]);
+
+        // Event markets are checked on conversion
+        for (value, valid) in markets.as_array().unwrap().iter().zip([true, false, false]) {
+            let event_market: EventMarket = serde_json::from_value(value.clone()).unwrap();
+            assert_eq!(event_market.to_market("event", None).is_some(), valid);
+        }
+
+        let app = Router::new().route(
+            "/markets",
+            get(move || {
+                let markets = markets.clone();
+                async move { Json(markets) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let config = create_test_config();
+        let fetched = GammaClient::new(&base_url, &config.execution)
+            .unwrap()
+            .fetch_markets(&config.markets)
+            .await
+            .unwrap();
+        let ids: Vec<_> = fetched.iter().map(|m| m.market.as_str()).collect();
+        assert_eq!(ids, vec!["0xvalid"]);
+    }
+
+    #[tokio::test]
+    async fn test_paper_live_signs_orders_without_submitting() {
+        use axum::{
+            routing::{get, post},
+            Json, Router,
+        };
+        use hfptm::executor::{OrderExecutor, MAX_PAPER_ORDERS};
+        use hfptm::utils::TradingMode;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // Just enough of the CLOB to authenticate and build orders
+        let submissions = Arc::new(AtomicUsize::new(0));
+        let submissions_h = submissions.clone();
+        let app = Router::new()
+            .route(
+                "/auth/api-key",
+                post(|| async {
+                    Json(serde_json::json!({
+                        "apiKey": "00000000-0000-0000-0000-000000000001",
+                        "secret": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+                        "passphrase": "paper"
+                    }))
+                }),
+            )
+            .route(
+                "/tick-size",
+                get(|| async { Json(serde_json::json!({ "minimum_tick_size": 0.01 })) }),
+            )
+            .route(
+                "/fee-rate",
+                get(|| async { Json(serde_json::json!({ "base_fee": 0 })) }),
+            )
+            .route(
+                "/neg-risk",
+                get(|| async { Json(serde_json::json!({ "neg_risk": false })) }),
+            )
+            .route(
+                "/orders",
+                post(move || {
+                    submissions_h.fetch_add(1, Ordering::SeqCst);
+                    async { Json(serde_json::json!([])) }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let mut config = create_test_config();
+        config.trading.trading_mode = TradingMode::PaperLive;
+        config.server.rest_url = base_url;
+        let manager = OrderBookManager::new(&config).unwrap();
+        let executor = OrderExecutor::new(&config).await.unwrap();
+
+        // Order building needs numeric token ids
+        let mut arb = binary_arb("paper_market", dec!(10), dec!(500));
+        arb.edges[0].asset_id = "1001".to_string();
+        arb.edges[1].asset_id = "1002".to_string();
+
+        let result = executor.execute_arbitrage(&arb, &manager).await.unwrap();
+        assert!(!result.success);
+        assert!(!result.filled);
+        assert!(result.orders.is_empty());
+        assert_eq!(result.filled_amount, Decimal::ZERO);
+        assert!(result.error_message.unwrap().contains("not submitted"));
+
+        let signed = executor.paper_orders(MAX_PAPER_ORDERS);
+        let assets: Vec<_> = signed.iter().map(|o| o.asset_id.as_str()).collect();
+        assert_eq!(assets, vec!["1001", "1002"]);
+        let newest = executor.paper_orders(1);
+        assert_eq!(newest.len(), 1);
+        assert_eq!(newest[0].asset_id, "1002");
+        assert!(signed.iter().all(|o| !o.order_hash.is_empty()));
+        assert!(signed
+            .iter()
+            .all(|o| o.price == dec!(0.45) && o.size == dec!(10)));
+        assert_eq!(submissions.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_market_fetch_falls_back_to_disk_cache() {
+        use axum::{http::StatusCode, routing::get, Json, Router};
+        use hfptm::gamma_api::GammaClient;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let gamma_down = Arc::new(AtomicBool::new(false));
+        let gamma_down_h = gamma_down.clone();
+        let app = Router::new().route(
+            "/markets",
+            get(move || {
+                let gamma_down = gamma_down_h.clone();
+                async move {
+                    if gamma_down.load(Ordering::SeqCst) {
+                        return Err(StatusCode::SERVICE_UNAVAILABLE);
+                    }
+                    let end_date = (chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339();
+                    Ok(Json(serde_json::json!([{
+                        "id": "1",
+                        "question": "Will the Lakers win?",
+                        "slug": "lakers-win",
+                        "conditionId": "0xlakers",
+                        "outcomes": "[\"Yes\", \"No\"]",
+                        "clobTokenIds": "[\"yes_token\", \"no_token\"]",
+                        "category": "Sports",
+                        "endDate": end_date,
+                        "volume24hr": 25000.0,
+                        "active": true,
+                        "enableOrderBook": true
+                    }])))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let cache_dir =
+            std::env::temp_dir().join(format!("hfptm_market_cache_{}", std::process::id()));
+        let cache_path = cache_dir.join("markets_cache.json");
+        let mut config = create_test_config();
+        config.markets.market_cache_path = cache_path.to_string_lossy().into_owned();
+
+        // A successful fetch is persisted
+        let fetched = GammaClient::new(&base_url, &config.execution)
+            .unwrap()
+            .fetch_markets_or_cached(&config.markets)
+            .await
+            .unwrap();
+        assert_eq!(fetched.len(), 1);
+        assert!(cache_path.exists());
+
+        // Gamma goes down: a fresh client starts from the on-disk cache
+        gamma_down.store(true, Ordering::SeqCst);
+        let client = GammaClient::new(&base_url, &config.execution).unwrap();
+        let cached = client
+            .fetch_markets_or_cached(&config.markets)
+            .await
+            .unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].market, "0xlakers");
+        assert_eq!(cached[0].assets_ids, vec!["yes_token", "no_token"]);
+        let names: Vec<_> = cached[0].outcomes.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, vec!["Yes", "No"]);
+        assert!(client.get_market("0xlakers").await.is_some());
+
+        // A cache past its max age is refused, so the fetch error surfaces
+        let snapshot: hfptm::gamma_api::MarketCacheFile =
+            serde_json::from_slice(&std::fs::read(&cache_path).unwrap()).unwrap();
+        let stale = hfptm::gamma_api::MarketCacheFile {
+            fetched_at: snapshot.fetched_at - 7200,
+            ..snapshot
+        };
+        std::fs::write(&cache_path, serde_json::to_vec(&stale).unwrap()).unwrap();
+        config.markets.market_cache_max_age_secs = 3600;
+        let err = GammaClient::new(&base_url, &config.execution)
+            .unwrap()
+            .fetch_markets_or_cached(&config.markets)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HftpmError::Network(_)), "{:?}", err);
+        assert!(err.is_retryable());
+        let err = GammaClient::new(&base_url, &config.execution)
+            .unwrap()
+            .load_cached_markets(&cache_path, 3600)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HftpmError::Stale(_)), "{:?}", err);
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[derive(Clone, Default)]
+    struct RecordingSink {
+        received: std::sync::Arc<std::sync::Mutex<Vec<Alert>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl hfptm::monitoring::AlertSink for RecordingSink {
+        async fn send(&self, alert: &Alert) {
+            self.received.lock().unwrap().push(alert.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_alerts_fan_out_to_every_sink() {
+        let config = create_test_config();
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let (first, second) = (RecordingSink::default(), RecordingSink::default());
+        monitor.add_sink(Box::new(first.clone()));
+        monitor.add_sink(Box::new(second.clone()));
+
+        monitor.alert_error("order rejected").await;
+        // Routine limits are recorded but don't page
+        monitor
+            .alert_risk_limit_breached("m1", RiskRejectReason::MaxConcurrentArbs)
+            .await;
+        monitor
+            .alert_risk_limit_breached("m1", RiskRejectReason::DailyLossLimit)
+            .await;
+
+        for sink in [&first, &second] {
+            let received = sink.received.lock().unwrap();
+            let types: Vec<_> = received.iter().map(|a| a.alert_type.clone()).collect();
+            assert_eq!(types, vec![AlertType::Error, AlertType::RiskLimitBreached]);
+            assert_eq!(received[0].message, "Error: order rejected");
+            assert_eq!(received[1].severity, AlertSeverity::Critical);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_risk_breach_alerted_once_per_cooldown() {
+        let mut config = create_test_config();
+        let mut monitor = Monitor::new(&config).await.unwrap();
+        let sink = RecordingSink::default();
+        monitor.add_sink(Box::new(sink.clone()));
+
+        for _ in 0..3 {
+            monitor
+                .alert_risk_limit_breached("m1", RiskRejectReason::DailyLossLimit)
+                .await;
+        }
+        // Another market or another limit is its own breach
+        monitor
+            .alert_risk_limit_breached("m2", RiskRejectReason::DailyLossLimit)
+            .await;
+        monitor
+            .alert_risk_limit_breached("m1", RiskRejectReason::InventoryDrift)
+            .await;
+
+        assert_eq!(monitor.get_recent_alerts(10).await.len(), 3);
+        assert_eq!(sink.received.lock().unwrap().len(), 3);
+
+        // Without a cooldown every breach is alerted
+        config.alerts.risk_alert_cooldown_secs = 0;
+        let monitor = Monitor::new(&config).await.unwrap();
+        for _ in 0..3 {
+            monitor
+                .alert_risk_limit_breached("m1", RiskRejectReason::DailyLossLimit)
+                .await;
+        }
+        assert_eq!(monitor.get_recent_alerts(10).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_latency_tracker() {
+        let mut tracker = LatencyTracker::new();
+
+        tracker.record(50_000_000);
+        tracker.record(150_000_000);
+
+        assert_eq!(tracker.count(), 2);
+        assert_eq!(tracker.avg_latency_ns(), 100_000_000);
+        assert_eq!(tracker.avg_latency_ms(), 100.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_cpu_pinning_sets_thread_affinity() {
+        use hfptm::utils::affinity::pin_current_thread;
+
+        let mut config = create_test_config();
+        let core = core_affinity::get_core_ids().unwrap()[0].id;
+        config.latency.target_cpu_core = core;
+
+        // Pin a scratch thread, not the test harness's
+        std::thread::spawn(move || {
+            assert!(!pin_current_thread(&config.latency));
+
+            config.latency.enable_cpu_pinning = true;
+            assert!(pin_current_thread(&config.latency));
+            let pinned: Vec<usize> = core_affinity::get_core_ids()
+                .unwrap()
+                .iter()
+                .map(|core_id| core_id.id)
+                .collect();
+            assert_eq!(pinned, vec![core]);
+
+            // A core outside the allowed set leaves the affinity alone
+            config.latency.target_cpu_core = usize::MAX;
+            assert!(!pin_current_thread(&config.latency));
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[test]
+    fn test_cpu_pinning_is_a_no_op_off_linux() {
+        let mut config = create_test_config();
+        config.latency.enable_cpu_pinning = true;
+        assert!(!hfptm::utils::affinity::pin_current_thread(&config.latency));
+    }
+
+    #[test]
+    fn test_circuit_breaker_trips_cools_down_and_recovers() {
+        use hfptm::executor::{BreakerPermit, BreakerState, CircuitBreaker};
+        use std::time::{Duration, Instant};
+
+        let window = Duration::from_secs(60);
+        let cooldown = Duration::from_secs(300);
+        let mut breaker = CircuitBreaker::new(3, window, cooldown);
+        let start = Instant::now();
+
+        // Failures spread wider than the window never add up to a trip
+        assert!(!breaker.record_failure(start));
+        assert!(!breaker.record_failure(start + Duration::from_secs(61)));
+        assert!(!breaker.record_failure(start + Duration::from_secs(122)));
+        assert_eq!(breaker.state(), BreakerState::Closed);
+
+        // A success resets the count
+        assert!(!breaker.record_success());
+        assert_eq!(breaker.recent_failures(), 0);
+
+        // Three in a row within the window trip it open
+        let t = start + Duration::from_secs(200);
+        assert_eq!(breaker.allow(t), BreakerPermit::Allowed);
+        assert!(!breaker.record_failure(t));
+        assert!(!breaker.record_failure(t + Duration::from_secs(1)));
+        assert!(breaker.record_failure(t + Duration::from_secs(2)));
+        let tripped_at = t + Duration::from_secs(2);
+        assert_eq!(
+            breaker.state(),
+            BreakerState::Open {
+                until: tripped_at + cooldown
+            }
+        );
+
+        // Nothing goes through during the cooldown
+        assert_eq!(breaker.allow(tripped_at), BreakerPermit::Rejected);
+        assert_eq!(
+            breaker.allow(tripped_at + cooldown - Duration::from_secs(1)),
+            BreakerPermit::Rejected
+        );
+
+        // After it, exactly one probe; a failed probe re-trips for another cooldown
+        let probe_at = tripped_at + cooldown;
+        assert_eq!(breaker.allow(probe_at), BreakerPermit::Probe);
+        assert_eq!(breaker.allow(probe_at), BreakerPermit::Rejected);
+        assert!(breaker.record_failure(probe_at));
+        assert_eq!(
+            breaker.allow(probe_at + cooldown - Duration::from_secs(1)),
+            BreakerPermit::Rejected
+        );
+
+        // A successful probe closes it
+        let probe_at = probe_at + cooldown;
+        assert_eq!(breaker.allow(probe_at), BreakerPermit::Probe);
+        assert!(breaker.record_success());
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert_eq!(breaker.allow(probe_at), BreakerPermit::Allowed);
+
+        // A probe that never reports back is replaced after a cooldown
+        for i in 0..3 {
+            breaker.record_failure(probe_at + Duration::from_secs(i));
+        }
+        let probe_at = probe_at + Duration::from_secs(2) + cooldown;
+        assert_eq!(breaker.allow(probe_at), BreakerPermit::Probe);
+        assert_eq!(breaker.allow(probe_at + cooldown), BreakerPermit::Probe);
+
+        // 0 disables it
+        let mut disabled = CircuitBreaker::new(0, window, cooldown);
+        for i in 0..10 {
+            assert!(!disabled.record_failure(start + Duration::from_millis(i)));
+        }
+        assert_eq!(disabled.allow(start), BreakerPermit::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_order_submissions_queue_beyond_rate_budget() {
+        use hfptm::executor::OrderRateLimiter;
+        use std::sync::Arc;
+        use std::time::{Duration, Instant};
+
+        let limiter = Arc::new(OrderRateLimiter::new(10));
+        let start = Instant::now();
+
+        // 15 orders against a 10/sec budget: the first 10 go out immediately,
+        // the other 5 are spaced one refill (100ms) apart
+        let submissions = (0..15).map(|_| {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                limiter.acquire().await;
+                start.elapsed()
+            })
+        });
+        let mut sent_at: Vec<Duration> = futures::future::join_all(submissions)
+            .await
+            .into_iter()
+            .map(|r| r.unwrap())
+            .collect();
+        sent_at.sort();
+
+        assert!(sent_at[9] < Duration::from_millis(50));
+        assert!(sent_at[10] >= Duration::from_millis(90));
+        assert!(sent_at[14] >= Duration::from_millis(450));
+
+        // A 429 drains the bucket and holds the next submission for the backoff
+        limiter.back_off(Duration::from_millis(200)).await;
+        let paused = Instant::now();
+        limiter.acquire().await;
+        assert!(paused.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_arb_legs_submitted_in_one_batch() {
+        use hfptm::executor::{
+            submit_orders_batch, OrderRateLimiter, OrderSubmitter, RetryPolicy, SignedOrder,
+            MAX_BATCH_ORDERS,
+        };
+        use polymarket_client_sdk::clob::types::{
+            OrderStatusType, PostOrderResponse, PostOrderResponseBuilder, Side,
+        };
+
+        /// Accepts every order except those for `reject`, recording each request
+        struct RecordingSubmitter {
+            batches: parking_lot::Mutex<Vec<Vec<String>>>,
+            reject: &'static str,
+        }
+
+        #[async_trait::async_trait]
+        impl OrderSubmitter for RecordingSubmitter {
+            async fn post_orders(
+                &self,
+                orders: &[SignedOrder],
+            ) -> hfptm::error::Result<Vec<PostOrderResponse>> {
+                self.batches
+                    .lock()
+                    .push(orders.iter().map(|o| o.asset_id.clone()).collect());
+                Ok(orders
+                    .iter()
+                    .map(|o| {
+                        let accepted = o.asset_id != self.reject;
+                        let mut response = PostOrderResponseBuilder::default()
+                            .making_amount(Decimal::ZERO)
+                            .taking_amount(Decimal::ZERO)
+                            .order_id(if accepted {
+                                format!("id_{}", o.asset_id)
+                            } else {
+                                String::new()
+                            })
+                            .status(OrderStatusType::Live)
+                            .success(accepted);
+                        if !accepted {
+                            response = response.error_msg("not enough balance");
+                        }
+                        response.build().unwrap()
+                    })
+                    .collect())
+            }
+        }
+
+        let signed = |asset_id: &str| SignedOrder {
+            asset_id: asset_id.to_string(),
+            side: Side::Buy,
+            price: dec!(0.45),
+            size: dec!(10),
+            order_hash: format!("hash_{}", asset_id),
+            created_at: std::time::Instant::now(),
+            signed: None,
+        };
+        let limiter = OrderRateLimiter::new(100);
+        let submitter = RecordingSubmitter {
+            batches: parking_lot::Mutex::new(Vec::new()),
+            reject: "no_asset",
+        };
+
+        // Both legs of a binary arb go out in a single request
+        let arb = binary_arb("batch_market", dec!(10), dec!(500));
+        let orders: Vec<SignedOrder> = arb.legs().iter().map(|leg| signed(&leg.asset_id)).collect();
+        let results = submit_orders_batch(&submitter, &limiter, &orders, RetryPolicy::NONE).await;
+
+        assert_eq!(
+            *submitter.batches.lock(),
+            vec![vec!["yes_asset".to_string(), "no_asset".to_string()]]
+        );
+        assert!(results[0].success);
+        assert_eq!(results[0].order_id.as_deref(), Some("id_yes_asset"));
+        // Not atomic: the other leg can still be rejected on its own
+        assert!(!results[1].success);
+        assert_eq!(results[1].error.as_deref(), Some("not enough balance"));
+
+        // More legs than the endpoint takes are split into back-to-back batches
+        submitter.batches.lock().clear();
+        let orders: Vec<SignedOrder> = (0..MAX_BATCH_ORDERS + 5)
+            .map(|i| signed(&format!("bucket_{}", i)))
+            .collect();
+        let results = submit_orders_batch(&submitter, &limiter, &orders, RetryPolicy::NONE).await;
+
+        let batch_sizes: Vec<usize> = submitter.batches.lock().iter().map(Vec::len).collect();
+        assert_eq!(batch_sizes, vec![MAX_BATCH_ORDERS, 5]);
+        assert_eq!(results.len(), MAX_BATCH_ORDERS + 5);
+        assert!(results.iter().all(|r| r.success));
+    }
+
+    #[tokio::test]
+    async fn test_live_costs_split_detected_from_paid_prices() {
+        use hfptm::executor::{
+            accepted_leg_costs, submit_orders_batch, OrderRateLimiter, OrderSubmitter, RetryPolicy,
+            SignedOrder,
+        };
+        use polymarket_client_sdk::clob::types::{
+            OrderStatusType, PostOrderResponse, PostOrderResponseBuilder, Side,
+        };
+
+        /// Matches the YES leg in full at 0.42 on placement, leaves NO resting
+        struct MatchingSubmitter;
+
+        #[async_trait::async_trait]
+        impl OrderSubmitter for MatchingSubmitter {
+            async fn post_orders(
+                &self,
+                orders: &[SignedOrder],
+            ) -> hfptm::error::Result<Vec<PostOrderResponse>> {
+                Ok(orders
+                    .iter()
+                    .map(|o| {
+                        let (making, taking) = if o.asset_id == "yes_asset" {
+                            (dec!(4.2), dec!(10))
+                        } else {
+                            (Decimal::ZERO, Decimal::ZERO)
+                        };
+                        PostOrderResponseBuilder::default()
+                            .making_amount(making)
+                            .taking_amount(taking)
+                            .order_id(format!("id_{}", o.asset_id))
+                            .status(OrderStatusType::Live)
+                            .success(true)
+                            .build()
+                            .unwrap()
+                    })
+                    .collect())
+            }
+        }
+
+        // Detected at 0.45 a leg; NO was sent a tick lower after rounding
+        let arb = binary_arb("cost_market", dec!(10), dec!(500));
+        let legs = arb.legs();
+        let orders: Vec<SignedOrder> = [("yes_asset", dec!(0.45)), ("no_asset", dec!(0.44))]
+            .into_iter()
+            .map(|(asset_id, price)| SignedOrder {
+                asset_id: asset_id.to_string(),
+                side: Side::Buy,
+                price,
+                size: dec!(10),
+                order_hash: format!("hash_{}", asset_id),
+                created_at: std::time::Instant::now(),
+                signed: None,
+            })
+            .collect();
+        let limiter = OrderRateLimiter::new(100);
+        let results =
+            submit_orders_batch(&MatchingSubmitter, &limiter, &orders, RetryPolicy::NONE).await;
+
+        assert_eq!(results[0].matched_price, Some(dec!(0.42)));
+        assert_eq!(results[1].matched_price, None);
+
+        let (expected, realized) = accepted_leg_costs(&legs, &orders, &results);
+        assert_eq!(expected, dec!(9.0));
+        // 10 matched at 0.42 plus 10 resting at the 0.44 it was sent with
+        assert_eq!(realized, dec!(8.6));
+    }
+
+    #[tokio::test]
+    async fn test_failed_leg_submission_retried_on_transient_errors() {
+        use hfptm::error::HftpmError;
+        use hfptm::executor::{
+            submit_orders_batch, OrderRateLimiter, OrderSubmitter, RetryPolicy, SignedOrder,
+        };
+        use polymarket_client_sdk::clob::types::{
+            OrderStatusType, PostOrderResponse, PostOrderResponseBuilder, Side,
+        };
+        use std::time::{Duration, Instant};
+
+        /// Fails the first `failures` requests with `error`, then accepts everything
+        struct FlakySubmitter {
+            requests: std::sync::atomic::AtomicUsize,
+            failures: usize,
+            error: fn() -> HftpmError,
+        }
+
+        #[async_trait::async_trait]
+        impl OrderSubmitter for FlakySubmitter {
+            async fn post_orders(
+                &self,
+                orders: &[SignedOrder],
+            ) -> hfptm::error::Result<Vec<PostOrderResponse>> {
+                let request = self
+                    .requests
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                if request < self.failures {
+                    return Err((self.error)());
+                }
+                Ok(orders
+                    .iter()
+                    .map(|o| {
+                        PostOrderResponseBuilder::default()
+                            .making_amount(Decimal::ZERO)
+                            .taking_amount(Decimal::ZERO)
+                            .order_id(format!("id_{}", o.asset_id))
+                            .status(OrderStatusType::Live)
+                            .success(true)
+                            .build()
+                            .unwrap()
+                    })
+                    .collect())
+            }
+        }
+
+        let flaky = |failures: usize, error: fn() -> HftpmError| FlakySubmitter {
+            requests: std::sync::atomic::AtomicUsize::new(0),
+            failures,
+            error,
+        };
+        let requests = |submitter: &FlakySubmitter| {
+            submitter.requests.load(std::sync::atomic::Ordering::SeqCst)
+        };
+        let orders: Vec<SignedOrder> = ["yes_asset", "no_asset"]
+            .iter()
+            .map(|asset_id| SignedOrder {
+                asset_id: asset_id.to_string(),
+                side: Side::Buy,
+                price: dec!(0.45),
+                size: dec!(10),
+                order_hash: format!("hash_{}", asset_id),
+                created_at: Instant::now(),
+                signed: None,
+            })
+            .collect();
+        let limiter = OrderRateLimiter::new(100);
+
+        let mut config = create_test_config();
+        config.execution.max_retries = 3;
+        config.execution.retry_backoff_ms = 10;
+        let retry = RetryPolicy::from_config(&config.execution, None);
+
+        // A timeout on the first request: one retry and both legs go through
+        let timeout = || HftpmError::Network("Failed to post orders: timed out".to_string());
+        let submitter = flaky(1, timeout);
+        let results = submit_orders_batch(&submitter, &limiter, &orders, retry).await;
+        assert_eq!(requests(&submitter), 2);
+        assert!(results.iter().all(|r| r.success));
+
+        // A terminal rejection is final
+        let rejected = || HftpmError::Exchange("Failed to post orders: invalid price".to_string());
+        let submitter = flaky(1, rejected);
+        let results = submit_orders_batch(&submitter, &limiter, &orders, retry).await;
+        assert_eq!(requests(&submitter), 1);
+        assert!(results.iter().all(|r| !r.success));
+
+        // Retries stop at max_retries
+        let submitter = flaky(10, timeout);
+        let results = submit_orders_batch(&submitter, &limiter, &orders, retry).await;
+        assert_eq!(requests(&submitter), 4);
+        assert!(results.iter().all(|r| !r.success));
+
+        // ...and never go out after the opportunity has aged past its limit
+        let expiring = RetryPolicy {
+            deadline: Some(Instant::now() + Duration::from_millis(5)),
+            ..retry
+        };
+        let submitter = flaky(1, timeout);
+        let results = submit_orders_batch(&submitter, &limiter, &orders, expiring).await;
+        assert_eq!(requests(&submitter), 1);
+        assert!(results.iter().all(|r| !r.success));
+
+        /// Rejects `reject`'s order in the first `rejections` requests, recording
+        /// the hashes sent in each
+        struct RejectingSubmitter {
+            requests: parking_lot::Mutex<Vec<Vec<String>>>,
+            rejections: usize,
+            reject: &'static str,
+        }
+
+        #[async_trait::async_trait]
+        impl OrderSubmitter for RejectingSubmitter {
+            async fn post_orders(
+                &self,
+                orders: &[SignedOrder],
+            ) -> hfptm::error::Result<Vec<PostOrderResponse>> {
+                let mut requests = self.requests.lock();
+                requests.push(orders.iter().map(|o| o.order_hash.clone()).collect());
+                let rejecting = requests.len() <= self.rejections;
+                Ok(orders
+                    .iter()
+                    .map(|o| {
+                        let accepted = !(rejecting && o.asset_id == self.reject);
+                        PostOrderResponseBuilder::default()
+                            .making_amount(Decimal::ZERO)
+                            .taking_amount(Decimal::ZERO)
+                            .order_id(format!("id_{}", o.asset_id))
+                            .status(OrderStatusType::Live)
+                            .success(accepted)
+                            .build()
+                            .unwrap()
+                    })
+                    .collect())
+            }
+        }
+
+        // A leg rejected inside an accepted batch is resent on its own, unchanged
+        let submitter = RejectingSubmitter {
+            requests: parking_lot::Mutex::new(Vec::new()),
+            rejections: 1,
+            reject: "no_asset",
+        };
+        let results = submit_orders_batch(&submitter, &limiter, &orders, retry).await;
+        assert_eq!(
+            *submitter.requests.lock(),
+            vec![
+                vec!["hash_yes_asset".to_string(), "hash_no_asset".to_string()],
+                vec!["hash_no_asset".to_string()],
+            ]
+        );
+        assert!(results.iter().all(|r| r.success));
+        assert_eq!(results[1].order_id.as_deref(), Some("id_no_asset"));
+
+        // Still bounded by max_retries
+        let submitter = RejectingSubmitter {
+            requests: parking_lot::Mutex::new(Vec::new()),
+            rejections: 10,
+            reject: "no_asset",
+        };
+        let results = submit_orders_batch(&submitter, &limiter, &orders, retry).await;
+        assert_eq!(submitter.requests.lock().len(), 4);
+        assert!(results[0].success);
+        assert!(!results[1].success);
     }
 
     fn create_test_config() -> Config {
         Config {
             server: ServerConfig {
                 wss_url: "wss://test.polymarket.com/ws/market".to_string(),
+                user_wss_url: "wss://test.polymarket.com/ws/user".to_string(),
+                ws_connections: 1,
+                ws_max_message_bytes: 5 * 1024 * 1024,
                 rest_url: "https://test.polymarket.com".to_string(),
                 gamma_url: "https://test.polymarket.com".to_string(),
                 polygon_rpc_url: "https://test.polygon.com".to_string(),
+                rest_fallback_enabled: false,
+                rest_fallback_after_secs: 30,
+                rest_fallback_poll_ms: 2000,
             },
             credentials: CredentialsConfig {
                 private_key: "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
@@ -161,6 +7561,7 @@ mod tests {
             },
             trading: TradingConfig {
                 trading_mode: hfptm::utils::TradingMode::Simulation,
+                strategy: hfptm::utils::Strategy::Arbitrage,
                 bankroll: 1000,
                 max_arb_size: 100,
                 min_edge: dec!(0.025),
@@ -169,6 +7570,36 @@ mod tests {
                 tick_size: "0.01".to_string(),
                 order_type: "FOK".to_string(),
                 slippage_tolerance: dec!(0.01),
+                min_executable_price: dec!(0.01),
+                min_cross_market_confidence: dec!(0.75),
+                min_net_profit_usd: dec!(0.5),
+                max_outcomes_for_arb: 0,
+                multi_outcome_require_full_fill: false,
+                mm_spread_bps: 200,
+                mm_order_size: 50,
+                mm_max_orders_per_market: 4,
+                mm_order_refresh_secs: 30,
+                mm_midpoint_mode: MidpointMode::Arithmetic,
+                mm_ladder_levels: 1,
+                mm_ladder_step_bps: 50,
+                mm_ladder_size_decay: dec!(1),
+                mm_reward_pool_usd: dec!(50),
+                mm_reward_max_spread: dec!(0.03),
+                mm_min_spread_ticks: 2,
+                mm_price_improve_ticks: 0,
+                vf_max_price: dec!(0.05),
+                vf_min_volume_per_trade: 100,
+                vf_daily_budget: 20,
+                short_window_min_edge: dec!(0.008),
+                short_window_max_size: 50,
+                short_window_fill_probability: dec!(0.25),
+                short_window_capital_utilization: dec!(0.5),
+                short_window_max_annualized_return: dec!(10),
+                short_window_edge_curve: Vec::new(),
+                short_window_edge_curve_mode: Default::default(),
+                enable_mean_reversion_signals: false,
+                mean_reversion_window: 30,
+                mean_reversion_band: dec!(0.02),
             },
             risk: RiskConfig {
                 max_exposure_per_market: 200,
@@ -178,6 +7609,11 @@ mod tests {
                 max_gas_gwei: 100,
                 position_timeout_seconds: 86400,
                 inventory_drift_threshold: dec!(0.05),
+                market_cooldown_secs: 300,
+                min_seconds_between_trades_per_market: 0,
+                blacklist_on_repeated_failure: true,
+                max_failures_before_blacklist: 3,
+                failure_window_secs: 3600,
             },
             markets: MarketsConfig {
                 prioritize_categories: vec!["sports".to_string()],
@@ -185,6 +7621,15 @@ mod tests {
                 min_volume_24h: 1000,
                 min_traders_24h: 10,
                 min_order_book_depth: 5,
+                enable_short_window_markets: true,
+                short_window_minutes: 30,
+                min_minutes_to_expiry: 2,
+                min_volume_24h_short: 100,
+                enforce_enable_order_book: true,
+                max_concurrent_event_fetches: 16,
+                market_cache_path: String::new(),
+                market_cache_max_age_secs: 21600,
+                market_refresh_interval_secs: 300,
             },
             execution: ExecutionConfig {
                 max_latency_ms: 150,
@@ -194,6 +7639,16 @@ mod tests {
                 retry_backoff_ms: 100,
                 http_timeout_secs: 5,
                 connection_pool_size: 10,
+                http_connect_timeout_secs: 3,
+                stale_book_sweep_interval_secs: 5,
+                stale_book_max_age_ms: 60_000,
+                enable_user_channel: true,
+                max_orders_per_sec: 10,
+                balance_reconcile_interval_secs: 60,
+                max_opportunity_age_ms: 500,
+                circuit_breaker_failures: 5,
+                circuit_breaker_window_secs: 60,
+                circuit_breaker_cooldown_secs: 300,
             },
             monitoring: MonitoringConfig {
                 log_level: "info".to_string(),
@@ -202,17 +7657,30 @@ mod tests {
                 enable_tracing: true,
                 log_file: "logs/test.log".to_string(),
                 metrics_retention_hours: 24,
+                daily_report_dir: String::new(),
+                daily_report_alert: false,
+                near_miss_band: Decimal::ZERO,
+                health_max_detection_idle_secs: 300,
+                stale_feed_age_ms: 60_000,
+                dashboard_token: String::new(),
             },
             alerts: AlertsConfig {
                 enable_telegram: false,
                 telegram_bot_token: "".to_string(),
                 telegram_chat_id: "".to_string(),
+                enable_discord: false,
+                discord_webhook_url: "".to_string(),
+                enable_webhook: false,
+                webhook_url: "".to_string(),
                 alert_on_trade_usd: 25,
                 alert_on_error: true,
                 alert_on_latency_spike: true,
                 latency_spike_threshold_ms: 200,
                 alert_on_pnl_drawdown: true,
                 pnl_drawdown_threshold_usd: 100,
+                ws_stall_threshold_ms: 30_000,
+                balance_deviation_threshold_usd: 25,
+                risk_alert_cooldown_secs: 300,
             },
             latency: LatencyConfig {
                 enable_cpu_pinning: false,
@@ -220,7 +7688,14 @@ mod tests {
                 use_jemalloc: true,
                 max_orderbook_updates_per_sec: 10000,
                 enable_zero_copy: true,
+                scanner_workers: 4,
+                detection_throttle_ms: 0,
             },
+            quality_scoring: Default::default(),
+            simulation: Default::default(),
+            schedule: Default::default(),
+            correlation: Default::default(),
+            strategy_budgets: Default::default(),
         }
     }
 }